@@ -0,0 +1,95 @@
+//! Compares `EventLog::refresh_all` with `EventLog::refresh_all_shared`
+//! when views resume from different offsets, the case where only the
+//! latter shares one read of the new events.
+//!
+//! Run with `cargo bench --bench refresh_all`. Each view count starts
+//! from a fresh copy of the same directory: view `i` was last refreshed
+//! after `i + 1` chunks of ten events, then `EVENTS` more were appended.
+
+use eventfold::{Event, EventLog};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+const EVENTS: u64 = 100_000;
+const RUNS: u32 = 3;
+
+fn counter(state: u64, _event: &Event) -> u64 {
+    state + 1
+}
+
+fn open(dir: &Path, views: impl IntoIterator<Item = usize>) -> EventLog {
+    let mut builder = EventLog::builder(dir);
+    for i in views {
+        builder = builder.view::<u64>(&format!("v{i}"), counter);
+    }
+    builder.open().unwrap()
+}
+
+fn append(log: &mut EventLog, n: u64) {
+    let events: Vec<_> = (0..n)
+        .map(|i| Event::new("tick", json!({ "i": i })))
+        .collect();
+    log.append_batch(&events).unwrap();
+}
+
+/// A log whose `views` views resume from `views` different offsets, with
+/// `EVENTS` events after the furthest one.
+fn prepare(dir: &Path, views: usize) {
+    for i in 0..views {
+        let mut log = open(dir, [i]);
+        append(&mut log, 10);
+        log.refresh_all().unwrap();
+    }
+    append(&mut open(dir, 0..views), EVENTS);
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+/// Best of `RUNS` refreshes, each of a fresh copy of `template`.
+fn time(template: &Path, views: usize, shared: bool) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let scratch = tempdir().unwrap();
+            copy_dir(template, scratch.path());
+            let mut log = open(scratch.path(), 0..views);
+            let start = Instant::now();
+            if shared {
+                log.refresh_all_shared().unwrap();
+            } else {
+                log.refresh_all_counted().unwrap();
+            }
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    println!("views  refresh_all  refresh_all_shared  ratio");
+    for views in [1, 2, 3, 4, 6, 8] {
+        let template = tempdir().unwrap();
+        prepare(template.path(), views);
+        let plain = time(template.path(), views, false);
+        let shared = time(template.path(), views, true);
+        println!(
+            "{views:>5}  {:>9.1}ms  {:>16.1}ms  {:>5.2}",
+            plain.as_secs_f64() * 1e3,
+            shared.as_secs_f64() * 1e3,
+            shared.as_secs_f64() / plain.as_secs_f64(),
+        );
+    }
+}
@@ -1,10 +1,11 @@
 //! Blocking tail — wait for new events with OS-level file notifications.
 //!
 //! Spawns a background writer that appends events every 200ms.
-//! The main thread uses `wait_for_events` to block until new data
-//! appears, avoiding busy-polling entirely.
+//! The main thread follows the log with `events_stream`, which blocks on
+//! OS-level file notifications until new data appears, avoiding
+//! busy-polling entirely.
 
-use eventfold::{Event, EventWriter, WaitResult};
+use eventfold::{Event, EventWriter};
 use serde_json::json;
 use std::thread;
 use std::time::Duration;
@@ -27,25 +28,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
-    // Blocking tail loop: wait for new events, process them, repeat.
-    let mut offset = 0u64;
+    // Blocking tail: the iterator waits for new events and tracks the
+    // offset itself, ending after two idle 1s polls.
     let mut seen = 0usize;
-
-    while seen < 10 {
-        match reader.wait_for_events(offset, Duration::from_secs(5))? {
-            WaitResult::NewData(_new_size) => {
-                for result in reader.read_from(offset)? {
-                    let (event, next_offset, _hash) = result?;
-                    let i = event.data["i"].as_u64().unwrap();
-                    println!("[reader] saw tick {i}");
-                    offset = next_offset;
-                    seen += 1;
-                }
-            }
-            WaitResult::Timeout => {
-                println!("[reader] timeout — no new events in 5s");
-            }
-        }
+    for result in reader.events_stream(0, Duration::from_secs(1)).max_idle(2) {
+        let (event, _next_offset, _hash) = result?;
+        let i = event.data["i"].as_u64().unwrap();
+        println!("[reader] saw tick {i}");
+        seen += 1;
     }
 
     handle.join().unwrap();
@@ -87,11 +87,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     let stats: &StatsState = log.view("stats")?;
-    let rate = if stats.created > 0 {
-        (stats.completed * 100) / stats.created
-    } else {
-        0
-    };
+    let rate = (stats.completed * 100)
+        .checked_div(stats.created)
+        .unwrap_or(0);
     println!(
         "Stats: {} created, {} completed, {} deleted ({}% completion rate)",
         stats.created, stats.completed, stats.deleted, rate
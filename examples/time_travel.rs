@@ -1,18 +1,23 @@
 //! Replaying to a specific point in the event history.
 //!
 //! Shows how to read events one by one and fold manually, stopping
-//! at any point to inspect intermediate state.
+//! at any point to inspect intermediate state, and how `View::state_at`
+//! does the same for a view at a byte offset.
 
-use eventfold::{Event, EventLog};
+use eventfold::{Event, EventLog, View};
 use serde_json::json;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let dir = tempfile::tempdir()?;
     let mut log = EventLog::open(dir.path())?;
 
-    // Append 20 events
+    // Append 20 events, noting where the 10th one ends
+    let mut offset_after_10 = 0;
     for i in 0..20 {
         log.append(&Event::new("tick", json!({"i": i})))?;
+        if i == 9 {
+            offset_after_10 = log.active_log_size()?;
+        }
     }
 
     // Full state: fold all events
@@ -27,6 +32,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let count_at_5 = count_events(&log, 5)?;
     println!("State at event 5: count = {}", count_at_5);
 
+    // The same, through a view, without touching its live state or snapshot
+    let view: View<u64> = View::new("count", |state, _event| state + 1, log.views_dir());
+    let at_offset = view.state_at(&log.reader(), offset_after_10)?;
+    println!("View state at offset {offset_after_10}: count = {at_offset}");
+
     Ok(())
 }
 
@@ -1,40 +1,456 @@
-use std::fs::{File, OpenOptions};
-use std::io::{self, BufRead, BufReader, Write};
-use std::path::Path;
+//! The archive: one frame per rotation, concatenated, encoded with the
+//! log's [`Codec`] — zstd in `archive.jsonl.zst` by default.
+//!
+//! [`EventReader::read_full`](crate::EventReader::read_full) streams the
+//! whole archive; the functions here address individual frames, for
+//! targeted reads of one rotation's worth of history. [`Retention`]
+//! bounds how many frames are kept.
 
-/// Compress data and append as a new zstd frame to the archive file.
+use crate::codec::{ArchiveCodec, Codec};
+use crate::crypto::{self, Cipher};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Archive-level metadata stored next to the archive file.
+///
+/// Holds every zstd dictionary ever used to compress a frame, keyed by
+/// dictionary ID, so reads can always decompress regardless of which
+/// dictionary (if any) the current writer is configured with.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ArchiveManifest {
+    /// Hex-encoded dictionaries keyed by their zstd dictionary ID.
+    dictionaries: BTreeMap<u32, String>,
+}
+
+/// Path of the manifest that accompanies `archive_path`.
+fn manifest_path(archive_path: &Path) -> PathBuf {
+    archive_path.with_file_name("archive.manifest.json")
+}
+
+fn load_manifest(archive_path: &Path) -> io::Result<ArchiveManifest> {
+    match fs::read_to_string(manifest_path(archive_path)) {
+        Ok(json) => {
+            serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(ArchiveManifest::default()),
+        Err(e) => Err(e),
+    }
+}
+
+fn save_manifest(archive_path: &Path, manifest: &ArchiveManifest) -> io::Result<()> {
+    let path = manifest_path(archive_path);
+    let tmp_path = path.with_extension("json.tmp");
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(json.as_bytes())?;
+    file.sync_data()?;
+    drop(file);
+    fs::rename(&tmp_path, &path)
+}
+
+/// Return the zstd dictionary ID embedded in a trained dictionary.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if the dictionary has no ID (raw content
+/// dictionaries can't be matched to frames on read), or `Unsupported`
+/// without the `zstd` feature.
+#[cfg(feature = "zstd")]
+pub(crate) fn dictionary_id(dict: &[u8]) -> io::Result<u32> {
+    zstd::zstd_safe::get_dict_id_from_dict(dict)
+        .map(|id| id.get())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "archive dictionary has no dictionary ID (use a trained dictionary)",
+            )
+        })
+}
+
+#[cfg(not(feature = "zstd"))]
+pub(crate) fn dictionary_id(_dict: &[u8]) -> io::Result<u32> {
+    Err(crate::codec::zstd_unsupported())
+}
+
+/// Maximum size of a dictionary produced by [`train_dictionary`].
+#[cfg(feature = "zstd")]
+const DICTIONARY_SIZE: usize = 16 * 1024;
+
+/// Train a zstd dictionary from `samples`, for
+/// [`EventLog::train_archive_dictionary`](crate::EventLog::train_archive_dictionary).
+#[cfg(feature = "zstd")]
+pub(crate) fn train_dictionary<S: AsRef<[u8]>>(samples: &[S]) -> io::Result<Vec<u8>> {
+    zstd::dict::from_samples(samples, DICTIONARY_SIZE)
+}
+
+#[cfg(not(feature = "zstd"))]
+pub(crate) fn train_dictionary<S: AsRef<[u8]>>(_samples: &[S]) -> io::Result<Vec<u8>> {
+    Err(crate::codec::zstd_unsupported())
+}
+
+/// Record `dict` in the archive manifest so frames compressed with it can
+/// always be read back. Idempotent.
+///
+/// # Errors
+///
+/// Returns an error if the dictionary has no ID or the manifest cannot be
+/// read or written.
+pub(crate) fn register_dictionary(archive_path: &Path, dict: &[u8]) -> io::Result<()> {
+    let id = dictionary_id(dict)?;
+    let mut manifest = load_manifest(archive_path)?;
+    if manifest.dictionaries.contains_key(&id) {
+        return Ok(());
+    }
+    manifest.dictionaries.insert(id, hex_encode(dict));
+    save_manifest(archive_path, &manifest)
+}
+
+/// Encode data (optionally with a zstd dictionary) with the codec
+/// `archive_path` names and append it as a new frame to the archive file.
 /// Creates the archive file if it doesn't exist.
 ///
+/// A dictionary must already be recorded via [`register_dictionary`], or
+/// the frame will be unreadable.
+///
 /// # Errors
 ///
 /// Returns an error if creating/opening the archive file fails, or if
 /// compression or writing fails.
-pub fn append_compressed_frame(archive_path: &Path, data: &[u8]) -> io::Result<()> {
-    let file = OpenOptions::new()
+#[cfg(feature = "testing")]
+pub(crate) fn append_compressed_frame_with_dictionary(
+    archive_path: &Path,
+    data: &[u8],
+    dict: Option<&[u8]>,
+) -> io::Result<()> {
+    let frame = Codec::from_path(archive_path).encode_frame(data, dict)?;
+    let mut file = fs::OpenOptions::new()
         .create(true)
         .append(true)
         .open(archive_path)?;
-    let mut encoder = zstd::Encoder::new(file, 3)?;
-    encoder.write_all(data)?;
-    let file = encoder.finish()?;
+    file.write_all(&frame)?;
     file.sync_data()?;
     Ok(())
 }
 
-/// Open the archive and return a streaming decompressor that reads through
-/// all concatenated frames as one continuous byte stream.
+/// Open the archive and return a streaming decoder that reads through all
+/// concatenated frames as one continuous byte stream.
 /// Returns `Ok(None)` if the archive doesn't exist.
 ///
+/// The codec is chosen by the file's extension: `archive.jsonl` is read as
+/// plain NDJSON, `.gz` as gzip, `.lz4` as LZ4, anything else as zstd. If the archive manifest records
+/// dictionaries, zstd frames are decoded one at a time, each with the
+/// dictionary its header names.
+///
 /// # Errors
 ///
-/// Returns an error if opening the file or initializing the zstd
-/// decoder fails.
-pub fn open_archive_reader(archive_path: &Path) -> io::Result<Option<Box<dyn BufRead>>> {
+/// Returns an error if opening the file or initializing the decoder fails.
+pub(crate) fn open_archive_reader(archive_path: &Path) -> io::Result<Option<Box<dyn BufRead>>> {
     let file = match File::open(archive_path) {
         Ok(f) => f,
         Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
         Err(e) => return Err(e),
     };
-    let decoder = zstd::Decoder::new(file)?;
-    Ok(Some(Box::new(BufReader::new(decoder))))
+    let codec = Codec::from_path(archive_path);
+    decode_archive(codec, Box::new(file), Some(archive_path)).map(Some)
+}
+
+/// Wrap a raw archive stream in a decoder that reads through all
+/// concatenated frames as one continuous byte stream.
+///
+/// Dictionaries are looked up in the manifest next to `archive_path`; with
+/// no path, frames must not need one.
+///
+/// # Errors
+///
+/// Returns an error if reading the manifest or the stream fails, or if
+/// initializing the decoder fails.
+pub(crate) fn decode_archive(
+    codec: Codec,
+    raw: Box<dyn Read + Send>,
+    archive_path: Option<&Path>,
+) -> io::Result<Box<dyn BufRead>> {
+    codec.decode_stream(raw, load_dictionaries(archive_path)?)
+}
+
+/// Load the decoded dictionaries from the manifest next to `archive_path`.
+/// With no path, there are none.
+pub(crate) fn load_dictionaries(archive_path: Option<&Path>) -> io::Result<BTreeMap<u32, Vec<u8>>> {
+    let Some(archive_path) = archive_path else {
+        return Ok(BTreeMap::new());
+    };
+    load_manifest(archive_path)?
+        .dictionaries
+        .into_iter()
+        .map(|(id, hex)| Ok((id, hex_decode(&hex)?)))
+        .collect()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn hex_decode(hex: &str) -> io::Result<Vec<u8>> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid hex in archive manifest",
+        )
+    };
+    if !hex.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+/// Count the frames in the archive file.
+/// Returns 0 if the archive doesn't exist.
+///
+/// Each rotation appends exactly one zstd frame, so this is also the number
+/// of rotations the archive has absorbed. A plain ([`Codec::None`]) archive
+/// has no frame boundaries and counts as a single frame.
+///
+/// # Errors
+///
+/// Returns an error if reading the archive fails or if its frame
+/// structure is malformed.
+pub(crate) fn frame_count(archive_path: &Path) -> io::Result<u64> {
+    let bytes = read_archive_bytes(archive_path)?;
+    let codec = Codec::from_path(archive_path);
+    Ok(codec.frame_ranges(&bytes)?.len() as u64)
+}
+
+/// One frame of the archive, as reported by [`list_frames`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Byte range of the encoded frame within the archive file.
+    pub compressed: Range<u64>,
+
+    /// Number of complete, non-blank lines (events) in the frame.
+    pub event_count: u64,
+}
+
+/// Where in the history an event was read from, as reported by
+/// [`EventReader::read_full_with_segment`](crate::EventReader::read_full_with_segment).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SegmentId {
+    /// Archive frame `n` (0-based, oldest first), as listed by
+    /// [`list_frames`].
+    Archive(usize),
+
+    /// The active log.
+    Active,
+}
+
+/// List the archive's frames, oldest first, with each one's compressed byte
+/// range and event count. Returns an empty list if the archive doesn't
+/// exist.
+///
+/// Every frame is decompressed to count its events, but only one is held
+/// in memory at a time.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{list_frames, Event, EventLog};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// let mut log = EventLog::open(dir.path())?;
+/// log.append(&Event::new("a", json!({})))?;
+/// log.rotate()?;
+///
+/// let frames = list_frames(log.archive_path())?;
+/// assert_eq!(frames.len(), 1);
+/// assert_eq!(frames[0].event_count, 1);
+/// assert_eq!(frames[0].compressed.start, 0);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if reading the archive fails, if its frame structure
+/// is malformed, or if a frame fails to decompress.
+pub fn list_frames(archive_path: &Path) -> io::Result<Vec<FrameInfo>> {
+    let bytes = read_archive_bytes(archive_path)?;
+    let codec = Codec::from_path(archive_path);
+    frame_infos(codec, &bytes, &load_dictionaries(Some(archive_path))?)
+}
+
+/// Decompress only frame `index` (0-based) of the archive.
+///
+/// # Errors
+///
+/// Returns `NotFound` if the archive has no frame `index`. Returns an error
+/// if reading the archive fails, if its frame structure is malformed, or if
+/// the frame fails to decompress.
+pub fn read_frame(archive_path: &Path, index: usize) -> io::Result<Vec<u8>> {
+    let bytes = read_archive_bytes(archive_path)?;
+    let dictionaries = load_dictionaries(Some(archive_path))?;
+    decode_frame_at(Codec::from_path(archive_path), &bytes, index, &dictionaries)
+}
+
+/// [`list_frames`] over archive bytes already in memory.
+pub(crate) fn frame_infos(
+    codec: Codec,
+    bytes: &[u8],
+    dictionaries: &BTreeMap<u32, Vec<u8>>,
+) -> io::Result<Vec<FrameInfo>> {
+    codec
+        .frame_ranges(bytes)?
+        .into_iter()
+        .map(|range| {
+            let frame = codec.decode_frame(&bytes[range.clone()], dictionaries)?;
+            Ok(FrameInfo {
+                compressed: range.start as u64..range.end as u64,
+                event_count: crate::inspect::count_lines(frame.as_slice())?,
+            })
+        })
+        .collect()
+}
+
+/// [`read_frame`] over archive bytes already in memory.
+pub(crate) fn decode_frame_at(
+    codec: Codec,
+    bytes: &[u8],
+    index: usize,
+    dictionaries: &BTreeMap<u32, Vec<u8>>,
+) -> io::Result<Vec<u8>> {
+    let ranges = codec.frame_ranges(bytes)?;
+    let range = ranges.get(index).cloned().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("archive has {} frames, no frame {index}", ranges.len()),
+        )
+    })?;
+    codec.decode_frame(&bytes[range], dictionaries)
+}
+
+/// How much archived history to keep, set with
+/// [`EventLogBuilder::archive_retention`](crate::EventLogBuilder::archive_retention).
+///
+/// Applied after every rotation: the oldest frames that fall outside the
+/// policy are dropped and the archive is rewritten without them. **Dropped
+/// events are gone for good.** Views that already folded them keep their
+/// state, but a view rebuilt afterwards (a new view, [`View::rebuild`], or
+/// a snapshot that fails its integrity check) only sees what remains.
+/// Frame indices in the rotation history refer to the archive as it was
+/// when each rotation happened.
+///
+/// Retention needs frame boundaries, so it cannot be combined with
+/// [`Codec::None`].
+///
+/// [`View::rebuild`]: crate::View::rebuild
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{list_frames, Event, EventLog, Retention};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// # #[cfg(feature = "zstd")]
+/// # {
+/// let mut log = EventLog::builder(dir.path())
+///     .archive_retention(Retention::MaxFrames(2))
+///     .open()?;
+/// for i in 0..5 {
+///     log.append(&Event::new("tick", json!({"i": i})))?;
+///     log.rotate()?;
+/// }
+/// assert_eq!(list_frames(log.archive_path())?.len(), 2);
+/// assert_eq!(log.read_full()?.count(), 2);
+/// # }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retention {
+    /// Keep only the newest `n` frames, i.e. the last `n` rotations.
+    MaxFrames(u64),
+
+    /// Keep the newest frames whose combined compressed size is at most `n`
+    /// bytes. A single frame larger than `n` is dropped too.
+    MaxBytes(u64),
+
+    /// Drop frames whose newest event's `ts` is older than this. Frames
+    /// whose age cannot be read are kept, along with everything after them.
+    MaxAge(Duration),
+}
+
+/// Byte offset of the oldest frame in `bytes` that `retention` keeps, or
+/// `bytes.len()` if it keeps none. Lines are decrypted with `cipher` if
+/// the log is encrypted.
+pub(crate) fn retained_start(
+    codec: Codec,
+    bytes: &[u8],
+    dictionaries: &BTreeMap<u32, Vec<u8>>,
+    retention: Retention,
+    cipher: Option<&Cipher>,
+) -> io::Result<usize> {
+    let ranges = codec.frame_ranges(bytes)?;
+    let kept = match retention {
+        Retention::MaxFrames(n) => {
+            let n = usize::try_from(n).unwrap_or(usize::MAX);
+            ranges.len() - ranges.len().min(n)
+        }
+        Retention::MaxBytes(n) => {
+            let mut total = 0u64;
+            let newest_kept = ranges.iter().rev().take_while(|range| {
+                total += range.len() as u64;
+                total <= n
+            });
+            ranges.len() - newest_kept.count()
+        }
+        Retention::MaxAge(age) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            let cutoff = now.saturating_sub(age.as_secs());
+            let mut first_kept = ranges.len();
+            for (i, range) in ranges.iter().enumerate() {
+                let frame = codec.decode_frame(&bytes[range.clone()], dictionaries)?;
+                if newest_ts(&frame, cipher).is_none_or(|ts| ts >= cutoff) {
+                    first_kept = i;
+                    break;
+                }
+            }
+            first_kept
+        }
+    };
+    Ok(ranges.get(kept).map_or(bytes.len(), |range| range.start))
+}
+
+/// The `ts` of the last event in a decoded frame, if it can be read.
+fn newest_ts(frame: &[u8], cipher: Option<&Cipher>) -> Option<u64> {
+    #[derive(Deserialize)]
+    struct Ts {
+        ts: u64,
+    }
+    let last = frame
+        .split(|&b| b == b'\n')
+        .rfind(|line| !line.trim_ascii().is_empty())?;
+    let json = crypto::open_line_bytes(cipher, last).ok()?;
+    serde_json::from_slice::<Ts>(&json).ok().map(|t| t.ts)
+}
+
+/// Read the raw archive bytes, treating a missing archive as empty.
+fn read_archive_bytes(archive_path: &Path) -> io::Result<Vec<u8>> {
+    match fs::read(archive_path) {
+        Ok(b) => Ok(b),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
 }
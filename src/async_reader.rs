@@ -0,0 +1,268 @@
+//! Waiting for new events without blocking a thread.
+//!
+//! Runtime-agnostic: the futures here are woken by the same file watcher
+//! that backs [`EventReader::wait_for_events`], so they work under tokio,
+//! async-std, or any other executor without pulling one in. The only
+//! dependency is `futures-core`, for the [`Stream`] trait.
+
+use crate::event::Event;
+use crate::log::EventReader;
+use futures_core::Stream;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::VecDeque;
+use std::future;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+/// An [`EventReader`] whose waits are `async` instead of blocking.
+///
+/// Watches the log directory from construction on, so no append is missed
+/// between reading and waiting. Reading new events is still ordinary file
+/// I/O; only the wait for them yields to the executor.
+///
+/// Requires the `async` feature (or its alias, `tokio`).
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::{AsyncEventReader, Event, EventWriter};
+/// use serde_json::json;
+///
+/// # async fn run(writer: &mut EventWriter) -> std::io::Result<()> {
+/// let reader = AsyncEventReader::new(writer.reader())?;
+/// writer.append(&Event::new("click", json!({})))?;
+///
+/// let events = reader.next_events(0).await?;
+/// assert_eq!(events[0].0.event_type, "click");
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncEventReader {
+    reader: EventReader,
+    signal: Arc<Signal>,
+    _watcher: RecommendedWatcher,
+}
+
+impl std::fmt::Debug for AsyncEventReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncEventReader")
+            .field("reader", &self.reader)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AsyncEventReader {
+    /// Start watching `reader`'s log directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unsupported` if the log's [`Storage`](crate::Storage) has
+    /// no local directory. Returns an error if the file watcher cannot be
+    /// initialized.
+    pub fn new(reader: EventReader) -> io::Result<Self> {
+        let dir = reader.require_local("async reading")?.to_path_buf();
+        let signal = Arc::new(Signal::default());
+
+        let watcher_signal = Arc::clone(&signal);
+        let mut watcher = notify::recommended_watcher(move |res: Result<notify::Event, _>| {
+            if let Ok(event) = res
+                && matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_))
+            {
+                watcher_signal.fire();
+            }
+        })
+        .map_err(io::Error::other)?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(io::Error::other)?;
+
+        Ok(AsyncEventReader {
+            reader,
+            signal,
+            _watcher: watcher,
+        })
+    }
+
+    /// Returns the underlying blocking reader.
+    pub fn reader(&self) -> &EventReader {
+        &self.reader
+    }
+
+    /// Wait until at least one complete event exists after `offset` in the
+    /// active log, then return every complete event after it.
+    ///
+    /// Items are `(event, next_byte_offset, line_hash)` as yielded by
+    /// [`EventReader::read_from`]; a partial trailing line is skipped the
+    /// same way, so pass the last `next_byte_offset` back in to continue.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the log fails or a line cannot be
+    /// parsed.
+    pub async fn next_events(&self, offset: u64) -> io::Result<Vec<(Event, u64, String)>> {
+        future::poll_fn(|cx| self.poll_events(offset, cx)).await
+    }
+
+    /// Read every complete event after `offset`, or register `cx` to be
+    /// woken by the next change if there are none.
+    fn poll_events(
+        &self,
+        offset: u64,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<Vec<(Event, u64, String)>>> {
+        loop {
+            // Note the generation before reading, so an append that lands
+            // after the read still wakes the wait below.
+            let seen = self.signal.generation();
+            let events = match self.reader.read_from(offset) {
+                Ok(iter) => iter.collect::<io::Result<Vec<_>>>(),
+                Err(e) => Err(e),
+            };
+            match events {
+                Ok(events) if events.is_empty() => {}
+                result => return Poll::Ready(result),
+            }
+            if self.signal.wait(seen, cx.waker()) {
+                return Poll::Pending;
+            }
+        }
+    }
+
+    /// Returns a stream of events after `offset`, waiting for new ones as
+    /// they are appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eventfold::{AsyncEventReader, Event, EventWriter};
+    /// use serde_json::json;
+    ///
+    /// # async fn run(writer: &mut EventWriter) -> std::io::Result<()> {
+    /// let reader = AsyncEventReader::new(writer.reader())?;
+    /// let mut stream = reader.events(0);
+    /// writer.append(&Event::new("a", json!({})))?;
+    ///
+    /// let (event, next_offset, _hash) = stream.next().await?;
+    /// assert_eq!(event.event_type, "a");
+    /// assert_eq!(stream.offset(), next_offset);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// `EventStream` is also a [`Stream`], so it works with `StreamExt`
+    /// from `futures` or `tokio-stream`; there `next` yields
+    /// `Option<io::Result<_>>`, and is never `None`.
+    pub fn events(&self, offset: u64) -> EventStream<'_> {
+        EventStream {
+            reader: self,
+            offset,
+            buffered: VecDeque::new(),
+        }
+    }
+}
+
+/// An endless stream of events from an [`AsyncEventReader`], returned by
+/// [`AsyncEventReader::events`].
+///
+/// Implements [`Stream`] with items of `io::Result<(event,
+/// next_byte_offset, line_hash)>`. The stream never ends; after an error
+/// it stays at the same offset, so polling again retries.
+#[derive(Debug)]
+pub struct EventStream<'a> {
+    reader: &'a AsyncEventReader,
+    offset: u64,
+    buffered: VecDeque<(Event, u64, String)>,
+}
+
+impl EventStream<'_> {
+    /// Wait for and return the next event, as
+    /// `(event, next_byte_offset, line_hash)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the log fails or a line cannot be
+    /// parsed. The stream stays at the same offset, so calling `next`
+    /// again retries.
+    pub async fn next(&mut self) -> io::Result<(Event, u64, String)> {
+        future::poll_fn(|cx| self.poll_item(cx)).await
+    }
+
+    /// The next buffered event, reading more first if there are none.
+    fn poll_item(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<(Event, u64, String)>> {
+        if self.buffered.is_empty() {
+            match self.reader.poll_events(self.offset, cx) {
+                Poll::Ready(Ok(events)) => self.buffered = events.into(),
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        let item = self
+            .buffered
+            .pop_front()
+            .expect("poll_events returns at least one event");
+        self.offset = item.1;
+        Poll::Ready(Ok(item))
+    }
+
+    /// Returns the offset after the last event returned by
+    /// [`next`](Self::next).
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl Stream for EventStream<'_> {
+    type Item = io::Result<(Event, u64, String)>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_item(cx).map(Some)
+    }
+}
+
+/// Counts file change notifications and wakes the tasks waiting on them.
+#[derive(Default)]
+struct Signal {
+    state: Mutex<SignalState>,
+}
+
+#[derive(Default)]
+struct SignalState {
+    generation: u64,
+    wakers: Vec<Waker>,
+}
+
+impl Signal {
+    fn lock(&self) -> MutexGuard<'_, SignalState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    /// Register `waker` to be woken by the next change, unless the signal
+    /// has fired since generation `seen`. Returns `false` if it has.
+    fn wait(&self, seen: u64, waker: &Waker) -> bool {
+        let mut state = self.lock();
+        if state.generation != seen {
+            return false;
+        }
+        if !state.wakers.iter().any(|w| w.will_wake(waker)) {
+            state.wakers.push(waker.clone());
+        }
+        true
+    }
+
+    fn fire(&self) {
+        let wakers = {
+            let mut state = self.lock();
+            state.generation += 1;
+            std::mem::take(&mut state.wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Hash chaining: each event records the hash of the line before it.
+//!
+//! With [`EventLogBuilder::hash_chain`](crate::EventLogBuilder::hash_chain)
+//! on, every appended event carries the previous line's
+//! [`line_hash`](crate::line_hash) in its `meta` under the reserved
+//! `"prev"` key (an empty string for the first event of a log):
+//!
+//! ```text
+//! {"type":"a","data":{},"ts":1000,"meta":{"prev":""}}
+//! {"type":"b","data":{},"ts":1000,"meta":{"prev":"9f1c2e0b7a4d3e51"}}
+//! ```
+//!
+//! Since each link is inside the hashed line, editing, removing, or
+//! reordering any event breaks the link of the event after it.
+
+use crate::event::Event;
+use serde_json::{Map, Value};
+use std::io;
+
+/// Reserved `meta` key holding the hash of the previous line.
+pub(crate) const CHAIN_META_KEY: &str = "prev";
+
+/// The first broken link found by
+/// [`EventReader::verify_chain`](crate::EventReader::verify_chain).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "hash chain broken at event {index} (ending at byte {offset}): expected prev {expected:?}, found {}",
+    .found.as_deref().map(|f| format!("{f:?}")).unwrap_or_else(|| "no link".to_string())
+)]
+pub struct ChainBreak {
+    /// 0-based index of the event in the full history, as yielded by
+    /// [`read_full_indexed`](crate::EventReader::read_full_indexed).
+    pub index: u64,
+
+    /// Offset after the event in the full history — the decompressed
+    /// archive followed by the active log, as used by
+    /// [`View::state_at`](crate::View::state_at).
+    pub offset: u64,
+
+    /// Hash of the line before the event.
+    pub expected: String,
+
+    /// The hash the event links to, or `None` if it carries no link.
+    pub found: Option<String>,
+}
+
+/// Return a copy of `event` linked to the line hashed `prev`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if the event's `meta` is set but is not a JSON
+/// object.
+pub(crate) fn link(event: &Event, prev: &str) -> io::Result<Event> {
+    let mut event = event.clone();
+    let mut meta = match event.meta.take() {
+        None => Map::new(),
+        Some(Value::Object(map)) => map,
+        Some(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "hash-chained events must have object (or absent) meta",
+            ));
+        }
+    };
+    meta.insert(CHAIN_META_KEY.to_string(), Value::String(prev.to_string()));
+    event.meta = Some(Value::Object(meta));
+    Ok(event)
+}
+
+/// The hash `event` links to, if it carries a link.
+pub(crate) fn prev(event: &Event) -> Option<&str> {
+    event.meta.as_ref()?.get(CHAIN_META_KEY)?.as_str()
+}
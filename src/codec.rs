@@ -0,0 +1,622 @@
+//! Archive codecs: how rotation encodes the active log into an archive
+//! frame, and how reads decode it again.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read};
+#[cfg(any(feature = "zstd", feature = "gzip", feature = "lz4"))]
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+
+/// How rotation encodes the archive, set with
+/// [`EventLogBuilder::archive_codec`](crate::EventLogBuilder::archive_codec).
+///
+/// The archive's file name reflects its codec, so readers pick the right
+/// decoder from the file alone.
+///
+/// [`Codec::Zstd`] needs the default `zstd` feature; without it zstd
+/// archives can be neither written nor read, and [`Codec::None`] is the
+/// default. Likewise [`Codec::Gzip`] needs the `gzip` feature and
+/// [`Codec::Lz4`] the `lz4` feature.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Codec, Event, EventLog};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// let mut log = EventLog::builder(dir.path())
+///     .archive_codec(Codec::None)
+///     .open()?;
+/// log.append(&Event::new("click", json!({})))?;
+/// log.rotate()?;
+///
+/// // The archive is plain NDJSON, readable with ordinary tools.
+/// let archived = std::fs::read_to_string(dir.path().join("archive.jsonl"))?;
+/// assert!(archived.contains("\"click\""));
+/// assert_eq!(log.read_full()?.count(), 1);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Codec {
+    /// Concatenated zstd frames in `archive.jsonl.zst`, optionally with
+    /// dictionaries. The default with the `zstd` feature.
+    Zstd,
+
+    /// Uncompressed NDJSON in `archive.jsonl`. Rotations append their lines
+    /// unframed, so frame-level reads see the whole archive as one frame,
+    /// and [`Retention`](crate::Retention) is not supported. The default
+    /// without the `zstd` feature.
+    None,
+
+    /// Concatenated gzip members in `archive.jsonl.gz`, one per rotation,
+    /// readable with `zcat`. Dictionaries are ignored.
+    Gzip,
+
+    /// Concatenated LZ4 frames in `archive.jsonl.lz4`, one per rotation.
+    /// Faster than zstd, with larger archives. Dictionaries are ignored.
+    Lz4,
+}
+
+impl Default for Codec {
+    #[cfg(feature = "zstd")]
+    fn default() -> Self {
+        Codec::Zstd
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    fn default() -> Self {
+        Codec::None
+    }
+}
+
+impl Codec {
+    /// Every codec, in the order [`detect`](Self::detect) looks for them.
+    pub(crate) const ALL: [Codec; 4] = [Codec::Zstd, Codec::Gzip, Codec::Lz4, Codec::None];
+
+    /// Name of the archive file this codec writes, inside the log
+    /// directory.
+    pub(crate) fn file_name(self) -> &'static str {
+        match self {
+            Codec::Zstd => "archive.jsonl.zst",
+            Codec::None => "archive.jsonl",
+            Codec::Gzip => "archive.jsonl.gz",
+            Codec::Lz4 => "archive.jsonl.lz4",
+        }
+    }
+
+    /// The codec whose archive file exists in `dir`, if any.
+    pub(crate) fn detect(dir: &Path) -> Option<Codec> {
+        Codec::ALL
+            .into_iter()
+            .find(|codec| dir.join(codec.file_name()).exists())
+    }
+
+    /// The codec an archive at `path` was written with, judged by its
+    /// extension: `.jsonl`, `.gz` and `.lz4` name their codecs, and
+    /// anything else is taken to be zstd.
+    pub(crate) fn from_path(path: &Path) -> Codec {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("jsonl") => Codec::None,
+            Some("gz") => Codec::Gzip,
+            Some("lz4") => Codec::Lz4,
+            _ => Codec::Zstd,
+        }
+    }
+
+    fn implementation(self) -> &'static dyn ArchiveCodec {
+        match self {
+            Codec::Zstd => &ZstdCodec,
+            Codec::None => &PlainCodec,
+            #[cfg(feature = "gzip")]
+            Codec::Gzip => &GzipCodec,
+            #[cfg(not(feature = "gzip"))]
+            Codec::Gzip => &MissingCodec("gzip"),
+            #[cfg(feature = "lz4")]
+            Codec::Lz4 => &Lz4Codec,
+            #[cfg(not(feature = "lz4"))]
+            Codec::Lz4 => &MissingCodec("lz4"),
+        }
+    }
+}
+
+impl ArchiveCodec for Codec {
+    fn is_framed(&self) -> bool {
+        self.implementation().is_framed()
+    }
+
+    fn encode_frame(&self, data: &[u8], dict: Option<&[u8]>) -> io::Result<Vec<u8>> {
+        self.implementation().encode_frame(data, dict)
+    }
+
+    fn encoded_len(&self, data: &mut dyn Read, dict: Option<&[u8]>) -> io::Result<(u64, u64)> {
+        self.implementation().encoded_len(data, dict)
+    }
+
+    fn decode_stream(
+        &self,
+        raw: Box<dyn Read + Send>,
+        dictionaries: BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Box<dyn BufRead>> {
+        self.implementation().decode_stream(raw, dictionaries)
+    }
+
+    fn frame_ranges(&self, bytes: &[u8]) -> io::Result<Vec<Range<usize>>> {
+        self.implementation().frame_ranges(bytes)
+    }
+
+    fn decode_frame(
+        &self,
+        frame: &[u8],
+        dictionaries: &BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Vec<u8>> {
+        self.implementation().decode_frame(frame, dictionaries)
+    }
+}
+
+/// Encoding and decoding of archive frames. `dictionaries` are the zstd
+/// dictionaries recorded in the archive manifest; other codecs ignore them.
+pub(crate) trait ArchiveCodec: Send + Sync {
+    /// Whether [`frame_ranges`](Self::frame_ranges) finds one frame per
+    /// rotation, rather than the whole archive as one.
+    fn is_framed(&self) -> bool;
+
+    /// Encode one rotation's worth of the active log as a frame.
+    fn encode_frame(&self, data: &[u8], dict: Option<&[u8]>) -> io::Result<Vec<u8>>;
+
+    /// Size of the frame [`encode_frame`](Self::encode_frame) would produce
+    /// from `data`, without keeping it, as `(input bytes, frame bytes)`.
+    fn encoded_len(&self, data: &mut dyn Read, dict: Option<&[u8]>) -> io::Result<(u64, u64)>;
+
+    /// Decode a whole raw archive as one continuous byte stream.
+    fn decode_stream(
+        &self,
+        raw: Box<dyn Read + Send>,
+        dictionaries: BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Box<dyn BufRead>>;
+
+    /// Split a raw archive into its frames' byte ranges.
+    fn frame_ranges(&self, bytes: &[u8]) -> io::Result<Vec<Range<usize>>>;
+
+    /// Decode a single frame.
+    fn decode_frame(
+        &self,
+        frame: &[u8],
+        dictionaries: &BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Vec<u8>>;
+}
+
+/// Concatenated zstd frames.
+struct ZstdCodec;
+
+#[cfg(feature = "zstd")]
+impl ArchiveCodec for ZstdCodec {
+    fn is_framed(&self) -> bool {
+        true
+    }
+
+    fn encode_frame(&self, data: &[u8], dict: Option<&[u8]>) -> io::Result<Vec<u8>> {
+        let mut encoder = zstd_encoder(Vec::new(), dict)?;
+        encoder.write_all(data)?;
+        encoder.finish()
+    }
+
+    fn encoded_len(&self, data: &mut dyn Read, dict: Option<&[u8]>) -> io::Result<(u64, u64)> {
+        let mut encoder = zstd_encoder(CountingSink(0), dict)?;
+        let read = io::copy(data, &mut encoder)?;
+        Ok((read, encoder.finish()?.0))
+    }
+
+    fn decode_stream(
+        &self,
+        raw: Box<dyn Read + Send>,
+        dictionaries: BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Box<dyn BufRead>> {
+        if dictionaries.is_empty() {
+            let decoder = zstd::Decoder::new(raw)?;
+            return Ok(Box::new(BufReader::new(decoder)));
+        }
+
+        let mut bytes = Vec::new();
+        BufReader::new(raw).read_to_end(&mut bytes)?;
+        let ranges = self.frame_ranges(&bytes)?.into();
+        Ok(Box::new(BufReader::new(FrameDecoder {
+            codec: &ZstdCodec,
+            bytes,
+            ranges,
+            dictionaries,
+            current: io::Cursor::new(Vec::new()),
+        })))
+    }
+
+    fn frame_ranges(&self, bytes: &[u8]) -> io::Result<Vec<Range<usize>>> {
+        let mut ranges = Vec::new();
+        let mut pos = 0usize;
+        while pos < bytes.len() {
+            let len =
+                zstd::zstd_safe::find_frame_compressed_size(&bytes[pos..]).map_err(|code| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "malformed archive frame at byte {pos}: {}",
+                            zstd::zstd_safe::get_error_name(code)
+                        ),
+                    )
+                })?;
+            ranges.push(pos..pos + len);
+            pos += len;
+        }
+        Ok(ranges)
+    }
+
+    fn decode_frame(
+        &self,
+        frame: &[u8],
+        dictionaries: &BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Vec<u8>> {
+        let Some(id) = zstd::zstd_safe::get_dict_id_from_frame(frame) else {
+            return zstd::decode_all(frame);
+        };
+        let dict = dictionaries.get(&id.get()).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("archive frame needs dictionary {id}, which is not in the manifest"),
+            )
+        })?;
+        let mut decoder = zstd::Decoder::with_dictionary(frame, dict)?;
+        let mut out = Vec::new();
+        decoder.read_to_end(&mut out)?;
+        Ok(out)
+    }
+}
+
+/// Without the `zstd` feature, every zstd operation fails with
+/// `Unsupported`.
+#[cfg(not(feature = "zstd"))]
+impl ArchiveCodec for ZstdCodec {
+    fn is_framed(&self) -> bool {
+        true
+    }
+
+    fn encode_frame(&self, _data: &[u8], _dict: Option<&[u8]>) -> io::Result<Vec<u8>> {
+        Err(zstd_unsupported())
+    }
+
+    fn encoded_len(&self, _data: &mut dyn Read, _dict: Option<&[u8]>) -> io::Result<(u64, u64)> {
+        Err(zstd_unsupported())
+    }
+
+    fn decode_stream(
+        &self,
+        _raw: Box<dyn Read + Send>,
+        _dictionaries: BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Box<dyn BufRead>> {
+        Err(zstd_unsupported())
+    }
+
+    fn frame_ranges(&self, _bytes: &[u8]) -> io::Result<Vec<Range<usize>>> {
+        Err(zstd_unsupported())
+    }
+
+    fn decode_frame(
+        &self,
+        _frame: &[u8],
+        _dictionaries: &BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Vec<u8>> {
+        Err(zstd_unsupported())
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+pub(crate) fn zstd_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "zstd archives require the `zstd` feature",
+    )
+}
+
+/// A zstd encoder at the archive's compression level, writing to `sink`.
+#[cfg(feature = "zstd")]
+fn zstd_encoder<'a, W: Write>(sink: W, dict: Option<&[u8]>) -> io::Result<zstd::Encoder<'a, W>> {
+    match dict {
+        Some(dict) => zstd::Encoder::with_dictionary(sink, 3, dict),
+        None => zstd::Encoder::new(sink, 3),
+    }
+}
+
+/// Writer that discards its input and counts the bytes.
+#[cfg(any(feature = "zstd", feature = "gzip", feature = "lz4"))]
+struct CountingSink(u64);
+
+#[cfg(any(feature = "zstd", feature = "gzip", feature = "lz4"))]
+impl Write for CountingSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reader that decodes frames one at a time, zstd ones each with its own
+/// dictionary.
+#[cfg(any(feature = "zstd", feature = "lz4"))]
+struct FrameDecoder {
+    codec: &'static dyn ArchiveCodec,
+    bytes: Vec<u8>,
+    ranges: std::collections::VecDeque<Range<usize>>,
+    dictionaries: BTreeMap<u32, Vec<u8>>,
+    current: io::Cursor<Vec<u8>>,
+}
+
+#[cfg(any(feature = "zstd", feature = "lz4"))]
+impl Read for FrameDecoder {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let n = self.current.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                return Ok(n);
+            }
+            let Some(range) = self.ranges.pop_front() else {
+                return Ok(0);
+            };
+            let frame = self.codec.decode_frame(&self.bytes[range], &self.dictionaries)?;
+            self.current = io::Cursor::new(frame);
+        }
+    }
+}
+
+/// Plain NDJSON: frames are the bytes themselves, with no boundaries.
+struct PlainCodec;
+
+impl ArchiveCodec for PlainCodec {
+    fn is_framed(&self) -> bool {
+        false
+    }
+
+    fn encode_frame(&self, data: &[u8], _dict: Option<&[u8]>) -> io::Result<Vec<u8>> {
+        Ok(data.to_vec())
+    }
+
+    fn encoded_len(&self, data: &mut dyn Read, _dict: Option<&[u8]>) -> io::Result<(u64, u64)> {
+        let read = io::copy(data, &mut io::sink())?;
+        Ok((read, read))
+    }
+
+    fn decode_stream(
+        &self,
+        raw: Box<dyn Read + Send>,
+        _dictionaries: BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Box<dyn BufRead>> {
+        Ok(Box::new(BufReader::new(raw)))
+    }
+
+    fn frame_ranges(&self, bytes: &[u8]) -> io::Result<Vec<Range<usize>>> {
+        let whole = 0..bytes.len();
+        Ok(Some(whole).filter(|r| !r.is_empty()).into_iter().collect())
+    }
+
+    fn decode_frame(
+        &self,
+        frame: &[u8],
+        _dictionaries: &BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Vec<u8>> {
+        Ok(frame.to_vec())
+    }
+}
+
+/// Concatenated gzip members.
+#[cfg(feature = "gzip")]
+struct GzipCodec;
+
+#[cfg(feature = "gzip")]
+impl ArchiveCodec for GzipCodec {
+    fn is_framed(&self) -> bool {
+        true
+    }
+
+    fn encode_frame(&self, data: &[u8], _dict: Option<&[u8]>) -> io::Result<Vec<u8>> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()
+    }
+
+    fn encoded_len(&self, data: &mut dyn Read, _dict: Option<&[u8]>) -> io::Result<(u64, u64)> {
+        let mut encoder =
+            flate2::write::GzEncoder::new(CountingSink(0), flate2::Compression::default());
+        let read = io::copy(data, &mut encoder)?;
+        Ok((read, encoder.finish()?.0))
+    }
+
+    fn decode_stream(
+        &self,
+        raw: Box<dyn Read + Send>,
+        _dictionaries: BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Box<dyn BufRead>> {
+        let decoder = flate2::read::MultiGzDecoder::new(raw);
+        Ok(Box::new(BufReader::new(decoder)))
+    }
+
+    fn frame_ranges(&self, bytes: &[u8]) -> io::Result<Vec<Range<usize>>> {
+        // A member records no compressed length; decoding it is the only
+        // way to find where it ends.
+        let mut ranges = Vec::new();
+        let mut pos = 0usize;
+        while pos < bytes.len() {
+            let mut decoder = flate2::bufread::GzDecoder::new(&bytes[pos..]);
+            io::copy(&mut decoder, &mut io::sink()).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("malformed archive frame at byte {pos}: {e}"),
+                )
+            })?;
+            let len = bytes.len() - pos - decoder.into_inner().len();
+            ranges.push(pos..pos + len);
+            pos += len;
+        }
+        Ok(ranges)
+    }
+
+    fn decode_frame(
+        &self,
+        frame: &[u8],
+        _dictionaries: &BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        flate2::read::GzDecoder::new(frame).read_to_end(&mut out)?;
+        Ok(out)
+    }
+}
+
+/// Concatenated LZ4 frames.
+#[cfg(feature = "lz4")]
+struct Lz4Codec;
+
+#[cfg(feature = "lz4")]
+impl ArchiveCodec for Lz4Codec {
+    fn is_framed(&self) -> bool {
+        true
+    }
+
+    fn encode_frame(&self, data: &[u8], _dict: Option<&[u8]>) -> io::Result<Vec<u8>> {
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+        encoder.write_all(data)?;
+        encoder.finish().map_err(io::Error::from)
+    }
+
+    fn encoded_len(&self, data: &mut dyn Read, _dict: Option<&[u8]>) -> io::Result<(u64, u64)> {
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(CountingSink(0));
+        let read = io::copy(data, &mut encoder)?;
+        Ok((read, encoder.finish().map_err(io::Error::from)?.0))
+    }
+
+    fn decode_stream(
+        &self,
+        raw: Box<dyn Read + Send>,
+        _dictionaries: BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Box<dyn BufRead>> {
+        // The LZ4 decoder stops at the end of the first frame, so decode
+        // them one at a time.
+        let mut bytes = Vec::new();
+        BufReader::new(raw).read_to_end(&mut bytes)?;
+        let ranges = self.frame_ranges(&bytes)?.into();
+        Ok(Box::new(BufReader::new(FrameDecoder {
+            codec: &Lz4Codec,
+            bytes,
+            ranges,
+            dictionaries: BTreeMap::new(),
+            current: io::Cursor::new(Vec::new()),
+        })))
+    }
+
+    fn frame_ranges(&self, bytes: &[u8]) -> io::Result<Vec<Range<usize>>> {
+        let mut ranges = Vec::new();
+        let mut pos = 0usize;
+        while pos < bytes.len() {
+            let len = lz4_frame_len(&bytes[pos..]).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("malformed archive frame at byte {pos}"),
+                )
+            })?;
+            ranges.push(pos..pos + len);
+            pos += len;
+        }
+        Ok(ranges)
+    }
+
+    fn decode_frame(
+        &self,
+        frame: &[u8],
+        _dictionaries: &BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        lz4_flex::frame::FrameDecoder::new(frame).read_to_end(&mut out)?;
+        Ok(out)
+    }
+}
+
+/// Length of the LZ4 frame at the start of `bytes`, found by walking its
+/// header and block sizes (see the LZ4 frame format), or `None` if it is
+/// not a complete frame.
+#[cfg(feature = "lz4")]
+fn lz4_frame_len(bytes: &[u8]) -> Option<usize> {
+    const MAGIC: [u8; 4] = 0x184D_2204u32.to_le_bytes();
+    if bytes.get(..4)? != MAGIC {
+        return None;
+    }
+    let flags = *bytes.get(4)?;
+    let block_checksums = flags & 0x10 != 0;
+    let content_size = flags & 0x08 != 0;
+    let content_checksum = flags & 0x04 != 0;
+    let dict_id = flags & 0x01 != 0;
+
+    // Magic, flags, block descriptor, optional fields, header checksum.
+    let mut pos = 4 + 2 + 8 * usize::from(content_size) + 4 * usize::from(dict_id) + 1;
+    loop {
+        let size = u32::from_le_bytes(bytes.get(pos..pos + 4)?.try_into().ok()?);
+        pos += 4;
+        if size == 0 {
+            break;
+        }
+        pos += (size & 0x7FFF_FFFF) as usize + 4 * usize::from(block_checksums);
+    }
+    pos += 4 * usize::from(content_checksum);
+    (pos <= bytes.len()).then_some(pos)
+}
+
+/// A codec whose feature is not enabled: every operation fails with
+/// `Unsupported`, naming the feature.
+#[cfg(not(all(feature = "gzip", feature = "lz4")))]
+struct MissingCodec(&'static str);
+
+#[cfg(not(all(feature = "gzip", feature = "lz4")))]
+impl MissingCodec {
+    fn error(&self) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("{0} archives require the `{0}` feature", self.0),
+        )
+    }
+}
+
+#[cfg(not(all(feature = "gzip", feature = "lz4")))]
+impl ArchiveCodec for MissingCodec {
+    fn is_framed(&self) -> bool {
+        true
+    }
+
+    fn encode_frame(&self, _data: &[u8], _dict: Option<&[u8]>) -> io::Result<Vec<u8>> {
+        Err(self.error())
+    }
+
+    fn encoded_len(&self, _data: &mut dyn Read, _dict: Option<&[u8]>) -> io::Result<(u64, u64)> {
+        Err(self.error())
+    }
+
+    fn decode_stream(
+        &self,
+        _raw: Box<dyn Read + Send>,
+        _dictionaries: BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Box<dyn BufRead>> {
+        Err(self.error())
+    }
+
+    fn frame_ranges(&self, _bytes: &[u8]) -> io::Result<Vec<Range<usize>>> {
+        Err(self.error())
+    }
+
+    fn decode_frame(
+        &self,
+        _frame: &[u8],
+        _dictionaries: &BTreeMap<u32, Vec<u8>>,
+    ) -> io::Result<Vec<u8>> {
+        Err(self.error())
+    }
+}
@@ -0,0 +1,95 @@
+//! Compacting the active log by dropping events that are no longer needed.
+
+use crate::chain;
+use crate::crypto::{self, Cipher};
+use crate::event::Event;
+use crate::hash::HashAlgo;
+use crate::log::BOM;
+use crate::redact::ChainState;
+use std::io;
+
+/// Result of compacting the active log's bytes.
+pub(crate) struct Compacted {
+    /// The kept lines, each with its newline. Lines are copied
+    /// byte-for-byte unless their hash-chain link had to be repaired.
+    pub(crate) bytes: Vec<u8>,
+    /// Number of events kept.
+    pub(crate) kept: u64,
+    /// Number of events dropped.
+    pub(crate) dropped: u64,
+}
+
+/// Keep the complete event lines of `contents` for which `keep` returns
+/// `true`, in order. Blank lines, a leading byte-order mark, and a partial
+/// trailing line are dropped too.
+///
+/// A kept event whose hash-chain link pointed at a dropped line is
+/// re-linked, with lines hashed by `algo`, to the last line kept before
+/// it, or to the line before the active log. Lines are decrypted with
+/// `cipher` if set, and re-linked lines sealed again.
+pub(crate) fn compact(
+    contents: &[u8],
+    algo: HashAlgo,
+    cipher: Option<&Cipher>,
+    keep: impl Fn(&Event) -> bool,
+) -> io::Result<Compacted> {
+    let mut compacted = Compacted {
+        bytes: Vec::with_capacity(contents.len()),
+        kept: 0,
+        dropped: 0,
+    };
+    let mut links: Option<ChainState> = None;
+
+    let mut pos = 0;
+    for line in contents.split_inclusive(|&b| b == b'\n') {
+        let start = pos;
+        pos += line.len();
+        // A line without its newline is a partial write, not an event.
+        let Some(line) = line.strip_suffix(b"\n") else {
+            break;
+        };
+        let text = std::str::from_utf8(line).map_err(|e| invalid_line(start, e))?;
+        let text = if start == 0 {
+            text.strip_prefix(BOM).unwrap_or(text)
+        } else {
+            text
+        };
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        let json = crypto::open_line(cipher, text).map_err(|e| invalid_line(start, e))?;
+        let event: Event = serde_json::from_str(&json).map_err(|e| invalid_line(start, e))?;
+        // The first event's link names the line before the active log.
+        let links = links
+            .get_or_insert_with(|| ChainState::after(algo, chain::prev(&event).unwrap_or_default()));
+        if !keep(&event) {
+            links.skip(text);
+            compacted.dropped += 1;
+            continue;
+        }
+        match links.relink(&event)? {
+            Some(relinked) => {
+                let json = serde_json::to_string(&relinked)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                let line = crypto::seal_line(cipher, json)?;
+                links.advance(text, Some(&line));
+                compacted.bytes.extend_from_slice(line.as_bytes());
+            }
+            None => {
+                links.advance(text, None);
+                compacted.bytes.extend_from_slice(text.as_bytes());
+            }
+        }
+        compacted.bytes.push(b'\n');
+        compacted.kept += 1;
+    }
+    Ok(compacted)
+}
+
+fn invalid_line(offset: usize, e: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("cannot compact the line at byte {offset}: {e}"),
+    )
+}
@@ -0,0 +1,168 @@
+//! Encryption at rest: event lines, view snapshots and dead letters sealed
+//! with AES-256-GCM under the key set with
+//! [`EventLogBuilder::encryption`](crate::EventLogBuilder::encryption).
+//!
+//! It needs the `crypto` feature. Without it [`Cipher`] has no values, so
+//! every log is plaintext and an encrypted one cannot be opened.
+//!
+//! The sidecar files — manifests, `seq`, `app.index`, cursors, the readers
+//! lock and `rotations.log` — stay plaintext; the builder method lists what
+//! each holds. Only `rotations.log` can hold caller text: its notes.
+//!
+//! A sealed value is the base64 of a fresh random 96-bit nonce followed
+//! by the ciphertext and its tag. Every line carries its own nonce, so it
+//! decrypts from its own bytes: offset-based reads and rotation work on
+//! stored lines unchanged. Random nonces limit a key to about 2^32 sealed
+//! values before a repeat becomes a real risk.
+
+use std::borrow::Cow;
+use std::io;
+
+/// What a sealed value holds. Its label is authenticated with it, so a
+/// snapshot cannot be passed off as an event line or the other way round.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Kind {
+    Line,
+    Snapshot,
+    DeadLetter,
+}
+
+#[cfg_attr(not(feature = "crypto"), allow(dead_code))]
+impl Kind {
+    fn label(self) -> &'static [u8] {
+        match self {
+            Kind::Line => b"eventfold line",
+            Kind::Snapshot => b"eventfold snapshot",
+            Kind::DeadLetter => b"eventfold dead letter",
+        }
+    }
+}
+
+/// The cipher of an encrypted log.
+#[cfg(feature = "crypto")]
+#[derive(Clone)]
+pub(crate) struct Cipher(aes_gcm::Aes256Gcm);
+
+/// The cipher of an encrypted log, of which there are none without the
+/// `crypto` feature.
+#[cfg(not(feature = "crypto"))]
+#[derive(Debug, Clone)]
+pub(crate) enum Cipher {}
+
+#[cfg(feature = "crypto")]
+impl Cipher {
+    /// Length of the nonce in front of every sealed value.
+    const NONCE_LEN: usize = 12;
+
+    pub(crate) fn new(key: &[u8; 32]) -> Self {
+        use aes_gcm::KeyInit;
+        Cipher(aes_gcm::Aes256Gcm::new(key.into()))
+    }
+
+    /// Encrypt `plaintext` under a fresh nonce, as base64.
+    pub(crate) fn seal(&self, kind: Kind, plaintext: &[u8]) -> io::Result<String> {
+        use aes_gcm::aead::{Aead, Payload};
+        use base64::Engine;
+
+        let mut nonce = [0u8; Self::NONCE_LEN];
+        getrandom::fill(&mut nonce).map_err(|e| io::Error::other(e.to_string()))?;
+        let payload = Payload {
+            msg: plaintext,
+            aad: kind.label(),
+        };
+        let ciphertext = self
+            .0
+            .encrypt(&nonce.into(), payload)
+            .map_err(|_| io::Error::other("encryption failed"))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        Ok(base64::engine::general_purpose::STANDARD.encode(sealed))
+    }
+
+    /// Decrypt a value [`seal`](Self::seal) produced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if `sealed` is not base64, is too short, or
+    /// fails authentication: it was sealed under another key or as another
+    /// [`Kind`], was altered, or was never encrypted.
+    pub(crate) fn open(&self, kind: Kind, sealed: &str) -> io::Result<Vec<u8>> {
+        use aes_gcm::aead::{Aead, Payload};
+        use base64::Engine;
+
+        let undecryptable = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "cannot decrypt: not encrypted with this log's key, or corrupt",
+            )
+        };
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(sealed)
+            .map_err(|_| undecryptable())?;
+        if bytes.len() < Self::NONCE_LEN {
+            return Err(undecryptable());
+        }
+        let (nonce, ciphertext) = bytes.split_at(Self::NONCE_LEN);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: kind.label(),
+        };
+        self.0
+            .decrypt(nonce.into(), payload)
+            .map_err(|_| undecryptable())
+    }
+}
+
+#[cfg(not(feature = "crypto"))]
+impl Cipher {
+    pub(crate) fn seal(&self, _kind: Kind, _plaintext: &[u8]) -> io::Result<String> {
+        match *self {}
+    }
+
+    pub(crate) fn open(&self, _kind: Kind, _sealed: &str) -> io::Result<Vec<u8>> {
+        match *self {}
+    }
+}
+
+#[cfg(feature = "crypto")]
+impl std::fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Cipher(..)")
+    }
+}
+
+/// The serialized event `json` as it is stored: sealed if the log is
+/// encrypted.
+pub(crate) fn seal_line(cipher: Option<&Cipher>, json: String) -> io::Result<String> {
+    match cipher {
+        Some(cipher) => cipher.seal(Kind::Line, json.as_bytes()),
+        None => Ok(json),
+    }
+}
+
+/// The JSON of the stored line `line`: decrypted if the log is encrypted.
+///
+/// # Errors
+///
+/// Returns `InvalidData` if the line cannot be decrypted.
+pub(crate) fn open_line<'a>(cipher: Option<&Cipher>, line: &'a str) -> io::Result<Cow<'a, str>> {
+    match cipher {
+        Some(cipher) => String::from_utf8(cipher.open(Kind::Line, line)?)
+            .map(Cow::Owned)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        None => Ok(Cow::Borrowed(line)),
+    }
+}
+
+/// Like [`open_line`], for a line read as bytes, possibly still ending in
+/// its newline.
+pub(crate) fn open_line_bytes<'a>(cipher: Option<&Cipher>, line: &'a [u8]) -> io::Result<Cow<'a, [u8]>> {
+    match cipher {
+        Some(cipher) => {
+            let line = std::str::from_utf8(line.trim_ascii_end())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            cipher.open(Kind::Line, line).map(Cow::Owned)
+        }
+        None => Ok(Cow::Borrowed(line)),
+    }
+}
@@ -0,0 +1,120 @@
+//! Consumer cursors: durable "handled up to here" markers kept next to the
+//! log, under `cursors/`.
+
+use crate::snapshot;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Name of the directory holding cursor files, inside the log directory.
+const CURSORS_DIR: &str = "cursors";
+
+/// A named, durable position of an external consumer in the log, returned
+/// by [`EventLog::cursor`](crate::EventLog::cursor).
+///
+/// The position is a log-wide sequence number — the next event the
+/// consumer has yet to handle — so it stays valid across rotations, unlike
+/// a byte offset. It is stored as `cursors/{name}.cursor` in the log
+/// directory and replaced atomically on every [`save`](Self::save).
+///
+/// Saving after handling each event gives at-least-once processing: after
+/// a crash, [`EventReader::read_from_sequence`](crate::EventReader::read_from_sequence)
+/// with the loaded position redelivers at most the events handled since
+/// the last save.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventLog};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// let mut log = EventLog::open(dir.path())?;
+/// log.append(&Event::new("a", json!({})))?;
+/// log.append(&Event::new("b", json!({})))?;
+///
+/// let cursor = log.cursor("mailer")?;
+/// for result in log.reader().read_from_sequence(cursor.load()?)? {
+///     let (event, sequence, _) = result?;
+///     // ... handle `event` ...
+///     cursor.save(sequence + 1)?;
+/// }
+///
+/// log.rotate()?;
+/// log.append(&Event::new("c", json!({})))?;
+/// let pending: Vec<_> = log.reader().read_from_sequence(cursor.load()?)?.collect();
+/// assert_eq!(pending.len(), 1);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    name: String,
+    path: PathBuf,
+}
+
+impl Cursor {
+    /// The cursor named `name` in the log directory `dir`. Nothing is
+    /// created until the first save.
+    pub(crate) fn open(dir: &Path, name: &str) -> io::Result<Cursor> {
+        let mut components = Path::new(name).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(c)), None) if c == name => {}
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("cursor name {name:?} is not a single file name"),
+                ));
+            }
+        }
+        Ok(Cursor {
+            name: name.to_string(),
+            path: dir.join(CURSORS_DIR).join(format!("{name}.cursor")),
+        })
+    }
+
+    /// The cursor's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Path of the cursor file, which may not exist yet.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The saved position: the sequence number of the next event to
+    /// handle, or 0 if the cursor has never been saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if the cursor file is malformed, or an error
+    /// if it cannot be read.
+    pub fn load(&self) -> io::Result<u64> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+        text.trim().parse().map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("malformed cursor file {}: {e}", self.path.display()),
+            )
+        })
+    }
+
+    /// Durably replace the saved position with `sequence`, the sequence
+    /// number of the next event to handle — one past the last event
+    /// handled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `cursors/` directory or the cursor file
+    /// cannot be written.
+    pub fn save(&self, sequence: u64) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        snapshot::write_atomic(&self.path, format!("{sequence}\n").as_bytes())
+    }
+}
@@ -0,0 +1,67 @@
+//! Dead-letter log — events a view rejected, kept with the reason.
+
+use crate::crypto::{Cipher, Kind};
+use crate::event::Event;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// One line of `dead-letter.jsonl`.
+#[derive(Serialize, Deserialize)]
+struct DeadLetter {
+    reason: String,
+    event: Event,
+}
+
+/// Append a rejected event to the dead-letter file, creating it if needed.
+/// In an encrypted log, the record is sealed with `cipher`.
+pub(crate) fn append_record(
+    path: &Path,
+    event: &Event,
+    reason: &str,
+    cipher: Option<&Cipher>,
+) -> io::Result<()> {
+    let record = DeadLetter {
+        reason: reason.to_string(),
+        event: event.clone(),
+    };
+    let mut json = serde_json::to_string(&record)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    if let Some(cipher) = cipher {
+        json = cipher.seal(Kind::DeadLetter, json.as_bytes())?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{json}")?;
+    file.sync_data()
+}
+
+/// Read all dead letters as `(event, reason)`, oldest first. Returns an
+/// empty list if the file doesn't exist. A trailing partial line (crash
+/// mid-write) is skipped, matching the event log's semantics. Records are
+/// decrypted with `cipher` if the log is encrypted.
+pub(crate) fn read_records(
+    path: &Path,
+    cipher: Option<&Cipher>,
+) -> io::Result<Vec<(Event, String)>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    contents
+        .split_inclusive('\n')
+        .filter(|line| line.ends_with('\n') && !line.trim().is_empty())
+        .map(|line| {
+            let json = match cipher {
+                Some(cipher) => Cow::Owned(cipher.open(Kind::DeadLetter, line.trim_end())?),
+                None => Cow::Borrowed(line.as_bytes()),
+            };
+            serde_json::from_slice::<DeadLetter>(&json)
+                .map(|record| (record.event, record.reason))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
@@ -0,0 +1,49 @@
+//! The recent-key window behind
+//! [`EventWriter::append_idempotent`](crate::EventWriter::append_idempotent).
+
+use crate::log::AppendResult;
+use std::collections::{HashMap, VecDeque};
+
+/// The default number of recent event IDs remembered for deduplication.
+pub(crate) const DEFAULT_DEDUP_WINDOW: usize = 1024;
+
+/// The IDs of the most recently written events, with the result of writing
+/// each, oldest first. When an ID repeats inside the window, the earliest
+/// event keeps it.
+#[derive(Debug)]
+pub(crate) struct RecentKeys {
+    capacity: usize,
+    order: VecDeque<String>,
+    results: HashMap<String, AppendResult>,
+}
+
+impl RecentKeys {
+    /// An empty window holding at most `capacity` IDs.
+    pub(crate) fn new(capacity: usize) -> Self {
+        RecentKeys {
+            capacity,
+            order: VecDeque::new(),
+            results: HashMap::new(),
+        }
+    }
+
+    /// The result of writing the event with `key`, if it is in the window.
+    pub(crate) fn get(&self, key: &str) -> Option<&AppendResult> {
+        self.results.get(key)
+    }
+
+    /// Record that the event with `key` was written as `result`, forgetting
+    /// the oldest ID if the window is full.
+    pub(crate) fn insert(&mut self, key: &str, result: &AppendResult) {
+        if self.capacity == 0 || self.results.contains_key(key) {
+            return;
+        }
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.results.remove(&oldest);
+        }
+        self.order.push_back(key.to_string());
+        self.results.insert(key.to_string(), result.clone());
+    }
+}
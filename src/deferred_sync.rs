@@ -0,0 +1,167 @@
+//! Background `fsync` for appends that are visible immediately but made
+//! durable later.
+
+use crate::storage::Storage;
+use std::io;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+#[derive(Debug, Default)]
+struct SyncState {
+    /// Sequence number of the latest deferred append written to the file.
+    written: u64,
+    /// Every deferred append up to this sequence number is durable.
+    synced: u64,
+    /// Set if a background `fsync` failed; no later sync is attempted.
+    error: Option<String>,
+    /// Set when the writer is dropped; the syncer drains and exits.
+    closed: bool,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<SyncState>,
+    cond: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, SyncState> {
+        // The lock is never held across code that can panic.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Completion handle for an append made with
+/// [`EventWriter::append_deferred_sync`](crate::EventWriter::append_deferred_sync).
+///
+/// The event is already visible to readers; the handle reports when it is
+/// also durable on stable storage.
+#[derive(Debug, Clone)]
+pub struct SyncHandle {
+    shared: Arc<Shared>,
+    seq: u64,
+}
+
+impl SyncHandle {
+    /// Block until the event has been flushed to stable storage.
+    ///
+    /// Returns immediately if it already has.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the background `fsync` failed before covering
+    /// this event.
+    pub fn wait(&self) -> io::Result<()> {
+        let mut state = self.shared.lock();
+        loop {
+            if state.synced >= self.seq {
+                return Ok(());
+            }
+            if let Some(e) = &state.error {
+                return Err(io::Error::other(format!("deferred sync failed: {e}")));
+            }
+            state = self
+                .shared
+                .cond
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Returns `true` if the event has been flushed to stable storage.
+    ///
+    /// Never blocks, so async callers can poll it.
+    pub fn is_synced(&self) -> bool {
+        self.shared.lock().synced >= self.seq
+    }
+}
+
+/// Writer-side state for deferred appends: the shared sequence numbers and
+/// the background thread that syncs them.
+#[derive(Debug)]
+pub(crate) struct DeferredSync {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DeferredSync {
+    /// Start a syncer thread that flushes `storage` whenever deferred
+    /// appends are pending.
+    pub(crate) fn start(storage: Arc<dyn Storage>) -> io::Result<Self> {
+        let shared = Arc::new(Shared::default());
+        let thread = thread::Builder::new()
+            .name("eventfold-sync".to_string())
+            .spawn({
+                let shared = Arc::clone(&shared);
+                move || run_syncer(storage.as_ref(), &shared)
+            })?;
+        Ok(DeferredSync {
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    /// Fail if a previous background sync failed.
+    pub(crate) fn check(&self) -> io::Result<()> {
+        match &self.shared.lock().error {
+            Some(e) => Err(io::Error::other(format!("deferred sync failed: {e}"))),
+            None => Ok(()),
+        }
+    }
+
+    /// Record a deferred append that has just been written, wake the
+    /// syncer, and return its handle.
+    pub(crate) fn push(&self) -> SyncHandle {
+        let mut state = self.shared.lock();
+        state.written += 1;
+        let seq = state.written;
+        self.shared.cond.notify_all();
+        SyncHandle {
+            shared: Arc::clone(&self.shared),
+            seq,
+        }
+    }
+
+    /// Mark every deferred append written so far as durable. Called after
+    /// the writer itself has synced the file.
+    pub(crate) fn mark_synced(&self) {
+        let mut state = self.shared.lock();
+        state.synced = state.written;
+        self.shared.cond.notify_all();
+    }
+}
+
+impl Drop for DeferredSync {
+    fn drop(&mut self) {
+        self.shared.lock().closed = true;
+        self.shared.cond.notify_all();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn run_syncer(storage: &dyn Storage, shared: &Shared) {
+    let mut state = shared.lock();
+    loop {
+        if state.synced < state.written {
+            // Everything written so far is covered by one fsync.
+            let target = state.written;
+            drop(state);
+            let result = storage.sync();
+            state = shared.lock();
+            match result {
+                Ok(()) => state.synced = state.synced.max(target),
+                Err(e) => state.error = Some(e.to_string()),
+            }
+            shared.cond.notify_all();
+            if state.error.is_some() {
+                return;
+            }
+        } else if state.closed {
+            return;
+        } else {
+            state = shared.cond.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
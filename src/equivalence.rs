@@ -0,0 +1,228 @@
+//! Event-by-event comparison of two logs, for migration validation and
+//! checking replicas.
+
+use crate::event::Event;
+use crate::log::FullEventIter;
+use std::io;
+
+/// Selects which [`Event`] fields [`EventLog::equivalent_to`](crate::EventLog::equivalent_to)
+/// compares.
+///
+/// Start from [`EventCmp::ALL`] or [`EventCmp::CORE`] and switch individual
+/// fields off with struct update syntax.
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::EventCmp;
+///
+/// // Everything except `meta`.
+/// let cmp = EventCmp { meta: false, ..EventCmp::ALL };
+/// assert!(cmp.event_type && cmp.actor);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventCmp {
+    /// Compare `event_type`.
+    pub event_type: bool,
+    /// Compare `data`.
+    pub data: bool,
+    /// Compare `ts`.
+    pub ts: bool,
+    /// Compare `id`.
+    pub id: bool,
+    /// Compare `actor`.
+    pub actor: bool,
+    /// Compare `meta`.
+    pub meta: bool,
+}
+
+impl EventCmp {
+    /// Compare every field.
+    pub const ALL: EventCmp = EventCmp {
+        event_type: true,
+        data: true,
+        ts: true,
+        id: true,
+        actor: true,
+        meta: true,
+    };
+
+    /// Compare only `event_type`, `data`, and `ts`.
+    pub const CORE: EventCmp = EventCmp {
+        event_type: true,
+        data: true,
+        ts: true,
+        id: false,
+        actor: false,
+        meta: false,
+    };
+
+    /// Returns `true` if `a` and `b` agree on every selected field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eventfold::{Event, EventCmp};
+    /// use serde_json::json;
+    ///
+    /// let a = Event::new("click", json!({})).with_actor("alice");
+    /// let b = Event { actor: None, ..a.clone() };
+    /// assert!(EventCmp::CORE.matches(&a, &b));
+    /// assert!(!EventCmp::ALL.matches(&a, &b));
+    /// ```
+    pub fn matches(&self, a: &Event, b: &Event) -> bool {
+        (!self.event_type || a.event_type == b.event_type)
+            && (!self.data || a.data == b.data)
+            && (!self.ts || a.ts == b.ts)
+            && (!self.id || a.id == b.id)
+            && (!self.actor || a.actor == b.actor)
+            && (!self.meta || a.meta == b.meta)
+    }
+}
+
+/// The first point at which two logs differ.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// 0-based position of the differing event in the full history.
+    pub index: u64,
+
+    /// The event in this log, or `None` if this log ended first.
+    pub left: Option<Event>,
+
+    /// The event in the other log, or `None` if the other log ended first.
+    pub right: Option<Event>,
+}
+
+/// Result of [`EventLog::equivalent_to`](crate::EventLog::equivalent_to).
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquivalenceReport {
+    /// Number of event pairs that matched before the first divergence
+    /// (or in total, if the logs are equivalent).
+    pub matched: u64,
+
+    /// The first divergence, or `None` if the logs are equivalent.
+    pub divergence: Option<Divergence>,
+}
+
+impl EquivalenceReport {
+    /// Returns `true` if no divergence was found.
+    pub fn is_equivalent(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+/// Walk both histories in lockstep and stop at the first divergence.
+pub(crate) fn compare(
+    left: FullEventIter,
+    right: FullEventIter,
+    cmp: EventCmp,
+) -> io::Result<EquivalenceReport> {
+    let mut left = left.map(|r| r.map(|(event, _)| event));
+    let mut right = right.map(|r| r.map(|(event, _)| event));
+    let mut matched = 0;
+
+    loop {
+        match (left.next().transpose()?, right.next().transpose()?) {
+            (Some(a), Some(b)) if cmp.matches(&a, &b) => matched += 1,
+            (None, None) => {
+                return Ok(EquivalenceReport {
+                    matched,
+                    divergence: None,
+                });
+            }
+            (left, right) => {
+                return Ok(EquivalenceReport {
+                    matched,
+                    divergence: Some(Divergence {
+                        index: matched,
+                        left,
+                        right,
+                    }),
+                });
+            }
+        }
+    }
+}
+
+/// Result of [`EventLog::compare_tail`](crate::EventLog::compare_tail):
+/// how two histories relate, judged by their line hashes.
+///
+/// Offsets are byte offsets into each log's active log where the first
+/// differing line starts, or `None` if it is archived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompareResult {
+    /// Both histories hold the same `events` lines.
+    Identical {
+        /// Number of events in each history.
+        events: u64,
+    },
+
+    /// This log's history is a strict prefix of the other's, as for a
+    /// replica that has not caught up.
+    Behind {
+        /// Number of events in this log.
+        events: u64,
+        /// Where the other log's first extra event starts.
+        other_offset: Option<u64>,
+    },
+
+    /// The other log's history is a strict prefix of this one's.
+    Ahead {
+        /// Number of events in the other log.
+        events: u64,
+        /// Where this log's first extra event starts.
+        offset: Option<u64>,
+    },
+
+    /// The histories hold different lines at position `index`.
+    Diverged {
+        /// 0-based position of the first differing event in the full
+        /// history.
+        index: u64,
+        /// Where the differing line starts in this log.
+        offset: Option<u64>,
+        /// Where the differing line starts in the other log.
+        other_offset: Option<u64>,
+    },
+}
+
+impl CompareResult {
+    /// Returns `true` if neither history has a line the other lacks.
+    pub fn is_identical(&self) -> bool {
+        matches!(self, CompareResult::Identical { .. })
+    }
+}
+
+/// Walk two `(line_hash, active_offset)` streams in lockstep and stop at the
+/// first difference.
+pub(crate) fn compare_hashes(
+    mut left: impl Iterator<Item = io::Result<(String, Option<u64>)>>,
+    mut right: impl Iterator<Item = io::Result<(String, Option<u64>)>>,
+) -> io::Result<CompareResult> {
+    let mut index = 0;
+    loop {
+        match (left.next().transpose()?, right.next().transpose()?) {
+            (Some((a, _)), Some((b, _))) if a == b => index += 1,
+            (None, None) => return Ok(CompareResult::Identical { events: index }),
+            (None, Some((_, other_offset))) => {
+                return Ok(CompareResult::Behind {
+                    events: index,
+                    other_offset,
+                });
+            }
+            (Some((_, offset)), None) => {
+                return Ok(CompareResult::Ahead {
+                    events: index,
+                    offset,
+                });
+            }
+            (Some((_, offset)), Some((_, other_offset))) => {
+                return Ok(CompareResult::Diverged {
+                    index,
+                    offset,
+                    other_offset,
+                });
+            }
+        }
+    }
+}
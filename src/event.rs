@@ -1,7 +1,50 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::io;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// A Rust type that is the `data` of one event type.
+///
+/// Implement it on a payload struct (or enum) to build events with
+/// [`Event::from_typed`] and read them back with [`Event::as_typed`],
+/// instead of picking fields out of a [`Value`]. Opt in per event type;
+/// the log itself stays untyped.
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::{Event, TypedEvent};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct TodoAdded {
+///     text: String,
+/// }
+///
+/// impl TypedEvent for TodoAdded {
+///     fn event_type() -> &'static str {
+///         "todo_added"
+///     }
+/// }
+///
+/// fn reducer(mut todos: Vec<String>, event: &Event) -> Vec<String> {
+///     if let Some(added) = event.as_typed::<TodoAdded>() {
+///         todos.push(added.text);
+///     }
+///     todos
+/// }
+///
+/// let event = Event::from_typed(&TodoAdded { text: "milk".into() })?;
+/// assert_eq!(event.event_type, "todo_added");
+/// assert_eq!(reducer(Vec::new(), &event), ["milk"]);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub trait TypedEvent {
+    /// The `event_type` events carrying this payload are written with.
+    fn event_type() -> &'static str;
+}
+
 /// An immutable event record stored in the log.
 ///
 /// Events are serialized as single JSON lines in `app.jsonl`. The `data` field
@@ -31,7 +74,26 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// assert_eq!(event.id, Some("ord-001".to_string()));
 /// assert_eq!(event.actor, Some("user_42".to_string()));
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+///
+/// When constructing an `Event` with a struct literal, finish with
+/// `..Default::default()` so the code keeps compiling as optional fields
+/// are added:
+///
+/// ```
+/// use eventfold::Event;
+/// use serde_json::json;
+///
+/// let event = Event {
+///     event_type: "imported".to_string(),
+///     data: json!({"row": 1}),
+///     ts: 1_700_000_000,
+///     ..Default::default()
+/// };
+/// assert_eq!(event.id, None);
+/// ```
+///
+/// The default event has an empty type, `null` data, and `ts` of 0.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Event {
     /// The event type identifier (e.g. `"todo_added"`, `"user_clicked"`).
     ///
@@ -48,8 +110,9 @@ pub struct Event {
 
     /// Unique event identifier.
     ///
-    /// Not auto-generated — callers provide their own (uuid, ulid, etc.)
-    /// or leave as `None` for simple use cases.
+    /// `Event::new` leaves this `None`. Callers provide their own (uuid,
+    /// ulid, etc.), or let the log fill it in on append with
+    /// [`EventLogBuilder::auto_id`](crate::EventLogBuilder::auto_id).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
 
@@ -154,4 +217,164 @@ impl Event {
         self.meta = Some(meta);
         self
     }
+
+    /// Key under which [`with_schema_version`](Self::with_schema_version)
+    /// records the payload's version in `meta`.
+    pub const SCHEMA_VERSION_KEY: &'static str = "schema_version";
+
+    /// Record the version of `data`'s shape in `meta`, so
+    /// [upcasters](crate::EventLogBuilder::upcaster) can migrate the event
+    /// when it is read back.
+    ///
+    /// Other keys in `meta` are kept; a `meta` that is not a JSON object is
+    /// replaced. Call this after [`with_meta`](Self::with_meta), which
+    /// overwrites the whole bag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eventfold::Event;
+    /// use serde_json::json;
+    ///
+    /// let event = Event::new("click", json!({}))
+    ///     .with_meta(json!({"session": "sess_abc"}))
+    ///     .with_schema_version(2);
+    /// assert_eq!(event.schema_version(), Some(2));
+    /// assert_eq!(event.meta.unwrap()["session"], "sess_abc");
+    /// ```
+    pub fn with_schema_version(mut self, version: u32) -> Self {
+        match &mut self.meta {
+            Some(Value::Object(meta)) => {
+                meta.insert(Self::SCHEMA_VERSION_KEY.to_string(), version.into());
+            }
+            meta => *meta = Some(serde_json::json!({ Self::SCHEMA_VERSION_KEY: version })),
+        }
+        self
+    }
+
+    /// The payload version set by
+    /// [`with_schema_version`](Self::with_schema_version), or `None` if
+    /// `meta` holds no valid one.
+    pub fn schema_version(&self) -> Option<u32> {
+        let version = self.meta.as_ref()?.get(Self::SCHEMA_VERSION_KEY)?;
+        u32::try_from(version.as_u64()?).ok()
+    }
+
+    /// Event type of the tombstones made by [`Event::tombstone`].
+    pub const TOMBSTONE_TYPE: &'static str = "__tombstone";
+
+    /// Create a tombstone: an event of type [`TOMBSTONE_TYPE`] whose data
+    /// is `{"target": target_id}`, marking the event with that `id` as
+    /// deleted.
+    ///
+    /// Appending one deletes nothing — the log stays append-only. Readers
+    /// honor it with
+    /// [`EventReader::read_full_redacted`](crate::EventReader::read_full_redacted),
+    /// and reducers can check [`tombstone_target`](Self::tombstone_target).
+    /// For events that must physically leave the log, see
+    /// [`EventLog::redact`](crate::EventLog::redact).
+    ///
+    /// [`TOMBSTONE_TYPE`]: Self::TOMBSTONE_TYPE
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eventfold::Event;
+    ///
+    /// let tombstone = Event::tombstone("evt-123");
+    /// assert_eq!(tombstone.event_type, Event::TOMBSTONE_TYPE);
+    /// assert_eq!(tombstone.tombstone_target(), Some("evt-123"));
+    /// ```
+    pub fn tombstone(target_id: &str) -> Self {
+        Event::new(
+            Self::TOMBSTONE_TYPE,
+            serde_json::json!({ "target": target_id }),
+        )
+    }
+
+    /// The `id` a tombstone made by [`Event::tombstone`] deletes, or `None`
+    /// if this event is not a tombstone.
+    pub fn tombstone_target(&self) -> Option<&str> {
+        if self.event_type != Self::TOMBSTONE_TYPE {
+            return None;
+        }
+        self.data.get("target")?.as_str()
+    }
+
+    /// Create an event of type `T::event_type()` with `value` serialized
+    /// as its `data`. The timestamp is set as by [`Event::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eventfold::{Event, TypedEvent};
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Deposit {
+    ///     amount: u64,
+    /// }
+    ///
+    /// impl TypedEvent for Deposit {
+    ///     fn event_type() -> &'static str {
+    ///         "deposit"
+    ///     }
+    /// }
+    ///
+    /// let event = Event::from_typed(&Deposit { amount: 10 })?;
+    /// assert_eq!(event.event_type, "deposit");
+    /// assert_eq!(event.data["amount"], 10);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if `value` cannot be represented as JSON
+    /// (for example, a map with non-string keys).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system clock is set before the Unix epoch.
+    pub fn from_typed<T: TypedEvent + Serialize>(value: &T) -> io::Result<Self> {
+        let data = serde_json::to_value(value)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Event::new(T::event_type(), data))
+    }
+
+    /// Read this event's `data` as `T`.
+    ///
+    /// Returns `None` if the event's type is not `T::event_type()`, or if
+    /// its `data` does not deserialize into `T`. Metadata (`id`, `actor`,
+    /// `meta`, `ts`) stays on the event.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eventfold::{Event, TypedEvent};
+    /// use serde::Deserialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Deposit {
+    ///     amount: u64,
+    /// }
+    ///
+    /// impl TypedEvent for Deposit {
+    ///     fn event_type() -> &'static str {
+    ///         "deposit"
+    ///     }
+    /// }
+    ///
+    /// let event = Event::new("deposit", json!({"amount": 10}));
+    /// assert_eq!(event.as_typed::<Deposit>().map(|d| d.amount), Some(10));
+    ///
+    /// let other = Event::new("withdrawal", json!({"amount": 10}));
+    /// assert!(other.as_typed::<Deposit>().is_none());
+    /// ```
+    pub fn as_typed<T: TypedEvent + DeserializeOwned>(&self) -> Option<T> {
+        if self.event_type != T::event_type() {
+            return None;
+        }
+        T::deserialize(&self.data).ok()
+    }
 }
@@ -0,0 +1,103 @@
+//! Exporting the event history as CSV or NDJSON, and importing NDJSON.
+
+use crate::event::Event;
+use crate::log::BOM;
+use serde_json::Value;
+use std::io::{self, BufWriter, Write};
+
+/// Events an NDJSON import appends per write and sync.
+pub(crate) const IMPORT_BATCH_SIZE: usize = 1000;
+
+/// The columns every export starts with.
+const BASE_COLUMNS: [&str; 5] = ["ts", "type", "id", "actor", "data"];
+
+/// Write `events` to `out` as RFC 4180 CSV: a header row, then one row per
+/// event with the base columns followed by the top-level `data` keys named
+/// in `columns`.
+pub(crate) fn write_csv<W: Write>(
+    events: impl Iterator<Item = io::Result<Event>>,
+    out: W,
+    columns: &[&str],
+) -> io::Result<()> {
+    let mut out = BufWriter::new(out);
+    write_row(&mut out, BASE_COLUMNS.iter().chain(columns).copied())?;
+
+    for event in events {
+        let event = event?;
+        let data = event.data.to_string();
+        let ts = event.ts.to_string();
+        let extra: Vec<String> = columns
+            .iter()
+            .map(|&column| scalar(event.data.get(column)))
+            .collect();
+        let base = [
+            ts.as_str(),
+            event.event_type.as_str(),
+            event.id.as_deref().unwrap_or(""),
+            event.actor.as_deref().unwrap_or(""),
+            data.as_str(),
+        ];
+        write_row(
+            &mut out,
+            base.into_iter().chain(extra.iter().map(String::as_str)),
+        )?;
+    }
+    out.flush()
+}
+
+/// A `data` value as a CSV field: scalars as plain text, anything else
+/// (missing, `null`, arrays, objects) empty.
+fn scalar(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(value @ (Value::Number(_) | Value::Bool(_))) => value.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn write_row<'a>(out: &mut impl Write, fields: impl Iterator<Item = &'a str>) -> io::Result<()> {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.write_all(b",")?;
+        }
+        if field.contains([',', '"', '\r', '\n']) {
+            write!(out, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            out.write_all(field.as_bytes())?;
+        }
+    }
+    out.write_all(b"\r\n")
+}
+
+/// Write `events` to `out` as NDJSON, one event per line.
+pub(crate) fn write_ndjson<W: Write>(
+    events: impl Iterator<Item = io::Result<Event>>,
+    out: W,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(out);
+    for event in events {
+        serde_json::to_writer(&mut out, &event?)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()
+}
+
+/// Parse line `number` (1-based) of an NDJSON import, without its line
+/// ending. Returns `None` for a blank line; a byte-order mark on the first
+/// line is ignored.
+pub(crate) fn parse_ndjson_line(line: &str, number: u64) -> io::Result<Option<Event>> {
+    let line = if number == 1 {
+        line.strip_prefix(BOM).unwrap_or(line)
+    } else {
+        line
+    };
+    if line.trim().is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(line).map(Some).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("line {number} is not a valid event: {e}"),
+        )
+    })
+}
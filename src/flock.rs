@@ -0,0 +1,56 @@
+//! Advisory file locks, for the writer's lock on `app.jsonl` and for
+//! readers announcing themselves.
+//!
+//! They need the `std-fs` feature. Without it every lock fails with
+//! `Unsupported`, so only [`LockMode::None`](crate::LockMode::None) writers
+//! open, and [`Storage`](crate::Storage) other than [`FsStorage`](crate::FsStorage)
+//! is the way to go.
+
+use std::fs::File;
+use std::io;
+
+/// Take an exclusive lock on `file` without waiting. Returns `false` if
+/// another handle holds a lock on it.
+#[cfg(feature = "std-fs")]
+pub(crate) fn try_lock_exclusive(file: &File) -> io::Result<bool> {
+    match fs2::FileExt::try_lock_exclusive(file) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == fs2::lock_contended_error().kind() => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Take a shared lock on `file`, waiting while an exclusive one is held.
+#[cfg(feature = "std-fs")]
+pub(crate) fn lock_shared(file: &File) -> io::Result<()> {
+    fs2::FileExt::lock_shared(file)
+}
+
+/// Release this handle's lock on `file`.
+#[cfg(feature = "std-fs")]
+pub(crate) fn unlock(file: &File) -> io::Result<()> {
+    fs2::FileExt::unlock(file)
+}
+
+#[cfg(not(feature = "std-fs"))]
+pub(crate) fn try_lock_exclusive(_file: &File) -> io::Result<bool> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "std-fs"))]
+pub(crate) fn lock_shared(_file: &File) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "std-fs"))]
+pub(crate) fn unlock(_file: &File) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "std-fs"))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "file locking requires the `std-fs` feature",
+    )
+}
@@ -0,0 +1,107 @@
+//! Line hashes: the algorithm behind every `line_hash`.
+//!
+//! A log keeps one algorithm for its whole life, since snapshots, hash
+//! chains and conditional appends all compare hashes computed at different
+//! times. The choice is recorded in the directory's
+//! [`Manifest`](crate::Manifest).
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The algorithm used to hash event lines, set with
+/// [`EventLogBuilder::hash_algo`](crate::EventLogBuilder::hash_algo).
+///
+/// It is used for [`AppendResult::line_hash`](crate::AppendResult::line_hash),
+/// the `hash` in view snapshots, hash chains, and integrity checks, and is
+/// recorded in the log's [`Manifest`](crate::Manifest) so every later
+/// writer and reader agrees.
+///
+/// [`HashAlgo::Sha256`] needs the default `sha256` feature and
+/// [`HashAlgo::Blake3`] the `blake3` feature. A log whose manifest records
+/// an algorithm this build lacks cannot be opened.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventLog, HashAlgo};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// # #[cfg(feature = "sha256")]
+/// # {
+/// let mut log = EventLog::builder(dir.path())
+///     .hash_algo(HashAlgo::Sha256)
+///     .open()?;
+/// let result = log.append(&Event::new("click", json!({})))?;
+/// assert_eq!(result.line_hash.len(), 64);
+///
+/// let line = std::fs::read_to_string(log.log_path())?;
+/// assert_eq!(result.line_hash, HashAlgo::Sha256.hash(line.trim_end().as_bytes()));
+/// # }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum HashAlgo {
+    /// 64-bit xxHash as 16 hex digits. Fast, not cryptographic. The
+    /// default, and what [`line_hash`](crate::line_hash) computes.
+    #[default]
+    Xxh64,
+
+    /// SHA-256 as 64 hex digits, for interop with content-addressed
+    /// systems.
+    #[cfg(feature = "sha256")]
+    Sha256,
+
+    /// BLAKE3 as 64 hex digits: cryptographic, and faster than SHA-256.
+    #[cfg(feature = "blake3")]
+    Blake3,
+}
+
+impl HashAlgo {
+    /// Hash one event line (without its newline) as lowercase hex.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eventfold::{line_hash, HashAlgo};
+    ///
+    /// assert_eq!(HashAlgo::Xxh64.hash(b"hello"), line_hash(b"hello"));
+    /// # #[cfg(feature = "sha256")]
+    /// assert_eq!(
+    ///     HashAlgo::Sha256.hash(b"abc"),
+    ///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    /// );
+    /// ```
+    pub fn hash(self, line: &[u8]) -> String {
+        match self {
+            HashAlgo::Xxh64 => format!("{:016x}", xxhash_rust::xxh64::xxh64(line, 0)),
+            #[cfg(feature = "sha256")]
+            HashAlgo::Sha256 => {
+                use sha2::Digest;
+                let digest = sha2::Sha256::digest(line);
+                digest.iter().map(|b| format!("{b:02x}")).collect()
+            }
+            #[cfg(feature = "blake3")]
+            HashAlgo::Blake3 => blake3::hash(line).to_hex().to_string(),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            HashAlgo::Xxh64 => "xxh64",
+            #[cfg(feature = "sha256")]
+            HashAlgo::Sha256 => "sha256",
+            #[cfg(feature = "blake3")]
+            HashAlgo::Blake3 => "blake3",
+        }
+    }
+}
+
+impl fmt::Display for HashAlgo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
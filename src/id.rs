@@ -0,0 +1,87 @@
+//! Generated event IDs.
+
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Crockford's base32 alphabet, as used by ULIDs.
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// How [`EventLogBuilder::auto_id`](crate::EventLogBuilder::auto_id)
+/// generates IDs for events appended without one.
+///
+/// Both schemes start with a millisecond timestamp, so IDs sort roughly by
+/// creation time, followed by random bits.
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::IdScheme;
+///
+/// let id = IdScheme::Uuidv7.generate()?;
+/// assert_eq!(id.len(), 36);
+/// assert_eq!(&id[14..15], "7"); // version nibble
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdScheme {
+    /// A [ULID](https://github.com/ulid/spec): 26 characters of Crockford
+    /// base32, e.g. `01ARZ3NDEKTSV4RRFFQ69G5FAV`.
+    Ulid,
+
+    /// A version 7 UUID (RFC 9562) in hyphenated lowercase hex, e.g.
+    /// `01890a5d-ac96-774b-bcce-b302099a8057`.
+    Uuidv7,
+}
+
+impl IdScheme {
+    /// Generate a new ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operating system's random number generator
+    /// is unavailable.
+    pub fn generate(self) -> io::Result<String> {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default()
+            & 0xffff_ffff_ffff; // 48 bits
+        let mut random = [0u8; 10];
+        getrandom::fill(&mut random).map_err(|e| io::Error::other(e.to_string()))?;
+
+        Ok(match self {
+            IdScheme::Ulid => ulid(millis, random),
+            IdScheme::Uuidv7 => uuidv7(millis, random),
+        })
+    }
+}
+
+/// 48-bit timestamp then 80 random bits, as 26 base32 characters.
+fn ulid(millis: u64, random: [u8; 10]) -> String {
+    let value = random
+        .iter()
+        .fold(u128::from(millis), |acc, &b| (acc << 8) | u128::from(b));
+    (0..26)
+        .rev()
+        .map(|i| CROCKFORD[((value >> (5 * i)) & 0x1f) as usize] as char)
+        .collect()
+}
+
+/// 48-bit timestamp, version 7, variant `10`, and 74 random bits.
+fn uuidv7(millis: u64, random: [u8; 10]) -> String {
+    let mut bytes = [0u8; 16];
+    bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+    bytes[6..].copy_from_slice(&random);
+    bytes[6] = 0x70 | (bytes[6] & 0x0f);
+    bytes[8] = 0x80 | (bytes[8] & 0x3f);
+
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
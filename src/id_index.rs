@@ -0,0 +1,56 @@
+//! In-memory index from event ID to sequence number, enabled with
+//! [`EventLogBuilder::id_index`](crate::EventLogBuilder::id_index).
+
+use crate::log::EventReader;
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, RwLock};
+
+/// The sequence number of the first event with each ID, shared by a writer
+/// and the readers it hands out.
+#[derive(Clone, Default)]
+pub(crate) struct IdIndex(Arc<RwLock<HashMap<String, u64>>>);
+
+impl std::fmt::Debug for IdIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ids = self.0.read().unwrap_or_else(|e| e.into_inner());
+        f.debug_struct("IdIndex").field("ids", &ids.len()).finish()
+    }
+}
+
+impl IdIndex {
+    /// Index every event in the full history `reader` sees.
+    pub(crate) fn build(reader: &EventReader) -> io::Result<Self> {
+        let index = IdIndex::default();
+        index.rebuild(reader)?;
+        Ok(index)
+    }
+
+    /// Replace the index with one of the full history `reader` sees, after
+    /// sequence numbers moved or events were dropped.
+    pub(crate) fn rebuild(&self, reader: &EventReader) -> io::Result<()> {
+        let mut ids = HashMap::new();
+        for result in reader.read_from_sequence(0)? {
+            let (event, sequence, _) = result?;
+            if let Some(id) = event.id {
+                ids.entry(id).or_insert(sequence);
+            }
+        }
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = ids;
+        Ok(())
+    }
+
+    /// Record that event `sequence` has `id`, unless an earlier event did.
+    pub(crate) fn insert(&self, id: &str, sequence: u64) {
+        let mut ids = self.0.write().unwrap_or_else(|e| e.into_inner());
+        if !ids.contains_key(id) {
+            ids.insert(id.to_string(), sequence);
+        }
+    }
+
+    /// Sequence number of the first event with `id`.
+    pub(crate) fn get(&self, id: &str) -> Option<u64> {
+        let ids = self.0.read().unwrap_or_else(|e| e.into_inner());
+        ids.get(id).copied()
+    }
+}
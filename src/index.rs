@@ -0,0 +1,109 @@
+//! Sparse index of the active log — the `app.index` sidecar.
+//!
+//! With [`EventLogBuilder::index`](crate::EventLogBuilder::index), the
+//! writer records the byte offset of every [`STRIDE`]th event in the active
+//! log, one decimal offset per line: line `i` holds the offset of event
+//! `(i + 1) * STRIDE`. Event 0 always starts the log, so it needs no entry.
+//!
+//! The index is only a shortcut. It is rebuilt from the log whenever the
+//! writer opens, emptied before rotation truncates the log, and rebuilt
+//! after compaction; readers ignore entries at or past the end of the log.
+
+use crate::snapshot;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+/// Number of events between index entries.
+pub(crate) const STRIDE: u64 = 256;
+
+/// Name of the index file, inside the log directory.
+const INDEX_FILE: &str = "app.index";
+
+/// The writer's handle on the index file.
+#[derive(Debug)]
+pub(crate) struct EventIndex {
+    file: File,
+}
+
+impl EventIndex {
+    /// Index the active log read from `log`, replacing any saved index.
+    pub(crate) fn rebuild(dir: &Path, mut log: impl BufRead) -> io::Result<Self> {
+        let mut contents = String::new();
+        let mut events: u64 = 0;
+        let mut offset = 0;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = log.read_until(b'\n', &mut line)?;
+            if read == 0 || !line.ends_with(b"\n") {
+                break;
+            }
+            if !line.trim_ascii().is_empty() {
+                if events > 0 && events.is_multiple_of(STRIDE) {
+                    contents.push_str(&format!("{offset}\n"));
+                }
+                events += 1;
+            }
+            offset += read as u64;
+        }
+
+        let path = dir.join(INDEX_FILE);
+        snapshot::write_atomic(&path, contents.as_bytes())?;
+        let file = OpenOptions::new().append(true).open(&path)?;
+        Ok(EventIndex { file })
+    }
+
+    /// Note that event number `event` of the active log starts at `offset`.
+    /// Only every [`STRIDE`]th event is written.
+    pub(crate) fn record(&mut self, event: u64, offset: u64) -> io::Result<()> {
+        if event > 0 && event.is_multiple_of(STRIDE) {
+            writeln!(self.file, "{offset}")?;
+        }
+        Ok(())
+    }
+
+    /// Drop every entry, ahead of the active log being emptied.
+    pub(crate) fn clear(&mut self) -> io::Result<()> {
+        self.file.set_len(0)?;
+        self.file.sync_data()
+    }
+}
+
+/// Delete the index file, if any, so a log written without one is never
+/// read through stale entries.
+pub(crate) fn remove(dir: &Path) -> io::Result<()> {
+    match fs::remove_file(dir.join(INDEX_FILE)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// The indexed event closest to event `n` without passing it, as
+/// `(event, offset)`, or `None` if the index has no such entry. Entries at
+/// or past `log_size` are ignored, as is everything from the first
+/// malformed line on.
+pub(crate) fn lookup(dir: &Path, n: u64, log_size: u64) -> io::Result<Option<(u64, u64)>> {
+    let wanted = n / STRIDE;
+    if wanted == 0 {
+        return Ok(None);
+    }
+    let contents = match fs::read_to_string(dir.join(INDEX_FILE)) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut best = None;
+    for (i, line) in contents.split_inclusive('\n').enumerate() {
+        let event = (i as u64 + 1) * STRIDE;
+        let Some(offset) = line.strip_suffix('\n').and_then(|l| l.parse().ok()) else {
+            break;
+        };
+        if event > n || offset >= log_size {
+            break;
+        }
+        best = Some((event, offset));
+    }
+    Ok(best)
+}
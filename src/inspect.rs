@@ -0,0 +1,210 @@
+//! Read-only diagnostics for an event log directory.
+
+use crate::archive;
+use crate::codec::Codec;
+use crate::log::BOM;
+use crate::manifest;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// Zstd frame magic number, as it appears on disk.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Encoding of the active log, as detected by [`EventLog::inspect`](crate::EventLog::inspect).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LogFormat {
+    /// Newline-delimited JSON events (or an empty log).
+    Jsonl,
+    /// Events encrypted line by line, as the manifest records (see
+    /// `EventLogBuilder::encryption`, with the `crypto` feature).
+    Encrypted,
+    /// The first line is not a JSON object — not written by eventfold, or
+    /// written in a format this version doesn't recognize.
+    Unknown,
+}
+
+/// Compression of the archive file, as detected by [`EventLog::inspect`](crate::EventLog::inspect).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ArchiveCompression {
+    /// Zstd frames without dictionaries.
+    Zstd,
+    /// Zstd frames, with dictionaries recorded in `archive.manifest.json`.
+    ZstdDictionary,
+    /// Uncompressed NDJSON in `archive.jsonl`, written with [`Codec::None`].
+    None,
+    /// Gzip members in `archive.jsonl.gz`, written with [`Codec::Gzip`].
+    Gzip,
+    /// LZ4 frames in `archive.jsonl.lz4`, written with [`Codec::Lz4`].
+    Lz4,
+    /// The archive does not start with a recognized magic number.
+    Unknown,
+}
+
+/// Diagnostic summary of an event log directory.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{EventLog, LogFormat};
+/// # let dir = tempdir()?;
+/// # drop(EventLog::open(dir.path())?);
+/// let info = EventLog::inspect(dir.path())?;
+/// assert_eq!(info.format, LogFormat::Jsonl);
+/// assert!(!info.has_archive);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogInfo {
+    /// Encoding of the active log.
+    pub format: LogFormat,
+
+    /// Compression of the archive, or `None` if there is no archive.
+    pub compression: Option<ArchiveCompression>,
+
+    /// Number of complete, non-empty lines across the archive and active
+    /// log. Lines are counted, not parsed, so a corrupt line still counts.
+    pub event_count_estimate: u64,
+
+    /// Whether an archive file exists.
+    pub has_archive: bool,
+
+    /// Names of views with persisted state in `views/`, sorted.
+    pub view_names: Vec<String>,
+
+    /// Number of rotations the archive has absorbed (its frame count).
+    pub generation: u64,
+}
+
+/// Inspect `dir` without opening it for writing or taking any lock.
+pub(crate) fn inspect(dir: &Path) -> io::Result<LogInfo> {
+    if !dir.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is not an event log directory", dir.display()),
+        ));
+    }
+
+    let log_path = dir.join("app.jsonl");
+    let codec = Codec::detect(dir).unwrap_or_default();
+    let archive_path = dir.join(codec.file_name());
+
+    let (format, active_lines) = match File::open(&log_path) {
+        Ok(file) => sniff_active_log(file)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => (LogFormat::Jsonl, 0),
+        Err(e) => return Err(e),
+    };
+    let encrypted = manifest::load(dir)
+        .ok()
+        .flatten()
+        .is_some_and(|manifest| manifest.encrypted);
+    let format = if encrypted { LogFormat::Encrypted } else { format };
+
+    let has_archive = archive_path.exists();
+    let (compression, archive_lines, generation) = if has_archive {
+        let compression = match codec {
+            Codec::None => ArchiveCompression::None,
+            Codec::Gzip => ArchiveCompression::Gzip,
+            Codec::Lz4 => ArchiveCompression::Lz4,
+            Codec::Zstd => sniff_archive(&archive_path)?,
+        };
+        if compression == ArchiveCompression::Unknown {
+            (Some(compression), 0, 0)
+        } else {
+            let lines = match archive::open_archive_reader(&archive_path)? {
+                Some(reader) => count_lines(reader)?,
+                None => 0,
+            };
+            (
+                Some(compression),
+                lines,
+                archive::frame_count(&archive_path)?,
+            )
+        }
+    } else {
+        (None, 0, 0)
+    };
+
+    Ok(LogInfo {
+        format,
+        compression,
+        event_count_estimate: active_lines + archive_lines,
+        has_archive,
+        view_names: view_names(&dir.join("views"))?,
+        generation,
+    })
+}
+
+fn sniff_active_log(file: File) -> io::Result<(LogFormat, u64)> {
+    let mut reader = BufReader::new(file);
+    let mut first = Vec::new();
+    reader.read_until(b'\n', &mut first)?;
+    let first = first.strip_prefix(BOM.as_bytes()).unwrap_or(&first);
+    let trimmed = first.trim_ascii();
+    let format = if trimmed.is_empty() || trimmed.starts_with(b"{") {
+        LogFormat::Jsonl
+    } else {
+        LogFormat::Unknown
+    };
+    let first_count = u64::from(first.ends_with(b"\n") && !trimmed.is_empty());
+    Ok((format, first_count + count_lines(reader)?))
+}
+
+fn sniff_archive(archive_path: &Path) -> io::Result<ArchiveCompression> {
+    let mut magic = [0u8; 4];
+    let mut file = File::open(archive_path)?;
+    if file.read(&mut magic)? < magic.len() || magic != ZSTD_MAGIC {
+        return Ok(ArchiveCompression::Unknown);
+    }
+    if archive_path
+        .with_file_name("archive.manifest.json")
+        .exists()
+    {
+        Ok(ArchiveCompression::ZstdDictionary)
+    } else {
+        Ok(ArchiveCompression::Zstd)
+    }
+}
+
+/// Count complete, non-blank lines; a trailing partial line is not counted.
+pub(crate) fn count_lines(mut reader: impl BufRead) -> io::Result<u64> {
+    let mut count = 0;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(count);
+        }
+        if line.ends_with(b"\n") && !line.trim_ascii().is_empty() {
+            count += 1;
+        }
+    }
+}
+
+fn view_names(views_dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(views_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut names = Vec::new();
+    for entry in entries {
+        let file_name = entry?.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+        if let Some(name) = file_name
+            .strip_suffix(".snapshot.json")
+            .or_else(|| file_name.strip_suffix(".checkpoint.json"))
+        {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
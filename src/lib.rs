@@ -48,16 +48,93 @@
 //!
 //! See `docs/guide.md` for a detailed concepts guide.
 
+/// Emit a warning through the [`log`](https://docs.rs/log) facade when the
+/// `log` feature is enabled; otherwise stay silent.
+macro_rules! warn {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "log")]
+        ::log::warn!($($arg)*);
+        #[cfg(not(feature = "log"))]
+        let _ = format_args!($($arg)*);
+    }};
+}
+
 mod archive;
+#[cfg(feature = "async")]
+mod async_reader;
+mod chain;
+mod codec;
+mod compact;
+mod crypto;
+mod cursor;
+mod dead_letter;
+mod dedup;
+mod deferred_sync;
+mod equivalence;
 mod event;
+mod export;
+mod flock;
+mod hash;
+mod id;
+mod id_index;
+mod index;
+mod inspect;
 mod log;
+mod log_view;
+mod manifest;
+mod migrate;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod observer;
+mod reader_lock;
+mod redact;
+mod repair;
+mod rotation;
+mod schema;
+mod sequence;
 pub mod snapshot;
+mod split;
+mod storage;
+mod swap;
+mod tail;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod txn;
+mod upcast;
+mod verify;
 mod view;
 
-pub use event::Event;
+pub use archive::{list_frames, read_frame, FrameInfo, Retention, SegmentId};
+#[cfg(feature = "async")]
+pub use async_reader::{AsyncEventReader, EventStream};
+pub use chain::ChainBreak;
+pub use codec::Codec;
+pub use cursor::Cursor;
+pub use deferred_sync::SyncHandle;
+pub use equivalence::{CompareResult, Divergence, EquivalenceReport, EventCmp};
+pub use event::{Event, TypedEvent};
+pub use hash::HashAlgo;
+pub use id::IdScheme;
+pub use inspect::{ArchiveCompression, LogFormat, LogInfo};
 pub use log::{
     line_hash, AppendConflict, AppendResult, ConditionalAppendError, EventLog, EventLogBuilder,
-    EventReader, EventWriter, LockMode, WaitResult,
+    EventReader, EventWriter, LenientIter, LockMode, ReadUntil, StopAt, Subscriber, SyncPolicy,
+    WaitResult,
 };
+pub use log_view::{LogView, WriteFn};
+pub use manifest::Manifest;
+pub use migrate::MigrationReport;
+pub use observer::{LogObserver, RebuildReason};
+pub use reader_lock::ReaderLock;
+pub use repair::{RepairMode, RepairReport};
+pub use rotation::{RotationPreview, RotationRecord, RotationStatus};
+pub use schema::SchemaViolation;
 pub use snapshot::Snapshot;
-pub use view::{ReduceFn, View, ViewOps};
+pub use split::SplitReport;
+pub use storage::{FsStorage, MemStorage, Storage};
+pub use tail::EventTail;
+pub use verify::{ArchiveProblem, LineProblem, VerifyReport, ViewStatus};
+pub use view::{
+    ContextReduceFn, EventContext, Merge, ReduceError, ReduceFailure, ReduceFn, RefreshStats,
+    SnapshotPolicy, TryReduceFn, TypedReduceFn, View, ViewOps,
+};
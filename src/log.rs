@@ -1,41 +1,160 @@
-use crate::archive;
+use crate::archive::{self, FrameInfo, Retention, SegmentId};
+use crate::chain::{self, ChainBreak};
+use crate::codec::{ArchiveCodec, Codec};
+use crate::compact;
+use crate::crypto::{self, Cipher};
+use crate::cursor::Cursor;
+use crate::dead_letter;
+use crate::dedup::{DEFAULT_DEDUP_WINDOW, RecentKeys};
+use crate::deferred_sync::{DeferredSync, SyncHandle};
+use crate::equivalence::{self, CompareResult, EquivalenceReport, EventCmp};
 use crate::event::Event;
-use crate::view::{ReduceFn, View, ViewOps};
-use fs2::FileExt;
+use crate::export;
+use crate::hash::HashAlgo;
+use crate::id::IdScheme;
+use crate::id_index::IdIndex;
+use crate::index::{self, EventIndex};
+use crate::inspect::{self, LogInfo};
+use crate::log_view::{LogView, WriteFn};
+use crate::manifest::{self, Manifest};
+use crate::migrate::{self, MigrationReport};
+#[cfg(feature = "mmap")]
+use crate::mmap::MmapCache;
+use crate::observer::{LogObserver, Observer};
+use crate::reader_lock::{self, ReaderLock};
+use crate::redact;
+use crate::repair::{self, RepairMode, RepairReport};
+use crate::rotation::{self, RotationPreview, RotationRecord, RotationStatus};
+use crate::schema::Schemas;
+use crate::sequence;
+use crate::snapshot::{self, SnapshotStore};
+use crate::split::{self, SplitReport};
+use crate::storage::{FsStorage, MemStorage, Storage};
+use crate::swap;
+use crate::tail::EventTail;
+use crate::txn::{self, TransactionIter};
+use crate::upcast::{UpcastFn, Upcasters};
+use crate::verify::{self, VerifyReport};
+use crate::view::{
+    ContextReduceFn, ReduceFn, RefreshStats, SnapshotPolicy, TryReduceFn, TypedReduceFn, View,
+    ViewOps,
+};
+#[cfg(feature = "std-fs")]
 use notify::{EventKind, RecursiveMode, Watcher};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::collections::HashMap;
-use std::fs::{self, File, OpenOptions};
-use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::ops::{ControlFlow, Range};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+#[cfg(feature = "std-fs")]
 use std::sync::mpsc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// UTF-8 byte-order mark. Editors may write one at the start of a file;
+/// it is ignored there, and only there.
+pub(crate) const BOM: &str = "\u{feff}";
 
 /// Boxed iterator over `(Event, line_hash)` pairs from `read_full()`.
-type FullEventIter = Box<dyn Iterator<Item = io::Result<(Event, String)>>>;
+pub(crate) type FullEventIter = Box<dyn Iterator<Item = io::Result<(Event, String)>>>;
+
+/// Boxed iterator over unparsed `(line, line_hash)` pairs of the full
+/// history.
+type FullLineIter = Box<dyn Iterator<Item = io::Result<(String, String)>>>;
 
 /// Controls file locking behavior for an [`EventWriter`].
 ///
+/// The locking modes need the default `std-fs` feature; without it only
+/// [`LockMode::None`] opens, and it is the default.
+///
 /// # Examples
 ///
 /// ```
 /// use eventfold::LockMode;
+/// # #[cfg(feature = "std-fs")]
 /// assert_eq!(LockMode::default(), LockMode::Flock);
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LockMode {
     /// Acquire an exclusive advisory lock on `app.jsonl`.
     /// Prevents other processes from opening a writer on the same file.
-    /// This is the default.
-    #[default]
+    /// This is the default with the `std-fs` feature.
     Flock,
 
+    /// Like [`Flock`](Self::Flock), but if another writer holds the lock,
+    /// wait up to `timeout` for it to be released instead of failing
+    /// immediately. Lets short-lived processes take turns appending.
+    FlockBlocking {
+        /// How long to wait for the lock before giving up with `TimedOut`.
+        timeout: Duration,
+    },
+
     /// No locking. Use when you know only one process accesses the log,
     /// or in test scenarios where multiple writers are intentionally used.
+    /// This is the default without the `std-fs` feature.
     None,
 }
 
+impl Default for LockMode {
+    #[cfg(feature = "std-fs")]
+    fn default() -> Self {
+        LockMode::Flock
+    }
+
+    #[cfg(not(feature = "std-fs"))]
+    fn default() -> Self {
+        LockMode::None
+    }
+}
+
+/// Controls when appended data is flushed to stable storage with `fsync`.
+///
+/// Relaxed policies trade durability for throughput: events written but not
+/// yet synced are visible to readers immediately, but may be lost on power
+/// failure or OS crash. Every event is written to the file before `append`
+/// returns, so a crash of the process alone loses nothing under any
+/// policy. Pair a relaxed policy with
+/// [`EventLogBuilder::max_unsynced_events`] to bound the worst-case loss.
+///
+/// Whatever the policy, rotation and [`EventWriter::sync`] sync
+/// everything written so far, and a synced event is never lost.
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::SyncPolicy;
+/// use std::time::Duration;
+/// assert_eq!(SyncPolicy::default(), SyncPolicy::EveryAppend);
+/// let relaxed = SyncPolicy::Interval(Duration::from_millis(100));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncPolicy {
+    /// `fsync` after every append. Nothing acknowledged is ever lost.
+    /// This is the default.
+    #[default]
+    EveryAppend,
+
+    /// `fsync` on the first append after the interval has elapsed since the
+    /// last sync. There is no background timer — an idle writer does not
+    /// sync until its next append or an explicit [`EventWriter::sync`].
+    /// On power loss, the events appended since the last sync — up to one
+    /// interval's worth, plus any written while idle — may be lost.
+    Interval(Duration),
+
+    /// `fsync` once this many appended events are unsynced. On power loss,
+    /// up to `n - 1` of the most recent events may be lost. `EveryN(1)`
+    /// (or `0`) behaves like [`EveryAppend`](Self::EveryAppend).
+    EveryN(u64),
+
+    /// Never `fsync` on append; leave flushing to the OS, rotation and
+    /// explicit [`EventWriter::sync`] calls. The fastest choice for bulk
+    /// loads: on power loss, everything appended since the last sync may
+    /// be lost, so sync once the load is done.
+    Never,
+}
+
 /// Result of waiting for new events.
 ///
 /// # Examples
@@ -53,6 +172,113 @@ pub enum WaitResult {
     Timeout,
 }
 
+/// Whether [`EventReader::read_until`] and
+/// [`EventReader::read_full_until`] return the event they stop at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopAt {
+    /// Stop before the matching event: it is not returned, and reading
+    /// resumes with it.
+    Before,
+    /// Stop after the matching event: it is the last one returned, and
+    /// reading resumes right after it.
+    After,
+}
+
+/// Events read by [`EventReader::read_until`] or
+/// [`EventReader::read_full_until`], and where to resume.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventWriter, StopAt};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// let mut writer = EventWriter::open(dir.path())?;
+/// writer.append(&Event::new("a", json!({})))?;
+/// writer.append(&Event::new("checkpoint", json!({})))?;
+/// writer.append(&Event::new("b", json!({})))?;
+///
+/// let read = writer
+///     .reader()
+///     .read_until(0, StopAt::After, |e| e.event_type == "checkpoint")?;
+/// assert!(read.stopped);
+/// assert_eq!(read.events.len(), 2);
+///
+/// let rest = writer.reader().read_until(read.resume, StopAt::After, |_| false)?;
+/// assert_eq!(rest.events[0].event_type, "b");
+/// assert!(!rest.stopped);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadUntil {
+    /// The events read, oldest first.
+    pub events: Vec<Event>,
+
+    /// Where to resume: a byte offset into the active log for
+    /// [`read_until`](EventReader::read_until), a sequence number for
+    /// [`read_full_until`](EventReader::read_full_until).
+    pub resume: u64,
+
+    /// Whether an event matched the stop predicate. `false` if the end of
+    /// the log was reached first.
+    pub stopped: bool,
+}
+
+/// Events read by [`EventReader::read_from_lenient`] or
+/// [`EventReader::read_full_lenient`], skipping lines that are not valid
+/// events.
+///
+/// Read errors are still yielded; only lines that fail to parse are
+/// skipped, and [`skipped`](Self::skipped) counts them so far.
+pub struct LenientIter<T> {
+    events: Box<dyn Iterator<Item = io::Result<Option<T>>>>,
+    skipped: u64,
+}
+
+impl<T> LenientIter<T> {
+    fn new(events: impl Iterator<Item = io::Result<Option<T>>> + 'static) -> Self {
+        LenientIter {
+            events: Box::new(events),
+            skipped: 0,
+        }
+    }
+
+    /// Number of malformed lines skipped so far.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+}
+
+impl<T> Iterator for LenientIter<T> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.events.next()? {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => self.skipped += 1,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for LenientIter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LenientIter")
+            .field("skipped", &self.skipped)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The event on a stored line, or `None` if it cannot be decrypted or
+/// parsed, for the lenient reads.
+fn parse_lenient(cipher: Option<&Cipher>, line: &str) -> Option<Event> {
+    let json = crypto::open_line(cipher, line).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
 /// Conflict details when a conditional append fails.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AppendConflict {
@@ -111,6 +337,16 @@ pub struct AppendResult {
     /// xxh64 hash of the serialized event line (hex-encoded, without
     /// the trailing newline).
     pub line_hash: String,
+
+    /// The ID generated for the event, if the log assigns IDs (see
+    /// [`EventLogBuilder::auto_id`]) and the event had none. `None` if the
+    /// event was written with its own `id`.
+    pub assigned_id: Option<String>,
+
+    /// The event's log-wide sequence number. Unlike byte offsets, sequence
+    /// numbers keep counting across rotations; see
+    /// [`EventReader::read_from_sequence`].
+    pub sequence: u64,
 }
 
 /// Exclusive writer for a single event log file.
@@ -131,20 +367,78 @@ pub struct AppendResult {
 /// # Ok::<(), std::io::Error>(())
 /// ```
 pub struct EventWriter {
-    file: File,
+    storage: Arc<dyn Storage>,
     log_path: PathBuf,
     archive_path: PathBuf,
     views_dir: PathBuf,
     max_log_size: u64,
+    max_log_events: u64,
+    /// Events in the active log.
+    active_events: u64,
+    /// Sequence number of the first event in the active log.
+    sequence_base: u64,
+    sync_policy: SyncPolicy,
+    max_unsynced_events: u64,
+    unsynced_events: u64,
+    /// When the log was last synced, kept only under
+    /// [`SyncPolicy::Interval`]: the clock is read only when a policy
+    /// needs it, as `wasm32-unknown-unknown` has none.
+    last_sync: Option<Instant>,
+    archive_dictionary: Option<Vec<u8>>,
+    archive_retention: Option<Retention>,
+    id_scheme: Option<IdScheme>,
+    schemas: Schemas,
+    /// Hash of the last line, while hash chaining is on.
+    chain_head: Option<String>,
+    deferred_sync: Option<DeferredSync>,
+    observer: Observer,
+    upcasters: Upcasters,
+    /// The sparse index of the active log, while indexing is on.
+    index: Option<EventIndex>,
+    ids: Option<IdIndex>,
+    /// Capacity of the recent-key window of idempotent appends.
+    dedup_window: usize,
+    /// The recent-key window, once an idempotent append has seeded it.
+    recent: Option<RecentKeys>,
+    /// Set for [`EventLog::open_readonly`]: every write fails.
+    read_only: bool,
+    /// Whether views save their snapshots through this writer.
+    snapshot_writes: bool,
+    /// Whether those snapshots are indented.
+    pretty_snapshots: bool,
+    /// Where views keep their snapshots instead of the storage, if set.
+    snapshot_dir: Option<PathBuf>,
+    manifest: Manifest,
+    /// Whether `manifest` was created by this writer, so its settings may
+    /// still be chosen.
+    manifest_is_new: bool,
+    /// Seals lines and snapshots, if the log is encrypted.
+    cipher: Option<Arc<Cipher>>,
 }
 
 impl std::fmt::Debug for EventWriter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("EventWriter")
+            .field("storage", &self.storage)
             .field("log_path", &self.log_path)
             .field("archive_path", &self.archive_path)
             .field("views_dir", &self.views_dir)
             .field("max_log_size", &self.max_log_size)
+            .field("max_log_events", &self.max_log_events)
+            .field("sequence_base", &self.sequence_base)
+            .field("sync_policy", &self.sync_policy)
+            .field("unsynced_events", &self.unsynced_events)
+            .field("archive_dictionary", &self.archive_dictionary.is_some())
+            .field("archive_retention", &self.archive_retention)
+            .field("id_scheme", &self.id_scheme)
+            .field("schemas", &self.schemas)
+            .field("hash_chain", &self.chain_head.is_some())
+            .field("encrypted", &self.cipher.is_some())
+            .field("index", &self.index.is_some())
+            .field("ids", &self.ids)
+            .field("dedup_window", &self.dedup_window)
+            .field("observer", &self.observer)
+            .field("upcasters", &self.upcasters)
             .finish()
     }
 }
@@ -153,7 +447,8 @@ impl EventWriter {
     /// Open or create an event log directory for writing.
     ///
     /// Creates `dir/`, `dir/views/`, and `dir/app.jsonl` if they don't exist.
-    /// Opens `app.jsonl` in append mode and acquires an exclusive advisory lock.
+    /// Opens `app.jsonl` in append mode and acquires an exclusive advisory
+    /// lock, with the default [`LockMode`].
     ///
     /// # Examples
     ///
@@ -170,7 +465,7 @@ impl EventWriter {
     /// Returns an error if directory creation fails, if the log file cannot
     /// be opened, or if another writer already holds the lock.
     pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
-        Self::open_with_lock(dir, LockMode::Flock)
+        Self::open_with_lock(dir, LockMode::default())
     }
 
     /// Open or create an event log directory with an explicit lock mode.
@@ -179,8 +474,16 @@ impl EventWriter {
     /// `app.jsonl`. If another writer holds the lock, returns an error
     /// immediately (non-blocking).
     ///
+    /// With [`LockMode::FlockBlocking`], waits up to the given timeout for
+    /// another writer to release the lock.
+    ///
     /// With [`LockMode::None`], no lock is acquired.
     ///
+    /// Neither mode interacts with readers' locks: a reader holding
+    /// [`EventReader::try_lock_shared`] never blocks the writer, and the
+    /// writer can check for such readers with
+    /// [`readers_present`](Self::readers_present).
+    ///
     /// # Examples
     ///
     /// ```
@@ -195,39 +498,102 @@ impl EventWriter {
     ///
     /// Returns an error if directory creation fails, if the log file cannot
     /// be opened, or if locking fails (including when another writer holds
-    /// the lock).
+    /// the lock). With [`LockMode::FlockBlocking`], returns `TimedOut` if
+    /// the lock is still held when the timeout elapses.
     pub fn open_with_lock(dir: impl AsRef<Path>, lock: LockMode) -> io::Result<Self> {
-        let dir = dir.as_ref().to_path_buf();
-        let views_dir = dir.join("views");
-        let log_path = dir.join("app.jsonl");
-        let archive_path = dir.join("archive.jsonl.zst");
+        Self::from_storage(Arc::new(FsStorage::open(dir, lock)?), None)
+    }
 
-        fs::create_dir_all(&views_dir)?;
+    /// Create a writer over any [`Storage`]. Paths are reported relative to
+    /// the storage's local directory, or as bare file names if it has none.
+    ///
+    /// Counts the events in the active log and loads the sequence base, and
+    /// removes a partial line a crash left at its end. Lines are sealed
+    /// with `cipher`, which must match whether the log is encrypted.
+    pub(crate) fn from_storage(
+        storage: Arc<dyn Storage>,
+        cipher: Option<Arc<Cipher>>,
+    ) -> io::Result<Self> {
+        let mut writer = Self::over_storage(storage, false, cipher)?;
+        writer.truncate_partial_tail()?;
+        // An index left by an earlier writer goes stale as soon as this one
+        // appends without it.
+        writer.set_index(false)?;
+        Ok(writer)
+    }
 
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_path)?;
+    /// A writer over `storage` that refuses every write, for
+    /// [`EventLog::open_readonly`]. Touches no file.
+    pub(crate) fn from_storage_read_only(
+        storage: Arc<dyn Storage>,
+        cipher: Option<Arc<Cipher>>,
+    ) -> io::Result<Self> {
+        Self::over_storage(storage, true, cipher)
+    }
 
-        if lock == LockMode::Flock {
-            file.try_lock_exclusive().map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::AlreadyExists,
-                    format!(
-                        "another writer holds the lock on {}: {e}",
-                        log_path.display()
-                    ),
-                )
-            })?;
+    fn over_storage(
+        storage: Arc<dyn Storage>,
+        read_only: bool,
+        cipher: Option<Arc<Cipher>>,
+    ) -> io::Result<Self> {
+        let dir = storage.local_dir().unwrap_or(Path::new("")).to_path_buf();
+        let (manifest, manifest_is_new) = match storage.local_dir() {
+            Some(dir) => manifest::open(dir, !read_only)?,
+            None => (Manifest::default(), true),
+        };
+        let codec = manifest.archive_codec;
+        let mut writer = EventWriter {
+            storage,
+            log_path: dir.join("app.jsonl"),
+            archive_path: dir.join(codec.file_name()),
+            views_dir: dir.join("views"),
+            max_log_size: 0,
+            max_log_events: 0,
+            active_events: 0,
+            sequence_base: 0,
+            sync_policy: SyncPolicy::default(),
+            max_unsynced_events: 0,
+            unsynced_events: 0,
+            last_sync: None,
+            archive_dictionary: None,
+            archive_retention: None,
+            id_scheme: None,
+            schemas: Schemas::default(),
+            chain_head: None,
+            deferred_sync: None,
+            observer: Observer::default(),
+            upcasters: Upcasters::default(),
+            index: None,
+            ids: None,
+            dedup_window: DEFAULT_DEDUP_WINDOW,
+            recent: None,
+            read_only,
+            snapshot_writes: true,
+            pretty_snapshots: true,
+            snapshot_dir: None,
+            manifest,
+            manifest_is_new,
+            cipher: None,
+        };
+
+        writer.active_events = inspect::count_lines(BufReader::new(writer.storage.read_from(0)?))?;
+        writer.set_cipher(cipher)?;
+        if !read_only && let Some(dir) = writer.storage.local_dir() {
+            sequence::recover(dir)?;
         }
+        writer.sequence_base = writer.reader().sequence_base()?;
+        Ok(writer)
+    }
 
-        Ok(EventWriter {
-            file,
-            log_path,
-            archive_path,
-            views_dir,
-            max_log_size: 0,
-        })
+    /// Fail with `PermissionDenied` if the log was opened read-only.
+    fn check_writable(&self) -> io::Result<()> {
+        if self.read_only {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is opened read-only", self.dir().display()),
+            ));
+        }
+        Ok(())
     }
 
     /// Append an event to the log.
@@ -260,24 +626,362 @@ impl EventWriter {
     ///
     /// Returns `(AppendResult, needs_rotate)`.
     pub(crate) fn append_raw(&mut self, event: &Event) -> io::Result<(AppendResult, bool)> {
-        let start_offset = self.file.seek(SeekFrom::End(0))?;
-        let json = serde_json::to_string(event)
+        let (result, _) = self.write_line(event)?;
+        let needs_rotate = self.finish_append()?;
+        Ok((result, needs_rotate))
+    }
+
+    /// Append an event and return it exactly as it was written, along with
+    /// the [`AppendResult`].
+    ///
+    /// The returned event includes every field the writer fills in: an ID
+    /// from [`EventLogBuilder::auto_id`] and the `meta.prev` link from
+    /// [`EventLogBuilder::hash_chain`]. It can be broadcast or cached
+    /// without reading the log back. Syncing follows the [`SyncPolicy`],
+    /// as for [`append`](Self::append).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, IdScheme};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path()).auto_id(IdScheme::Ulid).open()?;
+    /// let (stored, result) = log.append_returning(Event::new("click", json!({})))?;
+    /// assert_eq!(stored.id, result.assigned_id);
+    ///
+    /// let (read, _, _) = log.read_from(result.start_offset)?.next().unwrap()?;
+    /// assert_eq!(read, stored);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization or writing to disk fails.
+    pub fn append_returning(&mut self, event: Event) -> io::Result<(Event, AppendResult)> {
+        let (stored, result, _) = self.append_returning_raw(event)?;
+        Ok((stored, result))
+    }
+
+    /// [`append_returning`](Self::append_returning), also indicating whether
+    /// rotation is needed.
+    pub(crate) fn append_returning_raw(
+        &mut self,
+        event: Event,
+    ) -> io::Result<(Event, AppendResult, bool)> {
+        let (result, stored) = self.write_line(&event)?;
+        let stored = match stored {
+            Cow::Owned(stored) => stored,
+            Cow::Borrowed(_) => event,
+        };
+        let needs_rotate = self.finish_append()?;
+        Ok((stored, result, needs_rotate))
+    }
+
+    /// Append `event` under the idempotency key `key`, unless an event with
+    /// that key was already appended — then return that event's
+    /// [`AppendResult`] without writing anything.
+    ///
+    /// The key is the event's `id`: an event without one is written with
+    /// `key` as its ID, so retries survive a restart. Duplicates are found
+    /// in a window of the most recent event IDs, 1024 by default (see
+    /// [`EventLogBuilder::dedup_window`]), which is seeded from the active
+    /// log on first use and then follows every append, idempotent or not.
+    /// A retry of an event that has fallen out of the window is written
+    /// again, unless [`EventLogBuilder::id_index`] is on: the window then
+    /// falls back to the index, making deduplication cover the full
+    /// history.
+    ///
+    /// The result returned for a duplicate is the one from when the event
+    /// was written; its offsets are stale if the log has rotated since.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let event = Event::new("payment", json!({"amount": 10}));
+    /// let first = writer.append_idempotent(&event, "cmd-42")?;
+    /// let retry = writer.append_idempotent(&event, "cmd-42")?;
+    /// assert_eq!(first, retry);
+    /// assert_eq!(writer.reader().read_from(0)?.count(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if `key` is empty or `event` has a different
+    /// `id`. Returns `AlreadyExists` if, through the ID index, the key is
+    /// found on an event that has since been archived. Returns an error if
+    /// the active log cannot be read to seed the window, or if
+    /// serialization or writing fails.
+    pub fn append_idempotent(&mut self, event: &Event, key: &str) -> io::Result<AppendResult> {
+        let (result, _) = self.append_idempotent_raw(event, key)?;
+        Ok(result)
+    }
+
+    /// [`append_idempotent`](Self::append_idempotent), also indicating
+    /// whether the event was written.
+    pub(crate) fn append_idempotent_raw(
+        &mut self,
+        event: &Event,
+        key: &str,
+    ) -> io::Result<(AppendResult, bool)> {
+        self.check_writable()?;
+        if key.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "an idempotency key cannot be empty",
+            ));
+        }
+        if let Some(id) = event.id.as_deref().filter(|&id| id != key) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("event id {id:?} does not match idempotency key {key:?}"),
+            ));
+        }
+        if let Some(result) = self.find_appended(key)? {
+            return Ok((result, false));
+        }
+
+        let keyed;
+        let event = if event.id.is_none() {
+            keyed = event.clone().with_id(key);
+            &keyed
+        } else {
+            event
+        };
+        let (result, _) = self.write_line(event)?;
+        self.finish_append()?;
+        Ok((result, true))
+    }
+
+    /// The result of writing the event with ID `key`, if the recent-key
+    /// window (seeded from the active log on first use) or the ID index
+    /// has it.
+    fn find_appended(&mut self, key: &str) -> io::Result<Option<AppendResult>> {
+        if self.recent.is_none() {
+            let mut recent = RecentKeys::new(self.dedup_window);
+            let mut start_offset = 0;
+            for (sequence, line) in (self.sequence_base..).zip(self.reader().read_from(0)?) {
+                let (event, end_offset, line_hash) = line?;
+                if let Some(id) = &event.id {
+                    let result = AppendResult {
+                        start_offset,
+                        end_offset,
+                        line_hash,
+                        assigned_id: None,
+                        sequence,
+                    };
+                    recent.insert(id, &result);
+                }
+                start_offset = end_offset;
+            }
+            self.recent = Some(recent);
+        }
+        if let Some(result) = self.recent.as_ref().and_then(|recent| recent.get(key)) {
+            return Ok(Some(result.clone()));
+        }
+
+        let Some(sequence) = self.ids.as_ref().and_then(|ids| ids.get(key)) else {
+            return Ok(None);
+        };
+        if sequence < self.sequence_base {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "an event with key {key:?} was appended and has been archived \
+                     (sequence {sequence})"
+                ),
+            ));
+        }
+        let reader = self.reader();
+        let Some(start_offset) = reader.offset_of_event(sequence - self.sequence_base)? else {
+            return Ok(None);
+        };
+        let Some(line) = reader.read_raw_from(start_offset)?.next() else {
+            return Ok(None);
+        };
+        let (_, end_offset, line_hash) = line?;
+        Ok(Some(AppendResult {
+            start_offset,
+            end_offset,
+            line_hash,
+            assigned_id: None,
+            sequence,
+        }))
+    }
+
+    /// Count a just-written event as unsynced, sync if the policy says so,
+    /// and report whether rotation is needed.
+    fn finish_append(&mut self) -> io::Result<bool> {
+        self.unsynced_events += 1;
+        if self.sync_due() {
+            self.sync()?;
+        }
+        self.rotation_due()
+    }
+
+    /// Whether the active log has reached `max_log_size` or `max_log_events`.
+    pub(crate) fn rotation_due(&self) -> io::Result<bool> {
+        if self.max_log_events > 0 && self.active_events >= self.max_log_events {
+            return Ok(true);
+        }
+        Ok(self.max_log_size > 0 && self.active_log_size()? >= self.max_log_size)
+    }
+
+    /// Serialize and write one event line, without syncing. Also returns
+    /// the event as written.
+    fn write_line<'a>(&mut self, event: &'a Event) -> io::Result<(AppendResult, Cow<'a, Event>)> {
+        self.check_writable()?;
+        let (json, assigned_id, stored) = self.serialize(event, self.chain_head.as_deref())?;
+        let result = self.write_json(&json, stored.id.as_deref(), assigned_id)?;
+        Ok((result, stored))
+    }
+
+    /// Append a line copied from another log exactly as it is, without
+    /// syncing beyond the [`SyncPolicy`]. The line must hold one event,
+    /// sealed with this log's key if it is encrypted; no ID is assigned and
+    /// no hash-chain link is added.
+    pub(crate) fn append_line(&mut self, line: &str) -> io::Result<AppendResult> {
+        self.check_writable()?;
+        if line.contains('\n') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "an event line cannot contain a newline",
+            ));
+        }
+        let json = crypto::open_line(self.cipher.as_deref(), line)?;
+        let event: Event = serde_json::from_str(&json)
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-        let hash = line_hash(json.as_bytes());
-        writeln!(self.file, "{json}")?;
-        self.file.sync_data()?;
+        let result = self.write_json(line, event.id.as_deref(), None)?;
+        self.finish_append()?;
+        Ok(result)
+    }
+
+    /// Write one serialized event line for an event with ID `id`, updating
+    /// the chain head, indexes and observer.
+    fn write_json(
+        &mut self,
+        json: &str,
+        id: Option<&str>,
+        assigned_id: Option<String>,
+    ) -> io::Result<AppendResult> {
+        let hash = self.manifest.hash_algo.hash(json.as_bytes());
+        let start_offset = self.storage.append(format!("{json}\n").as_bytes())?;
+        if self.chain_head.is_some() {
+            self.chain_head = Some(hash.clone());
+        }
+        if let Some(index) = &mut self.index {
+            index.record(self.active_events, start_offset)?;
+        }
+        let sequence = self.next_sequence();
+        if let Some(ids) = &self.ids
+            && let Some(id) = id
+        {
+            ids.insert(id, sequence);
+        }
+        self.active_events += 1;
         let end_offset = start_offset + json.len() as u64 + 1; // +1 for '\n'
 
-        let needs_rotate =
-            self.max_log_size > 0 && self.active_log_size()? >= self.max_log_size;
-        Ok((
-            AppendResult {
-                start_offset,
-                end_offset,
-                line_hash: hash,
-            },
-            needs_rotate,
-        ))
+        let result = AppendResult {
+            start_offset,
+            end_offset,
+            line_hash: hash,
+            assigned_id,
+            sequence,
+        };
+        if let Some(recent) = &mut self.recent
+            && let Some(id) = id
+        {
+            recent.insert(id, &result);
+        }
+        if let Some(observer) = self.observer.get() {
+            observer.on_append(&result);
+        }
+        Ok(result)
+    }
+
+    /// Validate and serialize one event line, first giving the event an ID
+    /// if this writer assigns them and it has none, and linking it to the
+    /// line hashed `prev` if hash chaining is on. Returns the line as
+    /// stored (sealed, if the log is encrypted), the assigned ID, and the
+    /// event as serialized.
+    fn serialize<'a>(
+        &self,
+        event: &'a Event,
+        prev: Option<&str>,
+    ) -> io::Result<(String, Option<String>, Cow<'a, Event>)> {
+        self.schemas.check(event)?;
+        let assigned_id = match self.id_scheme {
+            Some(scheme) if event.id.is_none() => Some(scheme.generate()?),
+            _ => None,
+        };
+        let mut event = Cow::Borrowed(event);
+        if let Some(id) = &assigned_id {
+            event.to_mut().id = Some(id.clone());
+        }
+        if let Some(prev) = prev {
+            event = Cow::Owned(chain::link(&event, prev)?);
+        }
+        let json = serde_json::to_string(&*event)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let line = crypto::seal_line(self.cipher.as_deref(), json)?;
+        Ok((line, assigned_id, event))
+    }
+
+    /// Append an event and return without waiting for it to be durable.
+    ///
+    /// The event is written immediately, so readers see it as soon as this
+    /// returns. Durability is handled by a background thread that batches
+    /// pending deferred appends into one `fsync`; the returned [`SyncHandle`]
+    /// reports when this event is covered. An explicit [`sync`](Self::sync)
+    /// also completes every outstanding handle.
+    ///
+    /// Deferred appends bypass the [`SyncPolicy`] and are not counted by
+    /// [`unsynced_events`](Self::unsynced_events).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let event = Event::new("click", json!({}));
+    /// let (result, handle) = writer.append_deferred_sync(&event)?;
+    /// assert_eq!(result.start_offset, 0);
+    /// assert_eq!(writer.reader().read_from(0)?.count(), 1); // already visible
+    /// handle.wait()?; // now durable
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization or writing fails, if the background
+    /// sync thread cannot be started, or if an earlier background sync
+    /// failed (in which case nothing is written).
+    pub fn append_deferred_sync(
+        &mut self,
+        event: &Event,
+    ) -> io::Result<(AppendResult, SyncHandle)> {
+        match &self.deferred_sync {
+            Some(deferred) => deferred.check()?,
+            None => self.deferred_sync = Some(DeferredSync::start(Arc::clone(&self.storage))?),
+        }
+
+        let (result, _) = self.write_line(event)?;
+        let handle = self
+            .deferred_sync
+            .as_ref()
+            .expect("deferred sync was started above")
+            .push();
+        Ok((result, handle))
     }
 
     /// Append an event only if the log's current state matches expectations.
@@ -352,697 +1056,5804 @@ impl EventWriter {
         Ok(self.append(event)?)
     }
 
-    /// Manually trigger log rotation.
+    /// Append an event at the current end of the log, retrying on conflict.
     ///
-    /// Refreshes all views from the reader, compresses the active log to the
-    /// archive, truncates the active log, and resets all view offsets.
+    /// Reads the active log's current size and last line hash, then calls
+    /// [`append_if`](Self::append_if) with them. If another writer got in
+    /// between, it re-reads and tries again, up to `max_attempts` times in
+    /// all (at least once). With [`LockMode::None`] this lets several
+    /// processes share a log optimistically instead of the first one
+    /// locking out the rest.
+    ///
+    /// The check and the write are not atomic across processes: two
+    /// writers can both pass the check and then both append. Because the
+    /// log is opened in append mode each line still lands whole at the end
+    /// of the file, but an event may follow one its writer never saw. Use
+    /// this where interleaving is harmless, not to enforce invariants
+    /// across writers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, LockMode};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut a = EventWriter::open_with_lock(dir.path(), LockMode::None)?;
+    /// let mut b = EventWriter::open_with_lock(dir.path(), LockMode::None)?;
+    /// a.append_with_retry(&Event::new("from_a", json!({})), 3)?;
+    /// b.append_with_retry(&Event::new("from_b", json!({})), 3)?;
+    /// assert_eq!(a.reader().read_from(0)?.count(), 2);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if reading the log, compressing to archive,
-    /// truncating the file, or saving view snapshots fails.
-    pub fn rotate(
+    /// Returns the last [`ConditionalAppendError::Conflict`] if every
+    /// attempt conflicted. Returns [`ConditionalAppendError::Io`] on I/O
+    /// failures.
+    pub fn append_with_retry(
         &mut self,
-        reader: &EventReader,
-        views: &mut HashMap<String, Box<dyn ViewOps>>,
-    ) -> io::Result<()> {
-        // 1. Refresh all views so snapshots reflect everything in app.jsonl
-        for view in views.values_mut() {
-            view.refresh_boxed(reader)?;
-        }
-
-        // 2. Read active log contents
-        let contents = fs::read(&self.log_path)?;
-
-        // 3. No-op if empty
-        if contents.is_empty() {
-            return Ok(());
-        }
-
-        // 4. Compress and append to archive
-        archive::append_compressed_frame(&self.archive_path, &contents)?;
-
-        // 5. Truncate active log
-        self.file.set_len(0)?;
-        self.file.sync_data()?;
-
-        // 6. Reset all view offsets and save snapshots
-        for view in views.values_mut() {
-            view.reset_offset()?;
+        event: &Event,
+        max_attempts: u32,
+    ) -> Result<AppendResult, ConditionalAppendError> {
+        let mut attempt = 1;
+        loop {
+            let offset = self.active_log_size()?;
+            let hash = self
+                .reader()
+                .read_line_hash_before(offset)?
+                .unwrap_or_default();
+            match self.append_if(event, offset, &hash) {
+                Err(ConditionalAppendError::Conflict(_)) if attempt < max_attempts => {
+                    attempt += 1;
+                }
+                result => return result,
+            }
         }
-
-        Ok(())
     }
 
-    /// Get a cloneable reader pointing at the same log paths.
-    pub fn reader(&self) -> EventReader {
-        EventReader {
-            log_path: self.log_path.clone(),
-            archive_path: self.archive_path.clone(),
-        }
-    }
-
-    /// Returns the path to the data directory.
-    ///
-    /// # Panics
+    /// Append several events as one transaction.
     ///
-    /// Panics if the log path has no parent directory (should never occur
-    /// because the path is always constructed as `dir/app.jsonl`).
-    pub fn dir(&self) -> &Path {
-        self.log_path
-            .parent()
-            .expect("log_path always has a parent directory")
-    }
-
-    /// Returns the path to the active log file.
-    pub fn log_path(&self) -> &Path {
-        &self.log_path
-    }
-
-    /// Returns the path to the archive file.
-    pub fn archive_path(&self) -> &Path {
-        &self.archive_path
-    }
-
-    /// Returns the path to the `views/` directory.
-    pub fn views_dir(&self) -> &Path {
-        &self.views_dir
-    }
-
-    /// Returns the current size of `app.jsonl` in bytes.
+    /// All lines are serialized into a single buffer, written with one
+    /// `write_all`, and synced once — regardless of the [`SyncPolicy`].
+    /// Each event in a multi-event transaction is tagged with a marker in
+    /// its `meta` (under the reserved `"txn"` key) so that
+    /// [`EventReader::read_transactions_from`] can group them and discard a
+    /// transaction torn by a crash. A one-event transaction is written as a
+    /// plain, unmarked append.
     ///
-    /// # Errors
+    /// The transaction is atomic with respect to a crash: once this returns,
+    /// every line is durable; if the process dies mid-write, the lines that
+    /// did land form a prefix ending in a partial line, and
+    /// `read_transactions_from` drops the whole transaction. Plain
+    /// [`EventReader::read_from`] still yields its complete lines. It is
+    /// otherwise an ordinary append — there is no rollback, and a
+    /// transaction already synced stays in the log.
     ///
-    /// Returns an error if the file metadata cannot be read.
-    pub fn active_log_size(&self) -> io::Result<u64> {
-        Ok(fs::metadata(&self.log_path)?.len())
-    }
-
-    /// Set the maximum active log size for auto-rotation checks.
-    pub(crate) fn set_max_log_size(&mut self, bytes: u64) {
-        self.max_log_size = bytes;
-    }
-}
-
-/// Cheap, cloneable reader for an event log.
-///
-/// Opens fresh file handles per read call. Safe to use concurrently
-/// with an [`EventWriter`] on the same log — completed lines are immutable,
-/// and partial lines at EOF are detected and skipped.
-///
-/// # Examples
-///
-/// ```
-/// # use tempfile::tempdir;
-/// use eventfold::{Event, EventWriter, EventReader};
-/// use serde_json::json;
-/// # let dir = tempdir()?;
-/// let mut writer = EventWriter::open(dir.path())?;
-/// writer.append(&Event::new("click", json!({})))?;
-/// let reader = writer.reader();
-/// let events: Vec<_> = reader.read_from(0)?
-///     .collect::<Result<Vec<_>, _>>()?;
-/// assert_eq!(events.len(), 1);
-/// # Ok::<(), std::io::Error>(())
-/// ```
-#[derive(Debug, Clone)]
-pub struct EventReader {
-    log_path: PathBuf,
-    archive_path: PathBuf,
-}
-
-impl EventReader {
-    /// Create a reader pointing at the given log directory.
+    /// Returns one [`AppendResult`] per event, in order.
     ///
     /// # Examples
     ///
     /// ```
     /// # use tempfile::tempdir;
-    /// use eventfold::EventReader;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
     /// # let dir = tempdir()?;
-    /// let reader = EventReader::new(dir.path());
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let results = writer.append_transaction(&[
+    ///     Event::new("debit", json!({"amount": 10})),
+    ///     Event::new("credit", json!({"amount": 10})),
+    /// ])?;
+    /// assert_eq!(results.len(), 2);
+    /// assert_eq!(results[1].start_offset, results[0].end_offset);
     /// # Ok::<(), std::io::Error>(())
     /// ```
-    pub fn new(dir: impl AsRef<Path>) -> Self {
-        let dir = dir.as_ref();
-        EventReader {
-            log_path: dir.join("app.jsonl"),
-            archive_path: dir.join("archive.jsonl.zst"),
-        }
-    }
-
-    /// Read events from the active log starting at the given byte offset.
-    ///
-    /// Returns an iterator yielding `(event, next_byte_offset, line_hash)` for
-    /// each complete line. Empty lines are skipped. Partial lines (missing
-    /// trailing newline) are skipped silently.
     ///
     /// # Errors
     ///
-    /// Returns an error if the log file cannot be opened or seeked.
-    /// Individual iterator items may also yield errors on malformed JSON lines.
-    pub fn read_from(
-        &self,
-        offset: u64,
-    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
-        let mut file = File::open(&self.log_path)?;
-        file.seek(SeekFrom::Start(offset))?;
-
-        let file_len = file.metadata()?.len();
-        let reader = BufReader::new(file);
+    /// Returns `InvalidInput` if an event's `meta` is set to a non-object
+    /// value (the marker cannot be attached). Returns an error if
+    /// serialization or writing to disk fails.
+    pub fn append_transaction(&mut self, events: &[Event]) -> io::Result<Vec<AppendResult>> {
+        if events.len() == 1 {
+            return Ok(vec![self.append(&events[0])?]);
+        }
 
-        Ok(LogIterator {
-            lines: reader.lines(),
-            pos: offset,
-            file_len,
-        })
+        let marked = events
+            .iter()
+            .enumerate()
+            .map(|(index, event)| txn::mark(event, index, events.len()))
+            .collect::<io::Result<Vec<_>>>()?;
+        self.write_batch(&marked)
     }
 
-    /// Read the full event history: archive (if any) + active log.
+    /// Append several events with one write and one sync.
     ///
-    /// Returns an iterator yielding `(event, line_hash)` for each event
-    /// across all archived frames and the current active log.
+    /// All lines are serialized into a single buffer, written with one
+    /// `write_all`, and synced once — regardless of the [`SyncPolicy`]. The
+    /// lines are exactly what individual [`append`](Self::append) calls
+    /// would write, and so are the returned [`AppendResult`]s (one per
+    /// event, in order). Unlike [`append_transaction`](Self::append_transaction),
+    /// events are not marked, so a crash mid-write may leave a prefix of the
+    /// batch in the log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let events: Vec<_> = (0..100)
+    ///     .map(|i| Event::new("imported", json!({"row": i})))
+    ///     .collect();
+    /// let results = writer.append_batch(&events)?;
+    /// assert_eq!(results.len(), 100);
+    /// assert_eq!(results[99].end_offset, writer.active_log_size()?);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if the archive or active log cannot be opened.
-    /// Individual iterator items may also yield errors on malformed lines.
-    pub fn read_full(&self) -> io::Result<FullEventIter> {
-        let archive_iter: Box<dyn Iterator<Item = io::Result<(Event, String)>>> =
-            match archive::open_archive_reader(&self.archive_path)? {
-                Some(reader) => Box::new(EventLineIter {
-                    reader,
-                    buf: String::new(),
-                }),
-                None => Box::new(std::iter::empty()),
-            };
+    /// Returns an error if serialization or writing to disk fails. Nothing
+    /// is written if any event fails to serialize.
+    pub fn append_batch(&mut self, events: &[Event]) -> io::Result<Vec<AppendResult>> {
+        self.write_batch(events)
+    }
 
-        let file = File::open(&self.log_path)?;
-        let reader = BufReader::new(file);
-        let active_iter: Box<dyn Iterator<Item = io::Result<(Event, String)>>> =
-            Box::new(EventLineIter {
-                reader,
-                buf: String::new(),
-            });
+    /// Serialize `events` into one buffer, write it, and sync once.
+    fn write_batch(&mut self, events: &[Event]) -> io::Result<Vec<AppendResult>> {
+        self.check_writable()?;
+        let mut buf = Vec::new();
+        let mut lines = Vec::with_capacity(events.len());
+        let mut prev = self.chain_head.clone();
+        for event in events {
+            let (json, assigned_id, _) = self.serialize(event, prev.as_deref())?;
+            let len = json.len() as u64 + 1; // +1 for '\n'
+            let hash = self.manifest.hash_algo.hash(json.as_bytes());
+            if prev.is_some() {
+                prev = Some(hash.clone());
+            }
+            lines.push((len, hash, assigned_id));
+            buf.extend_from_slice(json.as_bytes());
+            buf.push(b'\n');
+        }
+        if buf.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        Ok(Box::new(archive_iter.chain(active_iter)))
+        let mut start_offset = self.storage.append(&buf)?;
+        self.chain_head = prev;
+        if let Some(index) = &mut self.index {
+            let mut offset = start_offset;
+            for (i, (len, ..)) in lines.iter().enumerate() {
+                index.record(self.active_events + i as u64, offset)?;
+                offset += len;
+            }
+        }
+        let mut sequence = self.next_sequence();
+        self.active_events += lines.len() as u64;
+        self.sync()?;
+        let results: Vec<AppendResult> = lines
+            .into_iter()
+            .map(|(len, line_hash, assigned_id)| {
+                let result = AppendResult {
+                    start_offset,
+                    end_offset: start_offset + len,
+                    line_hash,
+                    assigned_id,
+                    sequence,
+                };
+                start_offset = result.end_offset;
+                sequence += 1;
+                result
+            })
+            .collect();
+        for (event, result) in events.iter().zip(&results) {
+            if let Some(id) = result.assigned_id.as_deref().or(event.id.as_deref()) {
+                if let Some(ids) = &self.ids {
+                    ids.insert(id, result.sequence);
+                }
+                if let Some(recent) = &mut self.recent {
+                    recent.insert(id, result);
+                }
+            }
+        }
+        if let Some(observer) = self.observer.get() {
+            results.iter().for_each(|result| observer.on_append(result));
+        }
+        Ok(results)
     }
 
-    /// Read the line immediately before the given byte offset and return its hash.
+    /// Manually trigger log rotation.
     ///
-    /// The offset should point to the byte after the newline of the last consumed line.
-    /// Returns `None` if offset is 0 or beyond the file.
+    /// Refreshes all views from the reader, compresses the active log to the
+    /// archive, truncates the active log, and resets all view offsets.
     ///
     /// # Errors
     ///
-    /// Returns an error if the log file cannot be opened or read.
-    pub fn read_line_hash_before(&self, offset: u64) -> io::Result<Option<String>> {
-        if offset == 0 {
-            return Ok(None);
+    /// Returns an error if reading the log, compressing to archive,
+    /// truncating the file, or saving view snapshots fails.
+    pub fn rotate(
+        &mut self,
+        reader: &EventReader,
+        views: &mut HashMap<String, Box<dyn ViewOps>>,
+    ) -> io::Result<()> {
+        self.check_writable()?;
+
+        // 1. Refresh all views so snapshots reflect everything in app.jsonl
+        for view in views.values_mut() {
+            view.refresh_boxed(reader)?;
         }
 
-        let mut file = File::open(&self.log_path)?;
-        let file_len = file.metadata()?.len();
+        // 2. Read active log contents
+        let mut contents = Vec::new();
+        self.storage.read_from(0)?.read_to_end(&mut contents)?;
 
-        if offset > file_len {
-            return Ok(None);
+        // 3. No-op if empty
+        if contents.is_empty() {
+            return Ok(());
         }
 
-        // offset - 1 is the '\n' at end of previous line
-        // Scan backwards from offset - 2 to find start of that line
-        let newline_pos = offset - 1;
-        let mut start = 0u64;
+        // 4. Encode and append to archive (dictionary recorded first, so
+        //    the frame is never unreadable)
+        if let Some(dict) = &self.archive_dictionary {
+            archive::register_dictionary(&self.archive_path, dict)?;
+        }
+        let codec = Codec::from_path(&self.archive_path);
+        let frame = codec.encode_frame(&contents, self.archive_dictionary.as_deref())?;
+        self.storage.append_archive(&frame)?;
 
-        if newline_pos > 0 {
-            let scan_start = newline_pos.saturating_sub(8192);
-            file.seek(SeekFrom::Start(scan_start))?;
-            let mut buf = vec![0u8; (newline_pos - scan_start) as usize];
-            file.read_exact(&mut buf)?;
+        // 5. Truncate active log (everything unsynced is now durable in the
+        //    archive). The sequence base moves first: a crash in between
+        //    skips sequence numbers rather than reusing them.
+        self.advance_sequence(self.active_events)?;
+        self.clear_index()?;
+        self.storage.truncate()?;
+        self.active_events = 0;
+        self.sync()?;
 
-            if let Some(pos) = buf.iter().rposition(|&b| b == b'\n') {
-                start = scan_start + pos as u64 + 1;
-            } else {
-                start = scan_start;
-            }
+        // 6. Drop the archive frames the retention policy no longer keeps
+        if let Some(retention) = self.archive_retention {
+            self.apply_archive_retention(retention)?;
         }
 
-        file.seek(SeekFrom::Start(start))?;
-        let line_len = (newline_pos - start) as usize;
-        let mut line_buf = vec![0u8; line_len];
-        file.read_exact(&mut line_buf)?;
+        // 7. Reset all view offsets and save snapshots
+        for view in views.values_mut() {
+            view.reset_offset()?;
+        }
 
-        Ok(Some(line_hash(&line_buf)))
+        if let Some(observer) = self.observer.get() {
+            observer.on_rotate(contents.len() as u64);
+        }
+        Ok(())
     }
 
-    /// Returns the current size of `app.jsonl` in bytes.
+    /// Flush all appended data to stable storage now, regardless of the
+    /// configured [`SyncPolicy`].
     ///
-    /// This is a lightweight "version" check — if the size hasn't
-    /// changed, no new events have been appended.
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("click", json!({})))?;
+    /// writer.sync()?;
+    /// assert_eq!(writer.unsynced_events(), 0);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if the file metadata cannot be read.
-    pub fn active_log_size(&self) -> io::Result<u64> {
-        Ok(fs::metadata(&self.log_path)?.len())
+    /// Returns an error if `fsync` fails.
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.storage.sync()?;
+        self.unsynced_events = 0;
+        self.restart_sync_interval();
+        if let Some(deferred) = &self.deferred_sync {
+            deferred.mark_synced();
+        }
+        Ok(())
     }
 
-    /// Returns `true` if the active log contains data beyond `offset`.
+    /// Returns the number of appended events not yet flushed to stable storage.
     ///
-    /// This is a non-blocking metadata check (stat call). Use it to
-    /// implement poll-based tailing:
+    /// Always 0 under [`SyncPolicy::EveryAppend`].
+    pub fn unsynced_events(&self) -> u64 {
+        self.unsynced_events
+    }
+
+    /// Remove a partial line left at the end of the active log by a crash
+    /// mid-append, and return the number of bytes removed.
     ///
-    /// ```no_run
-    /// # use eventfold::EventReader;
-    /// let reader = EventReader::new("./data");
-    /// let mut offset = 0u64;
-    /// loop {
-    ///     if reader.has_new_events(offset).unwrap() {
-    ///         for result in reader.read_from(offset).unwrap() {
-    ///             let (event, next_offset, _hash) = result.unwrap();
-    ///             // process event
-    ///             offset = next_offset;
-    ///         }
-    ///     }
-    ///     std::thread::sleep(std::time::Duration::from_millis(50));
-    /// }
+    /// Readers already skip such a line, but an append after it would be
+    /// glued onto the garbage and become unparseable itself. Opening a
+    /// writer calls this, so it is only needed if something else may have
+    /// written to the log since. Only an unterminated tail is removed; a
+    /// complete line that fails to parse is left for
+    /// [`EventLog::repair`](crate::EventLog::repair).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// use std::io::Write;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let result = writer.append(&Event::new("a", json!({})))?;
+    /// std::fs::OpenOptions::new()
+    ///     .append(true)
+    ///     .open(writer.log_path())?
+    ///     .write_all(b"{\"type\":\"b\"")?;
+    ///
+    /// assert_eq!(writer.truncate_partial_tail()?, 11);
+    /// assert_eq!(writer.active_log_size()?, result.end_offset);
+    /// # Ok::<(), std::io::Error>(())
     /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if the file metadata cannot be read.
-    pub fn has_new_events(&self, offset: u64) -> io::Result<bool> {
-        Ok(fs::metadata(&self.log_path)?.len() > offset)
+    /// Returns `PermissionDenied` if the writer is read-only, or an error if
+    /// reading the log's tail or truncating it fails.
+    pub fn truncate_partial_tail(&mut self) -> io::Result<u64> {
+        self.check_writable()?;
+        let size = self.storage.size()?;
+        let complete = ReverseLines::new(Arc::clone(&self.storage))?.complete_len();
+        if complete == size {
+            return Ok(0);
+        }
+        self.storage.truncate_to(complete)?;
+        self.storage.sync()?;
+        Ok(size - complete)
     }
 
-    /// Block until new data appears after `offset` in the active log,
-    /// or until `timeout` elapses.
+    /// Returns `true` if any reader, in this process or another, holds a
+    /// lock from [`EventReader::try_lock_shared`] on this log.
     ///
-    /// Uses OS-level file system notifications (inotify on Linux,
-    /// kqueue on macOS, ReadDirectoryChangesW on Windows) for
-    /// near-zero-latency detection.
+    /// The answer is a snapshot: readers may take or release locks right
+    /// after it is given.
     ///
-    /// # Example
+    /// # Examples
     ///
-    /// ```no_run
-    /// # use eventfold::{EventReader, WaitResult};
-    /// # use std::time::Duration;
-    /// let reader = EventReader::new("./data");
-    /// let mut offset = 0u64;
-    /// loop {
-    ///     match reader.wait_for_events(offset, Duration::from_secs(5)).unwrap() {
-    ///         WaitResult::NewData(new_size) => {
-    ///             for result in reader.read_from(offset).unwrap() {
-    ///                 let (event, next_offset, _hash) = result.unwrap();
-    ///                 // process event
-    ///                 offset = next_offset;
-    ///             }
-    ///         }
-    ///         WaitResult::Timeout => {
-    ///             // No new events — do periodic housekeeping, etc.
-    ///         }
-    ///     }
-    /// }
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::EventWriter;
+    /// # let dir = tempdir()?;
+    /// # #[cfg(feature = "std-fs")]
+    /// # {
+    /// let writer = EventWriter::open(dir.path())?;
+    /// assert!(!writer.readers_present()?);
+    ///
+    /// let lock = writer.reader().try_lock_shared()?;
+    /// assert!(writer.readers_present()?);
+    /// drop(lock);
+    /// assert!(!writer.readers_present()?);
+    /// # }
+    /// # Ok::<(), std::io::Error>(())
     /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if the file watcher cannot be initialized, if
-    /// the watcher channel disconnects, or if reading file metadata fails.
-    pub fn wait_for_events(
-        &self,
-        offset: u64,
-        timeout: Duration,
-    ) -> io::Result<WaitResult> {
-        // Check immediately — data may already be available.
-        let current_size = self.active_log_size()?;
-        if current_size > offset {
-            return Ok(WaitResult::NewData(current_size));
+    /// Returns `Unsupported` if the log's [`Storage`] has no local
+    /// directory. Returns an error if the lock file cannot be opened or
+    /// probed.
+    pub fn readers_present(&self) -> io::Result<bool> {
+        let dir = require_local(self.storage.as_ref(), "reader locks")?;
+        reader_lock::readers_present(dir)
+    }
+
+    /// Whether the sync policy or the unsynced-event bound calls for a sync now.
+    fn sync_due(&self) -> bool {
+        if self.max_unsynced_events > 0 && self.unsynced_events >= self.max_unsynced_events {
+            return true;
+        }
+        match self.sync_policy {
+            SyncPolicy::EveryAppend => true,
+            SyncPolicy::Interval(interval) => {
+                self.last_sync.is_none_or(|last| last.elapsed() >= interval)
+            }
+            SyncPolicy::EveryN(n) => self.unsynced_events >= n,
+            SyncPolicy::Never => false,
         }
+    }
 
-        // Set up a file watcher on the log file's parent directory.
-        let (tx, rx) = mpsc::channel();
-        let mut watcher =
-            notify::recommended_watcher(move |res: Result<notify::Event, _>| {
-                if let Ok(event) = res
-                    && matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_))
-                {
-                    let _ = tx.send(());
-                }
-            })
-            .map_err(io::Error::other)?;
+    /// Get a cloneable reader pointing at the same log.
+    pub fn reader(&self) -> EventReader {
+        // A local directory gets its own read-only handle, so readers never
+        // keep the writer's lock alive.
+        let storage: Arc<dyn Storage> = match self.storage.local_dir() {
+            Some(dir) => {
+                let codec = Codec::from_path(&self.archive_path);
+                Arc::new(FsStorage::read_only(dir).with_archive_codec(codec))
+            }
+            None => Arc::clone(&self.storage),
+        };
+        EventReader {
+            storage,
+            log_path: self.log_path.clone(),
+            archive_path: self.archive_path.clone(),
+            observer: self.observer.clone(),
+            upcasters: self.upcasters.clone(),
+            ids: self.ids.clone(),
+            hash_algo: self.manifest.hash_algo,
+            cipher: self.cipher.clone(),
+            #[cfg(feature = "mmap")]
+            mmap: None,
+        }
+    }
 
-        watcher
-            .watch(
-                self.log_path.parent().unwrap_or(&self.log_path),
-                RecursiveMode::NonRecursive,
-            )
-            .map_err(io::Error::other)?;
+    /// Returns the path to the data directory. Empty if the log's
+    /// [`Storage`] has no local directory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the log path has no parent directory (should never occur
+    /// because the path is always constructed as `dir/app.jsonl`).
+    pub fn dir(&self) -> &Path {
+        self.log_path
+            .parent()
+            .expect("log_path always has a parent directory")
+    }
 
-        // Re-check after watcher is set up (avoid TOCTOU race).
-        let current_size = self.active_log_size()?;
-        if current_size > offset {
-            return Ok(WaitResult::NewData(current_size));
+    /// Returns the path to the active log file.
+    pub fn log_path(&self) -> &Path {
+        &self.log_path
+    }
+
+    /// The log directory's [`Manifest`]: its format versions and the
+    /// settings fixed when it was created.
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Returns the path to the archive file.
+    pub fn archive_path(&self) -> &Path {
+        &self.archive_path
+    }
+
+    /// Returns the path to the views directory: `views/` in the log
+    /// directory, unless the [`EventLogBuilder`] that opened this writer
+    /// moved it with [`views_dir_name`](EventLogBuilder::views_dir_name)
+    /// or [`views_path`](EventLogBuilder::views_path).
+    pub fn views_dir(&self) -> &Path {
+        &self.views_dir
+    }
+
+    /// Returns the current size of `app.jsonl` in bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file metadata cannot be read.
+    pub fn active_log_size(&self) -> io::Result<u64> {
+        self.storage.size()
+    }
+
+    /// The storage backing this writer.
+    pub(crate) fn storage(&self) -> &Arc<dyn Storage> {
+        &self.storage
+    }
+
+    /// Where a view named `view` keeps its snapshot in this writer's storage.
+    pub(crate) fn snapshot_store(&self, view: &str) -> SnapshotStore {
+        let store = match &self.snapshot_dir {
+            Some(dir) => SnapshotStore::File(dir.join(format!("{view}.snapshot.json"))),
+            None => SnapshotStore::Storage {
+                storage: Arc::clone(&self.storage),
+                view: view.to_string(),
+            },
+        };
+        let store = match &self.cipher {
+            Some(cipher) => SnapshotStore::Encrypted(Box::new(store), Arc::clone(cipher)),
+            None => store,
+        };
+        let store = if self.pretty_snapshots {
+            store
+        } else {
+            SnapshotStore::Compact(Box::new(store))
+        };
+        if self.snapshot_writes {
+            store
+        } else {
+            SnapshotStore::ReadOnly(Box::new(store))
         }
+    }
 
-        // Wait for a notification or timeout.
-        match rx.recv_timeout(timeout) {
-            Ok(()) => {
-                let new_size = self.active_log_size()?;
-                if new_size > offset {
-                    Ok(WaitResult::NewData(new_size))
+    /// Set whether views opened from this writer save their snapshots.
+    pub(crate) fn set_snapshot_writes(&mut self, enabled: bool) {
+        self.snapshot_writes = enabled;
+    }
+
+    /// Set whether views opened from this writer indent their snapshots.
+    pub(crate) fn set_pretty_snapshots(&mut self, enabled: bool) {
+        self.pretty_snapshots = enabled;
+    }
+
+    /// Keep view snapshots in `dir` rather than the storage's `views/`.
+    pub(crate) fn set_snapshot_dir(&mut self, dir: PathBuf) {
+        self.snapshot_dir = Some(dir);
+    }
+
+    /// The directory snapshot files are kept in, or `None` if snapshots
+    /// live in a [`Storage`] without one.
+    #[cfg(feature = "testing")]
+    pub(crate) fn snapshot_files_dir(&self) -> Option<&Path> {
+        self.snapshot_dir
+            .as_deref()
+            .or_else(|| self.storage.local_dir().map(|_| self.views_dir.as_path()))
+    }
+
+    /// Move the whole views tree to `dir`. Snapshots follow it unless a
+    /// snapshot directory of their own is already set.
+    pub(crate) fn set_views_dir(&mut self, dir: PathBuf) {
+        self.snapshot_dir.get_or_insert_with(|| dir.clone());
+        self.views_dir = dir;
+    }
+
+    /// Set the maximum active log size for auto-rotation checks.
+    pub(crate) fn set_max_log_size(&mut self, bytes: u64) {
+        self.max_log_size = bytes;
+    }
+
+    /// Set the maximum active log event count for auto-rotation checks.
+    pub(crate) fn set_max_log_events(&mut self, n: u64) {
+        self.max_log_events = n;
+    }
+
+    /// Estimate what [`rotate`](Self::rotate) would do, encoding the active
+    /// log into a counting sink rather than the archive.
+    pub(crate) fn rotation_preview(&self) -> io::Result<RotationPreview> {
+        let codec = Codec::from_path(&self.archive_path);
+        let mut log = self.storage.read_from(0)?;
+        let dict = self.archive_dictionary.as_deref();
+        let (active_log_size, estimated_frame_size) = match codec.encoded_len(&mut log, dict)? {
+            (0, _) => (0, 0),
+            sizes => sizes,
+        };
+        let archive_size = match self.storage.read_archive()? {
+            Some(mut raw) => io::copy(&mut raw, &mut io::sink())?,
+            None => 0,
+        };
+        Ok(RotationPreview {
+            active_log_size,
+            estimated_frame_size,
+            archive_size,
+        })
+    }
+
+    /// Set the codec future archive frames are encoded with, which also
+    /// names the archive file.
+    pub(crate) fn set_archive_codec(&mut self, codec: Codec) -> io::Result<()> {
+        self.archive_path = self.archive_path.with_file_name(codec.file_name());
+        if codec == self.manifest.archive_codec {
+            return Ok(());
+        }
+        self.manifest.archive_codec = codec;
+        self.save_manifest()
+    }
+
+    /// Hash lines with `algo`, recording it in the manifest. Only a log
+    /// whose manifest this writer created, with no history, can switch.
+    pub(crate) fn set_hash_algo(&mut self, algo: HashAlgo) -> io::Result<()> {
+        if algo == self.manifest.hash_algo {
+            return Ok(());
+        }
+        let has_history = self.active_events > 0 || self.storage.read_archive()?.is_some();
+        if !self.manifest_is_new || has_history {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "the log in {} hashes its lines with {}, not {algo}",
+                    self.dir().display(),
+                    self.manifest.hash_algo
+                ),
+            ));
+        }
+        require_local(self.storage.as_ref(), "a hash algorithm other than xxh64")?;
+        self.manifest.hash_algo = algo;
+        self.save_manifest()
+    }
+
+    /// Seal lines and snapshots with `cipher`, recording in the manifest
+    /// that the log is encrypted. Only a log whose manifest this writer
+    /// created, with no history, can become encrypted, and an encrypted
+    /// log opens only with a cipher. Without a local directory there is no
+    /// manifest to check, so the cipher is taken on trust.
+    fn set_cipher(&mut self, cipher: Option<Arc<Cipher>>) -> io::Result<()> {
+        if cipher.is_some() != self.manifest.encrypted {
+            let has_history = self.active_events > 0 || self.storage.read_archive()?.is_some();
+            let unchecked = self.storage.local_dir().is_none();
+            if cipher.is_none() || !self.manifest_is_new || (has_history && !unchecked) {
+                let problem = if self.manifest.encrypted {
+                    "is encrypted; open it with its key"
                 } else {
-                    // Spurious wakeup (e.g., metadata change, not a write).
-                    // For simplicity, return Timeout. Caller will retry.
-                    Ok(WaitResult::Timeout)
+                    "is not encrypted"
+                };
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("the log in {} {problem}", self.dir().display()),
+                ));
+            }
+            self.manifest.encrypted = true;
+            self.save_manifest()?;
+        }
+        self.cipher = cipher;
+        Ok(())
+    }
+
+    /// The cipher lines are sealed with, if the log is encrypted.
+    pub(crate) fn cipher(&self) -> Option<&Arc<Cipher>> {
+        self.cipher.as_ref()
+    }
+
+    /// Save the manifest, unless the log is read-only or has no local
+    /// directory.
+    fn save_manifest(&self) -> io::Result<()> {
+        match self.storage.local_dir() {
+            Some(dir) if !self.read_only => manifest::save(dir, &self.manifest),
+            _ => Ok(()),
+        }
+    }
+
+    /// Set the zstd dictionary used to compress future archive frames.
+    pub(crate) fn set_archive_dictionary(&mut self, dict: Option<Vec<u8>>) {
+        self.archive_dictionary = dict;
+    }
+
+    /// Atomically replace the active log with `bytes`, which hold `events`
+    /// complete events. The new contents are durable on return.
+    ///
+    /// The kept events are renumbered to end where the old log did, so
+    /// later appends never reuse a sequence number. The new base is staged
+    /// as pending until the log is replaced, so a crash in between never
+    /// pairs it with the old log.
+    pub(crate) fn replace_active_log(&mut self, bytes: &[u8], events: u64) -> io::Result<()> {
+        self.check_writable()?;
+        let dropped = self.active_events.saturating_sub(events);
+        let dir = self.storage.local_dir().filter(|_| dropped > 0).map(Path::to_path_buf);
+        if let Some(dir) = &dir {
+            sequence::save_pending(dir, self.sequence_base + dropped, bytes)?;
+        }
+        self.clear_index()?;
+        self.storage.replace_log(bytes)?;
+        self.active_events = events;
+        self.advance_sequence(dropped)?;
+        if let Some(dir) = &dir {
+            sequence::clear_pending(dir)?;
+        }
+        if self.chain_head.is_some() {
+            self.chain_head = Some(self.last_line_hash()?);
+        }
+        if self.index.is_some() {
+            self.set_index(true)?;
+        }
+        if let Some(ids) = &self.ids {
+            ids.rebuild(&self.reader())?;
+        }
+        self.recent = None;
+        self.sync()
+    }
+
+    /// Replace every event in the archive and the active log for which
+    /// `predicate` returns `true` with a tombstone, re-encoding only the
+    /// archive frames that change. Returns the number of events redacted.
+    pub(crate) fn redact(&mut self, predicate: &impl Fn(&Event) -> bool) -> io::Result<u64> {
+        self.check_writable()?;
+        let mut chain = redact::ChainState::new(self.manifest.hash_algo);
+        let mut redacted = 0;
+
+        if let Some(mut raw) = self.storage.read_archive()? {
+            let mut bytes = Vec::new();
+            raw.read_to_end(&mut bytes)?;
+            let archive_path = self
+                .storage
+                .local_dir()
+                .map(|_| self.archive_path.as_path());
+            let dictionaries = archive::load_dictionaries(archive_path)?;
+            let codec = Codec::from_path(&self.archive_path);
+
+            let mut rewritten = Vec::with_capacity(bytes.len());
+            let mut changed = false;
+            for range in codec.frame_ranges(&bytes)? {
+                let frame = codec.decode_frame(&bytes[range.clone()], &dictionaries)?;
+                let out = redact::redact(&frame, &mut chain, predicate, self.cipher.as_deref())?;
+                redacted += out.redacted;
+                if out.changed {
+                    if let Some(dict) = &self.archive_dictionary {
+                        archive::register_dictionary(&self.archive_path, dict)?;
+                    }
+                    let dict = self.archive_dictionary.as_deref();
+                    rewritten.extend(codec.encode_frame(&out.bytes, dict)?);
+                    changed = true;
+                } else {
+                    rewritten.extend_from_slice(&bytes[range]);
                 }
             }
-            Err(mpsc::RecvTimeoutError::Timeout) => Ok(WaitResult::Timeout),
-            Err(mpsc::RecvTimeoutError::Disconnected) => {
-                Err(io::Error::other("file watcher disconnected"))
+            if changed {
+                self.storage.replace_archive(&rewritten)?;
+            }
+        }
+
+        let mut contents = Vec::new();
+        self.storage.read_from(0)?.read_to_end(&mut contents)?;
+        let out = redact::redact(&contents, &mut chain, predicate, self.cipher.as_deref())?;
+        redacted += out.redacted;
+        if out.changed {
+            self.replace_active_log(&out.bytes, out.events)?;
+        } else if self.chain_head.is_some() && self.active_events == 0 {
+            self.chain_head = Some(self.last_line_hash()?);
+        }
+        Ok(redacted)
+    }
+
+    /// Keep an in-memory map from event ID to sequence number, shared with
+    /// this writer's readers, for [`EventReader::find_by_id`]. Enabling
+    /// reads the full history.
+    pub(crate) fn set_id_index(&mut self, enabled: bool) -> io::Result<()> {
+        self.ids = if enabled {
+            Some(IdIndex::build(&self.reader())?)
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    /// Keep a sparse index of the active log in `app.index`, for
+    /// [`EventReader::offset_of_event`]. Enabling rebuilds the index from
+    /// the log; disabling deletes it. Does nothing if the storage has no
+    /// local directory.
+    pub(crate) fn set_index(&mut self, enabled: bool) -> io::Result<()> {
+        let Some(dir) = self.storage.local_dir() else {
+            return Ok(());
+        };
+        self.index = if enabled {
+            let log = BufReader::new(self.storage.read_from(0)?);
+            Some(EventIndex::rebuild(dir, log)?)
+        } else {
+            index::remove(dir)?;
+            None
+        };
+        Ok(())
+    }
+
+    /// Empty the index, or delete a stale one left by an indexed writer,
+    /// before the active log is truncated or replaced.
+    fn clear_index(&mut self) -> io::Result<()> {
+        match (&mut self.index, self.storage.local_dir()) {
+            (Some(index), _) => index.clear(),
+            (None, Some(dir)) => index::remove(dir),
+            (None, None) => Ok(()),
+        }
+    }
+
+    /// Set the observer notified of appends and rotations, and handed to
+    /// readers so views can report refreshes and rebuilds.
+    pub(crate) fn set_observer(&mut self, observer: Observer) {
+        self.observer = observer;
+    }
+
+    /// Set the payload migrations handed to readers, so views fold old
+    /// events in their current shape.
+    pub(crate) fn set_upcasters(&mut self, upcasters: Upcasters) {
+        self.upcasters = upcasters;
+    }
+
+    /// Set the payload validators checked before each event is written.
+    pub(crate) fn set_schemas(&mut self, schemas: Schemas) {
+        self.schemas = schemas;
+    }
+
+    /// Link every appended event to the line before it. Enabling reads the
+    /// last line's hash, from the archive if the active log is empty.
+    pub(crate) fn set_hash_chain(&mut self, enabled: bool) -> io::Result<()> {
+        self.chain_head = if enabled {
+            Some(self.last_line_hash()?)
+        } else {
+            None
+        };
+        Ok(())
+    }
+
+    /// Hash of the last line in the full history, or `""` if it is empty.
+    fn last_line_hash(&self) -> io::Result<String> {
+        let reader = self.reader();
+        let mut last = String::new();
+        if self.active_events > 0 {
+            for result in reader.read_from(0)? {
+                last = result?.2;
             }
+        } else {
+            reader.for_each_full(|line| {
+                last = line.hash.to_string();
+                Ok(ControlFlow::Continue(()))
+            })?;
+        }
+        Ok(last)
+    }
+
+    /// The sequence number the next appended event will get.
+    fn next_sequence(&self) -> u64 {
+        self.sequence_base + self.active_events
+    }
+
+    /// Move the sequence base past `events` events that are leaving the
+    /// active log, saving it if the storage has a local directory.
+    fn advance_sequence(&mut self, events: u64) -> io::Result<()> {
+        let base = self.sequence_base + events;
+        if let Some(dir) = self.storage.local_dir() {
+            sequence::save(dir, base)?;
+        }
+        self.sequence_base = base;
+        Ok(())
+    }
+
+    /// Set the policy applied to the archive after each rotation.
+    pub(crate) fn set_archive_retention(&mut self, retention: Option<Retention>) {
+        self.archive_retention = retention;
+    }
+
+    /// Rewrite the archive without the oldest frames `retention` drops.
+    fn apply_archive_retention(&self, retention: Retention) -> io::Result<()> {
+        let Some(mut raw) = self.storage.read_archive()? else {
+            return Ok(());
+        };
+        let mut bytes = Vec::new();
+        raw.read_to_end(&mut bytes)?;
+        let archive_path = self.storage.local_dir().map(|_| self.archive_path.as_path());
+        let dictionaries = archive::load_dictionaries(archive_path)?;
+        let codec = Codec::from_path(&self.archive_path);
+
+        let cipher = self.cipher.as_deref();
+        let start = archive::retained_start(codec, &bytes, &dictionaries, retention, cipher)?;
+        if start > 0 {
+            self.storage.replace_archive(&bytes[start..])?;
+            if let Some(ids) = &self.ids {
+                ids.rebuild(&self.reader())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the scheme used to give ID-less events an ID (`None` = leave
+    /// them without one).
+    pub(crate) fn set_id_scheme(&mut self, scheme: Option<IdScheme>) {
+        self.id_scheme = scheme;
+    }
+
+    /// Set the sync policy for appends.
+    pub(crate) fn set_sync_policy(&mut self, policy: SyncPolicy) {
+        self.sync_policy = policy;
+        self.restart_sync_interval();
+    }
+
+    /// Start timing the sync interval from now, if the policy has one.
+    fn restart_sync_interval(&mut self) {
+        self.last_sync = matches!(self.sync_policy, SyncPolicy::Interval(_)).then(Instant::now);
+    }
+
+    /// Set the number of unsynced events that forces a sync (0 = no bound).
+    pub(crate) fn set_max_unsynced_events(&mut self, n: u64) {
+        self.max_unsynced_events = n;
+    }
+
+    /// Set how many recent event IDs idempotent appends remember.
+    pub(crate) fn set_dedup_window(&mut self, n: usize) {
+        self.dedup_window = n;
+        self.recent = None;
+    }
+}
+
+/// Cheap, cloneable reader for an event log.
+///
+/// Opens fresh file handles per read call. Safe to use concurrently
+/// with an [`EventWriter`] on the same log — completed lines are immutable,
+/// and partial lines at EOF are detected and skipped.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventWriter, EventReader};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// let mut writer = EventWriter::open(dir.path())?;
+/// writer.append(&Event::new("click", json!({})))?;
+/// let reader = writer.reader();
+/// let events: Vec<_> = reader.read_from(0)?
+///     .collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(events.len(), 1);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventReader {
+    storage: Arc<dyn Storage>,
+    log_path: PathBuf,
+    archive_path: PathBuf,
+    observer: Observer,
+    upcasters: Upcasters,
+    ids: Option<IdIndex>,
+    hash_algo: HashAlgo,
+    cipher: Option<Arc<Cipher>>,
+    #[cfg(feature = "mmap")]
+    mmap: Option<MmapCache>,
+}
+
+impl EventReader {
+    /// Create a reader pointing at the given log directory.
+    ///
+    /// The archive's [`Codec`] is taken from the archive file present now,
+    /// or assumed to be zstd if there is none yet; see
+    /// [`with_archive_codec`](Self::with_archive_codec). An encrypted log
+    /// also needs its key, set with `with_encryption`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::EventReader;
+    /// # let dir = tempdir()?;
+    /// let reader = EventReader::new(dir.path());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref();
+        let codec = manifest::archive_codec(dir);
+        EventReader {
+            storage: Arc::new(FsStorage::read_only(dir).with_archive_codec(codec)),
+            log_path: dir.join("app.jsonl"),
+            archive_path: dir.join(codec.file_name()),
+            observer: Observer::default(),
+            upcasters: Upcasters::default(),
+            ids: None,
+            hash_algo: manifest::load(dir)
+                .ok()
+                .flatten()
+                .map(|manifest| manifest.hash_algo)
+                .unwrap_or_default(),
+            cipher: None,
+            #[cfg(feature = "mmap")]
+            mmap: None,
+        }
+    }
+
+    /// Read events from the active log starting at the given byte offset.
+    ///
+    /// Returns an iterator yielding `(event, next_byte_offset, line_hash)` for
+    /// each complete line. Blank lines, and a UTF-8 byte-order mark at the
+    /// very start of the file, are skipped. Partial lines (missing
+    /// trailing newline) are skipped silently.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or seeked.
+    /// Individual iterator items may also yield errors on malformed JSON lines.
+    pub fn read_from(
+        &self,
+        offset: u64,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
+        self.read_range(offset, u64::MAX)
+    }
+
+    /// Read events from the active log between two byte offsets.
+    ///
+    /// Behaves like [`read_from(start)`](Self::read_from), but only yields
+    /// lines that end (newline included) at or before `end`: every yielded
+    /// `next_byte_offset` is `<= end`. A line that `end` cuts through is
+    /// treated like a partial line at EOF — the iterator stops before it.
+    /// Nothing past `end` is read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let first = writer.append(&Event::new("a", json!({})))?;
+    /// writer.append(&Event::new("b", json!({})))?;
+    ///
+    /// let page: Vec<_> = writer.reader()
+    ///     .read_range(0, first.end_offset)?
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(page.len(), 1);
+    /// assert_eq!(page[0].0.event_type, "a");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or seeked.
+    /// Individual iterator items may also yield errors on malformed JSON lines.
+    pub fn read_range(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
+        self.log_lines(start, end)
+    }
+
+    /// Read the active log from the given byte offset like
+    /// [`read_from`](Self::read_from), without parsing the lines.
+    ///
+    /// Yields `(line, next_byte_offset, line_hash)`, where `line` is the
+    /// JSON exactly as stored, without its newline. Only blank lines, a
+    /// byte-order mark at the start of the file, and a partial trailing
+    /// line are skipped, so a malformed line is passed through rather than
+    /// reported. Saves a parse and re-serialization when forwarding events
+    /// elsewhere, as a proxy or replicator does. The lines of an encrypted
+    /// log are yielded still encrypted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let result = writer.append(&Event::new("click", json!({"x": 1})))?;
+    ///
+    /// let (line, next, hash) = writer.reader().read_raw_from(0)?.next().unwrap()?;
+    /// assert!(line.starts_with('{') && line.contains("\"click\""));
+    /// assert_eq!(next, result.end_offset);
+    /// assert_eq!(hash, result.line_hash);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or seeked.
+    /// Individual iterator items may also yield read errors.
+    pub fn read_raw_from(
+        &self,
+        offset: u64,
+    ) -> io::Result<impl Iterator<Item = io::Result<(String, u64, String)>>> {
+        Ok(RawLogIterator(self.log_lines(offset, u64::MAX)?))
+    }
+
+    /// The line hash of every event in the full history, with the offset
+    /// of its line in the active log, or `None` if it is archived.
+    pub(crate) fn full_hashes(
+        &self,
+    ) -> io::Result<impl Iterator<Item = io::Result<(String, Option<u64>)>>> {
+        let archive_iter: Box<dyn Iterator<Item = io::Result<(String, Option<u64>)>>> =
+            match self.open_archive()? {
+                Some(reader) => Box::new(
+                    LineHashIter(EventLineIter {
+                        reader,
+                        buf: String::new(),
+                        at_start: true,
+                        consumed: 0,
+                        algo: self.hash_algo,
+                        cipher: self.cipher.clone(),
+                    })
+                    .map(|r| r.map(|(hash, _)| (hash, None))),
+                ),
+                None => Box::new(std::iter::empty()),
+            };
+
+        let active_iter = LineHashIter(EventLineIter {
+            reader: BufReader::new(self.storage.read_from(0)?),
+            buf: String::new(),
+            at_start: true,
+            consumed: 0,
+            algo: self.hash_algo,
+            cipher: self.cipher.clone(),
+        })
+        .map(|r| r.map(|(hash, start)| (hash, Some(start))));
+
+        Ok(archive_iter.chain(active_iter))
+    }
+
+    /// A [`LogIterator`] over the active log's lines between two byte
+    /// offsets.
+    fn log_lines(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> io::Result<LogIterator<io::Lines<Box<dyn BufRead + Send>>>> {
+        #[cfg(feature = "mmap")]
+        if let Some(mmap) = &self.mmap {
+            let range = mmap.range(&self.log_path, start, end)?;
+            let file_len = range.end();
+            let reader: Box<dyn BufRead + Send> = Box::new(io::Cursor::new(range));
+            return Ok(LogIterator {
+                lines: reader.lines(),
+                pos: start,
+                file_len,
+                algo: self.hash_algo,
+                cipher: self.cipher.clone(),
+            });
+        }
+
+        let stream = self.storage.read_from(start)?;
+        let file_len = self.storage.size()?.min(end);
+        let reader: Box<dyn BufRead + Send> =
+            Box::new(BufReader::new(stream.take(end.saturating_sub(start))));
+
+        Ok(LogIterator {
+            lines: reader.lines(),
+            pos: start,
+            file_len,
+            algo: self.hash_algo,
+            cipher: self.cipher.clone(),
+        })
+    }
+
+    /// Read the events of the active log whose `ts` falls in
+    /// `from_ts..=to_ts`.
+    ///
+    /// Yields `(event, next_byte_offset, line_hash)` like
+    /// [`read_from`](Self::read_from). The first event with
+    /// `ts >= from_ts` is found by binary search over byte offsets, reading
+    /// one line per step, so only the events in the window (plus about
+    /// `log2(size)` probes) are parsed; iteration stops at the first event
+    /// with `ts > to_ts`.
+    ///
+    /// This relies on `ts` being non-decreasing through the log, as it is
+    /// when events are appended in time order with their default
+    /// timestamps. If an event carries an earlier `ts` than one before it,
+    /// results may be incomplete: events out of order around the window's
+    /// edges can be missed. Use
+    /// [`read_from_filtered`](Self::read_from_filtered) for a full scan
+    /// instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// for ts in [100, 200, 300, 400] {
+    ///     let mut event = Event::new("tick", json!({}));
+    ///     event.ts = ts;
+    ///     writer.append(&event)?;
+    /// }
+    ///
+    /// let window: Vec<u64> = writer.reader()
+    ///     .read_time_range(150, 300)?
+    ///     .map(|r| r.map(|(event, _, _)| event.ts))
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(window, [200, 300]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log cannot be read, or if a line probed by
+    /// the search is malformed. Individual iterator items may also yield
+    /// errors on malformed JSON lines.
+    pub fn read_time_range(
+        &self,
+        from_ts: u64,
+        to_ts: u64,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
+        let size = self.active_log_size()?;
+        // Every event starting before `lo` is earlier than `from_ts`; the
+        // event starting at `hi`, if any, is not.
+        let (mut lo, mut hi) = (0, size);
+        while lo < hi {
+            let start = self.line_start_at(lo + (hi - lo) / 2)?;
+            let Some(probe) = self.read_range(start, hi)?.next() else {
+                break;
+            };
+            let (event, next, _) = probe?;
+            if event.ts < from_ts {
+                lo = next;
+            } else {
+                hi = start;
+            }
+        }
+
+        Ok(self
+            .read_range(lo, size)?
+            .filter(move |r| !matches!(r, Ok((event, _, _)) if event.ts < from_ts))
+            .take_while(move |r| !matches!(r, Ok((event, _, _)) if event.ts > to_ts)))
+    }
+
+    /// Offset of the first line in the active log starting at or after
+    /// `offset`, or its size if there is none.
+    fn line_start_at(&self, offset: u64) -> io::Result<u64> {
+        if offset == 0 {
+            return Ok(0);
+        }
+        let mut skipped = Vec::new();
+        BufReader::new(self.storage.read_from(offset - 1)?).read_until(b'\n', &mut skipped)?;
+        Ok(offset - 1 + skipped.len() as u64)
+    }
+
+    /// Serve reads of the active log from a memory map of `app.jsonl`
+    /// instead of opening and buffering the file on every call.
+    ///
+    /// The map is shared by clones of this reader and rebuilt whenever the
+    /// log's size changes or the file is replaced, so repeated
+    /// [`read_from`](Self::read_from) calls over a large log skip the open
+    /// and copy. Each read sees the log as long as it was when mapped; a
+    /// partial last line is skipped exactly as without the map. Readers
+    /// over a [`Storage`] with no local directory ignore this.
+    ///
+    /// Requires the `mmap` feature.
+    ///
+    /// # Caveats
+    ///
+    /// The mapped bytes are read straight from the file. The writer may
+    /// extend `app.jsonl` concurrently — the map covers only the length at
+    /// mapping time, so appends are harmless. Shrinking the file while an
+    /// iterator from this reader is alive is not: the process may crash
+    /// (`SIGBUS` on Unix) reading the truncated pages. Rotation (explicit
+    /// or automatic) and [`repair`](crate::EventLog::repair) truncate the
+    /// active log, so don't run either while one of this reader's
+    /// iterators is in use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("click", json!({})))?;
+    ///
+    /// // Nothing rotates or repairs this log while it is read.
+    /// let reader = writer.reader().with_mmap();
+    /// assert_eq!(reader.read_from(0)?.count(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn with_mmap(mut self) -> Self {
+        if self.storage.local_dir().is_some() {
+            self.mmap = Some(MmapCache::default());
+        }
+        self
+    }
+
+    /// Read events from the active log until `deadline` passes.
+    ///
+    /// Returns the events read and the byte offset reached, which always
+    /// falls on a line boundary — pass it back in to resume. The deadline is
+    /// checked after each event, so at least one event is returned whenever
+    /// one is available; a caller looping on this always makes progress.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// use std::time::{Duration, Instant};
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("click", json!({})))?;
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// let (events, offset) = writer.reader().read_until_deadline(0, deadline)?;
+    /// assert_eq!(events.len(), 1);
+    /// assert_eq!(offset, writer.active_log_size()?);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or seeked, or if a
+    /// line fails to parse.
+    pub fn read_until_deadline(
+        &self,
+        offset: u64,
+        deadline: Instant,
+    ) -> io::Result<(Vec<Event>, u64)> {
+        let mut events = Vec::new();
+        let mut reached = offset;
+        for result in self.read_from(offset)? {
+            let (event, next_offset, _) = result?;
+            events.push(event);
+            reached = next_offset;
+            if Instant::now() >= deadline {
+                break;
+            }
+        }
+        Ok((events, reached))
+    }
+
+    /// Read events from the active log, starting at `offset`, until `stop`
+    /// returns `true` for one.
+    ///
+    /// `at` decides whether that event is returned; either way
+    /// [`ReadUntil::resume`] is the byte offset to pass back in to carry
+    /// on, so replaying "up to the first checkpoint" and then resuming
+    /// after it reads every event exactly once. If no event matches, every
+    /// event to the end of the active log is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, StopAt};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("a", json!({})))?;
+    /// let checkpoint = writer.append(&Event::new("checkpoint", json!({})))?;
+    ///
+    /// let read = writer
+    ///     .reader()
+    ///     .read_until(0, StopAt::Before, |e| e.event_type == "checkpoint")?;
+    /// assert_eq!(read.events.len(), 1);
+    /// assert_eq!(read.resume, checkpoint.start_offset);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or seeked, or if a
+    /// line fails to parse.
+    pub fn read_until<F>(&self, offset: u64, at: StopAt, stop: F) -> io::Result<ReadUntil>
+    where
+        F: Fn(&Event) -> bool,
+    {
+        let mut read = ReadUntil {
+            events: Vec::new(),
+            resume: offset,
+            stopped: false,
+        };
+        for result in self.read_from(offset)? {
+            let (event, next_offset, _) = result?;
+            if stop(&event) {
+                read.stopped = true;
+                if at == StopAt::After {
+                    read.events.push(event);
+                    read.resume = next_offset;
+                }
+                break;
+            }
+            read.events.push(event);
+            read.resume = next_offset;
+        }
+        Ok(read)
+    }
+
+    /// Read the full history, archive included, from sequence number
+    /// `sequence` until `stop` returns `true` for an event.
+    ///
+    /// Like [`read_until`](Self::read_until), but reading as
+    /// [`read_from_sequence`](Self::read_from_sequence) does, so
+    /// [`ReadUntil::resume`] is a sequence number and stays valid across
+    /// rotations. Pass 0 to start from the oldest event.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, StopAt};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// log.append(&Event::new("checkpoint", json!({})))?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("b", json!({})))?;
+    ///
+    /// let reader = log.reader();
+    /// let read = reader.read_full_until(0, StopAt::After, |e| e.event_type == "checkpoint")?;
+    /// assert_eq!((read.events.len(), read.resume), (2, 2));
+    /// let rest = reader.read_full_until(read.resume, StopAt::After, |_| false)?;
+    /// assert_eq!(rest.events[0].event_type, "b");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sequence base cannot be loaded, if the
+    /// archive or active log cannot be opened, or if a line fails to parse.
+    pub fn read_full_until<F>(&self, sequence: u64, at: StopAt, stop: F) -> io::Result<ReadUntil>
+    where
+        F: Fn(&Event) -> bool,
+    {
+        let mut read = ReadUntil {
+            events: Vec::new(),
+            resume: sequence,
+            stopped: false,
+        };
+        for result in self.read_from_sequence(sequence)? {
+            let (event, sequence, _) = result?;
+            if stop(&event) {
+                read.stopped = true;
+                read.resume = sequence;
+                if at == StopAt::After {
+                    read.events.push(event);
+                    read.resume = sequence + 1;
+                }
+                break;
+            }
+            read.events.push(event);
+            read.resume = sequence + 1;
+        }
+        Ok(read)
+    }
+
+    /// Read the full event history: archive (if any) + active log.
+    ///
+    /// Returns an iterator yielding `(event, line_hash)` for each event
+    /// across all archived frames and the current active log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be opened.
+    /// Individual iterator items may also yield errors on malformed lines.
+    pub fn read_full(&self) -> io::Result<FullEventIter> {
+        let archive_iter: Box<dyn Iterator<Item = io::Result<(Event, String)>>> =
+            match self.open_archive()? {
+                Some(reader) => Box::new(EventLineIter {
+                    reader,
+                    buf: String::new(),
+                    at_start: true,
+                    consumed: 0,
+                    algo: self.hash_algo,
+                    cipher: self.cipher.clone(),
+                }),
+                None => Box::new(std::iter::empty()),
+            };
+
+        let reader = BufReader::new(self.storage.read_from(0)?);
+        let active_iter: Box<dyn Iterator<Item = io::Result<(Event, String)>>> =
+            Box::new(EventLineIter {
+                reader,
+                buf: String::new(),
+                at_start: true,
+                consumed: 0,
+                algo: self.hash_algo,
+                cipher: self.cipher.clone(),
+            });
+
+        Ok(Box::new(archive_iter.chain(active_iter)))
+    }
+
+    /// Read the full history like [`read_full`](Self::read_full), also
+    /// yielding where each event ends in the active log, or `None` if it
+    /// is archived.
+    pub(crate) fn read_full_located(
+        &self,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, Option<u64>, String)>>> {
+        let archive_iter = self
+            .open_archive()?
+            .map(|reader| EventLineIter {
+                reader,
+                buf: String::new(),
+                at_start: true,
+                consumed: 0,
+                algo: self.hash_algo,
+                cipher: self.cipher.clone(),
+            })
+            .into_iter()
+            .flatten()
+            .map(|r| r.map(|(event, hash)| (event, None, hash)));
+        let active_iter = self
+            .read_from(0)?
+            .map(|r| r.map(|(event, end, hash)| (event, Some(end), hash)));
+        Ok(archive_iter.chain(active_iter))
+    }
+
+    /// Read the full history like [`read_full_located`](Self::read_full_located),
+    /// without parsing the lines.
+    pub(crate) fn read_raw_full_located(
+        &self,
+    ) -> io::Result<impl Iterator<Item = io::Result<(String, Option<u64>, String)>>> {
+        let archive_iter = self
+            .open_archive()?
+            .map(|reader| {
+                RawLineIter(EventLineIter {
+                    reader,
+                    buf: String::new(),
+                    at_start: true,
+                    consumed: 0,
+                    algo: self.hash_algo,
+                    cipher: self.cipher.clone(),
+                })
+            })
+            .into_iter()
+            .flatten()
+            .map(|r| r.map(|(line, hash)| (line, None, hash)));
+        let active_iter = self
+            .read_raw_from(0)?
+            .map(|r| r.map(|(line, end, hash)| (line, Some(end), hash)));
+        Ok(archive_iter.chain(active_iter))
+    }
+
+    /// Read the full history like [`read_full`](Self::read_full), without
+    /// parsing the lines.
+    ///
+    /// Yields `(line, line_hash)` for each line of the decompressed archive
+    /// followed by the active log, skipping only blank lines, byte-order
+    /// marks, and a partial trailing line. See
+    /// [`read_raw_from`](Self::read_raw_from).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("b", json!({})))?;
+    ///
+    /// let lines: Vec<String> = log
+    ///     .reader()
+    ///     .read_raw_full()?
+    ///     .map(|r| r.map(|(line, _)| line))
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(lines.len(), 2);
+    /// assert!(lines[0].contains("\"a\""));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be opened.
+    /// Individual iterator items may also yield read errors.
+    pub fn read_raw_full(
+        &self,
+    ) -> io::Result<impl Iterator<Item = io::Result<(String, String)>>> {
+        self.raw_full_lines()
+    }
+
+    /// The lines [`read_raw_full`](Self::read_raw_full) yields, boxed so
+    /// they do not borrow the reader.
+    fn raw_full_lines(&self) -> io::Result<FullLineIter> {
+        let archive_iter: Box<dyn Iterator<Item = io::Result<(String, String)>>> =
+            match self.open_archive()? {
+                Some(reader) => Box::new(RawLineIter(EventLineIter {
+                    reader,
+                    buf: String::new(),
+                    at_start: true,
+                    consumed: 0,
+                    algo: self.hash_algo,
+                    cipher: self.cipher.clone(),
+                })),
+                None => Box::new(std::iter::empty()),
+            };
+
+        let active_iter = RawLineIter(EventLineIter {
+            reader: BufReader::new(self.storage.read_from(0)?),
+            buf: String::new(),
+            at_start: true,
+            consumed: 0,
+            algo: self.hash_algo,
+            cipher: self.cipher.clone(),
+        });
+
+        Ok(Box::new(archive_iter.chain(active_iter)))
+    }
+
+    /// Read the full event history like [`read_full`](Self::read_full),
+    /// numbering the events.
+    ///
+    /// Yields `(event, index, line_hash)`, where `index` counts events from
+    /// 0 across the archive and the active log. Unlike byte offsets, an
+    /// index stays valid across rotations, so it can record how far a
+    /// consumer has got. Dropping events (archive retention or
+    /// [`EventLog::compact`]) renumbers the ones after them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("b", json!({})))?;
+    ///
+    /// let indexed: Vec<(String, u64)> = log
+    ///     .reader()
+    ///     .read_full_indexed()?
+    ///     .map(|r| r.map(|(event, index, _)| (event.event_type, index)))
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(indexed, [("a".to_string(), 0), ("b".to_string(), 1)]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be opened.
+    /// Individual iterator items may also yield errors on malformed lines.
+    pub fn read_full_indexed(
+        &self,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
+        let mut next_index = 0;
+        Ok(self.read_full()?.map(move |result| {
+            let (event, hash) = result?;
+            let index = next_index;
+            next_index += 1;
+            Ok((event, index, hash))
+        }))
+    }
+
+    /// Returns the sequence number of the first event in the active log.
+    ///
+    /// Every event has a log-wide sequence number, counting from 0, that
+    /// is never reset by rotation (it is reported as
+    /// [`AppendResult::sequence`]). Within the active log, sequence numbers
+    /// follow file order from this base: the `k`-th event (0-based) has
+    /// sequence `sequence_base + k`, wherever its bytes start. Rotation
+    /// moves the base past the archived events; byte offsets go back to 0.
+    ///
+    /// The base is saved in the `seq` file by rotation and compaction.
+    /// Until then (or on storage without a local directory) it is the
+    /// number of events in the archive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// log.append(&Event::new("b", json!({})))?;
+    /// log.rotate()?;
+    ///
+    /// let result = log.append(&Event::new("c", json!({})))?;
+    /// assert_eq!(result.start_offset, 0);
+    /// assert_eq!(result.sequence, 2);
+    /// assert_eq!(log.reader().sequence_base()?, 2);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if the `seq` file is malformed. Returns an
+    /// error if it cannot be read, or, when there is none, if the archive
+    /// cannot be read to count its events.
+    pub fn sequence_base(&self) -> io::Result<u64> {
+        if let Some(dir) = self.storage.local_dir()
+            && let Some(base) = sequence::load(dir)?
+        {
+            return Ok(base);
+        }
+        self.archived_events()
+    }
+
+    /// Read the full event history from sequence number `sequence` on.
+    ///
+    /// Yields `(event, sequence, line_hash)`, reading from the archive if
+    /// `sequence` comes before the active log (see
+    /// [`sequence_base`](Self::sequence_base)). Pass the last sequence
+    /// processed plus one to resume after it, across any number of
+    /// rotations. If archive retention has purged `sequence`, reading
+    /// starts at the oldest event left, so the first item's sequence shows
+    /// the gap.
+    ///
+    /// Events before `sequence` in the same file are still read and
+    /// skipped; a cursor into the archive costs a pass to count its events
+    /// first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// log.append(&Event::new("b", json!({})))?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("c", json!({})))?;
+    ///
+    /// let types: Vec<(String, u64)> = log
+    ///     .reader()
+    ///     .read_from_sequence(1)?
+    ///     .map(|r| r.map(|(event, seq, _)| (event.event_type, seq)))
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(types, [("b".to_string(), 1), ("c".to_string(), 2)]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sequence base cannot be loaded, or if the
+    /// archive or active log cannot be opened. Individual iterator items
+    /// may also yield errors on malformed lines.
+    pub fn read_from_sequence(
+        &self,
+        sequence: u64,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
+        let base = self.sequence_base()?;
+        let (first, events): (u64, Box<dyn Iterator<Item = _> + '_>) = if sequence >= base {
+            let active = self
+                .read_from(0)?
+                .map(|r| r.map(|(event, _, hash)| (event, hash)));
+            (base, Box::new(active))
+        } else {
+            let first = base.saturating_sub(self.archived_events()?);
+            (first, self.read_full()?)
+        };
+
+        let mut next = first;
+        Ok(events
+            .map(move |result| {
+                let (event, hash) = result?;
+                let seq = next;
+                next += 1;
+                Ok((event, seq, hash))
+            })
+            .filter(move |result| !matches!(result, Ok((_, seq, _)) if *seq < sequence)))
+    }
+
+    /// Byte offset of event number `n` in the active log (counting from
+    /// 0), or `None` if the active log holds `n` events or fewer.
+    ///
+    /// The offset is one [`read_from`](Self::read_from) resumes at to yield
+    /// event `n` first, so pagination by event position needs no full scan.
+    /// With [`EventLogBuilder::index`] on, the writer's `app.index` gets
+    /// the scan within 256 events of `n`; without it, this scans from the
+    /// start of the active log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path()).index(true).open()?;
+    /// for i in 0..1000 {
+    ///     log.append(&Event::new("tick", json!({ "i": i })))?;
+    /// }
+    ///
+    /// let offset = log.reader().offset_of_event(600)?.unwrap();
+    /// let (event, _, _) = log.read_from(offset)?.next().unwrap()?;
+    /// assert_eq!(event.data["i"], 600);
+    /// assert_eq!(log.reader().offset_of_event(1000)?, None);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log or index cannot be read, or if a line
+    /// scanned on the way to event `n` is malformed.
+    pub fn offset_of_event(&self, n: u64) -> io::Result<Option<u64>> {
+        let size = self.active_log_size()?;
+        let (mut event, mut offset) = match self.storage.local_dir() {
+            Some(dir) => index::lookup(dir, n, size)?.unwrap_or((0, 0)),
+            None => (0, 0),
+        };
+
+        for result in self.read_range(offset, size)? {
+            if event == n {
+                return Ok(Some(offset));
+            }
+            offset = result?.1;
+            event += 1;
+        }
+        Ok(None)
+    }
+
+    /// Find the first event in the full history whose `id` is `id`, along
+    /// with its [sequence number](Self::read_from_sequence).
+    ///
+    /// Without an ID index this scans the archive and active log in order.
+    /// With [`EventLogBuilder::id_index`] on, a reader from that log looks
+    /// the sequence number up in memory and reads only up to the event — a
+    /// short read (shorter still with [`EventLogBuilder::index`]) when it is
+    /// in the active log. If an ID appears more than once, the earliest
+    /// event wins either way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path()).id_index(true).open()?;
+    /// log.append(&Event::new("a", json!({})).with_id("req-1"))?;
+    /// log.append(&Event::new("b", json!({})).with_id("req-2"))?;
+    ///
+    /// let (event, sequence) = log.reader().find_by_id("req-2")?.unwrap();
+    /// assert_eq!((event.event_type.as_str(), sequence), ("b", 1));
+    /// assert!(log.reader().find_by_id("req-3")?.is_none());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log or archive cannot be read, or if a line
+    /// read on the way is malformed.
+    pub fn find_by_id(&self, id: &str) -> io::Result<Option<(Event, u64)>> {
+        if let Some(ids) = &self.ids {
+            let Some(sequence) = ids.get(id) else {
+                return Ok(None);
+            };
+            if let Some(event) = self.event_at_sequence(sequence)?
+                && event.id.as_deref() == Some(id)
+            {
+                return Ok(Some((event, sequence)));
+            }
+        }
+
+        for result in self.read_from_sequence(0)? {
+            let (event, sequence, _) = result?;
+            if event.id.as_deref() == Some(id) {
+                return Ok(Some((event, sequence)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The event with sequence number `sequence`, if it still exists.
+    fn event_at_sequence(&self, sequence: u64) -> io::Result<Option<Event>> {
+        let base = self.sequence_base()?;
+        let next = if sequence >= base {
+            let Some(offset) = self.offset_of_event(sequence - base)? else {
+                return Ok(None);
+            };
+            self.read_from(offset)?.next()
+        } else {
+            self.read_from_sequence(sequence)?.next()
+        };
+        Ok(next.transpose()?.map(|(event, _, _)| event))
+    }
+
+    /// Count the events in the archive.
+    pub(crate) fn archived_events(&self) -> io::Result<u64> {
+        match self.open_archive()? {
+            Some(archive) => inspect::count_lines(archive),
+            None => Ok(0),
+        }
+    }
+
+    /// Check the hash chain over the full history (archive + active log)
+    /// and return the first broken link, if any.
+    ///
+    /// Every event that has a previous line must link to that line's hash
+    /// once the chain has started (see
+    /// [`EventLogBuilder::hash_chain`]); events from before chaining was
+    /// turned on are not checked. Editing, removing, inserting, or
+    /// reordering events shows up as a [`ChainBreak`] at the event after
+    /// the change. The first event in the history is taken on trust, as
+    /// it may link to an event that archive retention purged. Removing
+    /// events from the end of the log cannot be detected this way.
+    ///
+    /// [`EventLog::compact`] re-links the events it keeps, so the chain
+    /// stays valid after compaction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path()).hash_chain(true).open()?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// log.append(&Event::new("b", json!({})))?;
+    /// log.append(&Event::new("c", json!({})))?;
+    /// assert!(log.verify_chain()?.is_ok());
+    ///
+    /// // Delete the middle event.
+    /// let contents = std::fs::read_to_string(log.log_path())?;
+    /// let lines: Vec<&str> = contents.lines().collect();
+    /// std::fs::write(log.log_path(), format!("{}\n{}\n", lines[0], lines[2]))?;
+    ///
+    /// let broken = log.verify_chain()?.unwrap_err();
+    /// assert_eq!(broken.index, 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be read, or a
+    /// line cannot be parsed.
+    pub fn verify_chain(&self) -> io::Result<Result<(), ChainBreak>> {
+        let mut prev: Option<String> = None;
+        let mut chained = false;
+        let mut index = 0;
+        let mut broken = None;
+        self.for_each_full(|line| {
+            let found = chain::prev(line.event);
+            if let Some(expected) = &prev
+                && (chained || found.is_some())
+                && found != Some(expected.as_str())
+            {
+                broken = Some(ChainBreak {
+                    index,
+                    offset: line.end,
+                    expected: expected.clone(),
+                    found: found.map(str::to_string),
+                });
+                return Ok(ControlFlow::Break(()));
+            }
+            chained |= found.is_some();
+            prev = Some(line.hash.to_string());
+            index += 1;
+            Ok(ControlFlow::Continue(()))
+        })?;
+        Ok(broken.map_or(Ok(()), Err))
+    }
+
+    /// Call `f` with each line of the full history in order, until it
+    /// returns `ControlFlow::Break`.
+    pub(crate) fn for_each_full(
+        &self,
+        mut f: impl FnMut(FullLine<'_>) -> io::Result<ControlFlow<()>>,
+    ) -> io::Result<()> {
+        let mut archived = 0;
+        if let Some(reader) = self.open_archive()? {
+            let mut lines = EventLineIter {
+                reader,
+                buf: String::new(),
+                at_start: true,
+                consumed: 0,
+                algo: self.hash_algo,
+                cipher: self.cipher.clone(),
+            };
+            while let Some(result) = lines.next() {
+                let (event, hash) = result?;
+                let line = FullLine {
+                    event: &event,
+                    hash: &hash,
+                    end: lines.consumed,
+                    active_end: None,
+                };
+                if f(line)?.is_break() {
+                    return Ok(());
+                }
+            }
+            archived = lines.consumed;
+        }
+
+        let mut lines = EventLineIter {
+            reader: BufReader::new(self.storage.read_from(0)?),
+            buf: String::new(),
+            at_start: true,
+            consumed: 0,
+            algo: self.hash_algo,
+            cipher: self.cipher.clone(),
+        };
+        while let Some(result) = lines.next() {
+            let (event, hash) = result?;
+            let line = FullLine {
+                event: &event,
+                hash: &hash,
+                end: archived + lines.consumed,
+                active_end: Some(lines.consumed),
+            };
+            if f(line)?.is_break() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read events from the active log grouped into the transactions they
+    /// were committed in.
+    ///
+    /// Events written by [`EventWriter::append_transaction`] are yielded
+    /// together as one `Vec`; every ordinary append is a one-event
+    /// transaction. A transaction whose lines are not all present (torn by a
+    /// crash mid-write) is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append_transaction(&[
+    ///     Event::new("debit", json!({})),
+    ///     Event::new("credit", json!({})),
+    /// ])?;
+    /// writer.append(&Event::new("audit", json!({})))?;
+    ///
+    /// let sizes: Vec<usize> = writer.reader().read_transactions_from(0)?
+    ///     .map(|txn| txn.map(|events| events.len()))
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(sizes, vec![2, 1]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or seeked.
+    /// Individual iterator items may also yield errors on malformed JSON lines.
+    pub fn read_transactions_from(
+        &self,
+        offset: u64,
+    ) -> io::Result<impl Iterator<Item = io::Result<Vec<Event>>>> {
+        Ok(TransactionIter {
+            inner: self.read_from(offset)?,
+        })
+    }
+
+    /// Count the events in the active log from `offset` to EOF without
+    /// deserializing them.
+    ///
+    /// Counts complete, non-blank lines; a trailing partial line is not
+    /// counted. Lines are not parsed, so event structure is not validated —
+    /// a corrupt line still counts. That keeps this fast and allocation-free
+    /// per event, suitable for dashboards and health checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let first = writer.append(&Event::new("a", json!({})))?;
+    /// writer.append(&Event::new("b", json!({})))?;
+    /// assert_eq!(writer.reader().count_from(0)?, 2);
+    /// assert_eq!(writer.reader().count_from(first.end_offset)?, 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or read.
+    pub fn count_from(&self, offset: u64) -> io::Result<u64> {
+        inspect::count_lines(BufReader::new(self.storage.read_from(offset)?))
+    }
+
+    /// Count the events in the full history — archive and active log —
+    /// without deserializing them.
+    ///
+    /// Like [`count_from`](Self::count_from), lines are counted, not parsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be read, or if
+    /// the archive fails to decompress.
+    pub fn count_full(&self) -> io::Result<u64> {
+        let archived = match self.open_archive()? {
+            Some(reader) => inspect::count_lines(reader)?,
+            None => 0,
+        };
+        Ok(archived + self.count_from(0)?)
+    }
+
+    /// Read events from the active log starting at `offset`, yielding only
+    /// those whose `event_type` is one of `types`.
+    ///
+    /// Items keep the `(event, next_byte_offset, line_hash)` shape of
+    /// [`read_from`](Self::read_from). Skipped events still advance the
+    /// offset, so the `next_byte_offset` of the last yielded event is a
+    /// valid resume point — though events of other types may follow it.
+    /// Errors are always passed through, never filtered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("todo_added", json!({"text": "milk"})))?;
+    /// writer.append(&Event::new("todo_completed", json!({"id": 0})))?;
+    ///
+    /// let added: Vec<_> = writer.reader()
+    ///     .read_from_filtered(0, &["todo_added"])?
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(added.len(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or seeked.
+    /// Individual iterator items may also yield errors on malformed JSON lines.
+    pub fn read_from_filtered(
+        &self,
+        offset: u64,
+        types: &[&str],
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
+        let types: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+        Ok(self.read_from(offset)?.filter(move |result| match result {
+            Ok((event, _, _)) => types.contains(&event.event_type),
+            Err(_) => true,
+        }))
+    }
+
+    /// Read the full event history, yielding only events for which `pred`
+    /// returns `true`.
+    ///
+    /// A read-time filter — the log itself is untouched. Useful for exports
+    /// that must exclude some events, such as everything from one actor.
+    /// Errors are always passed through, never filtered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("a", json!({})).with_actor("alice"))?;
+    /// writer.append(&Event::new("b", json!({})).with_actor("bob"))?;
+    ///
+    /// let kept: Vec<_> = writer.reader()
+    ///     .read_full_filtered(|e| e.actor.as_deref() != Some("alice"))?
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(kept.len(), 1);
+    /// assert_eq!(kept[0].0.event_type, "b");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be opened.
+    /// Individual iterator items may also yield errors on malformed lines.
+    pub fn read_full_filtered<F>(
+        &self,
+        pred: F,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, String)>>>
+    where
+        F: Fn(&Event) -> bool,
+    {
+        Ok(self.read_full()?.filter(move |result| match result {
+            Ok((event, _)) => pred(event),
+            Err(_) => true,
+        }))
+    }
+
+    /// Read the active log from the given byte offset like
+    /// [`read_from`](Self::read_from), skipping lines that are not valid
+    /// events instead of yielding an error for them.
+    ///
+    /// For best-effort bulk reads, such as analytics, that would rather
+    /// lose a corrupt line than stop at it. Offsets still advance past the
+    /// skipped lines, and [`LenientIter::skipped`] counts them. Read errors
+    /// are yielded as usual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// use std::io::Write;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("a", json!({})))?;
+    /// std::fs::OpenOptions::new()
+    ///     .append(true)
+    ///     .open(dir.path().join("app.jsonl"))?
+    ///     .write_all(b"not json\n")?;
+    /// let last = writer.append(&Event::new("b", json!({})))?;
+    ///
+    /// let mut events = writer.reader().read_from_lenient(0)?;
+    /// let read: Vec<_> = events.by_ref().collect::<Result<_, _>>()?;
+    /// assert_eq!(read.len(), 2);
+    /// assert_eq!(read[1].1, last.end_offset);
+    /// assert_eq!(events.skipped(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or seeked.
+    /// Individual iterator items may also yield read errors.
+    pub fn read_from_lenient(&self, offset: u64) -> io::Result<LenientIter<(Event, u64, String)>> {
+        let lines = RawLogIterator(self.log_lines(offset, u64::MAX)?);
+        let cipher = self.cipher.clone();
+        Ok(LenientIter::new(lines.map(move |r| {
+            r.map(|(line, end, hash)| {
+                parse_lenient(cipher.as_deref(), &line).map(|event| (event, end, hash))
+            })
+        })))
+    }
+
+    /// Read the full event history like [`read_full`](Self::read_full),
+    /// skipping lines that are not valid events. See
+    /// [`read_from_lenient`](Self::read_from_lenient).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be opened.
+    /// Individual iterator items may also yield read errors.
+    pub fn read_full_lenient(&self) -> io::Result<LenientIter<(Event, String)>> {
+        let lines = self.raw_full_lines()?;
+        let cipher = self.cipher.clone();
+        Ok(LenientIter::new(lines.map(move |r| {
+            r.map(|(line, hash)| parse_lenient(cipher.as_deref(), &line).map(|event| (event, hash)))
+        })))
+    }
+
+    /// Read the full event history without the events deleted by a
+    /// tombstone (see [`Event::tombstone`]): any event whose `id` is the
+    /// target of a later tombstone is skipped, and so are the tombstones
+    /// themselves. The log itself is untouched.
+    ///
+    /// Reads the history twice: once to collect the tombstones, then to
+    /// yield the events. A tombstone appended in between may not be
+    /// honored. Errors are passed through, never filtered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("note", json!({"text": "oops"})).with_id("n1"))?;
+    /// writer.append(&Event::new("note", json!({"text": "hello"})).with_id("n2"))?;
+    /// writer.append(&Event::tombstone("n1"))?;
+    ///
+    /// let kept: Vec<_> = writer.reader()
+    ///     .read_full_redacted()?
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(kept.len(), 1);
+    /// assert_eq!(kept[0].0.id.as_deref(), Some("n2"));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be opened.
+    /// Individual iterator items may also yield errors on malformed lines.
+    pub fn read_full_redacted(
+        &self,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, String)>>> {
+        // Index of the last tombstone naming each ID. Bad lines are
+        // skipped here and reported by the second pass.
+        let mut tombstones = HashMap::new();
+        for (event, index, _) in self.read_full_indexed()?.flatten() {
+            if let Some(target) = event.tombstone_target() {
+                tombstones.insert(target.to_string(), index);
+            }
+        }
+
+        Ok(self.read_full_indexed()?.filter_map(move |result| {
+            let (event, index, hash) = match result {
+                Ok(item) => item,
+                Err(e) => return Some(Err(e)),
+            };
+            let deleted = event.tombstone_target().is_some()
+                || event
+                    .id
+                    .as_ref()
+                    .and_then(|id| tombstones.get(id))
+                    .is_some_and(|&tombstone| tombstone > index);
+            (!deleted).then_some(Ok((event, hash)))
+        }))
+    }
+
+    /// Replay the full event history, pacing callbacks by the events' `ts`.
+    ///
+    /// Reads [`read_full`](Self::read_full) and calls `f` for each event,
+    /// sleeping between events for their `ts` delta divided by `speed`.
+    /// A `speed` of `2.0` replays twice as fast as the events were recorded.
+    /// Events whose `ts` is not later than the latest seen so far are
+    /// delivered without sleeping, so out-of-order timestamps never stall
+    /// the replay.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// for ts in [100, 101] {
+    ///     let mut event = Event::new("tick", json!({}));
+    ///     event.ts = ts;
+    ///     writer.append(&event)?;
+    /// }
+    /// let mut seen = Vec::new();
+    /// writer.reader().replay_timed(1000.0, |e| seen.push(e.ts))?;
+    /// assert_eq!(seen, vec![100, 101]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if `speed` is not a positive, finite number.
+    /// Returns an error if the log cannot be read or a line fails to parse.
+    pub fn replay_timed(&self, speed: f64, mut f: impl FnMut(&Event)) -> io::Result<()> {
+        if !(speed.is_finite() && speed > 0.0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "replay speed must be a positive, finite number",
+            ));
+        }
+
+        let mut latest = None;
+        for result in self.read_full()? {
+            let (event, _) = result?;
+            if let Some(latest_ts) = latest {
+                let delta = event.ts.saturating_sub(latest_ts);
+                if delta > 0 {
+                    std::thread::sleep(Duration::from_secs_f64(delta as f64 / speed));
+                }
+            }
+            latest = latest.max(Some(event.ts));
+            f(&event);
+        }
+        Ok(())
+    }
+
+    /// Read the line immediately before the given byte offset and return its hash.
+    ///
+    /// The offset should point to the byte after the newline of the last consumed line.
+    /// Returns `None` if offset is 0 or beyond the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or read.
+    pub fn read_line_hash_before(&self, offset: u64) -> io::Result<Option<String>> {
+        if offset == 0 {
+            return Ok(None);
+        }
+
+        let file_len = self.storage.size()?;
+
+        if offset > file_len {
+            return Ok(None);
+        }
+
+        // offset - 1 is the '\n' at end of previous line
+        // Scan backwards from offset - 2 to find start of that line
+        let newline_pos = offset - 1;
+        let mut start = 0u64;
+
+        if newline_pos > 0 {
+            let scan_start = newline_pos.saturating_sub(8192);
+            let mut buf = vec![0u8; (newline_pos - scan_start) as usize];
+            self.storage.read_at(scan_start, &mut buf)?;
+
+            if let Some(pos) = buf.iter().rposition(|&b| b == b'\n') {
+                start = scan_start + pos as u64 + 1;
+            } else {
+                start = scan_start;
+            }
+        }
+
+        let line_len = (newline_pos - start) as usize;
+        let mut line_buf = vec![0u8; line_len];
+        self.storage.read_at(start, &mut line_buf)?;
+
+        let line = if start == 0 {
+            line_buf.strip_prefix(BOM.as_bytes()).unwrap_or(&line_buf)
+        } else {
+            &line_buf
+        };
+        Ok(Some(self.hash_algo.hash(line)))
+    }
+
+    /// Compute the event rate, in events per second, over the most recent
+    /// `window` of the active log.
+    ///
+    /// Scans backward from the tail and counts events whose `ts` falls
+    /// within `window` of the latest event's `ts` (`latest - ts < window`).
+    /// Only the tail that falls inside the window is read. Timestamps have
+    /// one-second resolution, and the scan stops at the first event outside
+    /// the window, so it assumes `ts` is non-decreasing. Events already
+    /// rotated into the archive are not counted.
+    ///
+    /// Returns `0.0` for an empty log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// use std::time::Duration;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// for ts in [100, 101, 102, 103] {
+    ///     let mut event = Event::new("tick", json!({}));
+    ///     event.ts = ts;
+    ///     writer.append(&event)?;
+    /// }
+    /// let rate = writer.reader().rate(Duration::from_secs(2))?;
+    /// assert_eq!(rate, 1.0); // ts 102 and 103 fall in the window
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if `window` is shorter than one second.
+    /// Returns an error if the log cannot be read or a line fails to parse.
+    pub fn rate(&self, window: Duration) -> io::Result<f64> {
+        let window_secs = window.as_secs();
+        if window_secs == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "rate window must be at least one second",
+            ));
+        }
+
+        let mut latest = None;
+        let mut count = 0u64;
+        for result in self.read_lines_backward()? {
+            let (line, _) = result?;
+            let json = crypto::open_line_bytes(self.cipher.as_deref(), &line)?;
+            let event: Event = serde_json::from_slice(&json)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let latest_ts = *latest.get_or_insert(event.ts);
+            if latest_ts.saturating_sub(event.ts) >= window_secs {
+                break;
+            }
+            count += 1;
+        }
+        Ok(count as f64 / window.as_secs_f64())
+    }
+
+    /// Iterate complete, non-empty lines of the active log from the tail
+    /// backward, yielding `(line_bytes, start_offset)`. A trailing partial
+    /// line is skipped.
+    pub(crate) fn read_lines_backward(&self) -> io::Result<ReverseLines> {
+        ReverseLines::new(Arc::clone(&self.storage))
+    }
+
+    /// Read the events that views rejected, with the reason for each,
+    /// oldest first.
+    ///
+    /// Rejected events come from typed views (see [`View::new_typed`]) whose
+    /// payload type did not match. Returns an empty iterator if nothing has
+    /// been rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dead-letter.jsonl` cannot be read or contains a
+    /// malformed record.
+    pub fn read_dead_letters(&self) -> io::Result<impl Iterator<Item = (Event, String)>> {
+        self.require_local("dead letters")?;
+        Ok(dead_letter::read_records(&self.dead_letter_path(), self.cipher())?.into_iter())
+    }
+
+    /// Returns the path to the dead-letter file (`dead-letter.jsonl` next
+    /// to the active log).
+    pub fn dead_letter_path(&self) -> PathBuf {
+        self.log_path.with_file_name("dead-letter.jsonl")
+    }
+
+    /// Returns the current size of `app.jsonl` in bytes.
+    ///
+    /// This is a lightweight "version" check — if the size hasn't
+    /// changed, no new events have been appended.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file metadata cannot be read.
+    pub fn active_log_size(&self) -> io::Result<u64> {
+        self.storage.size()
+    }
+
+    /// Returns `true` if the active log contains data beyond `offset`.
+    ///
+    /// This is a non-blocking metadata check (stat call). Use it to
+    /// implement poll-based tailing:
+    ///
+    /// ```no_run
+    /// # use eventfold::EventReader;
+    /// let reader = EventReader::new("./data");
+    /// let mut offset = 0u64;
+    /// loop {
+    ///     if reader.has_new_events(offset).unwrap() {
+    ///         for result in reader.read_from(offset).unwrap() {
+    ///             let (event, next_offset, _hash) = result.unwrap();
+    ///             // process event
+    ///             offset = next_offset;
+    ///         }
+    ///     }
+    ///     std::thread::sleep(std::time::Duration::from_millis(50));
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file metadata cannot be read.
+    pub fn has_new_events(&self, offset: u64) -> io::Result<bool> {
+        Ok(self.storage.size()? > offset)
+    }
+
+    /// Compare the active log's size with `last_known_size`, the size (or
+    /// offset) a tailer last saw, to tell whether its offsets still hold.
+    ///
+    /// A tailer that gets [`RotationStatus::Rotated`] or
+    /// [`RotationStatus::Shrunk`] should resume from offset 0; events it
+    /// missed before a rotation are at the end of the archive.
+    ///
+    /// This is a heuristic based on sizes alone. The active log only
+    /// shrinks when it is rotated or rewritten, so a shrink is always
+    /// reported, but:
+    ///
+    /// - a rotation followed by enough appends to regrow the log past
+    ///   `last_known_size` looks like [`Appended`](RotationStatus::Appended)
+    ///   — poll more often than the log fills up, or watch
+    ///   [`sequence_base`](Self::sequence_base), which changes on every
+    ///   rotation;
+    /// - a [`compact`](crate::EventLog::compact) or
+    ///   [`repair`](crate::EventLog::repair) of a log that has an archive is
+    ///   reported as [`Rotated`](RotationStatus::Rotated). Either way the
+    ///   offsets are invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, RotationStatus};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// let reader = log.reader();
+    /// let seen = reader.active_log_size()?;
+    /// assert_eq!(reader.detect_rotation(seen)?, RotationStatus::Unchanged);
+    ///
+    /// log.rotate()?;
+    /// assert_eq!(reader.detect_rotation(seen)?, RotationStatus::Rotated);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log's size or the archive cannot be read.
+    pub fn detect_rotation(&self, last_known_size: u64) -> io::Result<RotationStatus> {
+        let size = self.storage.size()?;
+        if size > last_known_size {
+            return Ok(RotationStatus::Appended);
+        }
+        if size == last_known_size {
+            return Ok(RotationStatus::Unchanged);
+        }
+        let archived = match self.storage.read_archive()? {
+            Some(mut archive) => archive.read(&mut [0])? > 0,
+            None => false,
+        };
+        Ok(if archived {
+            RotationStatus::Rotated
+        } else {
+            RotationStatus::Shrunk
+        })
+    }
+
+    /// Announce this reader to the writer by taking a shared advisory lock,
+    /// held until the returned guard is dropped.
+    ///
+    /// Any number of readers can hold the lock at once, and it never
+    /// conflicts with the writer's own lock, so this succeeds whether or
+    /// not a writer is running. The writer sees it through
+    /// [`EventWriter::readers_present`]. The lock lives in `readers.lock`
+    /// in the log directory, which is created if missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{EventReader, EventWriter};
+    /// # let dir = tempdir()?;
+    /// # #[cfg(feature = "std-fs")]
+    /// # {
+    /// let writer = EventWriter::open(dir.path())?;
+    /// let reader = EventReader::new(dir.path());
+    /// let _lock = reader.try_lock_shared()?;
+    /// assert!(writer.readers_present()?);
+    /// # }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unsupported` if the log's [`Storage`] has no local
+    /// directory or the crate is built without the `std-fs` feature.
+    /// Returns an error if the lock file cannot be created or locked.
+    pub fn try_lock_shared(&self) -> io::Result<ReaderLock> {
+        reader_lock::lock_shared(self.require_local("reader locks")?)
+    }
+
+    /// Block until new data appears after `offset` in the active log,
+    /// or until `timeout` elapses.
+    ///
+    /// Uses OS-level file system notifications (inotify on Linux,
+    /// kqueue on macOS, ReadDirectoryChangesW on Windows) for
+    /// near-zero-latency detection.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use eventfold::{EventReader, WaitResult};
+    /// # use std::time::Duration;
+    /// let reader = EventReader::new("./data");
+    /// let mut offset = 0u64;
+    /// loop {
+    ///     match reader.wait_for_events(offset, Duration::from_secs(5)).unwrap() {
+    ///         WaitResult::NewData(new_size) => {
+    ///             for result in reader.read_from(offset).unwrap() {
+    ///                 let (event, next_offset, _hash) = result.unwrap();
+    ///                 // process event
+    ///                 offset = next_offset;
+    ///             }
+    ///         }
+    ///         WaitResult::Timeout => {
+    ///             // No new events — do periodic housekeeping, etc.
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unsupported` if the log's [`Storage`] has no local
+    /// directory, or if the crate is built without the `std-fs` feature.
+    /// Returns an error if the file watcher cannot be initialized, if the
+    /// watcher channel disconnects, or if reading file metadata fails.
+    pub fn wait_for_events(
+        &self,
+        offset: u64,
+        timeout: Duration,
+    ) -> io::Result<WaitResult> {
+        let dir = self.require_local("waiting for events")?;
+        self.watch_for_events(dir, offset, timeout)
+    }
+
+    /// [`wait_for_events`](Self::wait_for_events) in the log directory
+    /// `dir`.
+    #[cfg(feature = "std-fs")]
+    fn watch_for_events(
+        &self,
+        dir: &Path,
+        offset: u64,
+        timeout: Duration,
+    ) -> io::Result<WaitResult> {
+        // Check immediately — data may already be available.
+        let current_size = self.active_log_size()?;
+        if current_size > offset {
+            return Ok(WaitResult::NewData(current_size));
+        }
+
+        // Set up a file watcher on the log file's parent directory.
+        let (tx, rx) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(move |res: Result<notify::Event, _>| {
+                if let Ok(event) = res
+                    && matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_))
+                {
+                    let _ = tx.send(());
+                }
+            })
+            .map_err(io::Error::other)?;
+
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(io::Error::other)?;
+
+        // Re-check after watcher is set up (avoid TOCTOU race).
+        let current_size = self.active_log_size()?;
+        if current_size > offset {
+            return Ok(WaitResult::NewData(current_size));
+        }
+
+        // Wait for a notification or timeout.
+        match rx.recv_timeout(timeout) {
+            Ok(()) => {
+                let new_size = self.active_log_size()?;
+                if new_size > offset {
+                    Ok(WaitResult::NewData(new_size))
+                } else {
+                    // Spurious wakeup (e.g., metadata change, not a write).
+                    // For simplicity, return Timeout. Caller will retry.
+                    Ok(WaitResult::Timeout)
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => Ok(WaitResult::Timeout),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                Err(io::Error::other("file watcher disconnected"))
+            }
+        }
+    }
+
+    #[cfg(not(feature = "std-fs"))]
+    fn watch_for_events(
+        &self,
+        _dir: &Path,
+        _offset: u64,
+        _timeout: Duration,
+    ) -> io::Result<WaitResult> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "waiting for events requires the `std-fs` feature",
+        ))
+    }
+
+    /// Follow the active log from `start_offset`, yielding each event as
+    /// it is appended.
+    ///
+    /// Replaces the hand-written [`wait_for_events`](Self::wait_for_events)
+    /// loop: the returned [`EventTail`] waits up to `poll_interval` at a
+    /// time, reads what arrived, and tracks its own offset. It runs until
+    /// an error unless limited with [`EventTail::max_idle`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use eventfold::EventReader;
+    /// # use std::time::Duration;
+    /// let reader = EventReader::new("./data");
+    /// for result in reader.events_stream(0, Duration::from_secs(5)) {
+    ///     let (event, _next_offset, _hash) = result?;
+    ///     println!("{}", event.event_type);
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// Errors are yielded by the iterator; see
+    /// [`wait_for_events`](Self::wait_for_events) and
+    /// [`read_from`](Self::read_from).
+    pub fn events_stream(&self, start_offset: u64, poll_interval: Duration) -> EventTail {
+        EventTail::new(self.clone(), start_offset, poll_interval)
+    }
+
+    /// Returns the path to the active log file.
+    pub fn log_path(&self) -> &Path {
+        &self.log_path
+    }
+
+    /// Returns the path to the archive file.
+    pub fn archive_path(&self) -> &Path {
+        &self.archive_path
+    }
+
+    /// Read the archive as encoded with `codec`, for a reader created with
+    /// [`new`](Self::new) before a log using a non-default
+    /// [`EventLogBuilder::archive_codec`] first rotated. Readers from
+    /// [`EventLog::reader`] already use the log's codec.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Codec, Event, EventLog, EventReader};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let reader = EventReader::new(dir.path()).with_archive_codec(Codec::None);
+    ///
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .archive_codec(Codec::None)
+    ///     .open()?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// log.rotate()?;
+    /// assert_eq!(reader.read_full()?.count(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn with_archive_codec(mut self, codec: Codec) -> Self {
+        self.archive_path = self.archive_path.with_file_name(codec.file_name());
+        if let Some(dir) = self.storage.local_dir() {
+            self.storage = Arc::new(FsStorage::read_only(dir).with_archive_codec(codec));
+        }
+        self
+    }
+
+    /// Decrypt lines with `key`, for a reader created with
+    /// [`new`](Self::new) over a log encrypted with
+    /// [`EventLogBuilder::encryption`]. Readers from [`EventLog::reader`]
+    /// already have the log's key. Requires the `crypto` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, EventReader};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let key = [7u8; 32];
+    /// let mut log = EventLog::builder(dir.path()).encryption(key).open()?;
+    /// log.append(&Event::new("click", json!({})))?;
+    ///
+    /// let reader = EventReader::new(dir.path()).with_encryption(key);
+    /// assert_eq!(reader.read_full()?.count(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn with_encryption(mut self, key: [u8; 32]) -> Self {
+        self.cipher = Some(Arc::new(Cipher::new(&key)));
+        self
+    }
+
+    /// The codec the archive is read with, named by its file.
+    fn archive_codec(&self) -> Codec {
+        Codec::from_path(&self.archive_path)
+    }
+
+    /// List the archive's frames, oldest first — one per rotation — with
+    /// each one's compressed byte range and event count.
+    ///
+    /// See [`list_frames`](crate::list_frames). Returns an empty list if there is no
+    /// archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the archive fails, if its frame
+    /// structure is malformed, or if a frame fails to decompress.
+    pub fn archive_frames(&self) -> io::Result<Vec<FrameInfo>> {
+        let bytes = self.archive_bytes()?;
+        archive::frame_infos(self.archive_codec(), &bytes, &self.archive_dictionaries()?)
+    }
+
+    /// Read the events of one archived frame (0-based, oldest first),
+    /// decompressing only that frame.
+    ///
+    /// Yields `(event, line_hash)` like [`read_full`](Self::read_full).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// # #[cfg(feature = "zstd")]
+    /// # {
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("first", json!({})))?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("second", json!({})))?;
+    /// log.rotate()?;
+    ///
+    /// let (event, _) = log.reader().read_archive_frame(1)?.next().unwrap()?;
+    /// assert_eq!(event.event_type, "second");
+    /// # }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if the archive has no frame `index`. Returns an
+    /// error if reading the archive fails, if its frame structure is
+    /// malformed, or if the frame fails to decompress. Individual iterator
+    /// items may also yield errors on malformed lines.
+    pub fn read_archive_frame(
+        &self,
+        index: usize,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, String)>>> {
+        let bytes = self.archive_bytes()?;
+        let dictionaries = self.archive_dictionaries()?;
+        let frame = archive::decode_frame_at(self.archive_codec(), &bytes, index, &dictionaries)?;
+        Ok(EventLineIter {
+            reader: io::Cursor::new(frame),
+            buf: String::new(),
+            at_start: true,
+            consumed: 0,
+            algo: self.hash_algo,
+            cipher: self.cipher.clone(),
+        })
+    }
+
+    /// Read the full history like [`read_full`](Self::read_full), also
+    /// yielding which part of it each event came from: an archive frame,
+    /// numbered as in [`archive_frames`](Self::archive_frames), or the
+    /// active log.
+    ///
+    /// Yields `(event, line_hash, segment)`. The compressed archive is held
+    /// in memory and decompressed one frame at a time. An archive written
+    /// with [`Codec::None`] has no frame boundaries, so all of it is frame
+    /// 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, SegmentId};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("first", json!({})))?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("second", json!({})))?;
+    ///
+    /// let segments: Vec<SegmentId> = log
+    ///     .reader()
+    ///     .read_full_with_segment()?
+    ///     .map(|r| r.map(|(_, _, segment)| segment))
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(segments, [SegmentId::Archive(0), SegmentId::Active]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the archive fails or its frame structure
+    /// is malformed, or if the active log cannot be opened. Individual
+    /// iterator items may also yield errors on malformed lines or frames
+    /// that fail to decompress.
+    pub fn read_full_with_segment(
+        &self,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, String, SegmentId)>>> {
+        let codec = self.archive_codec();
+        let bytes = self.archive_bytes()?;
+        let dictionaries = self.archive_dictionaries()?;
+        let ranges = codec.frame_ranges(&bytes)?;
+        let algo = self.hash_algo;
+        let cipher = self.cipher.clone();
+        let archive_iter = ranges
+            .into_iter()
+            .enumerate()
+            .flat_map(move |(index, range)| {
+                let segment = SegmentId::Archive(index);
+                let lines: Box<dyn Iterator<Item = io::Result<(Event, String)>>> =
+                    match codec.decode_frame(&bytes[range], &dictionaries) {
+                        Ok(frame) => Box::new(EventLineIter {
+                            reader: io::Cursor::new(frame),
+                            buf: String::new(),
+                            at_start: true,
+                            consumed: 0,
+                            algo,
+                            cipher: cipher.clone(),
+                        }),
+                        Err(e) => Box::new(std::iter::once(Err(e))),
+                    };
+                lines.map(move |r| r.map(|(event, hash)| (event, hash, segment)))
+            });
+        let active_iter = self
+            .read_from(0)?
+            .map(|r| r.map(|(event, _, hash)| (event, hash, SegmentId::Active)));
+        Ok(archive_iter.chain(active_iter))
+    }
+
+    /// The file checks of [`EventLog::verify`]: the active log and the
+    /// archive, without views.
+    pub(crate) fn verify_files(&self) -> io::Result<VerifyReport> {
+        let mut report = VerifyReport::default();
+        let cipher = self.cipher.as_deref();
+        verify::check_active(BufReader::new(self.storage.read_from(0)?), cipher, &mut report)?;
+        let bytes = self.archive_bytes()?;
+        let dictionaries = self.archive_dictionaries()?;
+        verify::check_archive(self.archive_codec(), &bytes, &dictionaries, cipher, &mut report);
+        Ok(report)
+    }
+
+    /// Every archive frame, decoded on its own, oldest first.
+    #[cfg(feature = "testing")]
+    pub(crate) fn archive_frame_bytes(&self) -> io::Result<Vec<Vec<u8>>> {
+        let bytes = self.archive_bytes()?;
+        let dictionaries = self.archive_dictionaries()?;
+        let codec = self.archive_codec();
+        codec
+            .frame_ranges(&bytes)?
+            .into_iter()
+            .map(|range| codec.decode_frame(&bytes[range], &dictionaries))
+            .collect()
+    }
+
+    /// The raw archive bytes, empty if there is no archive.
+    fn archive_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        if let Some(mut raw) = self.storage.read_archive()? {
+            raw.read_to_end(&mut bytes)?;
+        }
+        Ok(bytes)
+    }
+
+    /// The archive's dictionaries; only available with a local directory.
+    fn archive_dictionaries(&self) -> io::Result<BTreeMap<u32, Vec<u8>>> {
+        let local_archive = self.storage.local_dir().map(|_| self.archive_path.as_path());
+        archive::load_dictionaries(local_archive)
+    }
+
+    /// Open the archive through the storage, or `None` if there is none.
+    /// Dictionaries are only available with a local directory.
+    fn open_archive(&self) -> io::Result<Option<Box<dyn BufRead>>> {
+        let local_archive = self.storage.local_dir().map(|_| self.archive_path.as_path());
+        match self.storage.read_archive()? {
+            Some(raw) => {
+                archive::decode_archive(self.archive_codec(), raw, local_archive).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Return the storage's local directory, or `Unsupported` naming the
+    /// `feature` that needs one.
+    pub(crate) fn require_local(&self, feature: &str) -> io::Result<&Path> {
+        require_local(self.storage.as_ref(), feature)
+    }
+
+    /// The observer of the writer this reader came from, if any.
+    pub(crate) fn observer(&self) -> Option<&dyn LogObserver> {
+        self.observer.get()
+    }
+
+    /// The upcasters of the writer this reader came from.
+    pub(crate) fn upcasters(&self) -> &Upcasters {
+        &self.upcasters
+    }
+
+    /// The cipher lines are sealed with, if the log is encrypted.
+    pub(crate) fn cipher(&self) -> Option<&Cipher> {
+        self.cipher.as_deref()
+    }
+}
+
+/// Return `storage`'s local directory, or `Unsupported` naming the
+/// `feature` that needs one.
+fn require_local<'a>(storage: &'a dyn Storage, feature: &str) -> io::Result<&'a Path> {
+    storage.local_dir().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("{feature} requires storage with a local directory"),
+        )
+    })
+}
+
+/// An append-only event log backed by files in a single directory.
+///
+/// The log manages an active log file (`app.jsonl`), a compressed archive
+/// (`archive.jsonl.zst`), a views directory for snapshots, and an optional
+/// set of registered views for auto-rotation and bulk refresh.
+///
+/// Composes an [`EventWriter`] and [`EventReader`] with a view registry.
+/// For advanced use cases (multiple readers, direct writer access), use
+/// [`EventWriter`] and [`EventReader`] directly.
+///
+/// Use [`EventLog::builder`] to configure views and auto-rotation, or
+/// [`EventLog::open`] for a bare log without registered views.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventLog};
+/// use serde_json::json;
+///
+/// # let dir = tempdir().unwrap();
+/// let mut log = EventLog::open(dir.path()).unwrap();
+/// log.append(&Event::new("click", json!({"x": 10}))).unwrap();
+///
+/// let events: Vec<_> = log.read_from(0).unwrap()
+///     .collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(events.len(), 1);
+/// ```
+pub struct EventLog {
+    writer: EventWriter,
+    reader: EventReader,
+    views: HashMap<String, Box<dyn ViewOps>>,
+    subscribers: Vec<Subscriber>,
+    /// Cleared by [`EventLog::set_auto_rotation`] to hold rotations back.
+    auto_rotation: bool,
+}
+
+/// A side effect run for every event appended through an [`EventLog`]; see
+/// [`EventLog::subscribe`].
+pub type Subscriber = Box<dyn Fn(&Event, &AppendResult)>;
+
+impl std::fmt::Debug for EventLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventLog")
+            .field("writer", &self.writer)
+            .field("reader", &self.reader)
+            .field("view_count", &self.views.len())
+            .field("subscriber_count", &self.subscribers.len())
+            .field("auto_rotation", &self.auto_rotation)
+            .finish()
+    }
+}
+
+impl Drop for EventLog {
+    /// Best-effort save of view snapshots held back by a
+    /// [`SnapshotPolicy`]. Errors are only logged; call
+    /// [`EventLog::flush_views`] for guaranteed persistence. Views already
+    /// flushed are not written again.
+    fn drop(&mut self) {
+        if let Err(e) = self.flush_views() {
+            warn!("eventfold: failed to flush view snapshots on drop: {e}");
+        }
+    }
+}
+
+/// A factory closure that creates a boxed view for the writer it will
+/// follow.
+type ViewFactory = Box<dyn FnOnce(&EventWriter) -> io::Result<Box<dyn ViewOps>>>;
+
+/// Builder for configuring and opening an [`EventLog`].
+///
+/// Register views and set auto-rotation thresholds before calling
+/// [`open`](EventLogBuilder::open) to create the log.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// # use eventfold::{Event, EventLog};
+/// # use serde::{Serialize, Deserialize};
+/// # #[derive(Default, Clone, Serialize, Deserialize)]
+/// # struct Counter { count: u64 }
+/// # fn count(mut s: Counter, _e: &Event) -> Counter { s.count += 1; s }
+/// # let dir = tempdir().unwrap();
+/// let mut log = EventLog::builder(dir.path())
+///     .max_log_size(10_000_000)  // auto-rotate at 10 MB
+///     .view::<Counter>("counter", count)
+///     .open()
+///     .unwrap();
+/// ```
+pub struct EventLogBuilder {
+    dir: PathBuf,
+    storage: Option<Arc<dyn Storage>>,
+    max_log_size: u64,
+    max_log_events: u64,
+    lock_mode: LockMode,
+    sync_policy: SyncPolicy,
+    max_unsynced_events: u64,
+    archive_codec: Option<Codec>,
+    hash_algo: Option<HashAlgo>,
+    encryption: Option<Arc<Cipher>>,
+    archive_dictionary: Option<Vec<u8>>,
+    archive_retention: Option<Retention>,
+    id_scheme: Option<IdScheme>,
+    schemas: Schemas,
+    upcasters: Upcasters,
+    hash_chain: bool,
+    index: bool,
+    id_index: bool,
+    dedup_window: usize,
+    observer: Option<Arc<dyn LogObserver>>,
+    snapshot_policy: SnapshotPolicy,
+    read_only: bool,
+    write_snapshots: Option<bool>,
+    pretty_snapshots: bool,
+    view_snapshot_dir: Option<PathBuf>,
+    views_dir_name: Option<String>,
+    views_path: Option<PathBuf>,
+    view_factories: Vec<ViewFactory>,
+}
+
+impl std::fmt::Debug for EventLogBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventLogBuilder")
+            .field("dir", &self.dir)
+            .field("storage", &self.storage)
+            .field("max_log_size", &self.max_log_size)
+            .field("max_log_events", &self.max_log_events)
+            .field("lock_mode", &self.lock_mode)
+            .field("sync_policy", &self.sync_policy)
+            .field("max_unsynced_events", &self.max_unsynced_events)
+            .field("archive_codec", &self.archive_codec)
+            .field("hash_algo", &self.hash_algo)
+            .field("encryption", &self.encryption.is_some())
+            .field("archive_dictionary", &self.archive_dictionary.is_some())
+            .field("archive_retention", &self.archive_retention)
+            .field("id_scheme", &self.id_scheme)
+            .field("schemas", &self.schemas)
+            .field("upcasters", &self.upcasters)
+            .field("hash_chain", &self.hash_chain)
+            .field("index", &self.index)
+            .field("id_index", &self.id_index)
+            .field("dedup_window", &self.dedup_window)
+            .field("observer", &self.observer.is_some())
+            .field("snapshot_policy", &self.snapshot_policy)
+            .field("read_only", &self.read_only)
+            .field("write_snapshots", &self.write_snapshots)
+            .field("pretty_snapshots", &self.pretty_snapshots)
+            .field("view_snapshot_dir", &self.view_snapshot_dir)
+            .field("views_dir_name", &self.views_dir_name)
+            .field("views_path", &self.views_path)
+            .field("view_count", &self.view_factories.len())
+            .finish()
+    }
+}
+
+impl EventLogBuilder {
+    /// Set the maximum active log size in bytes before auto-rotation triggers.
+    /// A value of 0 (the default) disables auto-rotation.
+    ///
+    /// An event whose line alone is larger than `bytes` is still written
+    /// in full, and the rotation that follows moves it into an archive frame
+    /// of its own. The active log is empty again afterwards, so each
+    /// oversized append costs exactly one rotation and later appends are
+    /// unaffected.
+    pub fn max_log_size(mut self, bytes: u64) -> Self {
+        self.max_log_size = bytes;
+        self
+    }
+
+    /// Set the maximum number of events in the active log before
+    /// auto-rotation triggers, regardless of their size. A value of 0 (the
+    /// default) disables count-based rotation. Combined with
+    /// [`max_log_size`](Self::max_log_size), whichever limit is reached
+    /// first triggers a rotation.
+    ///
+    /// The count is recovered by counting the active log's lines on open.
+    /// A batch append is counted as a whole and checked once, so a frame
+    /// may exceed `n` events by up to one batch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .max_log_events(2)
+    ///     .open()?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// log.append(&Event::new("b", json!({})))?; // rotates
+    /// assert_eq!(log.active_log_size()?, 0);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn max_log_events(mut self, n: u64) -> Self {
+        self.max_log_events = n;
+        self
+    }
+
+    /// Set the file locking mode. Default is [`LockMode::Flock`].
+    pub fn lock_mode(mut self, mode: LockMode) -> Self {
+        self.lock_mode = mode;
+        self
+    }
+
+    /// Set the sync policy for appends. Default is [`SyncPolicy::EveryAppend`].
+    /// See [`SyncPolicy`] for what each policy may lose on power failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, SyncPolicy};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .sync_policy(SyncPolicy::Never)
+    ///     .open()?;
+    /// for i in 0..1000 {
+    ///     log.append(&Event::new("imported", json!({"row": i})))?;
+    /// }
+    /// // One fsync for the whole load.
+    /// log.sync()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn sync_policy(mut self, policy: SyncPolicy) -> Self {
+        self.sync_policy = policy;
+        self
+    }
+
+    /// Force a sync once `n` appended events are unsynced, regardless of the
+    /// [`SyncPolicy`]. Bounds the worst-case number of events lost on power
+    /// failure under relaxed policies. A value of 0 (the default) means no bound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{EventLog, SyncPolicy};
+    /// use std::time::Duration;
+    /// # let dir = tempdir()?;
+    /// let log = EventLog::builder(dir.path())
+    ///     .sync_policy(SyncPolicy::Interval(Duration::from_secs(1)))
+    ///     .max_unsynced_events(100)
+    ///     .open()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn max_unsynced_events(mut self, n: u64) -> Self {
+        self.max_unsynced_events = n;
+        self
+    }
+
+    /// Encode the archive with `codec` instead of zstd. The archive file is
+    /// named after the codec (see [`Codec`]), so readers pick the right
+    /// decoder.
+    ///
+    /// By default a log keeps the codec of the archive it already has, or
+    /// uses [`Codec::Zstd`] for a new one. With [`EventLog::with_storage`],
+    /// open an [`FsStorage`] with [`FsStorage::with_archive_codec`] too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Codec, EventLog};
+    /// # let dir = tempdir()?;
+    /// let log = EventLog::builder(dir.path())
+    ///     .archive_codec(Codec::None)
+    ///     .open()?;
+    /// assert!(log.archive_path().ends_with("archive.jsonl"));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn archive_codec(mut self, codec: Codec) -> Self {
+        self.archive_codec = Some(codec);
+        self
+    }
+
+    /// Hash event lines with `algo` instead of xxh64 (see [`HashAlgo`]).
+    ///
+    /// The choice is recorded in the log directory when the log is
+    /// created, and every later writer and reader of the directory uses
+    /// it, with or without this option. It can't change afterwards:
+    /// [`open`](Self::open) fails with `InvalidInput` if the log already
+    /// has history hashed another way.
+    pub fn hash_algo(mut self, algo: HashAlgo) -> Self {
+        self.hash_algo = Some(algo);
+        self
+    }
+
+    /// Encrypt the log at rest with AES-256-GCM under `key`. Requires the
+    /// `crypto` feature.
+    ///
+    /// Each event line is sealed on its own, under a fresh random nonce
+    /// stored with it, and written as one base64 line. Byte offsets still
+    /// point at whole lines, so [`read_from`](EventReader::read_from),
+    /// [`read_range`](EventReader::read_range) and the other offset-based
+    /// reads work as before, and they and [`read_full`](EventReader::read_full)
+    /// decrypt transparently. Rotation moves the stored lines into the
+    /// archive, so frames are encrypted too (and compress little). The
+    /// snapshots of registered views and dead letters are sealed as well.
+    ///
+    /// [`AppendResult::line_hash`], snapshot hashes and hash-chain links
+    /// are hashes of the stored, encrypted line, not of the event's JSON.
+    /// [`EventLog::append_if`] works unchanged, since the hash it expects
+    /// comes from an earlier append or read of the same log; a hash
+    /// computed from the event itself never matches.
+    ///
+    /// Like [`hash_algo`](Self::hash_algo), encryption is recorded in the
+    /// log directory when the log is created and can't change afterwards:
+    /// [`open`](Self::open) fails with `InvalidInput` on an encrypted log
+    /// without a key, as [`EventLog::open`] does, and on a plaintext log
+    /// with history. A wrong key opens, but reads fail with `InvalidData`;
+    /// a log cannot be read at all without its key. Use
+    /// [`EventReader::with_encryption`] for a standalone reader. Random
+    /// nonces limit a key to about 2^32 lines and snapshots.
+    ///
+    /// [`LogView`]s, whose output is plaintext, can't be registered on an
+    /// encrypted log, and [`export_ndjson`](EventLog::export_ndjson) writes
+    /// plaintext events by design.
+    ///
+    /// These files beside the log stay plaintext. None holds event data:
+    ///
+    /// - `manifest.json` and `archive.manifest.json` (including any archive
+    ///   dictionary);
+    /// - `seq` and `seq.pending` (sequence numbers and a hash of the
+    ///   active log);
+    /// - `app.index` (byte offsets, with [`index`](Self::index));
+    /// - `cursors/*.cursor` (sequence numbers, see [`EventLog::cursor`]);
+    /// - `readers.lock`;
+    /// - `rotations.log`, whose timestamps, sequence numbers and **notes**
+    ///   are written as given to [`EventLog::rotate_annotated`], so keep
+    ///   secrets out of notes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let key = [7u8; 32];
+    /// let mut log = EventLog::builder(dir.path()).encryption(key).open()?;
+    /// let result = log.append(&Event::new("click", json!({"x": 1})))?;
+    ///
+    /// let stored = std::fs::read_to_string(log.log_path())?;
+    /// assert!(!stored.contains("click"));
+    /// let (event, _, hash) = log.read_from(0)?.next().unwrap()?;
+    /// assert_eq!(event.event_type, "click");
+    /// assert_eq!(hash, result.line_hash);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn encryption(mut self, key: [u8; 32]) -> Self {
+        self.encryption = Some(Arc::new(Cipher::new(&key)));
+        self
+    }
+
+    /// Compress future archive frames with a trained zstd dictionary
+    /// (see [`EventLog::train_archive_dictionary`]).
+    ///
+    /// The dictionary is recorded in `archive.manifest.json` before the first
+    /// frame that uses it is written, so the archive stays readable even if
+    /// the log is later opened without a dictionary, or with a different one.
+    /// Requires [`Storage`] with a local directory.
+    pub fn archive_dictionary(mut self, dict: Vec<u8>) -> Self {
+        self.archive_dictionary = Some(dict);
+        self
+    }
+
+    /// Bound the archive: after each rotation, drop the oldest frames that
+    /// fall outside `retention`. **Dropped events are permanently
+    /// deleted**; see [`Retention`] for what that means for views. By
+    /// default the archive grows forever.
+    ///
+    /// Requires a [`Storage`] that implements
+    /// [`replace_archive`](Storage::replace_archive), as [`FsStorage`]
+    /// does; otherwise rotation fails with `Unsupported` once there is
+    /// something to drop.
+    pub fn archive_retention(mut self, retention: Retention) -> Self {
+        self.archive_retention = Some(retention);
+        self
+    }
+
+    /// Give every appended event that has no `id` a generated one, using
+    /// `scheme`. The ID is written to the log line and returned in
+    /// [`AppendResult::assigned_id`]; events that already have an `id` are
+    /// written unchanged. Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, IdScheme};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .auto_id(IdScheme::Ulid)
+    ///     .open()?;
+    /// let result = log.append(&Event::new("click", json!({})))?;
+    /// let id = result.assigned_id.expect("an ID was generated");
+    /// let (event, _, _) = log.read_from(0)?.next().unwrap()?;
+    /// assert_eq!(event.id, Some(id));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn auto_id(mut self, scheme: IdScheme) -> Self {
+        self.id_scheme = Some(scheme);
+        self
+    }
+
+    /// Validate the `data` of every appended event of type `event_type`
+    /// with `validator` before it is written.
+    ///
+    /// If the validator returns `Err(message)`, the append fails with a
+    /// [`SchemaViolation`](crate::SchemaViolation) and nothing is written;
+    /// in a batch or transaction, a single rejected event fails the whole
+    /// write. Event types without a validator are not checked. Registering
+    /// a type again replaces its validator. Events already in the log are
+    /// never checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .schema("order_placed", |data| {
+    ///         if data["qty"].as_u64().is_some_and(|qty| qty > 0) {
+    ///             Ok(())
+    ///         } else {
+    ///             Err("qty must be a positive integer".to_string())
+    ///         }
+    ///     })
+    ///     .open()?;
+    ///
+    /// log.append(&Event::new("order_placed", json!({"qty": 2})))?;
+    /// assert!(log.append(&Event::new("order_placed", json!({"qty": "2"}))).is_err());
+    /// log.append(&Event::new("note", json!("anything")))?;
+    /// assert_eq!(log.read_from(0)?.count(), 2);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn schema<F>(mut self, event_type: &str, validator: F) -> Self
+    where
+        F: Fn(&serde_json::Value) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.schemas.insert(event_type, Box::new(validator));
+        self
+    }
+
+    /// Migrate the `data` of `event_type` events at schema version
+    /// `from_version` to version `from_version + 1` with `upcast` before
+    /// views fold them.
+    ///
+    /// Versions are set with [`Event::with_schema_version`]; events without
+    /// one count as version 1. Upcasters chain, so an event several
+    /// versions behind passes through each in turn, and the event the
+    /// reducer sees carries the version it ended at. The log on disk is
+    /// never rewritten, and reads other than view refreshes and replays
+    /// return events as written. Registering the same type and version
+    /// again replaces its upcaster. Changing upcasters changes what views
+    /// fold, so pair it with
+    /// [`view_versioned`](Self::view_versioned) or
+    /// [`EventLog::rebuild_all`] to discard stale snapshots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    ///
+    /// fn total(state: u64, event: &Event) -> u64 {
+    ///     state + event.data["cents"].as_u64().unwrap_or(0)
+    /// }
+    ///
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     // Version 1 stored whole dollars; version 2 stores cents.
+    ///     .upcaster("paid", 1, |data| {
+    ///         let dollars = data["dollars"].as_u64().unwrap_or(0);
+    ///         *data = json!({"cents": dollars * 100});
+    ///     })
+    ///     .view::<u64>("total", total)
+    ///     .open()?;
+    ///
+    /// log.append(&Event::new("paid", json!({"dollars": 3})))?;
+    /// log.append(&Event::new("paid", json!({"cents": 50})).with_schema_version(2))?;
+    /// log.refresh_all()?;
+    /// assert_eq!(*log.view::<u64>("total")?, 350);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn upcaster<F>(mut self, event_type: &str, from_version: u32, upcast: F) -> Self
+    where
+        F: Fn(&mut serde_json::Value) + Send + Sync + 'static,
+    {
+        let upcast: UpcastFn = Arc::new(upcast);
+        self.upcasters.insert(event_type, from_version, upcast);
+        self
+    }
+
+    /// Link every appended event to the line before it, making edits
+    /// anywhere in the history detectable with
+    /// [`verify_chain`](EventLog::verify_chain). Off by default.
+    ///
+    /// Each event gets the previous line's [`line_hash`] in its `meta`
+    /// under the reserved `"prev"` key, so an event with a non-object
+    /// `meta` is rejected with `InvalidInput`. The chain continues across
+    /// rotations and reopens; opening reads the last line's hash, which
+    /// means decompressing the archive when the active log is empty. It
+    /// assumes a single writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path()).hash_chain(true).open()?;
+    /// let first = log.append(&Event::new("a", json!({})))?;
+    /// log.append(&Event::new("b", json!({})))?;
+    ///
+    /// let events: Vec<_> = log.read_from(0)?.map(|r| r.unwrap().0).collect();
+    /// assert_eq!(events[0].meta.as_ref().unwrap()["prev"], "");
+    /// assert_eq!(events[1].meta.as_ref().unwrap()["prev"], first.line_hash);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn hash_chain(mut self, enabled: bool) -> Self {
+        self.hash_chain = enabled;
+        self
+    }
+
+    /// Keep a sparse index of the active log in `app.index`, recording the
+    /// offset of every 256th event, so
+    /// [`EventReader::offset_of_event`] seeks to any event by position in
+    /// near-constant time. Off by default.
+    ///
+    /// Opening rebuilds the index from the active log, so it is always
+    /// consistent after a crash; rotation empties it along with the log.
+    /// Opening a writer without it deletes any existing index. Requires
+    /// storage with a local directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::EventLog;
+    /// # let dir = tempdir()?;
+    /// let log = EventLog::builder(dir.path()).index(true).open()?;
+    /// assert!(dir.path().join("app.index").exists());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn index(mut self, enabled: bool) -> Self {
+        self.index = enabled;
+        self
+    }
+
+    /// Keep an in-memory map from each event ID to the sequence number of
+    /// the first event carrying it, so [`EventReader::find_by_id`] on this
+    /// log's readers skips the scan. Off by default.
+    ///
+    /// Opening reads the full history to build the map, and appends keep it
+    /// current, including IDs assigned by [`auto_id`](Self::auto_id). It is
+    /// rebuilt after compaction, repair, and archive retention drop or
+    /// renumber events. Memory grows with the number of distinct IDs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, IdScheme};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .auto_id(IdScheme::Ulid)
+    ///     .id_index(true)
+    ///     .open()?;
+    /// let id = log.append(&Event::new("a", json!({})))?.assigned_id.unwrap();
+    /// assert_eq!(log.reader().find_by_id(&id)?.unwrap().1, 0);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn id_index(mut self, enabled: bool) -> Self {
+        self.id_index = enabled;
+        self
+    }
+
+    /// Remember the IDs of the last `n` events for
+    /// [`EventLog::append_idempotent`], instead of 1024. A retry arriving
+    /// more than `n` events after the original is written again, unless
+    /// [`id_index`](Self::id_index) is on. A value of 0 remembers none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path()).dedup_window(1).open()?;
+    /// let event = Event::new("payment", json!({}));
+    /// log.append_idempotent(&event, "a")?;
+    /// log.append_idempotent(&event, "b")?;
+    /// log.append_idempotent(&event, "a")?; // out of the window
+    /// assert_eq!(log.read_from(0)?.count(), 3);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn dedup_window(mut self, n: usize) -> Self {
+        self.dedup_window = n;
+        self
+    }
+
+    /// Notify `observer` of appends, rotations, and view refreshes and
+    /// rebuilds. No observer by default.
+    ///
+    /// Views report through the reader they refresh from, so a standalone
+    /// [`View`] refreshed with [`EventLog::reader`] is observed too. See
+    /// [`LogObserver`] for an example.
+    pub fn observer(mut self, observer: Arc<dyn LogObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Save registered views' snapshots on the cadence `policy` sets
+    /// instead of after every refresh that applies an event, to cut
+    /// snapshot writes under frequent appends. Snapshots held back are
+    /// saved when the log is dropped or by [`EventLog::flush_views`].
+    ///
+    /// A crash may leave a snapshot up to one policy window behind; the
+    /// view then replays those events on its next refresh. See
+    /// [`SnapshotPolicy`] for an example. [`LogView`]s are unaffected: their
+    /// checkpoint tracks their output file on every refresh.
+    pub fn snapshot_every(mut self, policy: SnapshotPolicy) -> Self {
+        self.snapshot_policy = policy;
+        self
+    }
+
+    /// Open the log for reading only (see [`EventLog::open_readonly`]):
+    /// no lock is taken and nothing is created, appends, rotation,
+    /// compaction and repair fail with `PermissionDenied`, and views don't
+    /// save snapshots unless [`write_snapshots`](Self::write_snapshots)
+    /// says so. The sparse [`index`](Self::index) and
+    /// [`id_index`](Self::id_index) can't be kept up to date without
+    /// appending, so they are refused.
+    pub fn read_only(mut self, enabled: bool) -> Self {
+        self.read_only = enabled;
+        self
+    }
+
+    /// Whether registered views save their snapshots. Defaults to `true`,
+    /// or `false` for a [`read_only`](Self::read_only) log so it never
+    /// overwrites the writer's snapshots. A read-only log that writes them
+    /// lets the next reader resume where it stopped, at the cost of
+    /// racing the writer for the same files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::EventLog;
+    /// # let dir = tempdir()?;
+    /// # drop(EventLog::open(dir.path())?);
+    /// let log = EventLog::builder(dir.path())
+    ///     .read_only(true)
+    ///     .write_snapshots(true)
+    ///     .view::<u64>("counter", |state, _event| state + 1)
+    ///     .open()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn write_snapshots(mut self, enabled: bool) -> Self {
+        self.write_snapshots = Some(enabled);
+        self
+    }
+
+    /// Whether registered views indent their snapshot JSON. Defaults to
+    /// `true`, so snapshots are easy to read by hand; `false` writes
+    /// compact JSON, which for a large state can be a fraction of the
+    /// size. Snapshots load the same either way, so this can be changed
+    /// at any time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .pretty_snapshots(false)
+    ///     .view::<u64>("counter", |state, _event| state + 1)
+    ///     .open()?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// log.refresh_all()?;
+    ///
+    /// let snapshot = std::fs::read_to_string(dir.path().join("views/counter.snapshot.json"))?;
+    /// assert!(!snapshot.contains('\n'));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn pretty_snapshots(mut self, enabled: bool) -> Self {
+        self.pretty_snapshots = enabled;
+        self
+    }
+
+    /// Keep the registered views' snapshots in `dir` (created at open)
+    /// instead of the log's `views/`. The log directory still holds
+    /// `app.jsonl` and the archive; only snapshots move, so a
+    /// [`read_only`](Self::read_only) reader can keep its own under, say,
+    /// `views-reader/` without touching the writer's. Setting it turns
+    /// [`write_snapshots`](Self::write_snapshots) on by default.
+    /// [`LogView`] output and checkpoints stay in the views directory; to
+    /// move those too, use [`views_path`](Self::views_path).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// # {
+    /// #     let mut log = EventLog::open(dir.path())?;
+    /// #     log.append(&Event::new("click", json!({})))?;
+    /// # }
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .read_only(true)
+    ///     .view_snapshot_dir(dir.path().join("views-reader"))
+    ///     .view::<u64>("counter", |state, _event| state + 1)
+    ///     .open()?;
+    /// log.refresh_all()?;
+    /// assert!(dir.path().join("views-reader/counter.snapshot.json").exists());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn view_snapshot_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.view_snapshot_dir = Some(dir.into());
+        self
+    }
+
+    /// Name the views directory inside the log directory `name` instead of
+    /// `views` (created at open). Snapshots, [`LogView`] output and
+    /// checkpoints all move with it, and
+    /// [`EventLog::views_dir`] reports it.
+    ///
+    /// The name is not recorded in the log directory, so every open that
+    /// should share the views must set it: a plain [`EventWriter::open`]
+    /// still uses `views/`. [`EventReader`]s read only the log and archive
+    /// and are unaffected. It is ignored for a [`Storage`] without a local
+    /// directory, which keeps the views itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .views_dir_name("projections")
+    ///     .view::<u64>("counter", |state, _event| state + 1)
+    ///     .open()?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// log.refresh_all()?;
+    /// assert_eq!(log.views_dir(), dir.path().join("projections"));
+    /// assert!(dir.path().join("projections/counter.snapshot.json").exists());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn views_dir_name(mut self, name: &str) -> Self {
+        self.views_dir_name = Some(name.to_string());
+        self
+    }
+
+    /// Keep the whole views tree in `dir` (created at open), anywhere on
+    /// disk, for example on a tmpfs or beside a log directory mounted
+    /// read-only. Like [`views_dir_name`](Self::views_dir_name), which it
+    /// overrides, it moves snapshots, [`LogView`] output and checkpoints.
+    /// Snapshots stay in [`view_snapshot_dir`](Self::view_snapshot_dir) if
+    /// that is set too. Because views can then save without touching the
+    /// log directory, it turns [`write_snapshots`](Self::write_snapshots)
+    /// on by default for a [`read_only`](Self::read_only) log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// # let scratch = tempdir()?;
+    /// # EventLog::open(dir.path())?.append(&Event::new("click", json!({})))?;
+    /// let views = scratch.path().join("eventfold-views");
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .read_only(true)
+    ///     .views_path(&views)
+    ///     .view::<u64>("counter", |state, _event| state + 1)
+    ///     .open()?;
+    /// log.refresh_all()?;
+    /// assert!(views.join("counter.snapshot.json").exists());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn views_path(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.views_path = Some(dir.into());
+        self
+    }
+
+    /// Register a view with the given name and reducer function.
+    ///
+    /// View names must be unique across [`view`](Self::view),
+    /// [`view_versioned`](Self::view_versioned),
+    /// [`view_with_context`](Self::view_with_context),
+    /// [`try_view`](Self::try_view), [`typed_view`](Self::typed_view) and
+    /// [`log_view`](Self::log_view): they name the snapshot files, so
+    /// [`open`](Self::open) fails with `InvalidInput` if two views share
+    /// one.
+    pub fn view<S>(mut self, name: &str, reducer: ReduceFn<S>) -> Self
+    where
+        S: Serialize + DeserializeOwned + Default + Clone + 'static,
+    {
+        let name = name.to_string();
+        self.view_factories.push(Box::new(move |writer| {
+            let view = View::new(&name, reducer, writer.views_dir());
+            Ok(Box::new(view.with_snapshot_store(writer.snapshot_store(&name))))
+        }));
+        self
+    }
+
+    /// Register a view like [`view`](Self::view), tagged with a reducer
+    /// `version` (see [`View::with_reducer_version`]). Bump the version when
+    /// the reducer's logic changes and the view rebuilds from the full
+    /// history on its next refresh instead of trusting the old snapshot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// {
+    ///     let mut log = EventLog::builder(dir.path())
+    ///         .view_versioned::<u64>("total", |state, _event| state + 1, 1)
+    ///         .open()?;
+    ///     log.append(&Event::new("sale", json!({})))?;
+    ///     log.refresh_all()?;
+    /// }
+    ///
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .view_versioned::<u64>("total", |state, _event| state + 100, 2)
+    ///     .open()?;
+    /// log.refresh_all()?;
+    /// assert_eq!(*log.view::<u64>("total")?, 100);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn view_versioned<S>(mut self, name: &str, reducer: ReduceFn<S>, version: u32) -> Self
+    where
+        S: Serialize + DeserializeOwned + Default + Clone + 'static,
+    {
+        let name = name.to_string();
+        self.view_factories.push(Box::new(move |writer| {
+            let view = View::new(&name, reducer, writer.views_dir())
+                .with_snapshot_store(writer.snapshot_store(&name))
+                .with_reducer_version(version);
+            Ok(Box::new(view))
+        }));
+        self
+    }
+
+    /// Register a view whose reducer also receives each event's
+    /// [`EventContext`](crate::EventContext): its offset, sequence number,
+    /// and line hash (see [`View::new_with_context`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// use std::collections::BTreeMap;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .view_with_context::<BTreeMap<String, u64>>("by_id", |mut state, event, at| {
+    ///         if let Some(id) = &event.id {
+    ///             state.insert(id.clone(), at.sequence);
+    ///         }
+    ///         state
+    ///     })
+    ///     .open()?;
+    /// log.append(&Event::new("a", json!({})).with_id("first"))?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("b", json!({})).with_id("second"))?;
+    /// log.refresh_all()?;
+    ///
+    /// let by_id = log.view::<BTreeMap<String, u64>>("by_id")?;
+    /// assert_eq!(by_id["second"], 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn view_with_context<S>(mut self, name: &str, reducer: ContextReduceFn<S>) -> Self
+    where
+        S: Serialize + DeserializeOwned + Default + Clone + 'static,
+    {
+        let name = name.to_string();
+        self.view_factories.push(Box::new(move |writer| {
+            let view = View::new_with_context(&name, reducer, writer.views_dir());
+            Ok(Box::new(view.with_snapshot_store(writer.snapshot_store(&name))))
+        }));
+        self
+    }
+
+    /// Register a view whose reducer can fail. A failure stops the refresh
+    /// (and so [`EventLog::refresh_all`] and rotation) with a
+    /// [`ReduceFailure`](crate::ReduceFailure) (see [`View::new_fallible`]).
+    pub fn try_view<S>(mut self, name: &str, reducer: TryReduceFn<S>) -> Self
+    where
+        S: Serialize + DeserializeOwned + Default + Clone + 'static,
+    {
+        let name = name.to_string();
+        self.view_factories.push(Box::new(move |writer| {
+            let view = View::new_fallible(&name, reducer, writer.views_dir());
+            Ok(Box::new(view.with_snapshot_store(writer.snapshot_store(&name))))
+        }));
+        self
+    }
+
+    /// Register a view whose reducer receives each event's `data`
+    /// deserialized as `P`. Events that don't match are skipped and recorded
+    /// in the dead-letter log (see [`View::new_typed`]).
+    pub fn typed_view<S, P>(mut self, name: &str, reducer: TypedReduceFn<S, P>) -> Self
+    where
+        S: Serialize + DeserializeOwned + Default + Clone + 'static,
+        P: DeserializeOwned + 'static,
+    {
+        let name = name.to_string();
+        self.view_factories.push(Box::new(move |writer| {
+            let view = View::new_typed(&name, reducer, writer.views_dir());
+            Ok(Box::new(view.with_snapshot_store(writer.snapshot_store(&name))))
+        }));
+        self
+    }
+
+    /// Register an append-only [`LogView`] with the given name and writer
+    /// function. Its output lives at `views/{name}.output.jsonl` and is kept
+    /// consistent across rotations like any other registered view.
+    /// Requires [`Storage`] with a local directory.
+    pub fn log_view(mut self, name: &str, writer: WriteFn) -> Self {
+        let name = name.to_string();
+        self.view_factories.push(Box::new(move |event_writer| {
+            require_local(event_writer.storage().as_ref(), "log views")?;
+            if event_writer.cipher().is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "an encrypted log cannot keep a log view, whose output is plaintext",
+                ));
+            }
+            Ok(Box::new(LogView::new(&name, writer, event_writer.views_dir())))
+        }));
+        self
+    }
+
+    /// Open (or create) the event log with the configured settings.
+    ///
+    /// Creates the directory structure, initializes all registered views,
+    /// and performs auto-rotation if the active log has reached
+    /// `max_log_size` or `max_log_events`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if opening the writer fails (directory creation,
+    /// file open, lock acquisition) or if auto-rotation fails. Returns
+    /// `InvalidInput` if an archive dictionary has no dictionary ID, if an
+    /// archive dictionary or retention policy is combined with
+    /// [`Codec::None`], or if the directory already has an archive in a
+    /// different codec than [`archive_codec`](Self::archive_codec) asks
+    /// for, if two registered views share a name, if the log already
+    /// hashes its lines with a different [`hash_algo`](Self::hash_algo),
+    /// if an encrypted log is opened without its
+    /// [`encryption`](Self::encryption) key or a plaintext log with history
+    /// with one, or if a log view is registered on an encrypted log.
+    /// Returns `Unsupported` if an archive dictionary, log view, or
+    /// non-default hash algorithm is configured on storage without a
+    /// local directory.
+    pub fn open(self) -> io::Result<EventLog> {
+        if let Some(dict) = &self.archive_dictionary {
+            archive::dictionary_id(dict)?;
+        }
+        if let Some(codec) = self.archive_codec {
+            self.check_archive_codec(codec)?;
+        }
+        if self.read_only && (self.index || self.id_index) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "a read-only log cannot keep an event or ID index",
+            ));
+        }
+        let storage = match self.storage {
+            Some(storage) => storage,
+            None if self.read_only => {
+                let mut storage = FsStorage::read_only(&self.dir);
+                if let Some(codec) = self.archive_codec {
+                    storage = storage.with_archive_codec(codec);
+                }
+                Arc::new(storage)
+            }
+            None => {
+                let mut storage = FsStorage::open(&self.dir, self.lock_mode)?;
+                if let Some(codec) = self.archive_codec {
+                    storage = storage.with_archive_codec(codec);
+                }
+                Arc::new(storage)
+            }
+        };
+        if self.archive_dictionary.is_some() {
+            require_local(storage.as_ref(), "archive dictionaries")?;
+        }
+        if self.index {
+            require_local(storage.as_ref(), "the event index")?;
+        }
+        let mut writer = if self.read_only {
+            EventWriter::from_storage_read_only(storage, self.encryption)?
+        } else {
+            EventWriter::from_storage(storage, self.encryption)?
+        };
+        if let Some(dir) = self.view_snapshot_dir {
+            std::fs::create_dir_all(&dir)?;
+            writer.set_snapshot_dir(dir);
+        }
+        let views_dir = self.views_path.or_else(|| {
+            let name = self.views_dir_name?;
+            // Without a local directory, views live in the storage.
+            writer.storage().local_dir()?;
+            Some(writer.views_dir().with_file_name(name))
+        });
+        if let Some(dir) = views_dir {
+            std::fs::create_dir_all(&dir)?;
+            writer.set_views_dir(dir);
+        }
+        let snapshot_writes = !self.read_only || writer.snapshot_dir.is_some();
+        writer.set_snapshot_writes(self.write_snapshots.unwrap_or(snapshot_writes));
+        writer.set_pretty_snapshots(self.pretty_snapshots);
+        if let Some(algo) = self.hash_algo {
+            writer.set_hash_algo(algo)?;
+        }
+        if let Some(codec) = self.archive_codec {
+            writer.set_archive_codec(codec)?;
+        }
+        writer.set_max_log_size(self.max_log_size);
+        writer.set_max_log_events(self.max_log_events);
+        writer.set_sync_policy(self.sync_policy);
+        writer.set_max_unsynced_events(self.max_unsynced_events);
+        writer.set_dedup_window(self.dedup_window);
+        writer.set_archive_dictionary(self.archive_dictionary);
+        writer.set_archive_retention(self.archive_retention);
+        writer.set_id_scheme(self.id_scheme);
+        writer.set_schemas(self.schemas);
+        writer.set_upcasters(self.upcasters);
+        writer.set_hash_chain(self.hash_chain)?;
+        if !self.read_only {
+            writer.set_index(self.index)?;
+            writer.set_id_index(self.id_index)?;
+        }
+        writer.set_observer(Observer::new(self.observer));
+        let reader = writer.reader();
+
+        let mut views = HashMap::new();
+        for factory in self.view_factories {
+            let mut view = factory(&writer)?;
+            view.set_snapshot_policy(self.snapshot_policy);
+            let name = view.view_name().to_string();
+            if views.contains_key(&name) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("view '{name}' is registered more than once"),
+                ));
+            }
+            views.insert(name, view);
+        }
+
+        let mut log = EventLog {
+            writer,
+            reader,
+            views,
+            subscribers: Vec::new(),
+            auto_rotation: true,
+        };
+
+        if !self.read_only && log.writer.rotation_due()? {
+            log.rotate()?;
+        }
+
+        Ok(log)
+    }
+
+    /// Check that `codec` supports the configured archive options and
+    /// matches any archive already in the directory.
+    fn check_archive_codec(&self, codec: Codec) -> io::Result<()> {
+        if !codec.is_framed() {
+            let option = if self.archive_dictionary.is_some() {
+                Some("an archive dictionary")
+            } else if self.archive_retention.is_some() {
+                Some("archive retention")
+            } else {
+                None
+            };
+            if let Some(option) = option {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{option} cannot be used with the {codec:?} archive codec"),
+                ));
+            }
+        }
+        let dir = match &self.storage {
+            Some(storage) => storage.local_dir(),
+            None => Some(self.dir.as_path()),
+        };
+        match dir.and_then(Codec::detect) {
+            Some(existing) if existing != codec => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("the archive in this directory uses the {existing:?} codec, not {codec:?}"),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Compute xxh64 hash of raw line bytes (without trailing newline), hex-encoded.
+///
+/// This is the default [`HashAlgo`]; a log created with another
+/// [`EventLogBuilder::hash_algo`] hashes its lines with [`HashAlgo::hash`].
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::line_hash;
+/// let hash = line_hash(b"hello world");
+/// assert_eq!(hash.len(), 16);
+/// assert_eq!(hash, line_hash(b"hello world")); // deterministic
+/// ```
+pub fn line_hash(line: &[u8]) -> String {
+    let hash = xxhash_rust::xxh64::xxh64(line, 0);
+    format!("{:016x}", hash)
+}
+
+impl EventLog {
+    /// Open or create an event log in the given directory.
+    ///
+    /// Creates the directory and `views/` subdirectory if they don't exist.
+    /// Opens or creates `app.jsonl` in append mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::EventLog;
+    /// # let dir = tempdir()?;
+    /// let log = EventLog::open(dir.path())?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if directory creation, file open, or lock
+    /// acquisition fails.
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let writer = EventWriter::open(dir)?;
+        let reader = writer.reader();
+        Ok(EventLog {
+            writer,
+            reader,
+            views: HashMap::new(),
+            subscribers: Vec::new(),
+            auto_rotation: true,
+        })
+    }
+
+    /// Open an existing event log for reading only, alongside its writer
+    /// and any number of other readers.
+    ///
+    /// Takes no lock and creates nothing. [`append`](Self::append),
+    /// [`rotate`](Self::rotate) and the other writes fail with
+    /// `PermissionDenied`. Views registered through
+    /// [`EventLogBuilder::read_only`] still refresh, starting from the
+    /// writer's snapshots, but don't save their own; see
+    /// [`EventLogBuilder::write_snapshots`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventLog::open(dir.path())?;
+    /// writer.append(&Event::new("click", json!({})))?;
+    ///
+    /// let mut reader = EventLog::open_readonly(dir.path())?;
+    /// assert_eq!(reader.read_full()?.count(), 1);
+    /// let err = reader.append(&Event::new("click", json!({}))).unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the active log cannot be read — including
+    /// `NotFound` if `dir` holds no log.
+    pub fn open_readonly(dir: impl AsRef<Path>) -> io::Result<Self> {
+        EventLog::builder(dir).read_only(true).open()
+    }
+
+    /// Inspect an event log directory without opening it for writing.
+    ///
+    /// Sniffs the active log, archive, and views directory and reports what
+    /// it finds. Takes no lock, so it is safe to run against a directory a
+    /// live writer is using.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("click", json!({})))?;
+    ///
+    /// let info = EventLog::inspect(dir.path())?;
+    /// assert_eq!(info.event_count_estimate, 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if `dir` is not a directory. Returns an error if
+    /// any file cannot be read or the archive is malformed.
+    pub fn inspect(dir: impl AsRef<Path>) -> io::Result<LogInfo> {
+        inspect::inspect(dir.as_ref())
+    }
+
+    /// Upgrade the on-disk format of the log in `dir` to `target_version`,
+    /// rewriting whatever changed and updating its [`Manifest`].
+    ///
+    /// Format 1, the only one so far, just adds the manifest: it changes
+    /// no data files, so the log, archive and snapshots are left as they
+    /// are. Later formats that change them will rewrite them here.
+    ///
+    /// Writers accept only the formats up to [`Manifest::FORMAT_VERSION`],
+    /// and refuse newer ones; this is the controlled way to bring an older
+    /// directory forward. Migrating a directory already at
+    /// `target_version` changes nothing, so it is safe to run on every
+    /// deploy. Takes the writer lock, so no writer may have the log open.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{EventLog, Manifest};
+    /// # let dir = tempdir()?;
+    /// # drop(EventLog::open(dir.path())?);
+    /// # #[cfg(feature = "std-fs")]
+    /// # {
+    /// let report = EventLog::migrate(dir.path(), Manifest::FORMAT_VERSION)?;
+    /// assert!(report.is_noop());
+    /// # }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if `dir` holds no log. Returns `InvalidInput` if
+    /// `target_version` is older than the directory's format (downgrades
+    /// are refused) or newer than this version of eventfold supports, and
+    /// `InvalidData` if the directory is from a newer format. Returns an
+    /// error if another writer holds the lock or a file cannot be written,
+    /// and `Unsupported` without the `std-fs` feature, which the lock needs.
+    pub fn migrate(dir: impl AsRef<Path>, target_version: u32) -> io::Result<MigrationReport> {
+        migrate::migrate(dir.as_ref(), target_version)
+    }
+
+    /// Replace the log directory `current` with `staged`, for swapping in a
+    /// log and views rebuilt elsewhere.
+    ///
+    /// Takes the writer lock on both directories first, so it fails if a
+    /// writer has either open. `staged` must hold a log; `current` must
+    /// hold one or not exist, so an unrelated directory is never replaced.
+    /// Both must be on the same filesystem.
+    ///
+    /// No supported platform renames a directory over a non-empty one, so
+    /// the swap is two renames: `current` moves aside to `<current>.old`,
+    /// `staged` moves to `current`, and the old directory is then deleted.
+    /// Between the renames `current` does not exist, and opening it then
+    /// fails or, for a writer, starts an empty log. Readers that already
+    /// hold files open keep reading the old data. If the second rename
+    /// fails, the old directory is moved back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let root = tempdir()?;
+    /// let current = root.path().join("data");
+    /// let staged = root.path().join("data.staged");
+    /// EventLog::open(&current)?.append(&Event::new("old", json!({})))?;
+    /// EventLog::open(&staged)?.append(&Event::new("new", json!({})))?;
+    ///
+    /// # #[cfg(feature = "std-fs")]
+    /// # {
+    /// EventLog::replace_dir(&current, &staged)?;
+    /// let log = EventLog::open(&current)?;
+    /// let (event, _) = log.read_full()?.next().unwrap()?;
+    /// assert_eq!(event.event_type, "new");
+    /// assert!(!staged.exists());
+    /// # }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if `staged` holds no log, and `InvalidInput` if
+    /// `current` is `staged` or a directory without a log. Returns
+    /// `AlreadyExists` if a writer holds the lock on either directory, or
+    /// if `<current>.old` is left over from an interrupted swap. Returns an
+    /// error if a rename or the removal of the old directory fails, and
+    /// `Unsupported` without the `std-fs` feature, which the locks need.
+    pub fn replace_dir(current: impl AsRef<Path>, staged: impl AsRef<Path>) -> io::Result<()> {
+        swap::replace_dir(current.as_ref(), staged.as_ref())
+    }
+
+    /// Create a builder for configuring and opening an event log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::EventLog;
+    /// # let dir = tempdir()?;
+    /// let log = EventLog::builder(dir.path())
+    ///     .max_log_size(10_000_000)
+    ///     .open()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn builder(dir: impl AsRef<Path>) -> EventLogBuilder {
+        EventLogBuilder {
+            dir: dir.as_ref().to_path_buf(),
+            storage: None,
+            max_log_size: 0,
+            max_log_events: 0,
+            lock_mode: LockMode::default(),
+            sync_policy: SyncPolicy::default(),
+            max_unsynced_events: 0,
+            archive_codec: None,
+            hash_algo: None,
+            encryption: None,
+            archive_dictionary: None,
+            archive_retention: None,
+            id_scheme: None,
+            schemas: Schemas::default(),
+            upcasters: Upcasters::default(),
+            hash_chain: false,
+            index: false,
+            id_index: false,
+            dedup_window: DEFAULT_DEDUP_WINDOW,
+            observer: None,
+            snapshot_policy: SnapshotPolicy::default(),
+            read_only: false,
+            write_snapshots: None,
+            pretty_snapshots: true,
+            view_snapshot_dir: None,
+            views_dir_name: None,
+            views_path: None,
+            view_factories: Vec::new(),
+        }
+    }
+
+    /// Create a builder for an event log kept in custom [`Storage`] — object
+    /// storage, memory, or anything else that isn't a local directory.
+    ///
+    /// Views, snapshots, rotation, and the archive all go through the
+    /// storage. The builder's [`lock_mode`](EventLogBuilder::lock_mode) is
+    /// ignored; locking, if any, is up to the storage. Features that need a
+    /// real directory (see [`Storage`]) return `Unsupported`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, FsStorage, LockMode};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let storage = FsStorage::open(dir.path(), LockMode::default())?;
+    /// let mut log = EventLog::with_storage(storage).max_log_size(1 << 20).open()?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn with_storage(storage: impl Storage + 'static) -> EventLogBuilder {
+        let storage: Arc<dyn Storage> = Arc::new(storage);
+        EventLogBuilder {
+            storage: Some(storage),
+            ..EventLog::builder(PathBuf::new())
+        }
+    }
+
+    /// Create a builder for an event log that never touches disk, kept in
+    /// a fresh [`MemStorage`].
+    ///
+    /// Appends, reads, views, rotation and the archive all work as usual,
+    /// without a temporary directory or an `fsync` per append, which makes
+    /// it a fast fit for unit tests. Everything is lost when the log is
+    /// dropped; to reopen the same contents, pass a clone of a
+    /// [`MemStorage`] to [`with_storage`](Self::with_storage) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    ///
+    /// let mut log = EventLog::in_memory()
+    ///     .view::<u64>("counter", |state, _event| state + 1)
+    ///     .open()?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// log.refresh_all()?;
+    /// assert_eq!(*log.view::<u64>("counter")?, 2);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn in_memory() -> EventLogBuilder {
+        EventLog::with_storage(MemStorage::new())
+    }
+
+    /// Train a zstd dictionary for archive compression from sample data.
+    ///
+    /// Samples are typically serialized event lines representative of what
+    /// the log stores. The resulting dictionary is passed to
+    /// [`EventLogBuilder::archive_dictionary`]. Dictionaries help most when
+    /// the log holds many small, similarly shaped events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    ///
+    /// let samples: Vec<String> = (0..1000)
+    ///     .map(|i| serde_json::to_string(
+    ///         &Event::new("reading", json!({"sensor": i % 7, "value": i}))
+    ///     ).unwrap())
+    ///     .collect();
+    /// # #[cfg(feature = "zstd")]
+    /// # {
+    /// let dict = EventLog::train_archive_dictionary(&samples)?;
+    /// assert!(!dict.is_empty());
+    /// # }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if training fails — most often because there are
+    /// too few samples, or they're too small, to learn from. Returns
+    /// `Unsupported` without the `zstd` feature.
+    pub fn train_archive_dictionary<S: AsRef<[u8]>>(sample_frames: &[S]) -> io::Result<Vec<u8>> {
+        archive::train_dictionary(sample_frames)
+    }
+
+    /// Append an event to the active log.
+    ///
+    /// Serializes the event as a single JSON line, appends it to `app.jsonl`,
+    /// and flushes to disk. Returns an [`AppendResult`] with the start offset,
+    /// end offset, and line hash.
+    /// May trigger auto-rotation if `max_log_size` or `max_log_events` is
+    /// configured and reached.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization, writing, or auto-rotation fails.
+    pub fn append(&mut self, event: &Event) -> io::Result<AppendResult> {
+        let (result, needs_rotate) = self.writer.append_raw(event)?;
+        self.notify(event, &result);
+        if needs_rotate && self.auto_rotation {
+            self.rotate()?;
+        }
+        Ok(result)
+    }
+
+    /// Append an event and return it exactly as it was written — see
+    /// [`EventWriter::append_returning`].
+    ///
+    /// May trigger auto-rotation, like [`append`](Self::append).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization, writing, or auto-rotation fails.
+    pub fn append_returning(&mut self, event: Event) -> io::Result<(Event, AppendResult)> {
+        let (stored, result, needs_rotate) = self.writer.append_returning_raw(event)?;
+        self.notify(&stored, &result);
+        if needs_rotate && self.auto_rotation {
+            self.rotate()?;
+        }
+        Ok((stored, result))
+    }
+
+    /// Append `event` under the idempotency key `key`, unless it was
+    /// already appended — see [`EventWriter::append_idempotent`] for how
+    /// far back duplicates are found. A duplicate is not written and runs
+    /// no [subscriber](Self::subscribe).
+    ///
+    /// May trigger auto-rotation, like [`append`](Self::append).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .view::<u64>("payments", |count, _event| count + 1)
+    ///     .open()?;
+    /// let event = Event::new("payment", json!({"amount": 10}));
+    /// for _attempt in 0..3 {
+    ///     log.append_idempotent(&event, "cmd-42")?;
+    /// }
+    /// log.refresh_all()?;
+    /// assert_eq!(*log.view::<u64>("payments")?, 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`EventWriter::append_idempotent`], or an
+    /// error if auto-rotation fails.
+    pub fn append_idempotent(&mut self, event: &Event, key: &str) -> io::Result<AppendResult> {
+        let (result, written) = self.writer.append_idempotent_raw(event, key)?;
+        if written {
+            self.notify(event, &result);
+            self.rotate_if_due()?;
+        }
+        Ok(result)
+    }
+
+    /// Append several events with one write and one sync — see
+    /// [`EventWriter::append_batch`].
+    ///
+    /// Auto-rotation is checked once, after the whole batch is written, so
+    /// a batch is never split across the archive and the active log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization, writing, or auto-rotation fails.
+    pub fn append_batch(&mut self, events: &[Event]) -> io::Result<Vec<AppendResult>> {
+        let results = self.writer.append_batch(events)?;
+        self.notify_all(events, &results);
+        self.rotate_if_due()?;
+        Ok(results)
+    }
+
+    /// Append several events as one crash-atomic transaction — see
+    /// [`EventWriter::append_transaction`].
+    ///
+    /// Auto-rotation is checked once, after the whole transaction is
+    /// written, so a transaction is never split across the archive and the
+    /// active log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append_transaction(&[
+    ///     Event::new("debit", json!({"amount": 10})),
+    ///     Event::new("credit", json!({"amount": 10})),
+    /// ])?;
+    /// let transactions: Vec<_> = log.reader().read_transactions_from(0)?.collect();
+    /// assert_eq!(transactions.len(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if an event's `meta` is not an object. Returns
+    /// an error if serialization, writing, or auto-rotation fails.
+    pub fn append_transaction(&mut self, events: &[Event]) -> io::Result<Vec<AppendResult>> {
+        let results = self.writer.append_transaction(events)?;
+        self.notify_all(events, &results);
+        self.rotate_if_due()?;
+        Ok(results)
+    }
+
+    /// Conditional append — delegates to the inner writer.
+    ///
+    /// Appends an event only if the log's current state matches expectations.
+    /// May trigger auto-rotation if `max_log_size` or `max_log_events` is
+    /// configured and reached.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConditionalAppendError::Conflict`] if the offset or hash
+    /// doesn't match. Returns [`ConditionalAppendError::Io`] on I/O or
+    /// auto-rotation failures.
+    pub fn append_if(
+        &mut self,
+        event: &Event,
+        expected_offset: u64,
+        expected_hash: &str,
+    ) -> Result<AppendResult, ConditionalAppendError> {
+        let result = self.writer.append_if(event, expected_offset, expected_hash)?;
+        self.notify(event, &result);
+        self.rotate_if_due()?;
+        Ok(result)
+    }
+
+    /// Append at the current end of the log, retrying on conflict —
+    /// delegates to the inner writer.
+    ///
+    /// See [`EventWriter::append_with_retry`] for the consistency caveats.
+    /// May trigger auto-rotation if `max_log_size` or `max_log_events` is
+    /// configured and reached.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last [`ConditionalAppendError::Conflict`] if every
+    /// attempt conflicted. Returns [`ConditionalAppendError::Io`] on I/O or
+    /// auto-rotation failures.
+    pub fn append_with_retry(
+        &mut self,
+        event: &Event,
+        max_attempts: u32,
+    ) -> Result<AppendResult, ConditionalAppendError> {
+        let result = self.writer.append_with_retry(event, max_attempts)?;
+        self.notify(event, &result);
+        self.rotate_if_due()?;
+        Ok(result)
+    }
+
+    /// Run `subscriber` for every event appended through this log from now
+    /// on, once the event is written.
+    ///
+    /// Where views are pure folds, subscribers are for side effects:
+    /// sending a webhook, enqueuing a job. A subscriber gets the event as
+    /// given to the append method (as stored, for
+    /// [`append_returning`](Self::append_returning)) and its
+    /// [`AppendResult`], which carries any assigned ID and the sequence
+    /// number. It holds no state and is never replayed: events already in
+    /// the log when it subscribes, and events loaded while views refresh,
+    /// are not passed to it. Events appended by other writers, or through
+    /// [`writer_mut`](Self::writer_mut), are not seen either.
+    ///
+    /// Subscribers run synchronously, in the order they subscribed, inside
+    /// the append call and before any auto-rotation. They should be fast,
+    /// or hand the event off to a queue. An append that fails runs none.
+    ///
+    /// Written is durable only under the default
+    /// [`SyncPolicy::EveryAppend`]. Under `Interval`, `EveryN` or `Never`
+    /// the subscriber runs straight after the write, before any `fsync`,
+    /// so on power loss its side effect may outlive the event. Keep the
+    /// default if that matters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// use std::sync::mpsc;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// let (jobs, queue) = mpsc::channel();
+    /// log.subscribe(Box::new(move |event, result| {
+    ///     if event.event_type == "order_placed" {
+    ///         jobs.send(result.sequence).unwrap();
+    ///     }
+    /// }));
+    ///
+    /// log.append(&Event::new("order_placed", json!({"qty": 2})))?;
+    /// log.append(&Event::new("note", json!("skipped")))?;
+    /// assert_eq!(queue.try_iter().collect::<Vec<_>>(), [0]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn subscribe(&mut self, subscriber: Subscriber) {
+        self.subscribers.push(subscriber);
+    }
+
+    fn notify(&self, event: &Event, result: &AppendResult) {
+        for subscriber in &self.subscribers {
+            subscriber(event, result);
+        }
+    }
+
+    fn notify_all(&self, events: &[Event], results: &[AppendResult]) {
+        for (event, result) in events.iter().zip(results) {
+            self.notify(event, result);
+        }
+    }
+
+    /// Read events from the active log starting at the given byte offset.
+    ///
+    /// Returns an iterator yielding `(event, next_byte_offset, line_hash)` for
+    /// each complete line. Blank lines, and a UTF-8 byte-order mark at the
+    /// very start of the file, are skipped. Partial lines (missing
+    /// trailing newline) are skipped silently.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or seeked.
+    pub fn read_from(
+        &self,
+        offset: u64,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
+        self.reader.read_from(offset)
+    }
+
+    /// Read the full event history: archive (if any) + active log.
+    ///
+    /// Returns an iterator yielding `(event, line_hash)` for each event
+    /// across all archived frames and the current active log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be opened.
+    pub fn read_full(&self) -> io::Result<FullEventIter> {
+        self.reader.read_full()
+    }
+
+    /// Read the full event history with each event's index across the
+    /// archive and active log. See [`EventReader::read_full_indexed`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be opened.
+    pub fn read_full_indexed(
+        &self,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
+        self.reader.read_full_indexed()
+    }
+
+    /// Read the full event history from log-wide sequence number
+    /// `sequence` on. See [`EventReader::read_from_sequence`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sequence base cannot be loaded, or if the
+    /// archive or active log cannot be opened.
+    pub fn read_from_sequence(
+        &self,
+        sequence: u64,
+    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
+        self.reader.read_from_sequence(sequence)
+    }
+
+    /// Compare this log's full history with `other`'s, event by event.
+    ///
+    /// Both histories (archive + active log) are streamed in lockstep, so
+    /// neither is loaded into memory. `cmp` selects which fields must match.
+    /// The report carries the first divergence, if any — including one log
+    /// ending before the other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventCmp, EventLog, EventWriter};
+    /// use serde_json::json;
+    /// # let source_dir = tempdir()?;
+    /// # let target_dir = tempdir()?;
+    /// let event = Event::new("click", json!({"x": 1}));
+    /// let mut source = EventLog::open(source_dir.path())?;
+    /// source.append(&event)?;
+    /// let mut target = EventWriter::open(target_dir.path())?;
+    /// target.append(&event.clone().with_meta(json!({"migrated": true})))?;
+    ///
+    /// let report = source.equivalent_to(&target.reader(), EventCmp::CORE)?;
+    /// assert!(report.is_equivalent());
+    /// assert_eq!(report.matched, 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either history cannot be read or a line fails to
+    /// parse.
+    pub fn equivalent_to(
+        &self,
+        other: &EventReader,
+        cmp: EventCmp,
+    ) -> io::Result<EquivalenceReport> {
+        equivalence::compare(self.reader.read_full()?, other.read_full()?, cmp)
+    }
+
+    /// Compare this log's full history with `other`'s by line hash, and
+    /// find where they stop agreeing.
+    ///
+    /// Unlike [`equivalent_to`](Self::equivalent_to), no line is parsed:
+    /// two events match only if their lines are byte-identical, as after
+    /// [`replicate_to`](Self::replicate_to). Both histories, archive
+    /// included, are streamed in lockstep, so the result does not depend
+    /// on whether either log has rotated. Both logs must use the same
+    /// [`HashAlgo`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{CompareResult, Event, EventLog, EventWriter};
+    /// use serde_json::json;
+    /// # let (src, dst) = (tempdir()?, tempdir()?);
+    /// let mut log = EventLog::open(src.path())?;
+    /// let mut replica = EventWriter::open(dst.path())?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// log.replicate_to(0, &mut replica)?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("b", json!({})))?;
+    ///
+    /// assert_eq!(
+    ///     log.compare_tail(&replica.reader())?,
+    ///     CompareResult::Ahead { events: 1, offset: Some(0) }
+    /// );
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either history cannot be read.
+    pub fn compare_tail(&self, other: &EventReader) -> io::Result<CompareResult> {
+        equivalence::compare_hashes(self.reader.full_hashes()?, other.full_hashes()?)
+    }
+
+    /// Check the hash chain over the full history. See
+    /// [`EventReader::verify_chain`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the archive or active log cannot be read, or a
+    /// line cannot be parsed.
+    pub fn verify_chain(&self) -> io::Result<Result<(), ChainBreak>> {
+        self.reader.verify_chain()
+    }
+
+    /// Check the whole data directory: every active-log line parses, the
+    /// archive decompresses and every archived line parses, and every
+    /// registered view recomputed from scratch matches its snapshot. A
+    /// trailing partial line in the active log is reported too.
+    ///
+    /// Unlike the integrity check [`View::refresh`] runs — which only
+    /// compares the hash of the line before the snapshot's offset, and
+    /// rebuilds quietly on a mismatch — this reads everything, changes
+    /// nothing, and reports every problem it finds instead of stopping at
+    /// the first. Views are replayed from the full history, so expect a
+    /// [`ViewStatus::Mismatch`](crate::ViewStatus::Mismatch) for views
+    /// that folded events an archive [`Retention`] policy has since
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// use std::io::Write;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// let size = log.active_log_size()?;
+    ///
+    /// // Simulate a crash mid-write.
+    /// let mut file = std::fs::OpenOptions::new().append(true).open(log.log_path())?;
+    /// file.write_all(br#"{"type":"b""#)?;
+    ///
+    /// let report = log.verify()?;
+    /// assert_eq!(report.active_events, 1);
+    /// assert_eq!(report.partial_line, Some(size));
+    /// assert!(report.is_ok());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if a file cannot be read at all; malformed
+    /// content is recorded in the report.
+    pub fn verify(&self) -> io::Result<VerifyReport> {
+        let mut report = self.reader.verify_files()?;
+        for (name, view) in &self.views {
+            let status = view.verify_boxed(&self.reader)?;
+            report.views.insert(name.clone(), status);
+        }
+        Ok(report)
+    }
+
+    /// Write the full history (archive + active log) to `out` as CSV.
+    ///
+    /// The first row is the header `ts,type,id,actor,data`; each event
+    /// follows as one row, with `data` as its JSON text and a missing `id`
+    /// or `actor` left empty. Fields are quoted per RFC 4180 and rows end
+    /// in `\r\n`. Events are streamed, so the history is never held in
+    /// memory. To pull `data` keys into their own columns, use
+    /// [`export_csv_with_columns`](Self::export_csv_with_columns).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// let mut event = Event::new("login", json!({"user": "ada"})).with_actor("web");
+    /// event.ts = 1_700_000_000;
+    /// log.append(&event)?;
+    ///
+    /// let mut csv = Vec::new();
+    /// log.export_csv(&mut csv)?;
+    /// assert_eq!(
+    ///     String::from_utf8(csv).unwrap(),
+    ///     "ts,type,id,actor,data\r\n1700000000,login,,web,\"{\"\"user\"\":\"\"ada\"\"}\"\r\n",
+    /// );
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the history fails or a line cannot be
+    /// parsed, or if writing to `out` fails. Rows already written stay in
+    /// `out`.
+    pub fn export_csv<W: Write>(&self, out: W) -> io::Result<()> {
+        self.export_csv_with_columns(out, &[])
+    }
+
+    /// Like [`export_csv`](Self::export_csv), with an extra column for each
+    /// top-level `data` key in `columns`, after `data`.
+    ///
+    /// A string value is written as-is and a number or boolean as its JSON
+    /// text. A missing key, `null`, array, or object leaves the field empty
+    /// (the full value is still in `data`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("order", json!({"sku": "A-1", "qty": 2})))?;
+    ///
+    /// let mut csv = Vec::new();
+    /// log.export_csv_with_columns(&mut csv, &["sku", "qty"])?;
+    /// let csv = String::from_utf8(csv).unwrap();
+    /// let mut rows = csv.lines();
+    /// assert_eq!(rows.next(), Some("ts,type,id,actor,data,sku,qty"));
+    /// assert!(rows.next().unwrap().ends_with(",A-1,2"));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the history fails or a line cannot be
+    /// parsed, or if writing to `out` fails.
+    pub fn export_csv_with_columns<W: Write>(&self, out: W, columns: &[&str]) -> io::Result<()> {
+        let events = self.reader.read_full()?.map(|r| r.map(|(event, _)| event));
+        export::write_csv(events, out, columns)
+    }
+
+    /// Write the full history (archive + active log) to `out` as NDJSON,
+    /// one event per line, oldest first.
+    ///
+    /// The archive is decompressed on the fly, so the output is plain text
+    /// that [`import_ndjson`](Self::import_ndjson) (or any JSON tool) can
+    /// read back. Events are streamed, never held in memory all at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// # let restored_dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("b", json!({})))?;
+    ///
+    /// let mut backup = Vec::new();
+    /// log.export_ndjson(&mut backup)?;
+    ///
+    /// let mut restored = EventLog::open(restored_dir.path())?;
+    /// assert_eq!(restored.import_ndjson(backup.as_slice())?, 2);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the history fails or a line cannot be
+    /// parsed, or if writing to `out` fails.
+    pub fn export_ndjson<W: Write>(&self, out: W) -> io::Result<()> {
+        let events = self.reader.read_full()?.map(|r| r.map(|(event, _)| event));
+        export::write_ndjson(events, out)
+    }
+
+    /// Append every event in the NDJSON `input`, in order, and return how
+    /// many were appended.
+    ///
+    /// Each non-blank line must deserialize to an [`Event`]; it is appended
+    /// as-is, keeping its `ts`, `id`, and `actor`. Events are written in
+    /// batches (see [`append_batch`](Self::append_batch)), so auto-rotation
+    /// and ID assignment apply as usual. The last line may omit its
+    /// newline.
+    ///
+    /// Import stops at the first invalid line. Every line before it has
+    /// been appended by then; nothing from it or after it is, and no
+    /// partial line is ever written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::EventLog;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// let input = "{\"type\":\"a\",\"data\":{},\"ts\":1}\n\nnot json\n";
+    ///
+    /// let err = log.import_ndjson(input.as_bytes()).unwrap_err();
+    /// assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    /// assert!(err.to_string().contains("line 3"));
+    /// assert_eq!(log.read_from(0)?.count(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData`, naming the 1-based line number, if a line is
+    /// not valid UTF-8 or not a valid event. Returns an error if reading
+    /// `input` or appending fails.
+    pub fn import_ndjson<R: BufRead>(&mut self, input: R) -> io::Result<u64> {
+        let mut imported = 0;
+        let mut batch = Vec::with_capacity(export::IMPORT_BATCH_SIZE);
+        for (i, line) in input.lines().enumerate() {
+            let number = i as u64 + 1;
+            let parsed = line
+                .map_err(|e| io::Error::new(e.kind(), format!("cannot read line {number}: {e}")))
+                .and_then(|line| export::parse_ndjson_line(&line, number));
+            let event = match parsed {
+                Ok(Some(event)) => event,
+                Ok(None) => continue,
+                Err(e) => {
+                    // Keep everything before the bad line.
+                    self.append_batch(&batch)?;
+                    return Err(e);
+                }
+            };
+
+            batch.push(event);
+            if batch.len() == export::IMPORT_BATCH_SIZE {
+                imported += self.append_batch(&batch)?.len() as u64;
+                batch.clear();
+            }
+        }
+        imported += self.append_batch(&batch)?.len() as u64;
+        Ok(imported)
+    }
+
+    /// Split this log's full history into one log per routing key.
+    ///
+    /// Streams [`read_full`](Self::read_full) and appends each event,
+    /// unchanged, to the log in `dest_root/<key>/`, where `key` is
+    /// `route(&event)`. Shard directories are created as needed; a shard
+    /// that already holds a log is appended to. Each shard keeps its events
+    /// in source order. Shards are synced once, at the end. This log is not
+    /// modified. The shards of an encrypted log are encrypted with its key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// # let dest = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("order", json!({"region": "eu"})))?;
+    /// log.append(&Event::new("order", json!({"region": "us"})))?;
+    /// log.append(&Event::new("order", json!({"region": "eu"})))?;
+    ///
+    /// let report = log.split_by(
+    ///     |e| e.data["region"].as_str().unwrap_or("other").to_string(),
+    ///     dest.path(),
+    /// )?;
+    /// assert_eq!(report.shards["eu"], 2);
+    /// assert_eq!(EventLog::open(dest.path().join("us"))?.read_full()?.count(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if `route` returns a key that is not a single
+    /// directory name (empty, `..`, or containing a separator), or if an
+    /// existing shard's encryption differs from this log's. Returns an
+    /// error if this log cannot be read, or if a shard cannot be opened
+    /// (including when another writer holds its lock) or written.
+    pub fn split_by(
+        &self,
+        route: impl Fn(&Event) -> String,
+        dest_root: &Path,
+    ) -> io::Result<SplitReport> {
+        split::split(self.reader.read_full()?, route, dest_root, self.writer.cipher())
+    }
+
+    /// Copy the events after `from_offset` in this log's active log to
+    /// `dest`, byte for byte, and return the offset to continue from.
+    ///
+    /// Lines are appended exactly as stored, so their hashes match on both
+    /// sides; `dest` assigns no IDs and adds no hash-chain links, and syncs
+    /// according to its [`SyncPolicy`]. Calling this in a loop with
+    /// [`wait_for_events`](Self::wait_for_events) keeps a follower
+    /// directory up to date. The lines of an encrypted log stay encrypted,
+    /// so `dest` needs the same key.
+    ///
+    /// Offsets refer to the active log, so they restart at 0 when this log
+    /// rotates; events archived since the last call are not copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, EventWriter};
+    /// use serde_json::json;
+    /// # let (src, dst) = (tempdir()?, tempdir()?);
+    /// let mut log = EventLog::open(src.path())?;
+    /// let mut follower = EventWriter::open(dst.path())?;
+    ///
+    /// let appended = log.append(&Event::new("click", json!({})))?;
+    /// let offset = log.replicate_to(0, &mut follower)?;
+    /// assert_eq!(offset, appended.end_offset);
+    ///
+    /// let (_, _, hash) = follower.reader().read_from(0)?.next().unwrap()?;
+    /// assert_eq!(hash, appended.line_hash);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if `from_offset` is past the end of the
+    /// active log, as it is after a rotation. Returns `InvalidData` if a
+    /// line is not a valid event or `dest` cannot decrypt it, and an error
+    /// if this log cannot be read or `dest` cannot be written. Lines copied
+    /// before an error stay in `dest`.
+    pub fn replicate_to(&self, from_offset: u64, dest: &mut EventWriter) -> io::Result<u64> {
+        let size = self.reader.active_log_size()?;
+        if from_offset > size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "offset {from_offset} is past the end of the active log ({size} bytes); \
+                     the log may have rotated"
+                ),
+            ));
+        }
+        let mut offset = from_offset;
+        for result in self.reader.read_raw_from(from_offset)? {
+            let (line, next_offset, _) = result?;
+            dest.append_line(&line)?;
+            offset = next_offset;
+        }
+        Ok(offset)
+    }
+
+    /// Rewrite the active log keeping only the events for which `keep`
+    /// returns `true`, in their original order, then rebuild every
+    /// registered view. Returns the number of events dropped.
+    ///
+    /// Useful when later events supersede earlier ones, as in a key/value
+    /// projection. Unlike [`rotate`](Self::rotate), this **permanently
+    /// deletes** the dropped events; the archive is untouched. Kept lines are
+    /// copied byte-for-byte, but their offsets change, which is why views
+    /// are rebuilt from the full history. In a
+    /// [hash-chained](EventLogBuilder::hash_chain) log, an event that
+    /// followed a dropped one is re-linked to the event now before it, so
+    /// [`verify_chain`](EventReader::verify_chain) still passes. Views opened outside this log,
+    /// and readers in other processes, must be rebuilt by their owners
+    /// (their integrity check will usually notice on its own). Kept events
+    /// are given new [sequence numbers](EventReader::sequence_base) ending
+    /// where the old log's did, so appends never reuse one.
+    ///
+    /// The new log is written to a temporary file, synced, and renamed over
+    /// `app.jsonl`, so a crash leaves either the old log or the new one.
+    /// If nothing would change, the log is not rewritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("set", json!({"key": "a", "value": 1})))?;
+    /// log.append(&Event::new("set", json!({"key": "a", "value": 2})))?;
+    /// log.append(&Event::new("set", json!({"key": "b", "value": 3})))?;
+    ///
+    /// // Keep only the latest "set" per key.
+    /// let latest: std::collections::HashMap<_, _> = log
+    ///     .read_from(0)?
+    ///     .map(|r| r.map(|(e, _, _)| (e.data["key"].clone(), e.data["value"].clone())))
+    ///     .collect::<Result<_, _>>()?;
+    /// let dropped = log.compact(|e| latest[&e.data["key"]] == e.data["value"])?;
+    ///
+    /// assert_eq!(dropped, 1);
+    /// assert_eq!(log.read_from(0)?.count(), 2);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if a line of the active log is not a valid
+    /// event. Returns `Unsupported` if the log's [`Storage`] cannot
+    /// [`replace_log`](Storage::replace_log). Returns an error if reading
+    /// or rewriting the log, or rebuilding a view, fails.
+    pub fn compact<F>(&mut self, keep: F) -> io::Result<u64>
+    where
+        F: Fn(&Event) -> bool,
+    {
+        let mut contents = Vec::new();
+        self.writer
+            .storage()
+            .read_from(0)?
+            .read_to_end(&mut contents)?;
+        let algo = self.manifest().hash_algo;
+        let compacted = compact::compact(&contents, algo, self.reader.cipher(), keep)?;
+        if compacted.bytes == contents {
+            return Ok(0);
+        }
+
+        self.writer
+            .replace_active_log(&compacted.bytes, compacted.kept)?;
+        self.rebuild_all()?;
+        Ok(compacted.dropped)
+    }
+
+    /// Erase the events for which `predicate` returns `true` from the whole
+    /// history — archive and active log — then rebuild every registered
+    /// view. Returns the number of events redacted. Meant for erasure
+    /// requests, where unlike [`compact`](Self::compact) the archive must
+    /// be rewritten too.
+    ///
+    /// Each matching event is replaced by a tombstone that keeps its
+    /// `type`, `ts` and `id`, has `null` data, no actor, and `meta` of
+    /// `{"redacted": true}`. Tombstones keep their place, so event counts,
+    /// [sequence numbers](EventReader::sequence_base) and
+    /// [`find_by_id`](EventReader::find_by_id) are unaffected; reducers see
+    /// them like any other event and can skip them by the marker. In a
+    /// [hash-chained](EventLogBuilder::hash_chain) log the links after each
+    /// tombstone are updated, so [`verify_chain`](EventReader::verify_chain)
+    /// still passes.
+    ///
+    /// Archive frames holding a match are decompressed, rewritten and
+    /// recompressed; the others are copied as they are. The new archive
+    /// and log are each replaced atomically, archive first. A crash in
+    /// between leaves the archive redacted and the active log not; run the
+    /// redaction again to finish it. Line offsets change, so views and
+    /// readers outside this log must be rebuilt by their owners. Copies
+    /// made elsewhere — exports, `dead-letter.jsonl`, [`LogView`] output
+    /// written before the rebuild — are not touched. If nothing matches,
+    /// nothing is rewritten and views are left alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("signup", json!({"email": "a@example.com"})).with_actor("alice"))?;
+    /// log.rotate()?;
+    /// log.append(&Event::new("login", json!({})).with_actor("alice"))?;
+    /// log.append(&Event::new("login", json!({})).with_actor("bob"))?;
+    ///
+    /// let redacted = log.redact(|e| e.actor.as_deref() == Some("alice"))?;
+    /// assert_eq!(redacted, 2);
+    ///
+    /// let events: Vec<_> = log.read_full()?.collect::<Result<_, _>>()?;
+    /// assert_eq!(events.len(), 3);
+    /// assert_eq!(events[0].0.event_type, "signup");
+    /// assert!(events[0].0.data.is_null());
+    /// assert_eq!(events[0].0.meta, Some(json!({"redacted": true})));
+    /// assert_eq!(events[2].0.actor.as_deref(), Some("bob"));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidData` if a line of the archive or active log is not
+    /// a valid event. Returns `PermissionDenied` if the log was opened
+    /// read-only, and `Unsupported` if the log's [`Storage`] cannot
+    /// [`replace_archive`](Storage::replace_archive) or
+    /// [`replace_log`](Storage::replace_log). Returns an error if reading
+    /// or rewriting the history, or rebuilding a view, fails.
+    pub fn redact<F>(&mut self, predicate: F) -> io::Result<u64>
+    where
+        F: Fn(&Event) -> bool,
+    {
+        let redacted = self.writer.redact(&predicate)?;
+        if redacted > 0 {
+            self.rebuild_all()?;
         }
+        Ok(redacted)
     }
 
-    /// Returns the path to the active log file.
-    pub fn log_path(&self) -> &Path {
-        &self.log_path
-    }
+    /// Rewrite the active log without the lines that are not valid events,
+    /// then rebuild every registered view. Use it when
+    /// [`verify`](Self::verify) reports bad lines.
+    ///
+    /// [`RepairMode::TruncateAtFirstError`] keeps only the lines before the
+    /// first invalid one; [`RepairMode::DropInvalid`] drops just the
+    /// invalid lines. Either way a trailing partial line is dropped, and
+    /// kept lines are copied byte-for-byte. As with
+    /// [`compact`](Self::compact), offsets change, so views are rebuilt
+    /// from the full history, views and readers outside this log must be
+    /// rebuilt by their owners, and kept events get new
+    /// [sequence numbers](EventReader::sequence_base) so appends never
+    /// reuse one.
+    ///
+    /// The new log is written to a temporary file, synced, and renamed over
+    /// `app.jsonl`, so a crash leaves either the old log or the new one.
+    /// If nothing would be removed, the log is not rewritten and views are
+    /// left alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog, RepairMode};
+    /// use serde_json::json;
+    /// use std::io::Write;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// let mut file = std::fs::OpenOptions::new().append(true).open(log.log_path())?;
+    /// file.write_all(b"garbage\n")?;
+    /// log.append(&Event::new("b", json!({})))?;
+    ///
+    /// let report = log.repair(RepairMode::DropInvalid)?;
+    /// assert_eq!(report.invalid_lines, 1);
+    /// assert_eq!(report.bytes_dropped, 8);
+    /// assert_eq!(log.read_from(0)?.count(), 2);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unsupported` if the log's [`Storage`] cannot
+    /// [`replace_log`](Storage::replace_log). Returns an error if reading
+    /// or rewriting the log, or rebuilding a view, fails.
+    pub fn repair(&mut self, mode: RepairMode) -> io::Result<RepairReport> {
+        let mut contents = Vec::new();
+        self.writer
+            .storage()
+            .read_from(0)?
+            .read_to_end(&mut contents)?;
+        let repaired = repair::repair(&contents, mode, self.reader.cipher());
+        if repaired.report.is_empty() {
+            return Ok(repaired.report);
+        }
 
-    /// Returns the path to the archive file.
-    pub fn archive_path(&self) -> &Path {
-        &self.archive_path
+        self.writer
+            .replace_active_log(&repaired.bytes, repaired.kept)?;
+        self.rebuild_all()?;
+        Ok(repaired.report)
     }
-}
-
-/// An append-only event log backed by files in a single directory.
-///
-/// The log manages an active log file (`app.jsonl`), a compressed archive
-/// (`archive.jsonl.zst`), a views directory for snapshots, and an optional
-/// set of registered views for auto-rotation and bulk refresh.
-///
-/// Composes an [`EventWriter`] and [`EventReader`] with a view registry.
-/// For advanced use cases (multiple readers, direct writer access), use
-/// [`EventWriter`] and [`EventReader`] directly.
-///
-/// Use [`EventLog::builder`] to configure views and auto-rotation, or
-/// [`EventLog::open`] for a bare log without registered views.
-///
-/// # Examples
-///
-/// ```
-/// # use tempfile::tempdir;
-/// use eventfold::{Event, EventLog};
-/// use serde_json::json;
-///
-/// # let dir = tempdir().unwrap();
-/// let mut log = EventLog::open(dir.path()).unwrap();
-/// log.append(&Event::new("click", json!({"x": 10}))).unwrap();
-///
-/// let events: Vec<_> = log.read_from(0).unwrap()
-///     .collect::<Result<Vec<_>, _>>().unwrap();
-/// assert_eq!(events.len(), 1);
-/// ```
-pub struct EventLog {
-    writer: EventWriter,
-    reader: EventReader,
-    views: HashMap<String, Box<dyn ViewOps>>,
-}
 
-impl std::fmt::Debug for EventLog {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("EventLog")
-            .field("writer", &self.writer)
-            .field("reader", &self.reader)
-            .field("view_count", &self.views.len())
-            .finish()
+    /// Rotate the active log: refresh registered views, compress to archive,
+    /// truncate, and reset view offsets.
+    ///
+    /// If the active log is empty, this is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading, compressing, truncating, or saving
+    /// view snapshots fails.
+    pub fn rotate(&mut self) -> io::Result<()> {
+        self.writer.rotate(&self.reader, &mut self.views)
     }
-}
-
-/// A factory closure that creates a boxed view given a views directory path.
-type ViewFactory = Box<dyn FnOnce(&Path) -> Box<dyn ViewOps>>;
-
-/// Builder for configuring and opening an [`EventLog`].
-///
-/// Register views and set auto-rotation thresholds before calling
-/// [`open`](EventLogBuilder::open) to create the log.
-///
-/// # Examples
-///
-/// ```
-/// # use tempfile::tempdir;
-/// # use eventfold::{Event, EventLog};
-/// # use serde::{Serialize, Deserialize};
-/// # #[derive(Default, Clone, Serialize, Deserialize)]
-/// # struct Counter { count: u64 }
-/// # fn count(mut s: Counter, _e: &Event) -> Counter { s.count += 1; s }
-/// # let dir = tempdir().unwrap();
-/// let mut log = EventLog::builder(dir.path())
-///     .max_log_size(10_000_000)  // auto-rotate at 10 MB
-///     .view::<Counter>("counter", count)
-///     .open()
-///     .unwrap();
-/// ```
-pub struct EventLogBuilder {
-    dir: PathBuf,
-    max_log_size: u64,
-    lock_mode: LockMode,
-    view_factories: Vec<ViewFactory>,
-}
 
-impl std::fmt::Debug for EventLogBuilder {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("EventLogBuilder")
-            .field("dir", &self.dir)
-            .field("max_log_size", &self.max_log_size)
-            .field("lock_mode", &self.lock_mode)
-            .field("view_count", &self.view_factories.len())
-            .finish()
+    /// Pause or resume auto-rotation on append.
+    ///
+    /// While paused, appends never rotate, so the active log may grow past
+    /// [`EventLogBuilder::max_log_size`] or
+    /// [`EventLogBuilder::max_log_events`] — useful for keeping a bulk
+    /// import in one segment. Once resumed, the next append rotates if the
+    /// limit has been reached; call [`rotate`](Self::rotate) to do so
+    /// straight away. Auto-rotation is on when a log is opened.
+    pub fn set_auto_rotation(&mut self, enabled: bool) {
+        self.auto_rotation = enabled;
     }
-}
 
-impl EventLogBuilder {
-    /// Set the maximum active log size in bytes before auto-rotation triggers.
-    /// A value of 0 (the default) disables auto-rotation.
-    pub fn max_log_size(mut self, bytes: u64) -> Self {
-        self.max_log_size = bytes;
-        self
+    /// Whether appends rotate the active log when it reaches its limit;
+    /// see [`set_auto_rotation`](Self::set_auto_rotation).
+    pub fn auto_rotation(&self) -> bool {
+        self.auto_rotation
     }
 
-    /// Set the file locking mode. Default is [`LockMode::Flock`].
-    pub fn lock_mode(mut self, mode: LockMode) -> Self {
-        self.lock_mode = mode;
-        self
+    /// Rotate if auto-rotation is on and the active log has reached its
+    /// configured limit.
+    fn rotate_if_due(&mut self) -> io::Result<()> {
+        if self.auto_rotation && self.writer.rotation_due()? {
+            self.rotate()?;
+        }
+        Ok(())
     }
 
-    /// Register a view with the given name and reducer function.
-    pub fn view<S>(mut self, name: &str, reducer: ReduceFn<S>) -> Self
-    where
-        S: Serialize + DeserializeOwned + Default + Clone + 'static,
-    {
-        let name = name.to_string();
-        self.view_factories.push(Box::new(move |views_dir| {
-            Box::new(View::new(&name, reducer, views_dir))
-        }));
-        self
+    /// Estimate the effect of [`rotate`](Self::rotate) without doing it:
+    /// the active log's size, the size of the archive frame it would
+    /// become, and the archive's current size. Useful for tuning
+    /// [`EventLogBuilder::max_log_size`] and
+    /// [`EventLogBuilder::archive_retention`]; see [`RotationPreview`].
+    ///
+    /// The active log is compressed in memory, a buffer at a time, and
+    /// nothing is written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the active log or the archive fails, or
+    /// if compression fails.
+    pub fn rotation_preview(&self) -> io::Result<RotationPreview> {
+        self.writer.rotation_preview()
     }
 
-    /// Open (or create) the event log with the configured settings.
+    /// Rotate the active log and record an operator note in the rotation
+    /// history (`rotations.log` in the data directory).
     ///
-    /// Creates the directory structure, initializes all registered views,
-    /// and performs auto-rotation if the active log exceeds `max_log_size`.
+    /// The record captures the note, the completion timestamp, and the
+    /// sequence numbers of the first and last events the rotation archived,
+    /// which keep naming those events after retention drops older frames.
+    /// If the active log is empty, no rotation happens and nothing is
+    /// recorded — returns `Ok(None)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::open(dir.path())?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// log.rotate_annotated("monthly archive")?;
+    ///
+    /// let history = log.rotation_history()?;
+    /// assert_eq!(history.len(), 1);
+    /// assert_eq!(history[0].note, "monthly archive");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if opening the writer fails (directory creation,
-    /// file open, lock acquisition) or if auto-rotation fails.
-    pub fn open(self) -> io::Result<EventLog> {
-        let mut writer = EventWriter::open_with_lock(&self.dir, self.lock_mode)?;
-        writer.set_max_log_size(self.max_log_size);
-        let reader = writer.reader();
-
-        let mut views = HashMap::new();
-        for factory in self.view_factories {
-            let view = factory(writer.views_dir());
-            views.insert(view.view_name().to_string(), view);
+    /// Returns an error if the rotation itself fails, if the sequence base
+    /// cannot be read, or if writing the history fails.
+    pub fn rotate_annotated(&mut self, note: &str) -> io::Result<Option<RotationRecord>> {
+        self.reader.require_local("rotation history")?;
+        if self.active_log_size()? == 0 {
+            return Ok(None);
         }
 
-        let mut log = EventLog {
-            writer,
-            reader,
-            views,
-        };
+        let first_sequence = self.reader.sequence_base()?;
+        self.rotate()?;
+        let last_sequence = self.reader.sequence_base()?.saturating_sub(1);
 
-        if log.writer.max_log_size > 0
-            && log.reader.active_log_size()? >= log.writer.max_log_size
-        {
-            log.rotate()?;
-        }
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let record = RotationRecord {
+            ts,
+            first_sequence,
+            last_sequence,
+            note: note.to_string(),
+        };
+        rotation::append_record(&self.rotations_path(), &record)?;
+        Ok(Some(record))
+    }
 
-        Ok(log)
+    /// Return the history of annotated rotations, oldest first.
+    ///
+    /// Only rotations performed via [`rotate_annotated`](EventLog::rotate_annotated)
+    /// are recorded. Returns an empty list if none have happened.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `rotations.log` cannot be read or contains a
+    /// malformed record.
+    pub fn rotation_history(&self) -> io::Result<Vec<RotationRecord>> {
+        self.reader.require_local("rotation history")?;
+        rotation::read_records(&self.rotations_path())
     }
-}
 
-/// Compute xxh64 hash of raw line bytes (without trailing newline), hex-encoded.
-///
-/// # Examples
-///
-/// ```
-/// use eventfold::line_hash;
-/// let hash = line_hash(b"hello world");
-/// assert_eq!(hash.len(), 16);
-/// assert_eq!(hash, line_hash(b"hello world")); // deterministic
-/// ```
-pub fn line_hash(line: &[u8]) -> String {
-    let hash = xxhash_rust::xxh64::xxh64(line, 0);
-    format!("{:016x}", hash)
-}
+    /// Returns the path to the rotation history file.
+    pub fn rotations_path(&self) -> PathBuf {
+        self.dir().join("rotations.log")
+    }
 
-impl EventLog {
-    /// Open or create an event log in the given directory.
-    ///
-    /// Creates the directory and `views/` subdirectory if they don't exist.
-    /// Opens or creates `app.jsonl` in append mode.
+    /// [`Flush`](View::flush) every registered view: persist each snapshot
+    /// a [`SnapshotPolicy`] has held back, leaving up-to-date snapshot files
+    /// untouched. Call this from a graceful-shutdown handler; dropping the
+    /// log does it too, but only logs errors.
     ///
     /// # Examples
     ///
     /// ```
     /// # use tempfile::tempdir;
-    /// use eventfold::EventLog;
+    /// use eventfold::{Event, EventLog, SnapshotPolicy};
+    /// use serde_json::json;
     /// # let dir = tempdir()?;
-    /// let log = EventLog::open(dir.path())?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .snapshot_every(SnapshotPolicy::EveryNEvents(1000))
+    ///     .view::<u64>("counter", |state, _event| state + 1)
+    ///     .open()?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// log.refresh_all()?;
+    /// log.flush_views()?;
     /// # Ok::<(), std::io::Error>(())
     /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if directory creation, file open, or lock
-    /// acquisition fails.
-    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
-        let writer = EventWriter::open(dir)?;
-        let reader = writer.reader();
-        Ok(EventLog {
-            writer,
-            reader,
-            views: HashMap::new(),
-        })
+    /// Returns an error if saving a snapshot fails.
+    pub fn flush_views(&mut self) -> io::Result<()> {
+        for view in self.views.values_mut() {
+            view.flush_snapshot()?;
+        }
+        Ok(())
     }
 
-    /// Create a builder for configuring and opening an event log.
+    /// Delete the snapshots of views that are no longer registered, and
+    /// return their paths.
+    ///
+    /// Removing a view from the builder leaves its `{name}.snapshot.json`
+    /// behind. This deletes every such file, and any `.tmp` left by an
+    /// interrupted save, whose name matches no view registered on this log,
+    /// in the directory registered views keep their snapshots in: `views/`,
+    /// or the [`EventLogBuilder::view_snapshot_dir`]. Other files, including
+    /// [`LogView`] checkpoints, are left alone. Snapshots of standalone
+    /// [`View`]s in the same directory count as orphans.
     ///
     /// # Examples
     ///
     /// ```
     /// # use tempfile::tempdir;
-    /// use eventfold::EventLog;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
     /// # let dir = tempdir()?;
+    /// {
+    ///     let mut log = EventLog::builder(dir.path())
+    ///         .view::<u64>("old", |state, _event| state + 1)
+    ///         .open()?;
+    ///     log.append(&Event::new("click", json!({})))?;
+    ///     log.refresh_all()?;
+    /// }
+    ///
     /// let log = EventLog::builder(dir.path())
-    ///     .max_log_size(10_000_000)
+    ///     .view::<u64>("new", |state, _event| state + 1)
     ///     .open()?;
+    /// let pruned = log.prune_orphan_snapshots()?;
+    /// assert_eq!(pruned, [log.views_dir().join("old.snapshot.json")]);
     /// # Ok::<(), std::io::Error>(())
     /// ```
-    pub fn builder(dir: impl AsRef<Path>) -> EventLogBuilder {
-        EventLogBuilder {
-            dir: dir.as_ref().to_path_buf(),
-            max_log_size: 0,
-            lock_mode: LockMode::default(),
-            view_factories: Vec::new(),
-        }
+    ///
+    /// # Errors
+    ///
+    /// Returns `PermissionDenied` if the log is read-only and has no
+    /// snapshot directory of its own, and `Unsupported` if its [`Storage`]
+    /// has no local directory. Returns an error if the directory cannot be
+    /// listed or a file cannot be deleted.
+    pub fn prune_orphan_snapshots(&self) -> io::Result<Vec<PathBuf>> {
+        let dir = match &self.writer.snapshot_dir {
+            Some(dir) => dir.as_path(),
+            None => {
+                self.writer.check_writable()?;
+                self.reader.require_local("pruning snapshots")?;
+                self.views_dir()
+            }
+        };
+        snapshot::prune(dir, |name| self.views.contains_key(name))
     }
 
-    /// Append an event to the active log.
+    /// Refresh all registered views from the event log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading events or saving snapshots fails.
+    pub fn refresh_all(&mut self) -> io::Result<()> {
+        self.refresh_all_counted()?;
+        Ok(())
+    }
+
+    /// Rebuild all registered views: delete each snapshot, reset the state,
+    /// and replay the full history.
+    ///
+    /// Unlike [`refresh_all`](Self::refresh_all), this does not trust the
+    /// existing snapshots. Use it after changing a reducer's logic, when the
+    /// saved states no longer match what the reducers would compute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .view::<u64>("counter", |state, _event| state + 1)
+    ///     .open()?;
+    /// log.append(&Event::new("a", json!({})))?;
+    /// log.refresh_all()?;
     ///
-    /// Serializes the event as a single JSON line, appends it to `app.jsonl`,
-    /// and flushes to disk. Returns an [`AppendResult`] with the start offset,
-    /// end offset, and line hash.
-    /// May trigger auto-rotation if `max_log_size` is configured and exceeded.
+    /// log.rebuild_all()?;
+    /// assert_eq!(*log.view::<u64>("counter")?, 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if serialization, writing, or auto-rotation fails.
-    pub fn append(&mut self, event: &Event) -> io::Result<AppendResult> {
-        let (result, needs_rotate) = self.writer.append_raw(event)?;
-        if needs_rotate {
-            self.rotate()?;
+    /// Returns an error if deleting a snapshot, reading events, or saving
+    /// the new snapshots fails.
+    pub fn rebuild_all(&mut self) -> io::Result<()> {
+        for view in self.views.values_mut() {
+            view.rebuild_boxed(&self.reader)?;
         }
-        Ok(result)
+        Ok(())
     }
 
-    /// Conditional append — delegates to the inner writer.
+    /// Refresh all registered views, returning what each refresh did,
+    /// keyed by view name.
     ///
-    /// Appends an event only if the log's current state matches expectations.
-    /// May trigger auto-rotation if `max_log_size` is configured and exceeded.
+    /// When every view resumes from the same offset — the usual case once
+    /// they are caught up — the new events are read once and folded into
+    /// each view from a shared buffer. Otherwise each view reads for
+    /// itself; [`refresh_all_shared`](Self::refresh_all_shared) shares
+    /// the read in that case too.
     ///
-    /// # Errors
+    /// # Examples
     ///
-    /// Returns [`ConditionalAppendError::Conflict`] if the offset or hash
-    /// doesn't match. Returns [`ConditionalAppendError::Io`] on I/O or
-    /// auto-rotation failures.
-    pub fn append_if(
-        &mut self,
-        event: &Event,
-        expected_offset: u64,
-        expected_hash: &str,
-    ) -> Result<AppendResult, ConditionalAppendError> {
-        let result = self.writer.append_if(event, expected_offset, expected_hash)?;
-        if self.writer.max_log_size > 0
-            && self.writer.active_log_size()? >= self.writer.max_log_size
-        {
-            self.rotate()?;
-        }
-        Ok(result)
-    }
-
-    /// Read events from the active log starting at the given byte offset.
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .view::<u64>("counter", |state, _event| state + 1)
+    ///     .open()?;
+    /// log.append(&Event::new("a", json!({})))?;
     ///
-    /// Returns an iterator yielding `(event, next_byte_offset, line_hash)` for
-    /// each complete line. Empty lines are skipped. Partial lines (missing
-    /// trailing newline) are skipped silently.
+    /// let stats = log.refresh_all_counted()?;
+    /// assert_eq!(stats["counter"].applied, 1);
+    /// assert_eq!(log.refresh_all_counted()?["counter"].applied, 0);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if the log file cannot be opened or seeked.
-    pub fn read_from(
-        &self,
-        offset: u64,
-    ) -> io::Result<impl Iterator<Item = io::Result<(Event, u64, String)>>> {
-        self.reader.read_from(offset)
+    /// Returns an error if reading events or saving snapshots fails.
+    pub fn refresh_all_counted(&mut self) -> io::Result<HashMap<String, RefreshStats>> {
+        // In the steady state every view resumes from the same offset, so
+        // one read serves them all; otherwise each view reads for itself.
+        let offsets = self.resume_offsets()?;
+        if offsets.len() > 1 && offsets.iter().all(|&offset| offset == offsets[0]) {
+            return self.refresh_all_from(offsets[0]);
+        }
+
+        let mut stats = HashMap::with_capacity(self.views.len());
+        for (name, view) in self.views.iter_mut() {
+            stats.insert(name.clone(), view.refresh_boxed(&self.reader)?);
+        }
+        Ok(stats)
     }
 
-    /// Read the full event history: archive (if any) + active log.
+    /// Refresh all registered views like
+    /// [`refresh_all_counted`](Self::refresh_all_counted), but read the new
+    /// events once and feed them to every view even when the views resume
+    /// from different offsets.
     ///
-    /// Returns an iterator yielding `(event, line_hash)` for each event
-    /// across all archived frames and the current active log.
+    /// The read starts at the oldest offset any view resumes from, and the
+    /// events are buffered in memory while the views fold them in turn.
+    /// Views that need a full replay (no snapshot, or one that failed its
+    /// integrity check) and [`LogView`]s still read for themselves. Reducers
+    /// run on the calling thread: views are not required to be `Send`.
     ///
-    /// # Errors
+    /// The saving is the reading and parsing: with `n` views behind by
+    /// different amounts, each new line is parsed once rather than `n`
+    /// times, at the cost of buffering the backlog. `cargo bench --bench
+    /// refresh_all` compares the two over 100,000 small events with cheap
+    /// counter reducers; this comes out ahead from two views on, and the
+    /// gap grows with each view added. Expensive reducers shrink the gain,
+    /// since they run once per view either way. With fewer than two views
+    /// able to share the read, or when every view already resumes from
+    /// the same offset, this does the same as
+    /// [`refresh_all_counted`](Self::refresh_all_counted).
     ///
-    /// Returns an error if the archive or active log cannot be opened.
-    pub fn read_full(&self) -> io::Result<FullEventIter> {
-        self.reader.read_full()
-    }
-
-    /// Rotate the active log: refresh registered views, compress to archive,
-    /// truncate, and reset view offsets.
+    /// # Examples
     ///
-    /// If the active log is empty, this is a no-op.
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .view::<u64>("count", |state, _event| state + 1)
+    ///     .view::<u64>("clicks", |state, event| state + u64::from(event.event_type == "click"))
+    ///     .open()?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// log.append(&Event::new("scroll", json!({})))?;
+    ///
+    /// let stats = log.refresh_all_shared()?;
+    /// assert_eq!(stats["count"].applied, 2);
+    /// assert_eq!(*log.view::<u64>("clicks")?, 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
     ///
     /// # Errors
     ///
-    /// Returns an error if reading, compressing, truncating, or saving
-    /// view snapshots fails.
-    pub fn rotate(&mut self) -> io::Result<()> {
-        self.writer.rotate(&self.reader, &mut self.views)
+    /// Same as [`refresh_all`](Self::refresh_all).
+    pub fn refresh_all_shared(&mut self) -> io::Result<HashMap<String, RefreshStats>> {
+        let offsets = self.resume_offsets()?;
+        match offsets.iter().min() {
+            Some(&start) if offsets.len() > 1 => self.refresh_all_from(start),
+            _ => self.refresh_all_counted(),
+        }
     }
 
-    /// Refresh all registered views from the event log.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if reading events or saving snapshots fails.
-    pub fn refresh_all(&mut self) -> io::Result<()> {
+    /// The offsets of the views that can share a read, loading their
+    /// snapshots.
+    fn resume_offsets(&mut self) -> io::Result<Vec<u64>> {
+        let mut offsets = Vec::with_capacity(self.views.len());
         for view in self.views.values_mut() {
-            view.refresh_boxed(&self.reader)?;
+            offsets.extend(view.resume_offset(&self.reader)?);
         }
-        Ok(())
+        Ok(offsets)
+    }
+
+    /// Read the active log from `start` once and refresh every view from
+    /// the buffered events.
+    fn refresh_all_from(&mut self, start: u64) -> io::Result<HashMap<String, RefreshStats>> {
+        let events = self
+            .reader
+            .read_from(start)?
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut stats = HashMap::with_capacity(self.views.len());
+        for (name, view) in self.views.iter_mut() {
+            let refreshed = view.refresh_from_events(&self.reader, &events)?;
+            stats.insert(name.clone(), refreshed);
+        }
+        Ok(stats)
     }
 
     /// Get a reference to a registered view's current state by name.
@@ -1074,11 +6885,120 @@ impl EventLog {
         Ok(typed.state())
     }
 
+    /// Clone a registered view's current state, for handing it off to be
+    /// serialized or sent elsewhere.
+    ///
+    /// Like [`view`](Self::view), this returns the in-memory state without
+    /// refreshing; call [`refresh_all`](Self::refresh_all) first to include
+    /// the latest events.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if no view with the given name is registered.
+    /// Returns `InvalidInput` if the type `S` does not match the view's
+    /// actual state type.
+    pub fn export_view_state<S>(&self, name: &str) -> io::Result<S>
+    where
+        S: Serialize + DeserializeOwned + Default + Clone + 'static,
+    {
+        self.view::<S>(name).cloned()
+    }
+
+    /// Serialize a registered view's current state as pretty-printed JSON,
+    /// without the snapshot's `offset` and `hash`.
+    ///
+    /// The state type need not be named, so this suits shipping a
+    /// projection to a frontend. Like [`view`](Self::view), it does not
+    /// refresh first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventLog};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut log = EventLog::builder(dir.path())
+    ///     .view::<Vec<String>>("types", |mut types, event| {
+    ///         types.push(event.event_type.clone());
+    ///         types
+    ///     })
+    ///     .open()?;
+    /// log.append(&Event::new("click", json!({})))?;
+    /// log.refresh_all()?;
+    ///
+    /// let json = log.export_view_json("types")?;
+    /// assert_eq!(serde_json::from_str::<Vec<String>>(&json)?, ["click"]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if no view with the given name is registered, or
+    /// `InvalidInput` if it is a [`LogView`], which keeps no state.
+    /// Returns an error if the state cannot be serialized.
+    pub fn export_view_json(&self, name: &str) -> io::Result<String> {
+        let view = self.views.get(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("view '{name}' not found"),
+            )
+        })?;
+        Ok(serde_json::to_string_pretty(&view.state_json()?)?)
+    }
+
+    /// Get a reference to a registered [`LogView`] by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NotFound` if no view with the given name is registered.
+    /// Returns `InvalidInput` if the named view is not a `LogView`.
+    pub fn log_view(&self, name: &str) -> io::Result<&LogView> {
+        let view = self.views.get(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("view '{name}' not found"),
+            )
+        })?;
+        view.as_any().downcast_ref::<LogView>().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("view '{name}' is not a log view"),
+            )
+        })
+    }
+
     /// Get a cloneable reader for this log.
     pub fn reader(&self) -> EventReader {
         self.reader.clone()
     }
 
+    /// Returns `true` if any reader holds a lock from
+    /// [`EventReader::try_lock_shared`] on this log.
+    ///
+    /// Delegates to [`EventWriter::readers_present`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Unsupported` if the log's [`Storage`] has no local
+    /// directory. Returns an error if the lock file cannot be opened or
+    /// probed.
+    pub fn readers_present(&self) -> io::Result<bool> {
+        self.writer.readers_present()
+    }
+
+    /// Flush all appended data to stable storage now, regardless of the
+    /// configured [`SyncPolicy`].
+    ///
+    /// Delegates to [`EventWriter::sync`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `fsync` fails.
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.writer.sync()
+    }
+
     /// Get a reference to the inner writer.
     pub fn writer(&self) -> &EventWriter {
         &self.writer
@@ -1099,6 +7019,12 @@ impl EventLog {
         self.writer.log_path()
     }
 
+    /// Returns the directory's [`Manifest`]. See
+    /// [`EventWriter::manifest`].
+    pub fn manifest(&self) -> &Manifest {
+        self.writer.manifest()
+    }
+
     /// Returns the path to the archive file.
     pub fn archive_path(&self) -> &Path {
         self.writer.archive_path()
@@ -1109,6 +7035,27 @@ impl EventLog {
         self.writer.views_dir()
     }
 
+    /// Names of the registered views.
+    #[cfg(feature = "testing")]
+    pub(crate) fn view_names(&self) -> impl Iterator<Item = &str> {
+        self.views.keys().map(String::as_str)
+    }
+
+    /// The [`Cursor`] named `name`: an external consumer's durable
+    /// position in this log, kept in `cursors/{name}.cursor`.
+    ///
+    /// Cursors belong to consumers, not to the writer, so a log opened
+    /// with [`open_readonly`](Self::open_readonly) can save them too.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if `name` is not a single file name (empty,
+    /// `..`, or containing a separator). Returns `Unsupported` if the
+    /// log's [`Storage`] has no local directory.
+    pub fn cursor(&self, name: &str) -> io::Result<Cursor> {
+        Cursor::open(self.reader.require_local("cursors")?, name)
+    }
+
     /// Returns the current size in bytes of the active log file.
     ///
     /// # Errors
@@ -1143,6 +7090,15 @@ impl EventLog {
         self.reader.wait_for_events(offset, timeout)
     }
 
+    /// Follow the active log from `start_offset`, yielding each event as
+    /// it is appended.
+    ///
+    /// Delegates to [`EventReader::events_stream`]. The returned iterator
+    /// does not borrow the log, so appends can continue while it runs.
+    pub fn events_stream(&self, start_offset: u64, poll_interval: Duration) -> EventTail {
+        self.reader.events_stream(start_offset, poll_interval)
+    }
+
     /// Read the line immediately before the given byte offset and return its hash.
     ///
     /// The offset should point to the byte after the newline of the last consumed line.
@@ -1160,14 +7116,16 @@ struct LogIterator<I> {
     lines: I,
     pos: u64,
     file_len: u64,
+    algo: HashAlgo,
+    cipher: Option<Arc<Cipher>>,
 }
 
-impl<I: Iterator<Item = io::Result<String>>> Iterator for LogIterator<I> {
-    type Item = io::Result<(Event, u64, String)>;
-
-    fn next(&mut self) -> Option<Self::Item> {
+impl<I: Iterator<Item = io::Result<String>>> LogIterator<I> {
+    /// The next non-blank complete line, without its byte-order mark, and
+    /// the offset after it. `pos` is left at the line's start.
+    fn next_line(&mut self) -> Option<io::Result<(String, u64)>> {
         loop {
-            let line = match self.lines.next()? {
+            let mut line = match self.lines.next()? {
                 Ok(line) => line,
                 Err(e) => return Some(Err(e)),
             };
@@ -1183,61 +7141,252 @@ impl<I: Iterator<Item = io::Result<String>>> Iterator for LogIterator<I> {
             // Advance position past line + newline
             let next_pos = self.pos + line_bytes + 1; // +1 for the newline
 
-            // Skip empty lines
-            if line.is_empty() {
+            if self.pos == 0 && line.starts_with(BOM) {
+                line.drain(..BOM.len());
+            }
+
+            // Skip blank lines
+            if line.trim().is_empty() {
                 self.pos = next_pos;
                 continue;
             }
 
-            let hash = line_hash(line.as_bytes());
+            return Some(Ok((line, next_pos)));
+        }
+    }
+}
+
+impl<I: Iterator<Item = io::Result<String>>> Iterator for LogIterator<I> {
+    type Item = io::Result<(Event, u64, String)>;
 
-            let event: Event = match serde_json::from_str(&line) {
-                Ok(e) => e,
-                Err(e) => {
-                    return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e)));
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, next_pos) = match self.next_line()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e)),
+        };
+
+        let hash = self.algo.hash(line.as_bytes());
+
+        let json = match crypto::open_line(self.cipher.as_deref(), &line) {
+            Ok(json) => json,
+            Err(e) => return Some(Err(e)),
+        };
+        let event: Event = match serde_json::from_str(&json) {
+            Ok(e) => e,
+            Err(e) => {
+                return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e)));
+            }
+        };
+
+        self.pos = next_pos;
+        Some(Ok((event, next_pos, hash)))
+    }
+}
+
+/// [`LogIterator`] yielding each line as it is, unparsed.
+struct RawLogIterator<I>(LogIterator<I>);
+
+impl<I: Iterator<Item = io::Result<String>>> Iterator for RawLogIterator<I> {
+    type Item = io::Result<(String, u64, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, next_pos) = match self.0.next_line()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e)),
+        };
+        let hash = self.0.algo.hash(line.as_bytes());
+        self.0.pos = next_pos;
+        Some(Ok((line, next_pos, hash)))
+    }
+}
+
+/// Reads the active log's complete lines from the end toward the start, in
+/// chunks.
+pub(crate) struct ReverseLines {
+    storage: Arc<dyn Storage>,
+    /// Bytes `[buf_start, buf_start + buf.len())` of the file. While lines
+    /// remain, the last byte is the `'\n'` terminating the next line.
+    buf: Vec<u8>,
+    buf_start: u64,
+}
+
+impl ReverseLines {
+    const CHUNK: u64 = 8192;
+
+    fn new(storage: Arc<dyn Storage>) -> io::Result<Self> {
+        let buf_start = storage.size()?;
+        let mut lines = ReverseLines {
+            storage,
+            buf: Vec::new(),
+            buf_start,
+        };
+        // Drop any trailing partial line (crash mid-write).
+        loop {
+            if let Some(pos) = lines.buf.iter().rposition(|&b| b == b'\n') {
+                lines.buf.truncate(pos + 1);
+                break;
+            }
+            if lines.buf_start == 0 {
+                lines.buf.clear();
+                break;
+            }
+            lines.load_chunk()?;
+        }
+        Ok(lines)
+    }
+
+    /// Length of the log up to the end of its last complete line, before
+    /// any line has been yielded.
+    fn complete_len(&self) -> u64 {
+        self.buf_start + self.buf.len() as u64
+    }
+
+    /// Prepend the chunk before `buf_start` to the buffer.
+    fn load_chunk(&mut self) -> io::Result<()> {
+        let new_start = self.buf_start.saturating_sub(Self::CHUNK);
+        let mut chunk = vec![0u8; (self.buf_start - new_start) as usize];
+        self.storage.read_at(new_start, &mut chunk)?;
+        chunk.extend_from_slice(&self.buf);
+        self.buf = chunk;
+        self.buf_start = new_start;
+        Ok(())
+    }
+}
+
+impl Iterator for ReverseLines {
+    type Item = io::Result<(Vec<u8>, u64)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let body_len = self.buf.len().checked_sub(1)?;
+            let line_start = match self.buf[..body_len].iter().rposition(|&b| b == b'\n') {
+                Some(pos) => pos + 1,
+                None if self.buf_start == 0 => 0,
+                None => {
+                    if let Err(e) = self.load_chunk() {
+                        return Some(Err(e));
+                    }
+                    continue;
                 }
             };
-
-            self.pos = next_pos;
-            return Some(Ok((event, next_pos, hash)));
+            let mut line = &self.buf[line_start..body_len];
+            let offset = self.buf_start + line_start as u64;
+            if offset == 0 {
+                line = line.strip_prefix(BOM.as_bytes()).unwrap_or(line);
+            }
+            let line = line.to_vec();
+            self.buf.truncate(line_start);
+            if line.trim_ascii().is_empty() {
+                continue;
+            }
+            return Some(Ok((line, offset)));
         }
     }
 }
 
+/// One event of the full history, as passed to
+/// [`EventReader::for_each_full`].
+pub(crate) struct FullLine<'a> {
+    pub(crate) event: &'a Event,
+    pub(crate) hash: &'a str,
+    /// Offset after the line in the full history: the decompressed archive
+    /// followed by the active log.
+    pub(crate) end: u64,
+    /// Offset after the line in the active log, or `None` if archived.
+    pub(crate) active_end: Option<u64>,
+}
+
 /// Iterator that reads events line-by-line from any BufRead source.
 /// Used by `read_full()` for both archive and active log streams.
 struct EventLineIter<R> {
     reader: R,
     buf: String,
+    at_start: bool,
+    /// Bytes read so far, i.e. the end of the last line read.
+    consumed: u64,
+    algo: HashAlgo,
+    cipher: Option<Arc<Cipher>>,
 }
 
-impl<R: BufRead> Iterator for EventLineIter<R> {
-    type Item = io::Result<(Event, String)>;
-
-    fn next(&mut self) -> Option<Self::Item> {
+impl<R: BufRead> EventLineIter<R> {
+    /// Read the next non-blank complete line into `buf`, returning its
+    /// range without the line ending or byte-order mark.
+    fn next_line(&mut self) -> Option<io::Result<Range<usize>>> {
         loop {
             self.buf.clear();
             match self.reader.read_line(&mut self.buf) {
                 Ok(0) => return None,
-                Ok(_) => {
+                Ok(n) => {
+                    self.consumed += n as u64;
                     // Skip partial lines at EOF (no trailing newline — crash mid-write)
                     if !self.buf.ends_with('\n') {
                         return None;
                     }
                     let line = self.buf.trim_end_matches('\n').trim_end_matches('\r');
-                    if line.is_empty() {
-                        continue;
+                    let mut start = 0;
+                    if std::mem::take(&mut self.at_start) && line.starts_with(BOM) {
+                        start = BOM.len();
                     }
-                    let hash = line_hash(line.as_bytes());
-                    match serde_json::from_str::<Event>(line) {
-                        Ok(event) => return Some(Ok((event, hash))),
-                        Err(e) => {
-                            return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e)))
-                        }
+                    if line[start..].trim().is_empty() {
+                        continue;
                     }
+                    return Some(Ok(start..line.len()));
                 }
                 Err(e) => return Some(Err(e)),
             }
         }
     }
 }
+
+impl<R: BufRead> Iterator for EventLineIter<R> {
+    type Item = io::Result<(Event, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = match self.next_line()? {
+            Ok(range) => &self.buf[range],
+            Err(e) => return Some(Err(e)),
+        };
+        let hash = self.algo.hash(line.as_bytes());
+        let json = match crypto::open_line(self.cipher.as_deref(), line) {
+            Ok(json) => json,
+            Err(e) => return Some(Err(e)),
+        };
+        match serde_json::from_str::<Event>(&json) {
+            Ok(event) => Some(Ok((event, hash))),
+            Err(e) => Some(Err(io::Error::new(io::ErrorKind::InvalidData, e))),
+        }
+    }
+}
+
+/// [`EventLineIter`] yielding each line's hash and the offset where it
+/// starts, unparsed.
+struct LineHashIter<R>(EventLineIter<R>);
+
+impl<R: BufRead> Iterator for LineHashIter<R> {
+    type Item = io::Result<(String, u64)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = match self.0.next_line()? {
+            Ok(range) => &self.0.buf[range],
+            Err(e) => return Some(Err(e)),
+        };
+        let start = self.0.consumed - self.0.buf.len() as u64;
+        Some(Ok((self.0.algo.hash(line.as_bytes()), start)))
+    }
+}
+
+/// [`EventLineIter`] yielding each line as it is, unparsed.
+struct RawLineIter<R>(EventLineIter<R>);
+
+impl<R: BufRead> Iterator for RawLineIter<R> {
+    type Item = io::Result<(String, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = match self.0.next_line()? {
+            Ok(range) => &self.0.buf[range],
+            Err(e) => return Some(Err(e)),
+        };
+        let hash = self.0.algo.hash(line.as_bytes());
+        Some(Ok((line.to_string(), hash)))
+    }
+}
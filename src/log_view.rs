@@ -0,0 +1,366 @@
+//! Append-only views whose output is itself a log.
+
+use crate::event::Event;
+use crate::log::EventReader;
+use crate::observer::RebuildReason;
+use crate::snapshot::{self, Snapshot};
+use crate::verify::ViewStatus;
+use crate::view::{RefreshStats, SnapshotPolicy, ViewOps, sealed};
+use std::any::Any;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// A function that projects an event into lines of an append-only output.
+///
+/// Called once per event, in log order. Whatever it writes is appended to
+/// the view's output file. Like a [`ReduceFn`](crate::ReduceFn), it should
+/// be deterministic so a rebuild reproduces the same output.
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::{Event, WriteFn};
+/// use std::io::Write;
+///
+/// fn feed(event: &Event, out: &mut dyn Write) -> std::io::Result<()> {
+///     writeln!(out, "{}", event.event_type)
+/// }
+///
+/// let writer: WriteFn = feed;
+/// ```
+pub type WriteFn = fn(&Event, &mut dyn Write) -> std::io::Result<()>;
+
+/// A view whose "state" is an append-only output file.
+///
+/// Where a [`View`](crate::View) folds events into a value and replaces its
+/// snapshot on every refresh, a `LogView` hands each new event to a
+/// [`WriteFn`] and appends the result to `{name}.output.jsonl` in the views
+/// directory — a materialized feed, an export, a derived log.
+///
+/// Progress is tracked in a separate checkpoint file (`{name}.checkpoint.json`)
+/// recording the consumed log offset, the hash of the last consumed line,
+/// and the output length at that point. Incremental refreshes append to the
+/// output rather than rewriting it. If a crash leaves output written past the
+/// checkpoint, it is truncated back before resuming so no line is emitted
+/// twice.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventWriter, LogView};
+/// use serde_json::json;
+/// use std::io::Write;
+/// # let dir = tempdir()?;
+/// let mut writer = EventWriter::open(dir.path())?;
+/// writer.append(&Event::new("a", json!({})))?;
+/// writer.append(&Event::new("b", json!({})))?;
+///
+/// let mut feed = LogView::new(
+///     "feed",
+///     |event, out| writeln!(out, "{}", event.event_type),
+///     writer.views_dir(),
+/// );
+/// feed.refresh(&writer.reader())?;
+/// assert_eq!(std::fs::read_to_string(feed.output_path())?, "a\nb\n");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct LogView {
+    name: String,
+    writer: WriteFn,
+    output_path: PathBuf,
+    checkpoint_path: PathBuf,
+    offset: u64,
+    hash: String,
+    output_len: u64,
+    loaded: bool,
+    needs_full_replay: bool,
+}
+
+impl std::fmt::Debug for LogView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogView")
+            .field("name", &self.name)
+            .field("output_path", &self.output_path)
+            .field("offset", &self.offset)
+            .field("output_len", &self.output_len)
+            .finish()
+    }
+}
+
+impl LogView {
+    /// Create a new log view.
+    ///
+    /// `name` identifies this view (used for the output and checkpoint
+    /// filenames). `writer` is called for each event. `views_dir` is the
+    /// directory where both files are stored.
+    pub fn new(name: &str, writer: WriteFn, views_dir: &Path) -> Self {
+        LogView {
+            name: name.to_string(),
+            writer,
+            output_path: views_dir.join(format!("{name}.output.jsonl")),
+            checkpoint_path: views_dir.join(format!("{name}.checkpoint.json")),
+            offset: 0,
+            hash: String::new(),
+            output_len: 0,
+            loaded: false,
+            needs_full_replay: false,
+        }
+    }
+
+    /// Append output for every event not yet consumed.
+    ///
+    /// On first call, loads the checkpoint and verifies it against the log
+    /// (same integrity checks as [`View::refresh`](crate::View::refresh)).
+    /// A missing or invalid checkpoint triggers a rebuild.
+    ///
+    /// Returns the number of events processed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading events, calling the writer function,
+    /// writing the output, or saving the checkpoint fails.
+    pub fn refresh(&mut self, reader: &EventReader) -> io::Result<u64> {
+        Ok(self.refresh_stats(reader)?.applied)
+    }
+
+    /// Refresh, reporting to the reader's observer.
+    fn refresh_stats(&mut self, reader: &EventReader) -> io::Result<RefreshStats> {
+        let stats = self.append_new_output(reader)?;
+        if let Some(observer) = reader.observer() {
+            observer.on_refresh(&self.name, stats.applied);
+        }
+        Ok(stats)
+    }
+
+    /// The body of [`refresh`](Self::refresh).
+    fn append_new_output(&mut self, reader: &EventReader) -> io::Result<RefreshStats> {
+        if !self.loaded {
+            self.load(reader)?;
+        }
+
+        if self.needs_full_replay {
+            self.needs_full_replay = false;
+            let file = File::create(&self.output_path)?;
+            let mut out = BufWriter::new(file);
+            let mut count = 0u64;
+            let mut new_hash = String::new();
+            for result in reader.read_full()? {
+                let (event, line_hash) = result?;
+                (self.writer)(&event, &mut out)?;
+                new_hash = line_hash;
+                count += 1;
+            }
+            let new_offset = if count > 0 {
+                reader.active_log_size()?
+            } else {
+                0
+            };
+            self.finish(out, new_offset, new_hash)?;
+            return Ok(RefreshStats {
+                applied: count,
+                new_offset: self.offset,
+                rebuilt: true,
+            });
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.output_path)?;
+        let mut out = BufWriter::new(file);
+        let mut count = 0u64;
+        let mut new_offset = self.offset;
+        let mut new_hash = self.hash.clone();
+        for result in reader.read_from(self.offset)? {
+            let (event, next_offset, line_hash) = result?;
+            (self.writer)(&event, &mut out)?;
+            new_offset = next_offset;
+            new_hash = line_hash;
+            count += 1;
+        }
+        if count > 0 {
+            self.finish(out, new_offset, new_hash)?;
+        }
+        Ok(RefreshStats {
+            applied: count,
+            new_offset: self.offset,
+            rebuilt: false,
+        })
+    }
+
+    /// Truncate the output and replay the full history (archive + active log).
+    ///
+    /// Returns the number of events processed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deleting the checkpoint, reading events, or
+    /// writing the output fails.
+    pub fn rebuild(&mut self, reader: &EventReader) -> io::Result<u64> {
+        snapshot::delete(&self.checkpoint_path)?;
+        self.offset = 0;
+        self.hash = String::new();
+        self.output_len = 0;
+        self.loaded = true;
+        self.needs_full_replay = true;
+        self.report_rebuild(reader, RebuildReason::Requested);
+        self.refresh(reader)
+    }
+
+    /// Returns the view name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path to the append-only output file.
+    pub fn output_path(&self) -> &Path {
+        &self.output_path
+    }
+
+    /// Returns the byte offset into `app.jsonl` consumed so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn load(&mut self, reader: &EventReader) -> io::Result<()> {
+        self.loaded = true;
+
+        // The checkpoint's `state` is the output length it vouches for.
+        let Some(snap) = snapshot::load::<u64>(&self.checkpoint_path)? else {
+            self.needs_full_replay = true;
+            self.report_rebuild(reader, RebuildReason::MissingSnapshot);
+            return Ok(());
+        };
+
+        let output_len = match fs::metadata(&self.output_path) {
+            Ok(m) => m.len(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e),
+        };
+        let log_len = reader.active_log_size()?;
+        let hash_ok = snap.offset == 0
+            || reader
+                .read_line_hash_before(snap.offset)?
+                .is_none_or(|hash| hash == snap.hash);
+
+        let mismatch = if snap.offset > log_len {
+            Some(RebuildReason::OffsetBeyondEof)
+        } else if !hash_ok {
+            Some(RebuildReason::HashMismatch)
+        } else if output_len < snap.state {
+            Some(RebuildReason::OutputTruncated)
+        } else {
+            None
+        };
+        if let Some(reason) = mismatch {
+            warn!(
+                "eventfold: log view '{}': checkpoint does not match log or output, rebuilding",
+                self.name
+            );
+            self.needs_full_replay = true;
+            self.report_rebuild(reader, reason);
+            return Ok(());
+        }
+
+        if output_len > snap.state {
+            // Output written after the last checkpoint (crash mid-refresh).
+            OpenOptions::new()
+                .write(true)
+                .open(&self.output_path)?
+                .set_len(snap.state)?;
+        }
+
+        self.offset = snap.offset;
+        self.hash = snap.hash;
+        self.output_len = snap.state;
+        Ok(())
+    }
+
+    fn report_rebuild(&self, reader: &EventReader, reason: RebuildReason) {
+        if let Some(observer) = reader.observer() {
+            observer.on_view_rebuild(&self.name, reason);
+        }
+    }
+
+    /// Flush and sync the output, then persist the checkpoint.
+    fn finish(&mut self, out: BufWriter<File>, offset: u64, hash: String) -> io::Result<()> {
+        let file = out.into_inner().map_err(|e| e.into_error())?;
+        file.sync_data()?;
+        self.output_len = file.metadata()?.len();
+        self.offset = offset;
+        self.hash = hash;
+        self.save_checkpoint()
+    }
+
+    fn save_checkpoint(&self) -> io::Result<()> {
+        snapshot::save(
+            &self.checkpoint_path,
+            &Snapshot::new(self.output_len, self.offset, self.hash.clone()),
+        )
+    }
+}
+
+impl sealed::Sealed for LogView {}
+
+impl ViewOps for LogView {
+    fn refresh_boxed(&mut self, reader: &EventReader) -> io::Result<RefreshStats> {
+        self.refresh_stats(reader)
+    }
+
+    fn rebuild_boxed(&mut self, reader: &EventReader) -> io::Result<()> {
+        self.rebuild(reader)?;
+        Ok(())
+    }
+
+    fn resume_offset(&mut self, _reader: &EventReader) -> io::Result<Option<u64>> {
+        Ok(None)
+    }
+
+    fn refresh_from_events(
+        &mut self,
+        reader: &EventReader,
+        _events: &[(Event, u64, String)],
+    ) -> io::Result<RefreshStats> {
+        self.refresh_stats(reader)
+    }
+
+    fn verify_boxed(&self, _reader: &EventReader) -> io::Result<ViewStatus> {
+        Ok(ViewStatus::Skipped)
+    }
+
+    fn reset_offset(&mut self) -> io::Result<()> {
+        self.offset = 0;
+        self.hash = String::new();
+        self.save_checkpoint()
+    }
+
+    /// The checkpoint must track the output file, so it is saved on every
+    /// refresh regardless of policy.
+    fn set_snapshot_policy(&mut self, _policy: SnapshotPolicy) {}
+
+    fn flush_snapshot(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// A log view's output lives in its file, not in memory.
+    fn state_json(&self) -> io::Result<serde_json::Value> {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("view '{}' is a log view and has no state", self.name),
+        ))
+    }
+
+    fn view_name(&self) -> &str {
+        &self.name
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
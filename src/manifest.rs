@@ -0,0 +1,193 @@
+//! The directory manifest: `manifest.json`, recording the on-disk format
+//! and the settings every writer and reader of the log must agree on.
+
+use crate::codec::Codec;
+use crate::hash::HashAlgo;
+use crate::snapshot;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Name of the manifest file, inside the log directory.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// What a log directory's `manifest.json` records: the on-disk format
+/// versions and the settings fixed when the log was created. Returned by
+/// [`EventLog::manifest`](crate::EventLog::manifest).
+///
+/// The manifest is written when a writer first opens the directory, and
+/// checked on every later open: a directory written by a newer format
+/// than this version of eventfold understands is refused rather than
+/// misread. A directory from before manifests existed is adopted as
+/// format 1, with its settings taken from the files present.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Codec, EventLog, HashAlgo, Manifest};
+/// # let dir = tempdir()?;
+/// let log = EventLog::open(dir.path())?;
+/// let manifest = log.manifest();
+/// assert_eq!(manifest.format_version, Manifest::FORMAT_VERSION);
+/// assert_eq!(manifest.hash_algo, HashAlgo::Xxh64);
+/// assert_eq!(manifest.archive_codec, Codec::default());
+/// assert!(dir.path().join("manifest.json").exists());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Manifest {
+    /// Version of the directory layout and log line format.
+    pub format_version: u32,
+
+    /// Algorithm of every line hash (see
+    /// [`EventLogBuilder::hash_algo`](crate::EventLogBuilder::hash_algo)).
+    pub hash_algo: HashAlgo,
+
+    /// Codec of the archive (see
+    /// [`EventLogBuilder::archive_codec`](crate::EventLogBuilder::archive_codec)).
+    pub archive_codec: Codec,
+
+    /// Version of the view snapshot format.
+    pub snapshot_format: u32,
+
+    /// Whether lines, snapshots and dead letters are encrypted (see
+    /// `EventLogBuilder::encryption`, with the `crypto` feature). Such a
+    /// log opens only with its key.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encrypted: bool,
+}
+
+impl Manifest {
+    /// The newest directory format this version of eventfold reads and
+    /// writes.
+    pub const FORMAT_VERSION: u32 = 1;
+
+    /// The newest snapshot format this version of eventfold reads and
+    /// writes.
+    pub const SNAPSHOT_FORMAT: u32 = 1;
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Manifest {
+            format_version: Self::FORMAT_VERSION,
+            hash_algo: HashAlgo::default(),
+            archive_codec: Codec::default(),
+            snapshot_format: Self::SNAPSHOT_FORMAT,
+            encrypted: false,
+        }
+    }
+}
+
+/// Load the manifest of `dir` and check that it can be read. A directory
+/// without one gets a manifest describing what is there, saved if `save`
+/// is set. Also returns whether the manifest is new.
+///
+/// # Errors
+///
+/// Returns `InvalidData` if the manifest is malformed, was written by a
+/// newer format, or names a different codec than the archive present.
+pub(crate) fn open(dir: &Path, save: bool) -> io::Result<(Manifest, bool)> {
+    let path = dir.join(MANIFEST_FILE);
+    let detected = Codec::detect(dir);
+    let Some(manifest) = load(dir)? else {
+        let manifest = Manifest {
+            archive_codec: detected.unwrap_or_default(),
+            ..Manifest::default()
+        };
+        if save && !create(dir, &manifest)? {
+            // Another writer created it first.
+            return open(dir, false);
+        }
+        return Ok((manifest, true));
+    };
+
+    if manifest.format_version > Manifest::FORMAT_VERSION
+        || manifest.snapshot_format > Manifest::SNAPSHOT_FORMAT
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{} was written by a newer eventfold (format {}, snapshot format {}); \
+                 this version supports up to format {}, snapshot format {}",
+                dir.display(),
+                manifest.format_version,
+                manifest.snapshot_format,
+                Manifest::FORMAT_VERSION,
+                Manifest::SNAPSHOT_FORMAT,
+            ),
+        ));
+    }
+    if let Some(codec) = detected.filter(|&codec| codec != manifest.archive_codec) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "manifest {} records a {:?} archive, but {} is present",
+                path.display(),
+                manifest.archive_codec,
+                codec.file_name()
+            ),
+        ));
+    }
+    Ok((manifest, false))
+}
+
+/// Load the manifest of `dir`, or `None` if it has none, without checking
+/// it.
+pub(crate) fn load(dir: &Path) -> io::Result<Option<Manifest>> {
+    let path = dir.join(MANIFEST_FILE);
+    match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map(Some).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("malformed manifest {}: {e}", path.display()),
+            )
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// The codec of the archive in `dir`, or if there is none yet, the one
+/// its manifest records. Zstd if neither says.
+pub(crate) fn archive_codec(dir: &Path) -> Codec {
+    Codec::detect(dir)
+        .or_else(|| Some(load(dir).ok()??.archive_codec))
+        .unwrap_or_default()
+}
+
+/// Durably write the first manifest of `dir`, returning `false` if one
+/// appeared in the meantime. Concurrent first opens each write their own
+/// temporary file and link it into place, so none sees a partial manifest.
+fn create(dir: &Path, manifest: &Manifest) -> io::Result<bool> {
+    let mut random = [0u8; 8];
+    getrandom::fill(&mut random).map_err(|e| io::Error::other(e.to_string()))?;
+    let tmp_path = dir.join(format!(
+        "{MANIFEST_FILE}.{:016x}.tmp",
+        u64::from_le_bytes(random)
+    ));
+    let json = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(&json)?;
+    file.sync_data()?;
+    drop(file);
+
+    let linked = fs::hard_link(&tmp_path, dir.join(MANIFEST_FILE));
+    fs::remove_file(&tmp_path)?;
+    match linked {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Durably replace the manifest of `dir`.
+pub(crate) fn save(dir: &Path, manifest: &Manifest) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    snapshot::write_atomic(&dir.join(MANIFEST_FILE), &json)
+}
@@ -0,0 +1,102 @@
+//! Explicit upgrades of a log directory's on-disk format.
+
+use crate::log::LockMode;
+use crate::manifest::{self, Manifest};
+use crate::storage::FsStorage;
+use std::io;
+use std::path::Path;
+
+/// What [`EventLog::migrate`](crate::EventLog::migrate) did.
+///
+/// Directories from before [`Manifest`]s existed count as format `0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Format of the directory before the migration.
+    pub from_version: u32,
+
+    /// Format of the directory after the migration.
+    pub to_version: u32,
+}
+
+impl MigrationReport {
+    /// `true` if the directory was already at the target format.
+    pub fn is_noop(&self) -> bool {
+        self.from_version == self.to_version
+    }
+}
+
+/// Upgrade `dir` one format at a time until it reaches `target`, holding
+/// the writer lock throughout.
+pub(crate) fn migrate(dir: &Path, target: u32) -> io::Result<MigrationReport> {
+    if !dir.join("app.jsonl").is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} holds no event log", dir.display()),
+        ));
+    }
+    if target > Manifest::FORMAT_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "cannot migrate to format {target}: this version of eventfold supports up to format {}",
+                Manifest::FORMAT_VERSION
+            ),
+        ));
+    }
+    let _lock = FsStorage::open(dir, LockMode::Flock)?;
+
+    let from = manifest::load(dir)?.map_or(0, |m| m.format_version);
+    if from > Manifest::FORMAT_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{} was written by a newer eventfold (format {from})",
+                dir.display()
+            ),
+        ));
+    }
+    if target < from {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} is at format {from}; refusing to downgrade to format {target}",
+                dir.display()
+            ),
+        ));
+    }
+
+    for version in from + 1..=target {
+        step(dir, version)?;
+    }
+    Ok(MigrationReport {
+        from_version: from,
+        to_version: target,
+    })
+}
+
+/// Bring `dir` from format `version - 1` to `version`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if there is no step to `version`.
+fn step(dir: &Path, version: u32) -> io::Result<()> {
+    match version {
+        1 => to_v1(dir),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("no migration step to format {version}"),
+        )),
+    }
+}
+
+/// Format 1 adds the manifest, describing the files already present. It
+/// rewrites no data files: the log lines, archive and snapshots are the
+/// same in formats 0 and 1.
+fn to_v1(dir: &Path) -> io::Result<()> {
+    let (manifest, _) = manifest::open(dir, false)?;
+    let manifest = Manifest {
+        format_version: 1,
+        ..manifest
+    };
+    manifest::save(dir, &manifest)
+}
@@ -0,0 +1,97 @@
+//! Memory-mapped reads of the active log, behind the `mmap` feature. See
+//! [`EventReader::with_mmap`](crate::EventReader::with_mmap).
+
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// A read-only map of a whole file, as long as it was when mapped.
+pub(crate) struct Mapping {
+    map: Mmap,
+    /// Modification time of the mapped file, to notice when the log is
+    /// replaced by one of the same length.
+    modified: Option<SystemTime>,
+}
+
+impl Mapping {
+    /// Map `file`, or return `None` if it is empty.
+    fn new(file: &File) -> io::Result<Option<Self>> {
+        let metadata = file.metadata()?;
+        if metadata.len() == 0 {
+            return Ok(None);
+        }
+        // SAFETY: the map is only read through `MappedRange`, never
+        // written. Bytes appended after mapping are outside it. Truncating
+        // the file while a range is read is the caveat documented on
+        // `EventReader::with_mmap`.
+        let map = unsafe { Mmap::map(file)? };
+        Ok(Some(Mapping {
+            map,
+            modified: metadata.modified().ok(),
+        }))
+    }
+}
+
+/// A reader's current mapping of the active log, shared by its clones and
+/// replaced when the log's size changes.
+#[derive(Clone, Default)]
+pub(crate) struct MmapCache(Arc<Mutex<Option<Arc<Mapping>>>>);
+
+impl std::fmt::Debug for MmapCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mapped = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        f.debug_struct("MmapCache")
+            .field("len", &mapped.as_ref().map(|m| m.map.len()))
+            .finish()
+    }
+}
+
+impl MmapCache {
+    /// The bytes of the file at `path` that `start..end` covers, mapping it
+    /// again if its size changed or it was replaced since the last call.
+    /// The range is clamped to the mapped length.
+    pub(crate) fn range(&self, path: &Path, start: u64, end: u64) -> io::Result<MappedRange> {
+        let metadata = std::fs::metadata(path)?;
+        let mut cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let stale = cached.as_ref().is_none_or(|m| {
+            m.map.len() as u64 != metadata.len() || m.modified != metadata.modified().ok()
+        });
+        if stale {
+            *cached = Mapping::new(&File::open(path)?)?.map(Arc::new);
+        }
+
+        let len = cached.as_ref().map_or(0, |m| m.map.len()) as u64;
+        let end = end.min(len);
+        let start = start.min(end);
+        Ok(MappedRange {
+            mapping: cached.clone(),
+            range: start as usize..end as usize,
+        })
+    }
+}
+
+/// Part of a mapping, kept alive for as long as it is read.
+pub(crate) struct MappedRange {
+    mapping: Option<Arc<Mapping>>,
+    range: Range<usize>,
+}
+
+impl MappedRange {
+    /// Offset just past the range, i.e. where the readable bytes end.
+    pub(crate) fn end(&self) -> u64 {
+        self.range.end as u64
+    }
+}
+
+impl AsRef<[u8]> for MappedRange {
+    fn as_ref(&self) -> &[u8] {
+        match &self.mapping {
+            Some(mapping) => &mapping.map[self.range.clone()],
+            None => &[],
+        }
+    }
+}
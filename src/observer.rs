@@ -0,0 +1,119 @@
+//! Callbacks for monitoring a log, registered with
+//! [`EventLogBuilder::observer`](crate::EventLogBuilder::observer).
+
+use crate::log::AppendResult;
+use std::sync::Arc;
+
+/// Receives notifications about what a log and its views are doing.
+///
+/// Every method has an empty default, so implement only the ones you need.
+/// Methods are called synchronously on the thread doing the work, after it
+/// succeeds; keep them cheap (bump a counter, record a histogram sample).
+/// eventfold depends on no metrics library — wire these to whichever one
+/// you use.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{AppendResult, Event, EventLog, LogObserver};
+/// use serde_json::json;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+///
+/// #[derive(Default)]
+/// struct Metrics {
+///     appends: AtomicU64,
+/// }
+///
+/// impl LogObserver for Metrics {
+///     fn on_append(&self, _result: &AppendResult) {
+///         self.appends.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// # let dir = tempdir()?;
+/// let metrics = Arc::new(Metrics::default());
+/// let mut log = EventLog::builder(dir.path())
+///     .observer(metrics.clone())
+///     .open()?;
+/// log.append(&Event::new("click", json!({})))?;
+/// assert_eq!(metrics.appends.load(Ordering::Relaxed), 1);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub trait LogObserver: Send + Sync {
+    /// An event was written. Called once per event, batches included.
+    fn on_append(&self, result: &AppendResult) {
+        let _ = result;
+    }
+
+    /// The active log was rotated into the archive. `archived_bytes` is the
+    /// size of the active log that was archived, before compression.
+    fn on_rotate(&self, archived_bytes: u64) {
+        let _ = archived_bytes;
+    }
+
+    /// View `name` is about to replay the full history instead of resuming
+    /// from its snapshot, for `reason`.
+    fn on_view_rebuild(&self, name: &str, reason: RebuildReason) {
+        let _ = (name, reason);
+    }
+
+    /// View `name` finished a refresh that folded `applied` events (zero if
+    /// it was already up to date).
+    fn on_refresh(&self, name: &str, applied: u64) {
+        let _ = (name, applied);
+    }
+}
+
+/// Why a view replays the full history, as reported to
+/// [`LogObserver::on_view_rebuild`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RebuildReason {
+    /// The view has no snapshot (or checkpoint) yet, or it could not be
+    /// read.
+    MissingSnapshot,
+
+    /// The snapshot's offset is past the end of the active log.
+    OffsetBeyondEof,
+
+    /// The line before the snapshot's offset has a different hash than the
+    /// one recorded.
+    HashMismatch,
+
+    /// A [`LogView`](crate::LogView)'s output file is shorter than its
+    /// checkpoint says.
+    OutputTruncated,
+
+    /// The snapshot was written by a different reducer version than the
+    /// view's (see [`View::with_reducer_version`](crate::View::with_reducer_version)).
+    ReducerChanged,
+
+    /// The rebuild was asked for: [`View::rebuild`](crate::View::rebuild),
+    /// or a [`compact`](crate::EventLog::compact) or
+    /// [`repair`](crate::EventLog::repair) that moved offsets.
+    Requested,
+}
+
+/// The optional observer a writer and its readers share. Without one,
+/// notifying is a single `None` check.
+#[derive(Clone, Default)]
+pub(crate) struct Observer(Option<Arc<dyn LogObserver>>);
+
+impl std::fmt::Debug for Observer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(if self.0.is_some() { "Some(..)" } else { "None" })
+    }
+}
+
+impl Observer {
+    pub(crate) fn new(observer: Option<Arc<dyn LogObserver>>) -> Self {
+        Observer(observer)
+    }
+
+    /// The registered observer, if any.
+    pub(crate) fn get(&self) -> Option<&dyn LogObserver> {
+        self.0.as_deref()
+    }
+}
@@ -0,0 +1,55 @@
+//! Advisory locks that let reader processes announce themselves to the
+//! writer.
+//!
+//! The writer's lock is an exclusive flock on `app.jsonl`. Readers instead
+//! take shared flocks on a separate `readers.lock`, so they never contend
+//! with the writer; the writer probes that file to see whether any are
+//! held.
+
+use crate::flock;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+/// Name of the lock file readers share, inside the log directory.
+const READERS_LOCK: &str = "readers.lock";
+
+/// A shared advisory lock held by a reader, returned by
+/// [`EventReader::try_lock_shared`](crate::EventReader::try_lock_shared).
+///
+/// While any `ReaderLock` on a log is alive, in this process or another,
+/// [`EventWriter::readers_present`](crate::EventWriter::readers_present)
+/// returns `true`. The lock is released when the guard is dropped or the
+/// process exits.
+#[derive(Debug)]
+pub struct ReaderLock {
+    _file: File,
+}
+
+/// Take a shared lock on `dir/readers.lock`, creating it if needed.
+pub(crate) fn lock_shared(dir: &Path) -> io::Result<ReaderLock> {
+    let file = open(dir)?;
+    // Only a writer's momentary probe ever holds this exclusively, so
+    // blocking here waits at most for that probe.
+    flock::lock_shared(&file)?;
+    Ok(ReaderLock { _file: file })
+}
+
+/// Whether any process holds a shared lock on `dir/readers.lock`.
+pub(crate) fn readers_present(dir: &Path) -> io::Result<bool> {
+    let file = open(dir)?;
+    if flock::try_lock_exclusive(&file)? {
+        flock::unlock(&file)?;
+        Ok(false)
+    } else {
+        Ok(true)
+    }
+}
+
+fn open(dir: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(READERS_LOCK))
+}
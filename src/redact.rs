@@ -0,0 +1,170 @@
+//! Redacting events in place: matching events are replaced by tombstones
+//! that keep their position in the history.
+
+use crate::chain::{self, CHAIN_META_KEY};
+use crate::crypto::{self, Cipher};
+use crate::event::Event;
+use crate::hash::HashAlgo;
+use crate::log::BOM;
+use serde_json::{Map, Value};
+use std::io;
+
+/// `meta` key marking a tombstone left by
+/// [`EventLog::redact`](crate::EventLog::redact).
+pub(crate) const REDACTED_META_KEY: &str = "redacted";
+
+/// Hashes of the last line seen, before and after a rewrite, carried from
+/// one part of the history to the next so hash-chain links can be
+/// repaired across frames and into the active log. Shared with
+/// compaction, which re-links the events after each dropped one.
+#[derive(Debug)]
+pub(crate) struct ChainState {
+    algo: HashAlgo,
+    original: String,
+    rewritten: String,
+}
+
+impl ChainState {
+    /// State at the start of the history, hashing lines with `algo`.
+    pub(crate) fn new(algo: HashAlgo) -> Self {
+        Self::after(algo, "")
+    }
+
+    /// State after the line hashed `prev`, which is not being rewritten.
+    pub(crate) fn after(algo: HashAlgo, prev: &str) -> Self {
+        ChainState {
+            algo,
+            original: prev.to_string(),
+            rewritten: prev.to_string(),
+        }
+    }
+
+    /// `event` linked to the rewritten line before it, if it links to
+    /// that line's original and the two differ.
+    pub(crate) fn relink(&self, event: &Event) -> io::Result<Option<Event>> {
+        if self.original != self.rewritten && chain::prev(event) == Some(self.original.as_str()) {
+            chain::link(event, &self.rewritten).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Record the line `original`, written out as `rewritten` (`None` if
+    /// copied unchanged).
+    pub(crate) fn advance(&mut self, original: &str, rewritten: Option<&str>) {
+        self.original = self.algo.hash(original.as_bytes());
+        self.rewritten = match rewritten {
+            Some(line) => self.algo.hash(line.as_bytes()),
+            None => self.original.clone(),
+        };
+    }
+
+    /// Record the line `original` as dropped: the next line that links to
+    /// it is re-linked to the last line kept.
+    pub(crate) fn skip(&mut self, original: &str) {
+        self.original = self.algo.hash(original.as_bytes());
+    }
+}
+
+/// Result of redacting one part of the history.
+pub(crate) struct Redacted {
+    /// The rewritten lines, each with its newline. Lines that needed no
+    /// change are copied byte-for-byte.
+    pub(crate) bytes: Vec<u8>,
+    /// Number of events kept, tombstones included.
+    pub(crate) events: u64,
+    /// Number of events replaced by a tombstone.
+    pub(crate) redacted: u64,
+    /// Whether any line changed: a tombstone, or a re-linked successor.
+    pub(crate) changed: bool,
+}
+
+/// Replace the events of `contents` for which `redact` returns `true` with
+/// tombstones. Events whose hash-chain link pointed at a line that changed
+/// are re-linked to its new hash. Blank lines, a leading byte-order mark,
+/// and a partial trailing line are dropped, as in compaction. Lines are
+/// decrypted with `cipher` if set, and rewritten lines sealed again.
+pub(crate) fn redact(
+    contents: &[u8],
+    chain: &mut ChainState,
+    redact: &impl Fn(&Event) -> bool,
+    cipher: Option<&Cipher>,
+) -> io::Result<Redacted> {
+    let mut out = Redacted {
+        bytes: Vec::with_capacity(contents.len()),
+        events: 0,
+        redacted: 0,
+        changed: false,
+    };
+
+    let mut pos = 0;
+    for line in contents.split_inclusive(|&b| b == b'\n') {
+        let start = pos;
+        pos += line.len();
+        let Some(line) = line.strip_suffix(b"\n") else {
+            break;
+        };
+        let text = std::str::from_utf8(line).map_err(|e| invalid_line(start, e))?;
+        let text = if start == 0 {
+            text.strip_prefix(BOM).unwrap_or(text)
+        } else {
+            text
+        };
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        let json = crypto::open_line(cipher, text).map_err(|e| invalid_line(start, e))?;
+        let mut event: Event = serde_json::from_str(&json).map_err(|e| invalid_line(start, e))?;
+        let mut rewrite = false;
+        if redact(&event) {
+            event = tombstone(&event);
+            out.redacted += 1;
+            rewrite = true;
+        }
+        if let Some(relinked) = chain.relink(&event)? {
+            event = relinked;
+            rewrite = true;
+        }
+
+        if rewrite {
+            let json = serde_json::to_string(&event)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let line = crypto::seal_line(cipher, json)?;
+            chain.advance(text, Some(&line));
+            out.bytes.extend_from_slice(line.as_bytes());
+            out.changed = true;
+        } else {
+            chain.advance(text, None);
+            out.bytes.extend_from_slice(text.as_bytes());
+        }
+        out.bytes.push(b'\n');
+        out.events += 1;
+    }
+    Ok(out)
+}
+
+/// `event` with everything but its type, timestamp, ID and hash-chain link
+/// removed, marked as redacted.
+fn tombstone(event: &Event) -> Event {
+    let mut meta = Map::new();
+    if let Some(prev) = chain::prev(event) {
+        meta.insert(CHAIN_META_KEY.to_string(), Value::String(prev.to_string()));
+    }
+    meta.insert(REDACTED_META_KEY.to_string(), Value::Bool(true));
+    Event {
+        event_type: event.event_type.clone(),
+        data: Value::Null,
+        ts: event.ts,
+        id: event.id.clone(),
+        actor: None,
+        meta: Some(Value::Object(meta)),
+    }
+}
+
+fn invalid_line(offset: usize, e: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("cannot redact the line at byte {offset}: {e}"),
+    )
+}
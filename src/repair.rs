@@ -0,0 +1,92 @@
+//! Repairing an active log that holds lines which are not valid events.
+
+use crate::crypto::{self, Cipher};
+use crate::event::Event;
+use crate::log::BOM;
+
+/// How [`EventLog::repair`](crate::EventLog::repair) treats invalid lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairMode {
+    /// Keep only the lines before the first invalid one. Valid events after
+    /// it are dropped too, so the log stays a contiguous prefix of what was
+    /// written.
+    TruncateAtFirstError,
+
+    /// Drop each invalid line and keep every valid event around it.
+    DropInvalid,
+}
+
+/// What [`EventLog::repair`](crate::EventLog::repair) removed from the
+/// active log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    /// Bytes removed, including any trailing partial line.
+    pub bytes_dropped: u64,
+
+    /// Lines removed because they are not valid events.
+    pub invalid_lines: u64,
+
+    /// Valid events removed because they came after the first invalid line
+    /// ([`RepairMode::TruncateAtFirstError`] only).
+    pub events_dropped: u64,
+}
+
+impl RepairReport {
+    /// `true` if the repair removed nothing.
+    pub fn is_empty(&self) -> bool {
+        self.bytes_dropped == 0
+    }
+}
+
+/// Result of repairing the active log's bytes.
+pub(crate) struct Repaired {
+    /// The kept lines, byte-for-byte, each with its newline.
+    pub(crate) bytes: Vec<u8>,
+    /// Number of events kept.
+    pub(crate) kept: u64,
+    /// What was removed.
+    pub(crate) report: RepairReport,
+}
+
+/// Remove the invalid lines of `contents` as `mode` says, along with a
+/// trailing partial line. Kept lines, including blank ones and a leading
+/// byte-order mark, are copied unchanged. In an encrypted log, a line that
+/// `cipher` cannot decrypt is invalid too.
+pub(crate) fn repair(contents: &[u8], mode: RepairMode, cipher: Option<&Cipher>) -> Repaired {
+    let mut repaired = Repaired {
+        bytes: Vec::with_capacity(contents.len()),
+        kept: 0,
+        report: RepairReport::default(),
+    };
+
+    let mut truncated = false;
+    for (i, line) in contents.split_inclusive(|&b| b == b'\n').enumerate() {
+        // A line without its newline is a partial write, not an event.
+        if !line.ends_with(b"\n") {
+            break;
+        }
+        let text = if i == 0 {
+            line.strip_prefix(BOM.as_bytes()).unwrap_or(line)
+        } else {
+            line
+        };
+        let blank = text.trim_ascii().is_empty();
+        let valid = blank
+            || crypto::open_line_bytes(cipher, text)
+                .is_ok_and(|json| serde_json::from_slice::<Event>(&json).is_ok());
+
+        if !valid {
+            repaired.report.invalid_lines += 1;
+            truncated |= mode == RepairMode::TruncateAtFirstError;
+        } else if truncated {
+            if !blank {
+                repaired.report.events_dropped += 1;
+            }
+        } else {
+            repaired.bytes.extend_from_slice(line);
+            repaired.kept += u64::from(!blank);
+        }
+    }
+    repaired.report.bytes_dropped = (contents.len() - repaired.bytes.len()) as u64;
+    repaired
+}
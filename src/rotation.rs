@@ -0,0 +1,145 @@
+//! Rotation history — an audit trail of annotated rotations.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// One entry in the rotation history (`rotations.log`).
+///
+/// Written by [`EventLog::rotate_annotated`](crate::EventLog::rotate_annotated)
+/// as a single JSON line, so the file can be read with ordinary tools:
+///
+/// ```text
+/// $ cat rotations.log
+/// {"ts":1739900000,"first_sequence":0,"last_sequence":41,"note":"monthly archive"}
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventLog};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// let mut log = EventLog::open(dir.path())?;
+/// log.append(&Event::new("click", json!({})))?;
+/// log.append(&Event::new("click", json!({})))?;
+/// let record = log.rotate_annotated("monthly archive")?.unwrap();
+/// assert_eq!((record.first_sequence, record.last_sequence), (0, 1));
+/// assert_eq!(record.note, "monthly archive");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotationRecord {
+    /// Unix timestamp in seconds when the rotation completed.
+    pub ts: u64,
+
+    /// [Sequence number](crate::AppendResult::sequence) of the first event
+    /// the rotation archived. Unlike a frame index, it stays valid when
+    /// [`Retention`](crate::Retention) drops older frames.
+    pub first_sequence: u64,
+
+    /// Sequence number of the last event the rotation archived.
+    pub last_sequence: u64,
+
+    /// Free-form operator note (e.g. `"monthly archive"`).
+    pub note: String,
+}
+
+/// How the active log changed since a tailer last saw it, as reported by
+/// [`EventReader::detect_rotation`](crate::EventReader::detect_rotation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RotationStatus {
+    /// The active log is the size the tailer last saw.
+    Unchanged,
+
+    /// The active log grew; offsets up to the last known size are still
+    /// valid.
+    Appended,
+
+    /// The active log shrank and the archive holds events: most likely a
+    /// rotation moved them there. Offsets into the active log are invalid;
+    /// resume from 0.
+    Rotated,
+
+    /// The active log shrank but the archive is empty, so it was rewritten
+    /// in place (compaction, repair, or an outside edit). Offsets into the
+    /// active log are invalid; resume from 0.
+    Shrunk,
+}
+
+/// What rotating now would do to the log's sizes, as estimated by
+/// [`EventLog::rotation_preview`](crate::EventLog::rotation_preview)
+/// without touching any file.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventLog};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// # #[cfg(feature = "zstd")]
+/// # {
+/// let mut log = EventLog::open(dir.path())?;
+/// for i in 0..100 {
+///     log.append(&Event::new("reading", json!({"value": i})))?;
+/// }
+/// let preview = log.rotation_preview()?;
+/// assert!(preview.estimated_frame_size < preview.active_log_size);
+/// println!("compression ratio: {:.1}", preview.compression_ratio());
+/// # }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RotationPreview {
+    /// Bytes in the active log that a rotation would archive.
+    pub active_log_size: u64,
+
+    /// Bytes the archive frame for the active log would take, encoded with
+    /// the log's codec and dictionary. 0 if the active log is empty.
+    pub estimated_frame_size: u64,
+
+    /// Current size of the archive in bytes (0 if there is none).
+    pub archive_size: u64,
+}
+
+impl RotationPreview {
+    /// Active log bytes per archived byte (1.0 if the active log is empty).
+    pub fn compression_ratio(&self) -> f64 {
+        if self.estimated_frame_size == 0 {
+            return 1.0;
+        }
+        self.active_log_size as f64 / self.estimated_frame_size as f64
+    }
+}
+
+/// Append a record to the rotation history file, creating it if needed.
+pub(crate) fn append_record(path: &Path, record: &RotationRecord) -> io::Result<()> {
+    let json =
+        serde_json::to_string(record).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{json}")?;
+    file.sync_data()
+}
+
+/// Read all records from the rotation history file, oldest first.
+/// Returns an empty list if the file doesn't exist. A trailing partial
+/// line (crash mid-write) is skipped, matching the event log's semantics.
+pub(crate) fn read_records(path: &Path) -> io::Result<Vec<RotationRecord>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    contents
+        .split_inclusive('\n')
+        .filter(|line| line.ends_with('\n') && !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
@@ -0,0 +1,79 @@
+//! Write-time validation of event payloads, per event type.
+
+use crate::event::Event;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io;
+
+/// A payload validator registered with
+/// [`EventLogBuilder::schema`](crate::EventLogBuilder::schema).
+pub(crate) type SchemaFn = Box<dyn Fn(&Value) -> Result<(), String> + Send + Sync>;
+
+/// An event's `data` was rejected by the validator registered for its type.
+///
+/// Appends return this inside an [`io::Error`] of kind `InvalidData`;
+/// recover it with
+/// `err.get_ref().and_then(|e| e.downcast_ref::<SchemaViolation>())`.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventLog, SchemaViolation};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// let mut log = EventLog::builder(dir.path())
+///     .schema("user_created", |data| match data["email"].as_str() {
+///         Some(_) => Ok(()),
+///         None => Err("missing email".to_string()),
+///     })
+///     .open()?;
+///
+/// let err = log.append(&Event::new("user_created", json!({})))
+///     .unwrap_err();
+/// let violation = err.get_ref()
+///     .and_then(|e| e.downcast_ref::<SchemaViolation>())
+///     .unwrap();
+/// assert_eq!(violation.message, "missing email");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("event type '{event_type}' failed schema validation: {message}")]
+pub struct SchemaViolation {
+    /// Type of the rejected event.
+    pub event_type: String,
+
+    /// The validator's error message.
+    pub message: String,
+}
+
+/// The validators registered for a log, keyed by event type.
+#[derive(Default)]
+pub(crate) struct Schemas(HashMap<String, SchemaFn>);
+
+impl std::fmt::Debug for Schemas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl Schemas {
+    /// Register `validator` for `event_type`, replacing any earlier one.
+    pub(crate) fn insert(&mut self, event_type: &str, validator: SchemaFn) {
+        self.0.insert(event_type.to_string(), validator);
+    }
+
+    /// Run the validator for `event`'s type, if one is registered.
+    pub(crate) fn check(&self, event: &Event) -> io::Result<()> {
+        let Some(validator) = self.0.get(&event.event_type) else {
+            return Ok(());
+        };
+        validator(&event.data).map_err(|message| {
+            let violation = SchemaViolation {
+                event_type: event.event_type.clone(),
+                message,
+            };
+            io::Error::new(io::ErrorKind::InvalidData, violation)
+        })
+    }
+}
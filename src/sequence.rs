@@ -0,0 +1,122 @@
+//! Log-wide sequence numbers — event positions that survive rotation.
+//!
+//! Every event gets a sequence number, counting from 0 over the log's
+//! whole life. Only the sequence of the first event in the active log (the
+//! *base*) is stored, in the `seq` file; the rest follow from each event's
+//! position in the active log. The base advances when rotation moves
+//! events into the archive.
+//!
+//! Compaction changes the base and the active log together, which no
+//! single rename can do. It first records the new base in `seq.pending`,
+//! alongside the length and hash of the log it belongs to; that base
+//! applies only while the active log matches, so a crash on either side
+//! of the log swap leaves a base that agrees with the log on disk.
+
+use crate::hash::HashAlgo;
+use crate::snapshot;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Name of the file holding the sequence base, inside the log directory.
+const SEQ_FILE: &str = "seq";
+
+/// Name of the file holding a base that waits for the active log to be
+/// replaced, inside the log directory.
+const PENDING_FILE: &str = "seq.pending";
+
+/// A sequence base saved by [`save_pending`], valid only for the active
+/// log it describes.
+#[derive(Debug, Serialize, Deserialize)]
+struct Pending {
+    base: u64,
+    log_len: u64,
+    log_hash: String,
+}
+
+impl Pending {
+    fn new(base: u64, log: &[u8]) -> Self {
+        Pending {
+            base,
+            log_len: log.len() as u64,
+            log_hash: HashAlgo::Xxh64.hash(log),
+        }
+    }
+}
+
+/// Load the saved sequence base, or `None` if none has been saved.
+///
+/// A pending base whose log is the active one wins over `seq`.
+pub(crate) fn load(dir: &Path) -> io::Result<Option<u64>> {
+    if let Some(base) = load_pending(dir)? {
+        return Ok(Some(base));
+    }
+    let path = dir.join(SEQ_FILE);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    text.trim().parse().map(Some).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("malformed sequence file {}: {e}", path.display()),
+        )
+    })
+}
+
+/// Durably replace the saved sequence base.
+pub(crate) fn save(dir: &Path, base: u64) -> io::Result<()> {
+    snapshot::write_atomic(&dir.join(SEQ_FILE), format!("{base}\n").as_bytes())
+}
+
+/// Durably record `base` as the sequence base of the active log once it
+/// holds exactly `log`. Call before replacing the log, and
+/// [`save`] then [`clear_pending`] after.
+pub(crate) fn save_pending(dir: &Path, base: u64, log: &[u8]) -> io::Result<()> {
+    let json = serde_json::to_vec(&Pending::new(base, log))?;
+    snapshot::write_atomic(&dir.join(PENDING_FILE), &json)
+}
+
+/// Remove the pending base, if any.
+pub(crate) fn clear_pending(dir: &Path) -> io::Result<()> {
+    match fs::remove_file(dir.join(PENDING_FILE)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Settle a pending base left by a crash: save it to `seq` if the active
+/// log is the one it was recorded for, then drop it. Called when a writer
+/// opens, before anything is appended.
+pub(crate) fn recover(dir: &Path) -> io::Result<()> {
+    if let Some(base) = load_pending(dir)? {
+        save(dir, base)?;
+    }
+    clear_pending(dir)
+}
+
+/// The pending base, if there is one and the active log matches it.
+fn load_pending(dir: &Path) -> io::Result<Option<u64>> {
+    let path = dir.join(PENDING_FILE);
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let pending: Pending = serde_json::from_slice(&bytes).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("malformed pending sequence file {}: {e}", path.display()),
+        )
+    })?;
+    let log = match fs::read(dir.join("app.jsonl")) {
+        Ok(log) => log,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let current = Pending::new(pending.base, &log);
+    let matches = current.log_len == pending.log_len && current.log_hash == pending.log_hash;
+    Ok(matches.then_some(pending.base))
+}
@@ -1,10 +1,15 @@
 //! Snapshot persistence for derived view state.
 
+use crate::crypto::{Cipher, Kind};
+use crate::hash::HashAlgo;
+use crate::storage::Storage;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::fs;
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// A persisted checkpoint of a view's state.
 ///
@@ -19,9 +24,14 @@ use std::path::Path;
 /// {
 ///   "state": { "items": [...], "next_id": 3 },
 ///   "offset": 1284,
-///   "hash": "a3f2e1b09c4d..."
+///   "hash": "a3f2e1b09c4d...",
+///   "checksum": "5e0c9d2f7a81..."
 /// }
 /// ```
+///
+/// Views registered with a reducer version (see
+/// [`View::with_reducer_version`](crate::View::with_reducer_version)) also
+/// record it as `"reducer_version"`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Snapshot<S> {
     /// The derived state at the time of the snapshot.
@@ -32,9 +42,22 @@ pub struct Snapshot<S> {
     /// consumed everything in the archive.
     pub offset: u64,
 
-    /// Hex-encoded xxh64 hash of the last event line processed.
+    /// Hex-encoded hash of the last event line processed, computed with
+    /// the log's [`HashAlgo`](crate::HashAlgo).
     /// Used for integrity verification on the next refresh.
     pub hash: String,
+
+    /// Version of the reducer that produced `state`, if the view was given
+    /// one. A view whose version differs discards the snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reducer_version: Option<u32>,
+
+    /// xxh64 of `state` as compact JSON with sorted keys. Filled in by
+    /// [`save`], which ignores the value given; [`load`] treats a snapshot
+    /// whose state does not match it as corrupt. Snapshots written before
+    /// checksums existed have none and are trusted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
 }
 
 impl<S> Snapshot<S> {
@@ -52,6 +75,8 @@ impl<S> Snapshot<S> {
             state,
             offset,
             hash,
+            reducer_version: None,
+            checksum: None,
         }
     }
 }
@@ -77,13 +102,15 @@ impl<S> Snapshot<S> {
 /// Returns an error if serialization fails or if writing/renaming the
 /// file fails (permissions, disk full, etc.).
 pub fn save<S: Serialize>(path: &Path, snapshot: &Snapshot<S>) -> io::Result<()> {
-    let tmp_path = path.with_extension("json.tmp");
+    write_atomic(path, &encode(snapshot, true)?)
+}
 
-    let json = serde_json::to_string_pretty(snapshot)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+/// Write `bytes` to `path` via a synced `.tmp` file and a rename.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
 
     let mut file = fs::File::create(&tmp_path)?;
-    file.write_all(json.as_bytes())?;
+    file.write_all(bytes)?;
     file.sync_data()?;
     drop(file);
 
@@ -91,10 +118,76 @@ pub fn save<S: Serialize>(path: &Path, snapshot: &Snapshot<S>) -> io::Result<()>
     Ok(())
 }
 
+/// Serialize `snapshot`, indented if `pretty`, with a fresh checksum of its
+/// state.
+fn encode<S: Serialize>(snapshot: &Snapshot<S>, pretty: bool) -> io::Result<Vec<u8>> {
+    let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+    let state = serde_json::to_value(&snapshot.state).map_err(invalid)?;
+    let sealed = Snapshot {
+        checksum: Some(checksum(&state)),
+        state,
+        offset: snapshot.offset,
+        hash: snapshot.hash.clone(),
+        reducer_version: snapshot.reducer_version,
+    };
+    if pretty {
+        serde_json::to_vec_pretty(&sealed).map_err(invalid)
+    } else {
+        serde_json::to_vec(&sealed).map_err(invalid)
+    }
+}
+
+/// Parse a snapshot, or `None` if it is corrupt: malformed, not an `S`,
+/// or failing its checksum.
+fn decode<S: DeserializeOwned>(bytes: &[u8]) -> Option<Snapshot<S>> {
+    let raw: Snapshot<Value> = serde_json::from_slice(bytes).ok()?;
+    if raw.checksum.as_ref().is_some_and(|sum| *sum != checksum(&raw.state)) {
+        return None;
+    }
+    Some(Snapshot {
+        state: S::deserialize(raw.state).ok()?,
+        offset: raw.offset,
+        hash: raw.hash,
+        reducer_version: raw.reducer_version,
+        checksum: raw.checksum,
+    })
+}
+
+/// The checksum [`encode`] records for `state`. `Value`'s maps keep their
+/// keys sorted, so this does not depend on field order.
+fn checksum(state: &Value) -> String {
+    HashAlgo::Xxh64.hash(state.to_string().as_bytes())
+}
+
+/// The envelope an encrypted log's snapshots are saved in: JSON still, so
+/// tools and fixtures can handle the file, but with the encoded snapshot
+/// sealed.
+#[derive(Serialize, Deserialize)]
+struct Sealed {
+    encrypted: String,
+}
+
+/// `bytes` sealed with `cipher`, in a [`Sealed`] envelope.
+fn seal(cipher: &Cipher, bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let sealed = Sealed {
+        encrypted: cipher.seal(Kind::Snapshot, bytes)?,
+    };
+    serde_json::to_vec(&sealed).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// The bytes in a [`Sealed`] envelope, or `None` if they cannot be
+/// decrypted.
+fn open(cipher: &Cipher, bytes: &[u8]) -> Option<Vec<u8>> {
+    let sealed: Sealed = serde_json::from_slice(bytes).ok()?;
+    cipher.open(Kind::Snapshot, &sealed.encrypted).ok()
+}
+
 /// Load a snapshot from disk.
 ///
-/// Returns `Ok(None)` if the file doesn't exist or if deserialization fails
-/// (treating a corrupt snapshot as missing triggers a full rebuild).
+/// Returns `Ok(None)` if the file doesn't exist, if deserialization fails,
+/// or if the state does not match the snapshot's
+/// [`checksum`](Snapshot::checksum) (treating a corrupt snapshot as
+/// missing triggers a full rebuild).
 ///
 /// # Examples
 /// ```
@@ -113,15 +206,10 @@ pub fn save<S: Serialize>(path: &Path, snapshot: &Snapshot<S>) -> io::Result<()>
 ///
 /// Returns an error on I/O failures other than `NotFound` (e.g., permission denied).
 pub fn load<S: DeserializeOwned>(path: &Path) -> io::Result<Option<Snapshot<S>>> {
-    let contents = match fs::read_to_string(path) {
-        Ok(c) => c,
-        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
-        Err(e) => return Err(e),
-    };
-
-    match serde_json::from_str(&contents) {
-        Ok(snapshot) => Ok(Some(snapshot)),
-        Err(_) => Ok(None),
+    match fs::read(path) {
+        Ok(contents) => Ok(decode(&contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
     }
 }
 
@@ -162,3 +250,134 @@ pub fn delete(path: &Path) -> io::Result<()> {
 
     Ok(())
 }
+
+/// Delete the `{name}.snapshot.json` files in `dir`, and their `.tmp`
+/// siblings, whose view name `keep` rejects. Returns the deleted paths,
+/// sorted. Other files are left alone, and a missing `dir` has nothing to
+/// delete.
+pub(crate) fn prune(dir: &Path, keep: impl Fn(&str) -> bool) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut pruned = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let file_name = entry.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+        let view = file_name
+            .strip_suffix(".snapshot.json")
+            .or_else(|| file_name.strip_suffix(".snapshot.json.tmp"));
+        if let Some(view) = view
+            && !keep(view)
+        {
+            fs::remove_file(entry.path())?;
+            pruned.push(entry.path());
+        }
+    }
+    pruned.sort();
+    Ok(pruned)
+}
+
+/// Where a view keeps its snapshot: a file of its own, or a blob in the
+/// log's [`Storage`].
+#[derive(Debug, Clone)]
+pub(crate) enum SnapshotStore {
+    File(PathBuf),
+    Storage {
+        storage: Arc<dyn Storage>,
+        view: String,
+    },
+    /// Loads from the inner store but never writes to it.
+    ReadOnly(Box<SnapshotStore>),
+    /// Saves to the inner store as compact JSON instead of indented.
+    Compact(Box<SnapshotStore>),
+    /// Saves to the inner store sealed with the cipher, and decrypts on
+    /// load. Wraps a [`File`](Self::File) or [`Storage`](Self::Storage)
+    /// store directly.
+    Encrypted(Box<SnapshotStore>, Arc<Cipher>),
+}
+
+impl SnapshotStore {
+    pub(crate) fn save<S: Serialize>(&self, snapshot: &Snapshot<S>) -> io::Result<()> {
+        self.save_encoded(snapshot, true)
+    }
+
+    fn save_encoded<S: Serialize>(&self, snapshot: &Snapshot<S>, pretty: bool) -> io::Result<()> {
+        match self {
+            SnapshotStore::File(_) | SnapshotStore::Storage { .. } => {
+                self.save_bytes(&encode(snapshot, pretty)?)
+            }
+            SnapshotStore::ReadOnly(_) => Ok(()),
+            SnapshotStore::Compact(inner) => inner.save_encoded(snapshot, false),
+            SnapshotStore::Encrypted(inner, cipher) => {
+                inner.save_bytes(&seal(cipher, &encode(snapshot, pretty)?)?)
+            }
+        }
+    }
+
+    /// Save `bytes` to the store as they are.
+    fn save_bytes(&self, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            SnapshotStore::File(path) => {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                write_atomic(path, bytes)
+            }
+            SnapshotStore::Storage { storage, view } => storage.save_snapshot(view, bytes),
+            SnapshotStore::ReadOnly(_) => Ok(()),
+            SnapshotStore::Compact(inner) | SnapshotStore::Encrypted(inner, _) => {
+                inner.save_bytes(bytes)
+            }
+        }
+    }
+
+    /// Like [`load`], a corrupt snapshot is treated as missing, and so is
+    /// one that cannot be decrypted.
+    pub(crate) fn load<S: DeserializeOwned>(&self) -> io::Result<Option<Snapshot<S>>> {
+        match self {
+            SnapshotStore::File(path) => load(path),
+            SnapshotStore::Storage { storage, view } => Ok(storage
+                .load_snapshot(view)?
+                .and_then(|bytes| decode(&bytes))),
+            SnapshotStore::ReadOnly(inner) | SnapshotStore::Compact(inner) => inner.load(),
+            SnapshotStore::Encrypted(inner, cipher) => Ok(inner
+                .load_bytes()?
+                .and_then(|bytes| open(cipher, &bytes))
+                .and_then(|bytes| decode(&bytes))),
+        }
+    }
+
+    /// The stored snapshot as saved, without decrypting, decoding or
+    /// verifying it.
+    pub(crate) fn load_bytes(&self) -> io::Result<Option<Vec<u8>>> {
+        match self {
+            SnapshotStore::File(path) => match fs::read(path) {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e),
+            },
+            SnapshotStore::Storage { storage, view } => storage.load_snapshot(view),
+            SnapshotStore::ReadOnly(inner)
+            | SnapshotStore::Compact(inner)
+            | SnapshotStore::Encrypted(inner, _) => inner.load_bytes(),
+        }
+    }
+
+    pub(crate) fn delete(&self) -> io::Result<()> {
+        match self {
+            SnapshotStore::File(path) => delete(path),
+            SnapshotStore::Storage { storage, view } => storage.delete_snapshot(view),
+            SnapshotStore::ReadOnly(_) => Ok(()),
+            SnapshotStore::Compact(inner) | SnapshotStore::Encrypted(inner, _) => inner.delete(),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Splitting one log into several by a routing key, for sharding.
+
+use crate::crypto::Cipher;
+use crate::event::Event;
+use crate::log::{EventWriter, FullEventIter, LockMode, SyncPolicy};
+use crate::storage::FsStorage;
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Component, Path};
+use std::sync::Arc;
+
+/// Result of [`EventLog::split_by`](crate::EventLog::split_by).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SplitReport {
+    /// Number of events written to each shard, keyed by routing key.
+    pub shards: BTreeMap<String, u64>,
+}
+
+impl SplitReport {
+    /// Total number of events written across all shards.
+    pub fn total(&self) -> u64 {
+        self.shards.values().sum()
+    }
+}
+
+/// Append every event to the shard `route` picks under `dest_root`, then
+/// sync each shard once. Shards are encrypted with `cipher`, if set.
+pub(crate) fn split(
+    events: FullEventIter,
+    route: impl Fn(&Event) -> String,
+    dest_root: &Path,
+    cipher: Option<&Arc<Cipher>>,
+) -> io::Result<SplitReport> {
+    let mut writers: BTreeMap<String, EventWriter> = BTreeMap::new();
+    let mut report = SplitReport::default();
+
+    for result in events {
+        let (event, _) = result?;
+        let key = route(&event);
+        let writer = match writers.get_mut(&key) {
+            Some(writer) => writer,
+            None => {
+                check_key(&key)?;
+                let storage = FsStorage::open(dest_root.join(&key), LockMode::default())?;
+                let mut writer = EventWriter::from_storage(Arc::new(storage), cipher.cloned())?;
+                // One sync per shard at the end instead of one per event.
+                writer.set_sync_policy(SyncPolicy::Never);
+                writers.entry(key.clone()).or_insert(writer)
+            }
+        };
+        writer.append(&event)?;
+        *report.shards.entry(key).or_default() += 1;
+    }
+
+    for writer in writers.values_mut() {
+        writer.sync()?;
+    }
+    Ok(report)
+}
+
+/// A routing key must name exactly one directory under the destination.
+fn check_key(key: &str) -> io::Result<()> {
+    let mut components = Path::new(key).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("routing key {key:?} is not a single directory name"),
+        )),
+    }
+}
@@ -0,0 +1,553 @@
+//! Pluggable storage for the active log, archive, and view snapshots.
+
+use crate::codec::Codec;
+use crate::flock;
+use crate::log::LockMode;
+use crate::manifest;
+use crate::snapshot;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often [`LockMode::FlockBlocking`] retries a held lock.
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Where an event log keeps its bytes.
+///
+/// eventfold stores three things: the active log (an append-only byte
+/// stream of JSON lines), the archive (concatenated frames in the log's
+/// [`Codec`], one per rotation), and one snapshot blob per view. Implement this trait to keep
+/// them somewhere other than a local directory — object storage, memory,
+/// browser storage — and open the log with [`EventLog::with_storage`].
+/// The default is [`FsStorage`]; [`MemStorage`] keeps everything in memory.
+///
+/// Offsets are byte offsets into the active log. Data passed to
+/// [`append`](Self::append) must be visible to [`size`](Self::size) and the
+/// read methods as soon as the call returns; [`sync`](Self::sync) is what
+/// makes it durable.
+///
+/// Features that depend on the filesystem itself — file watching
+/// ([`EventReader::wait_for_events`]), the rotation history, the dead-letter
+/// log, [`LogView`](crate::LogView)s, and archive dictionaries — are only
+/// available when [`local_dir`](Self::local_dir) returns a directory; with
+/// other storage they return [`io::ErrorKind::Unsupported`].
+///
+/// [`EventLog::with_storage`]: crate::EventLog::with_storage
+/// [`EventReader::wait_for_events`]: crate::EventReader::wait_for_events
+pub trait Storage: std::fmt::Debug + Send + Sync {
+    /// Append `bytes` to the active log and return the offset they start at.
+    fn append(&self, bytes: &[u8]) -> io::Result<u64>;
+
+    /// Make everything appended so far durable.
+    fn sync(&self) -> io::Result<()>;
+
+    /// Current length of the active log in bytes.
+    fn size(&self) -> io::Result<u64>;
+
+    /// Stream the active log from `offset` to its current end.
+    fn read_from(&self, offset: u64) -> io::Result<Box<dyn Read + Send>>;
+
+    /// Fill `buf` with the active log's bytes starting at `offset`.
+    ///
+    /// Fails with `UnexpectedEof` if the log ends first.
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()>;
+
+    /// Discard the active log's contents. Called by rotation once the
+    /// contents are safely in the archive.
+    fn truncate(&self) -> io::Result<()>;
+
+    /// Cut the active log down to its first `len` bytes. Called on open to
+    /// drop a partial line a crash left at the end.
+    ///
+    /// The default rewrites the log's first `len` bytes with
+    /// [`replace_log`](Self::replace_log).
+    fn truncate_to(&self, len: u64) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        self.read_from(0)?.take(len).read_to_end(&mut bytes)?;
+        self.replace_log(&bytes)
+    }
+
+    /// Replace the active log's contents with `bytes`. Must be atomic: after
+    /// a crash the log holds either the old contents or the new ones.
+    ///
+    /// Only called by [`EventLog::compact`](crate::EventLog::compact). The
+    /// default returns [`io::ErrorKind::Unsupported`].
+    fn replace_log(&self, bytes: &[u8]) -> io::Result<()> {
+        let _ = bytes;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "this storage cannot rewrite its active log",
+        ))
+    }
+
+    /// Stream the raw (compressed) archive, or `None` if there is none yet.
+    fn read_archive(&self) -> io::Result<Option<Box<dyn Read + Send>>>;
+
+    /// Durably append one compressed frame to the archive, creating it if
+    /// needed.
+    fn append_archive(&self, frame: &[u8]) -> io::Result<()>;
+
+    /// Replace the whole archive with `bytes` (possibly empty). Must be
+    /// atomic, like [`save_snapshot`](Self::save_snapshot).
+    ///
+    /// Only called to drop old frames under an archive
+    /// [`Retention`](crate::Retention) policy and by
+    /// [`EventLog::redact`](crate::EventLog::redact). The default returns
+    /// [`io::ErrorKind::Unsupported`].
+    fn replace_archive(&self, bytes: &[u8]) -> io::Result<()> {
+        let _ = bytes;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "this storage cannot rewrite its archive",
+        ))
+    }
+
+    /// Load the snapshot blob saved for `view`, or `None` if there is none.
+    fn load_snapshot(&self, view: &str) -> io::Result<Option<Vec<u8>>>;
+
+    /// Replace the snapshot blob for `view`. Must be atomic: a reader sees
+    /// either the old blob or the new one, never a mix.
+    fn save_snapshot(&self, view: &str, bytes: &[u8]) -> io::Result<()>;
+
+    /// Remove the snapshot blob for `view`. Removing a missing snapshot is
+    /// not an error.
+    fn delete_snapshot(&self, view: &str) -> io::Result<()>;
+
+    /// The directory holding the standard on-disk layout, if this storage
+    /// is one. Enables the filesystem-only features listed above.
+    fn local_dir(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// The default [`Storage`]: files in a single directory.
+///
+/// Uses the layout described in the crate docs — `app.jsonl`,
+/// `archive.jsonl.zst` (or the file [`with_archive_codec`] names), and
+/// `views/{name}.snapshot.json`.
+///
+/// [`with_archive_codec`]: Self::with_archive_codec
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventLog, FsStorage, LockMode};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// let storage = FsStorage::open(dir.path(), LockMode::default())?;
+/// let mut log = EventLog::with_storage(storage).open()?;
+/// log.append(&Event::new("click", json!({})))?;
+/// assert!(dir.path().join("app.jsonl").exists());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct FsStorage {
+    dir: PathBuf,
+    log_path: PathBuf,
+    archive_path: PathBuf,
+    views_dir: PathBuf,
+    /// Append handle, present when opened for writing. Swapped for the new
+    /// file's handle by [`replace_log`](Storage::replace_log).
+    file: RwLock<Option<File>>,
+    /// Whether `file` holds the writer lock, so a replacement takes it too.
+    locked: bool,
+}
+
+impl FsStorage {
+    /// Open or create a log directory for writing.
+    ///
+    /// Creates `dir/`, `dir/views/`, and `dir/app.jsonl` if they don't exist.
+    /// With [`LockMode::Flock`] or [`LockMode::FlockBlocking`], acquires an
+    /// exclusive advisory lock on `app.jsonl`, held until the storage is
+    /// dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if directory creation fails, if the log file cannot
+    /// be opened, or if locking fails (including when another writer holds
+    /// the lock). With [`LockMode::FlockBlocking`], returns `TimedOut` if
+    /// the lock is still held when the timeout elapses.
+    pub fn open(dir: impl AsRef<Path>, lock: LockMode) -> io::Result<Self> {
+        let mut storage = Self::read_only(dir);
+
+        fs::create_dir_all(&storage.views_dir)?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&storage.log_path)?;
+
+        match lock {
+            LockMode::Flock => {
+                if !flock::try_lock_exclusive(&file)? {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        format!(
+                            "another writer holds the lock on {}",
+                            storage.log_path.display()
+                        ),
+                    ));
+                }
+            }
+            LockMode::FlockBlocking { timeout } => {
+                lock_within(&file, &storage.log_path, timeout)?;
+            }
+            LockMode::None => {}
+        }
+
+        storage.file = RwLock::new(Some(file));
+        storage.locked = lock != LockMode::None;
+        Ok(storage)
+    }
+
+    /// Point at a log directory for reading only. Nothing is created or
+    /// locked; [`append`](Storage::append) and [`truncate`](Storage::truncate)
+    /// fail.
+    ///
+    /// Both constructors use the archive file already in the directory, or
+    /// the one its [`Manifest`](crate::Manifest) names if there is none yet.
+    pub fn read_only(dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref().to_path_buf();
+        let codec = manifest::archive_codec(&dir);
+        FsStorage {
+            log_path: dir.join("app.jsonl"),
+            archive_path: dir.join(codec.file_name()),
+            views_dir: dir.join("views"),
+            dir,
+            file: RwLock::new(None),
+            locked: false,
+        }
+    }
+
+    /// Keep the archive in the file `codec` names, to match
+    /// [`EventLogBuilder::archive_codec`](crate::EventLogBuilder::archive_codec).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Codec, EventLog, FsStorage, LockMode};
+    /// # let dir = tempdir()?;
+    /// let storage = FsStorage::open(dir.path(), LockMode::default())?
+    ///     .with_archive_codec(Codec::None);
+    /// let log = EventLog::with_storage(storage)
+    ///     .archive_codec(Codec::None)
+    ///     .open()?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn with_archive_codec(mut self, codec: Codec) -> Self {
+        self.archive_path = self.dir.join(codec.file_name());
+        self
+    }
+
+    /// Run `f` on the append handle, or fail if opened read-only.
+    fn with_writable<T>(&self, f: impl FnOnce(&File) -> io::Result<T>) -> io::Result<T> {
+        let file = self.file.read().unwrap_or_else(|e| e.into_inner());
+        match file.as_ref() {
+            Some(file) => f(file),
+            None => Err(self.read_only_error()),
+        }
+    }
+
+    fn read_only_error(&self) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is opened read-only", self.dir.display()),
+        )
+    }
+
+    fn snapshot_path(&self, view: &str) -> PathBuf {
+        self.views_dir.join(format!("{view}.snapshot.json"))
+    }
+}
+
+/// Take an exclusive lock on `file`, retrying while another writer holds
+/// it until `timeout` elapses.
+fn lock_within(file: &File, path: &Path, timeout: Duration) -> io::Result<()> {
+    // `None` if the deadline is too far away to represent: wait forever.
+    let deadline = Instant::now().checked_add(timeout);
+    loop {
+        if flock::try_lock_exclusive(file)? {
+            return Ok(());
+        }
+        let remaining = match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => LOCK_RETRY_INTERVAL,
+        };
+        if remaining.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "another writer held the lock on {} for longer than {timeout:?}",
+                    path.display()
+                ),
+            ));
+        }
+        thread::sleep(remaining.min(LOCK_RETRY_INTERVAL));
+    }
+}
+
+impl Storage for FsStorage {
+    fn append(&self, bytes: &[u8]) -> io::Result<u64> {
+        self.with_writable(|mut file| {
+            let start_offset = file.seek(SeekFrom::End(0))?;
+            file.write_all(bytes)?;
+            Ok(start_offset)
+        })
+    }
+
+    fn sync(&self) -> io::Result<()> {
+        match &*self.file.read().unwrap_or_else(|e| e.into_inner()) {
+            Some(file) => file.sync_data(),
+            None => Ok(()),
+        }
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(fs::metadata(&self.log_path)?.len())
+    }
+
+    fn read_from(&self, offset: u64) -> io::Result<Box<dyn Read + Send>> {
+        let mut file = File::open(&self.log_path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        Ok(Box::new(file))
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        let mut file = File::open(&self.log_path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(buf)
+    }
+
+    fn truncate(&self) -> io::Result<()> {
+        self.with_writable(|file| file.set_len(0))
+    }
+
+    fn truncate_to(&self, len: u64) -> io::Result<()> {
+        self.with_writable(|file| file.set_len(len))
+    }
+
+    fn replace_log(&self, bytes: &[u8]) -> io::Result<()> {
+        let mut handle = self.file.write().unwrap_or_else(|e| e.into_inner());
+        if handle.is_none() {
+            return Err(self.read_only_error());
+        }
+
+        let tmp_path = self.log_path.with_extension("jsonl.tmp");
+        match fs::remove_file(&tmp_path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        let mut file = OpenOptions::new()
+            .create_new(true)
+            .append(true)
+            .open(&tmp_path)?;
+        file.write_all(bytes)?;
+        file.sync_data()?;
+        // Lock the new file before it takes the log's name, so no other
+        // writer can slip in between.
+        if self.locked && !flock::try_lock_exclusive(&file)? {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("another writer holds the lock on {}", tmp_path.display()),
+            ));
+        }
+        fs::rename(&tmp_path, &self.log_path)?;
+
+        // The old handle (and its lock) belong to the replaced file.
+        *handle = Some(file);
+        Ok(())
+    }
+
+    fn read_archive(&self) -> io::Result<Option<Box<dyn Read + Send>>> {
+        match File::open(&self.archive_path) {
+            Ok(file) => Ok(Some(Box::new(file))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn append_archive(&self, frame: &[u8]) -> io::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.archive_path)?;
+        file.write_all(frame)?;
+        file.sync_data()
+    }
+
+    fn replace_archive(&self, bytes: &[u8]) -> io::Result<()> {
+        let mut tmp_path = self.archive_path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(bytes)?;
+        file.sync_data()?;
+        drop(file);
+        fs::rename(&tmp_path, &self.archive_path)
+    }
+
+    fn load_snapshot(&self, view: &str) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.snapshot_path(view)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save_snapshot(&self, view: &str, bytes: &[u8]) -> io::Result<()> {
+        snapshot::write_atomic(&self.snapshot_path(view), bytes)
+    }
+
+    fn delete_snapshot(&self, view: &str) -> io::Result<()> {
+        snapshot::delete(&self.snapshot_path(view))
+    }
+
+    fn local_dir(&self) -> Option<&Path> {
+        Some(&self.dir)
+    }
+}
+
+/// A [`Storage`] kept entirely in memory, for tests and short-lived logs.
+///
+/// Nothing is durable: [`sync`](Storage::sync) does nothing. Clones share
+/// the same contents, so a log can be reopened from a clone, or its bytes
+/// inspected after the storage was handed to [`EventLog::with_storage`].
+/// The filesystem-only features listed on [`Storage`] are unavailable.
+///
+/// [`EventLog::with_storage`]: crate::EventLog::with_storage
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::{Event, EventLog, MemStorage};
+/// use serde_json::json;
+///
+/// let storage = MemStorage::new();
+/// let mut log = EventLog::with_storage(storage.clone()).open()?;
+/// log.append(&Event::new("click", json!({})))?;
+/// log.rotate()?;
+/// drop(log);
+///
+/// let log = EventLog::with_storage(storage.clone()).open()?;
+/// assert_eq!(log.read_full()?.count(), 1);
+/// assert!(storage.log_bytes().is_empty());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MemStorage(Arc<Mutex<MemState>>);
+
+#[derive(Debug, Default)]
+struct MemState {
+    log: Vec<u8>,
+    archive: Vec<u8>,
+    snapshots: HashMap<String, Vec<u8>>,
+}
+
+impl MemStorage {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A copy of the active log's bytes.
+    pub fn log_bytes(&self) -> Vec<u8> {
+        self.state().log.clone()
+    }
+
+    /// A copy of the archive's bytes, empty if nothing has been archived.
+    pub fn archive_bytes(&self) -> Vec<u8> {
+        self.state().archive.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemState> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Storage for MemStorage {
+    fn append(&self, bytes: &[u8]) -> io::Result<u64> {
+        let mut state = self.state();
+        let offset = state.log.len() as u64;
+        state.log.extend_from_slice(bytes);
+        Ok(offset)
+    }
+
+    fn sync(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.state().log.len() as u64)
+    }
+
+    fn read_from(&self, offset: u64) -> io::Result<Box<dyn Read + Send>> {
+        let log = &self.state().log;
+        let start = usize::try_from(offset).map_or(log.len(), |o| o.min(log.len()));
+        Ok(Box::new(Cursor::new(log[start..].to_vec())))
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        let log = &self.state().log;
+        let bytes = usize::try_from(offset)
+            .ok()
+            .and_then(|start| log.get(start..start.checked_add(buf.len())?))
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        buf.copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn truncate(&self) -> io::Result<()> {
+        self.state().log.clear();
+        Ok(())
+    }
+
+    fn truncate_to(&self, len: u64) -> io::Result<()> {
+        let log = &mut self.state().log;
+        log.truncate(usize::try_from(len).unwrap_or(log.len()));
+        Ok(())
+    }
+
+    fn replace_log(&self, bytes: &[u8]) -> io::Result<()> {
+        self.state().log = bytes.to_vec();
+        Ok(())
+    }
+
+    fn read_archive(&self) -> io::Result<Option<Box<dyn Read + Send>>> {
+        let archive = &self.state().archive;
+        if archive.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(Cursor::new(archive.clone()))))
+    }
+
+    fn append_archive(&self, frame: &[u8]) -> io::Result<()> {
+        self.state().archive.extend_from_slice(frame);
+        Ok(())
+    }
+
+    fn replace_archive(&self, bytes: &[u8]) -> io::Result<()> {
+        self.state().archive = bytes.to_vec();
+        Ok(())
+    }
+
+    fn load_snapshot(&self, view: &str) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.state().snapshots.get(view).cloned())
+    }
+
+    fn save_snapshot(&self, view: &str, bytes: &[u8]) -> io::Result<()> {
+        self.state()
+            .snapshots
+            .insert(view.to_string(), bytes.to_vec());
+        Ok(())
+    }
+
+    fn delete_snapshot(&self, view: &str) -> io::Result<()> {
+        self.state().snapshots.remove(view);
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+//! Swapping a whole log directory for one built elsewhere.
+
+use crate::log::LockMode;
+use crate::storage::FsStorage;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Move `staged` to `current`, replacing the log there, while holding the
+/// writer lock on both.
+pub(crate) fn replace_dir(current: &Path, staged: &Path) -> io::Result<()> {
+    if !staged.join("app.jsonl").is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} holds no event log", staged.display()),
+        ));
+    }
+    if current.exists() && fs::canonicalize(current)? == fs::canonicalize(staged)? {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("cannot replace {} with itself", current.display()),
+        ));
+    }
+    let staged_lock = FsStorage::open(staged, LockMode::Flock)?;
+    let current_lock = if current.join("app.jsonl").is_file() {
+        Some(FsStorage::open(current, LockMode::Flock)?)
+    } else if current.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} holds no event log; refusing to replace it",
+                current.display()
+            ),
+        ));
+    } else {
+        None
+    };
+
+    let Some(current_lock) = current_lock else {
+        fs::rename(staged, current)?;
+        return Ok(());
+    };
+    let aside = aside_path(current);
+    if aside.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{} exists, left by an earlier swap; remove it first",
+                aside.display()
+            ),
+        ));
+    }
+    fs::rename(current, &aside)?;
+    if let Err(e) = fs::rename(staged, current) {
+        fs::rename(&aside, current).map_err(|undo| {
+            io::Error::new(
+                undo.kind(),
+                format!(
+                    "swap failed ({e}) and the old log could not be moved back from {}: {undo}",
+                    aside.display()
+                ),
+            )
+        })?;
+        return Err(e);
+    }
+    drop((current_lock, staged_lock));
+    fs::remove_dir_all(&aside)
+}
+
+/// Where the old directory waits while the staged one is moved in:
+/// a sibling named `<current>.old`.
+fn aside_path(current: &Path) -> PathBuf {
+    let mut name = current
+        .file_name()
+        .map(OsString::from)
+        .unwrap_or_default();
+    name.push(".old");
+    current.with_file_name(name)
+}
@@ -0,0 +1,119 @@
+//! Following the active log as it grows.
+
+use crate::event::Event;
+use crate::log::{EventReader, WaitResult};
+use std::collections::VecDeque;
+use std::io;
+use std::time::Duration;
+
+/// A blocking iterator that yields events as they are appended, returned
+/// by [`EventReader::events_stream`].
+///
+/// Items are `(event, next_byte_offset, line_hash)`, as from
+/// [`EventReader::read_from`]. Between batches it blocks in
+/// [`EventReader::wait_for_events`] for up to the poll interval at a time.
+/// It runs forever unless limited with [`max_idle`](Self::max_idle); after
+/// yielding an error it ends.
+#[derive(Debug)]
+pub struct EventTail {
+    reader: EventReader,
+    offset: u64,
+    poll_interval: Duration,
+    max_idle: Option<u32>,
+    idle: u32,
+    /// Wait for the log to grow past this instead of `offset`, so a
+    /// partial trailing line doesn't turn the wait into a busy loop.
+    wait_from: u64,
+    buffered: VecDeque<(Event, u64, String)>,
+    done: bool,
+}
+
+impl EventTail {
+    pub(crate) fn new(reader: EventReader, offset: u64, poll_interval: Duration) -> Self {
+        EventTail {
+            reader,
+            offset,
+            poll_interval,
+            max_idle: None,
+            idle: 0,
+            wait_from: offset,
+            buffered: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// End the iterator after `polls` consecutive poll intervals pass with
+    /// no new events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter};
+    /// use serde_json::json;
+    /// use std::time::Duration;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("a", json!({})))?;
+    ///
+    /// let tail = writer
+    ///     .reader()
+    ///     .events_stream(0, Duration::from_millis(10))
+    ///     .max_idle(1);
+    /// assert_eq!(tail.count(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn max_idle(mut self, polls: u32) -> Self {
+        self.max_idle = Some(polls);
+        self
+    }
+
+    /// Returns the offset after the last event yielded.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Wait for new data and buffer every complete event after `offset`.
+    fn fill(&mut self) -> io::Result<()> {
+        let wait_from = self.wait_from.max(self.offset);
+        match self.reader.wait_for_events(wait_from, self.poll_interval)? {
+            WaitResult::NewData(size) => {
+                self.idle = 0;
+                self.buffered = self
+                    .reader
+                    .read_from(self.offset)?
+                    .collect::<io::Result<_>>()?;
+                if self.buffered.is_empty() {
+                    self.wait_from = size;
+                }
+            }
+            WaitResult::Timeout => {
+                self.idle += 1;
+                if self.max_idle.is_some_and(|max| self.idle >= max) {
+                    self.done = true;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for EventTail {
+    type Item = io::Result<(Event, u64, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.buffered.pop_front() {
+                self.offset = item.1;
+                return Some(Ok(item));
+            }
+            if self.done {
+                return None;
+            }
+            if let Err(e) = self.fill() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! Deterministic test fixtures for event log directories.
+//!
+//! Available with the `testing` cargo feature. A [`Fixture`] freezes the
+//! state of a log — active log, archive, view snapshots, manifest, and the
+//! sidecar files kept beside them — into a portable, serde-serializable
+//! value that can be committed to a repository and loaded into a fresh
+//! directory later.
+//!
+//! ```
+//! # use tempfile::tempdir;
+//! use eventfold::testing::{capture_fixture, load_fixture};
+//! use eventfold::{Event, EventLog};
+//! use serde_json::json;
+//! # let source = tempdir()?;
+//! # let target = tempdir()?;
+//! let mut log = EventLog::open(source.path())?;
+//! log.append(&Event::new("click", json!({})))?;
+//!
+//! let fixture = capture_fixture(&log)?;
+//! load_fixture(target.path(), &fixture)?;
+//!
+//! let restored = EventLog::open(target.path())?;
+//! assert_eq!(restored.read_full()?.count(), 1);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::archive;
+use crate::codec::Codec;
+use crate::log::EventLog;
+use crate::manifest::{self, Manifest};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Component, Path};
+
+/// The fixture format version written by [`capture_fixture`].
+///
+/// Version 2 added the archive codec, the views location, the manifest and
+/// sidecar files. [`load_fixture`] still loads version 1 fixtures.
+pub const FIXTURE_FORMAT_VERSION: u32 = 2;
+
+/// Files kept beside the log that fixtures capture, by path relative to
+/// the log directory. Cursors, under [`CURSORS_DIR`], are captured too.
+const SIDECARS: [&str; 4] = ["seq", "app.index", "rotations.log", "dead-letter.jsonl"];
+
+/// Directory of consumer cursors, relative to the log directory.
+const CURSORS_DIR: &str = "cursors";
+
+/// A portable capture of an event log directory.
+///
+/// Everything is stored as text so fixtures diff cleanly and stay readable
+/// across crate versions: the archive is kept as one decoded string per
+/// frame (re-encoded on load), and snapshots and the manifest as parsed
+/// JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    /// Fixture format version. [`load_fixture`] rejects versions it
+    /// doesn't understand.
+    pub format_version: u32,
+
+    /// Verbatim contents of `app.jsonl`, including any trailing partial line.
+    pub active_log: String,
+
+    /// Codec the archive is written with on load. Version 1 fixtures are
+    /// always zstd.
+    #[serde(default)]
+    pub archive_codec: Codec,
+
+    /// Decoded contents of each archive frame, oldest first.
+    pub archive_frames: Vec<String>,
+
+    /// The views tree, relative to the log directory, with `/` between
+    /// components.
+    #[serde(default = "default_views_dir")]
+    pub views_dir: String,
+
+    /// Snapshot contents keyed by view name.
+    pub snapshots: BTreeMap<String, Value>,
+
+    /// The log's [`Manifest`], or `None` in version 1 fixtures, where the
+    /// first writer to open the loaded directory creates one.
+    #[serde(default)]
+    pub manifest: Option<Value>,
+
+    /// Verbatim contents of the other files kept beside the log — the `seq`
+    /// file, `app.index`, `rotations.log`, `dead-letter.jsonl` and cursors
+    /// under `cursors/` — keyed by path relative to the log directory.
+    #[serde(default)]
+    pub sidecars: BTreeMap<String, String>,
+}
+
+fn default_views_dir() -> String {
+    "views".to_string()
+}
+
+/// Capture the persisted state of `log` as a [`Fixture`].
+///
+/// Everything is read through the log's [`Storage`](crate::Storage), so
+/// in-memory logs can be captured too; they have no sidecar files.
+/// Snapshots are captured for every registered view and, with a local
+/// directory, every snapshot file found there.
+///
+/// Captures what is persisted, not in-memory view state — call
+/// [`EventLog::refresh_all`] first if the snapshots should be current.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if the views tree is outside the log directory.
+/// Returns an error if any file cannot be read, if the active log, an
+/// archive frame, or a sidecar is not valid UTF-8, or if a snapshot is not
+/// valid JSON.
+pub fn capture_fixture(log: &EventLog) -> io::Result<Fixture> {
+    let writer = log.writer();
+    let local_dir = writer.storage().local_dir();
+
+    let mut active_log = Vec::new();
+    writer.storage().read_from(0)?.read_to_end(&mut active_log)?;
+
+    let archive_frames = log
+        .reader()
+        .archive_frame_bytes()?
+        .into_iter()
+        .map(utf8)
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let views_dir = match local_dir {
+        Some(dir) => relative_views_dir(dir, log.views_dir())?,
+        None => default_views_dir(),
+    };
+
+    let mut names: BTreeSet<String> = log.view_names().map(str::to_string).collect();
+    if let Some(dir) = writer.snapshot_files_dir() {
+        names.extend(snapshot_names(dir)?);
+    }
+    let mut snapshots = BTreeMap::new();
+    for name in names {
+        if let Some(bytes) = writer.snapshot_store(&name).load_bytes()? {
+            let value: Value = serde_json::from_slice(&bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            snapshots.insert(name, value);
+        }
+    }
+
+    let mut sidecars = BTreeMap::new();
+    if let Some(dir) = local_dir {
+        for name in SIDECARS {
+            if let Some(contents) = read_optional(&dir.join(name))? {
+                sidecars.insert(name.to_string(), contents);
+            }
+        }
+        for name in file_names(&dir.join(CURSORS_DIR))? {
+            let path = dir.join(CURSORS_DIR).join(&name);
+            if let Some(contents) = read_optional(&path)? {
+                sidecars.insert(format!("{CURSORS_DIR}/{name}"), contents);
+            }
+        }
+    }
+
+    Ok(Fixture {
+        format_version: FIXTURE_FORMAT_VERSION,
+        active_log: utf8(active_log)?,
+        archive_codec: Codec::from_path(log.archive_path()),
+        archive_frames,
+        views_dir,
+        snapshots,
+        manifest: Some(serde_json::to_value(log.manifest())?),
+        sidecars,
+    })
+}
+
+/// Materialize a [`Fixture`] into `dir`, ready to be opened as an event log.
+///
+/// Creates `dir` and the fixture's views tree if needed. Overwrites
+/// `app.jsonl`, the archive (removing one written with another codec), the
+/// manifest, and any snapshot or sidecar file named in the fixture.
+///
+/// # Errors
+///
+/// Returns `InvalidData` if the fixture's format version is unsupported,
+/// or if its views tree or a sidecar path is not a relative path inside
+/// the log directory. Returns an error if any file cannot be written.
+pub fn load_fixture(dir: impl AsRef<Path>, fixture: &Fixture) -> io::Result<()> {
+    if !(1..=FIXTURE_FORMAT_VERSION).contains(&fixture.format_version) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "unsupported fixture format version {} (expected 1 to {})",
+                fixture.format_version, FIXTURE_FORMAT_VERSION
+            ),
+        ));
+    }
+
+    let dir = dir.as_ref();
+    let views_dir = dir.join(contained(&fixture.views_dir)?);
+    fs::create_dir_all(&views_dir)?;
+
+    fs::write(dir.join("app.jsonl"), &fixture.active_log)?;
+
+    for codec in Codec::ALL {
+        match fs::remove_file(dir.join(codec.file_name())) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    let archive_path = dir.join(fixture.archive_codec.file_name());
+    for frame in &fixture.archive_frames {
+        archive::append_compressed_frame_with_dictionary(&archive_path, frame.as_bytes(), None)?;
+    }
+
+    if let Some(value) = &fixture.manifest {
+        let manifest: Manifest = serde_json::from_value(value.clone())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        manifest::save(dir, &manifest)?;
+    }
+
+    for (name, contents) in &fixture.sidecars {
+        let path = dir.join(contained(name)?);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+    }
+
+    for (name, value) in &fixture.snapshots {
+        let json = serde_json::to_string_pretty(value)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(views_dir.join(format!("{name}.snapshot.json")), json)?;
+    }
+
+    Ok(())
+}
+
+/// `views_dir` relative to the log directory `dir`, with `/` separators.
+fn relative_views_dir(dir: &Path, views_dir: &Path) -> io::Result<String> {
+    let relative = views_dir.strip_prefix(dir).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "views tree {} is outside the log directory and cannot be captured",
+                views_dir.display()
+            ),
+        )
+    })?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    Ok(parts.join("/"))
+}
+
+/// `path` as a relative path that stays inside the directory it is joined
+/// to.
+fn contained(path: &str) -> io::Result<&Path> {
+    let path = Path::new(path);
+    if path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        Ok(path)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("fixture path {} leaves the log directory", path.display()),
+        ))
+    }
+}
+
+/// Names of the views with a snapshot file in `dir`.
+fn snapshot_names(dir: &Path) -> io::Result<Vec<String>> {
+    Ok(file_names(dir)?
+        .into_iter()
+        .filter_map(|name| name.strip_suffix(".snapshot.json").map(str::to_string))
+        .collect())
+}
+
+/// Names of the files directly in `dir`, empty if it doesn't exist.
+fn file_names(dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_file()
+            && let Some(name) = entry.file_name().to_str()
+        {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// The contents of the text file at `path`, or `None` if it doesn't exist.
+fn read_optional(path: &Path) -> io::Result<Option<String>> {
+    match fs::read(path) {
+        Ok(bytes) => utf8(bytes).map(Some),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn utf8(bytes: Vec<u8>) -> io::Result<String> {
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,116 @@
+//! Transaction framing for multi-event appends.
+//!
+//! A transaction is written as consecutive lines in a single `write_all`.
+//! Every event in a multi-event transaction carries a marker in its `meta`
+//! under the reserved `"txn"` key:
+//!
+//! ```text
+//! {"type":"debit","data":{...},"ts":1000,"meta":{"txn":{"index":0,"size":2}}}
+//! {"type":"credit","data":{...},"ts":1000,"meta":{"txn":{"index":1,"size":2}}}
+//! ```
+//!
+//! The transaction is committed once its last line (`index == size - 1`) is
+//! present. Single-event appends carry no marker and form one-event
+//! transactions on their own.
+
+use crate::event::Event;
+use serde_json::{Map, Value, json};
+use std::io;
+
+/// Reserved `meta` key holding the transaction marker.
+pub(crate) const TXN_META_KEY: &str = "txn";
+
+/// Return a copy of `event` with a transaction marker inserted into its `meta`.
+///
+/// # Errors
+///
+/// Returns `InvalidInput` if the event's `meta` is set but is not a JSON object.
+pub(crate) fn mark(event: &Event, index: usize, size: usize) -> io::Result<Event> {
+    let mut event = event.clone();
+    let mut meta = match event.meta.take() {
+        None => Map::new(),
+        Some(Value::Object(map)) => map,
+        Some(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "transaction events must have object (or absent) meta",
+            ));
+        }
+    };
+    meta.insert(
+        TXN_META_KEY.to_string(),
+        json!({"index": index, "size": size}),
+    );
+    event.meta = Some(Value::Object(meta));
+    Ok(event)
+}
+
+/// Read the `(index, size)` transaction marker from an event, if present.
+pub(crate) fn marker(event: &Event) -> Option<(u64, u64)> {
+    let txn = event.meta.as_ref()?.get(TXN_META_KEY)?;
+    Some((txn.get("index")?.as_u64()?, txn.get("size")?.as_u64()?))
+}
+
+/// Iterator that groups events from a `read_from`-style stream into
+/// committed transactions.
+///
+/// Incomplete transactions — a torn write at EOF, or a group interrupted by
+/// an unrelated event — are never yielded.
+pub(crate) struct TransactionIter<I> {
+    pub(crate) inner: I,
+}
+
+impl<I> Iterator for TransactionIter<I>
+where
+    I: Iterator<Item = io::Result<(Event, u64, String)>>,
+{
+    type Item = io::Result<Vec<Event>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut pending: Vec<Event> = Vec::new();
+        let mut expected_size = 0u64;
+
+        loop {
+            let event = match self.inner.next()? {
+                Ok((event, _, _)) => event,
+                Err(e) => return Some(Err(e)),
+            };
+
+            match marker(&event) {
+                None => {
+                    if !pending.is_empty() {
+                        warn!(
+                            "eventfold: dropping incomplete transaction ({} of {} events)",
+                            pending.len(),
+                            expected_size
+                        );
+                    }
+                    return Some(Ok(vec![event]));
+                }
+                Some((index, size)) => {
+                    if index == 0 {
+                        if !pending.is_empty() {
+                            warn!(
+                                "eventfold: dropping incomplete transaction ({} of {} events)",
+                                pending.len(),
+                                expected_size
+                            );
+                        }
+                        pending.clear();
+                        expected_size = size;
+                    } else if index != pending.len() as u64 || size != expected_size {
+                        // Out-of-sequence member of a torn transaction.
+                        pending.clear();
+                        expected_size = 0;
+                        continue;
+                    }
+
+                    pending.push(event);
+                    if pending.len() as u64 == expected_size {
+                        return Some(Ok(pending));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Read-time migration of old event payloads, per event type and schema
+//! version.
+
+use crate::event::Event;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// A payload migration registered with
+/// [`EventLogBuilder::upcaster`](crate::EventLogBuilder::upcaster).
+pub(crate) type UpcastFn = Arc<dyn Fn(&mut Value) + Send + Sync>;
+
+/// The upcasters registered for a log, keyed by event type and the schema
+/// version each one upgrades from. Cheap to clone, so readers share them.
+#[derive(Clone, Default)]
+pub(crate) struct Upcasters(Arc<HashMap<String, BTreeMap<u32, UpcastFn>>>);
+
+impl std::fmt::Debug for Upcasters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|(ty, steps)| (ty, steps.keys())))
+            .finish()
+    }
+}
+
+impl Upcasters {
+    /// Version of events that carry no `schema_version`.
+    pub(crate) const UNVERSIONED: u32 = 1;
+
+    /// Register `upcast` to move `event_type` payloads from `from_version`
+    /// to `from_version + 1`, replacing any earlier one.
+    pub(crate) fn insert(&mut self, event_type: &str, from_version: u32, upcast: UpcastFn) {
+        Arc::make_mut(&mut self.0)
+            .entry(event_type.to_string())
+            .or_default()
+            .insert(from_version, upcast);
+    }
+
+    /// `event` with every upcaster from its schema version onwards applied
+    /// in turn, and its version raised to match. Borrowed if none applies.
+    pub(crate) fn apply<'a>(&self, event: &'a Event) -> Cow<'a, Event> {
+        let Some(steps) = self.0.get(&event.event_type) else {
+            return Cow::Borrowed(event);
+        };
+        let mut version = event.schema_version().unwrap_or(Self::UNVERSIONED);
+        let Some(mut upcast) = steps.get(&version) else {
+            return Cow::Borrowed(event);
+        };
+        let mut event = event.clone();
+        loop {
+            upcast(&mut event.data);
+            version += 1;
+            match steps.get(&version) {
+                Some(next) => upcast = next,
+                None => break,
+            }
+        }
+        Cow::Owned(event.with_schema_version(version))
+    }
+}
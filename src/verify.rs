@@ -0,0 +1,244 @@
+//! Full consistency check of a log directory, as run by
+//! [`EventLog::verify`](crate::EventLog::verify).
+
+use crate::codec::{ArchiveCodec, Codec};
+use crate::crypto::{self, Cipher};
+use crate::event::Event;
+use crate::log::BOM;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead};
+
+/// What [`EventLog::verify`](crate::EventLog::verify) found.
+///
+/// Each check records its problems separately; [`is_ok`](Self::is_ok)
+/// is `true` only if none found any.
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{Event, EventLog, ViewStatus};
+/// use serde_json::json;
+/// # let dir = tempdir()?;
+/// let mut log = EventLog::builder(dir.path())
+///     .view::<u64>("counter", |state, _event| state + 1)
+///     .open()?;
+/// log.append(&Event::new("click", json!({})))?;
+/// log.refresh_all()?;
+///
+/// let report = log.verify()?;
+/// assert!(report.is_ok());
+/// assert_eq!(report.active_events, 1);
+/// assert_eq!(report.views["counter"], ViewStatus::Matches);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Number of active-log lines that parsed as events.
+    pub active_events: u64,
+
+    /// Active-log lines that are not valid events.
+    pub bad_lines: Vec<LineProblem>,
+
+    /// Offset where a trailing partial line (no newline — a crash
+    /// mid-write) starts in the active log, if there is one. Readers
+    /// ignore it and the next append replaces it.
+    pub partial_line: Option<u64>,
+
+    /// Number of archive frames that decompressed.
+    pub archive_frames: u64,
+
+    /// Number of archived lines that parsed as events.
+    pub archived_events: u64,
+
+    /// Archive frames that failed to decompress or hold invalid lines.
+    pub archive_problems: Vec<ArchiveProblem>,
+
+    /// Result of recomputing each registered view, by name.
+    pub views: BTreeMap<String, ViewStatus>,
+}
+
+impl VerifyReport {
+    /// `true` if no check found a problem.
+    ///
+    /// A trailing partial line is not a problem on its own, and neither is
+    /// a view that has no snapshot yet or that keeps no state.
+    pub fn is_ok(&self) -> bool {
+        self.bad_lines.is_empty()
+            && self.archive_problems.is_empty()
+            && self.views.values().all(|status| {
+                matches!(
+                    status,
+                    ViewStatus::Matches | ViewStatus::NoSnapshot | ViewStatus::Skipped
+                )
+            })
+    }
+}
+
+/// An active-log line that is not a valid event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineProblem {
+    /// Offset where the line starts in the active log.
+    pub offset: u64,
+
+    /// Why the line failed to parse.
+    pub message: String,
+}
+
+/// A problem with the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveProblem {
+    /// 0-based index of the affected frame, or `None` if the archive's
+    /// frame structure is malformed, so frames past that point cannot be
+    /// told apart.
+    pub frame: Option<u64>,
+
+    /// Offset of the invalid line within the frame's decompressed
+    /// contents, or `None` if the frame (or archive) could not be read at
+    /// all.
+    pub offset: Option<u64>,
+
+    /// What went wrong.
+    pub message: String,
+}
+
+/// The result of recomputing one view from scratch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ViewStatus {
+    /// The recomputed state equals the snapshot's.
+    Matches,
+
+    /// The recomputed state differs from the snapshot's. Expected if the
+    /// archive has dropped events under a
+    /// [`Retention`](crate::Retention) policy since the snapshot folded
+    /// them, or if the reducer has changed.
+    Mismatch,
+
+    /// The snapshot does not fit the active log (its offset is past the
+    /// end, or the line before it has a different hash); the next refresh
+    /// will rebuild the view.
+    StaleSnapshot,
+
+    /// The view has no snapshot to compare against.
+    NoSnapshot,
+
+    /// The view keeps no comparable state, like a
+    /// [`LogView`](crate::LogView).
+    Skipped,
+
+    /// The view could not be recomputed: reading the history failed or a
+    /// fallible reducer rejected an event.
+    Failed(String),
+}
+
+/// Scan the active log, counting valid events and recording invalid lines
+/// and any trailing partial line. Lines are decrypted with `cipher` if the
+/// log is encrypted.
+pub(crate) fn check_active(
+    mut reader: impl BufRead,
+    cipher: Option<&Cipher>,
+    report: &mut VerifyReport,
+) -> io::Result<()> {
+    let mut offset = 0u64;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let n = reader.read_until(b'\n', &mut line)?;
+        if n == 0 {
+            return Ok(());
+        }
+        if !line.ends_with(b"\n") {
+            report.partial_line = Some(offset);
+            return Ok(());
+        }
+        let content = if offset == 0 {
+            line.strip_prefix(BOM.as_bytes()).unwrap_or(&line)
+        } else {
+            &line
+        };
+        match parse_line(content, cipher) {
+            Some(Ok(())) => report.active_events += 1,
+            Some(Err(message)) => report.bad_lines.push(LineProblem { offset, message }),
+            None => {}
+        }
+        offset += n as u64;
+    }
+}
+
+/// Decode every archive frame and parse its lines.
+pub(crate) fn check_archive(
+    codec: Codec,
+    bytes: &[u8],
+    dictionaries: &BTreeMap<u32, Vec<u8>>,
+    cipher: Option<&Cipher>,
+    report: &mut VerifyReport,
+) {
+    let ranges = match codec.frame_ranges(bytes) {
+        Ok(ranges) => ranges,
+        Err(e) => {
+            report.archive_problems.push(ArchiveProblem {
+                frame: None,
+                offset: None,
+                message: e.to_string(),
+            });
+            return;
+        }
+    };
+    for (index, range) in ranges.into_iter().enumerate() {
+        let frame = index as u64;
+        let contents = match codec.decode_frame(&bytes[range], dictionaries) {
+            Ok(contents) => contents,
+            Err(e) => {
+                report.archive_problems.push(ArchiveProblem {
+                    frame: Some(frame),
+                    offset: None,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+        report.archive_frames += 1;
+
+        let mut offset = 0u64;
+        for line in contents.split_inclusive(|&b| b == b'\n') {
+            let content = if offset == 0 {
+                line.strip_prefix(BOM.as_bytes()).unwrap_or(line)
+            } else {
+                line
+            };
+            let result = if line.ends_with(b"\n") {
+                parse_line(content, cipher)
+            } else {
+                Some(Err("frame ends with a partial line".to_string()))
+            };
+            match result {
+                Some(Ok(())) => report.archived_events += 1,
+                Some(Err(message)) => report.archive_problems.push(ArchiveProblem {
+                    frame: Some(frame),
+                    offset: Some(offset),
+                    message,
+                }),
+                None => {}
+            }
+            offset += line.len() as u64;
+        }
+    }
+}
+
+/// Parse one complete line, decrypting it first with `cipher` if set:
+/// `None` if it is blank, otherwise whether it is a valid event.
+fn parse_line(line: &[u8], cipher: Option<&Cipher>) -> Option<Result<(), String>> {
+    if line.trim_ascii().is_empty() {
+        return None;
+    }
+    let json = match crypto::open_line_bytes(cipher, line) {
+        Ok(json) => json,
+        Err(e) => return Some(Err(e.to_string())),
+    };
+    Some(
+        serde_json::from_slice::<Event>(&json)
+            .map(drop)
+            .map_err(|e| e.to_string()),
+    )
+}
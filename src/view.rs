@@ -1,11 +1,18 @@
+use crate::crypto::{self, Cipher};
+use crate::dead_letter;
 use crate::event::Event;
 use crate::log::EventReader;
-use crate::snapshot::{self, Snapshot};
+use crate::observer::RebuildReason;
+use crate::snapshot::{Snapshot, SnapshotStore};
+use crate::upcast::Upcasters;
+use crate::verify::ViewStatus;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::any::Any;
 use std::io;
-use std::path::{Path, PathBuf};
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 /// A pure function that folds an event into state.
 ///
@@ -26,7 +33,305 @@ use std::path::{Path, PathBuf};
 /// ```
 pub type ReduceFn<S> = fn(S, &Event) -> S;
 
-mod sealed {
+/// A reducer that receives the event's `data` deserialized as `P`.
+///
+/// Used with [`View::new_typed`]. Events whose `data` does not deserialize
+/// as `P` never reach the reducer; they are recorded in the dead-letter log
+/// instead (see [`EventReader::read_dead_letters`]).
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::{Event, TypedReduceFn};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Deposit { amount: u64 }
+///
+/// fn balance(state: u64, _event: &Event, deposit: Deposit) -> u64 {
+///     state + deposit.amount
+/// }
+///
+/// let reducer: TypedReduceFn<u64, Deposit> = balance;
+/// ```
+pub type TypedReduceFn<S, P> = fn(S, &Event, P) -> S;
+
+/// A reducer that also receives where the event sits in the log.
+///
+/// Used with [`View::new_with_context`] and
+/// [`EventLogBuilder::view_with_context`](crate::EventLogBuilder::view_with_context),
+/// for views that keep pointers back into the log, such as an index from
+/// domain keys to events whose payloads are loaded lazily.
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::{ContextReduceFn, Event, EventContext};
+/// use std::collections::BTreeMap;
+///
+/// type Latest = BTreeMap<String, u64>;
+///
+/// fn latest_by_type(mut state: Latest, event: &Event, at: EventContext<'_>) -> Latest {
+///     state.insert(event.event_type.clone(), at.sequence);
+///     state
+/// }
+///
+/// let reducer: ContextReduceFn<Latest> = latest_by_type;
+/// ```
+pub type ContextReduceFn<S> = fn(S, &Event, EventContext<'_>) -> S;
+
+/// View state that two folds over consecutive stretches of the log can be
+/// combined into, enabling [`View::rebuild_parallel`].
+///
+/// Implementing it is a promise about the view's reducer: folding events
+/// `a` then `b` from `S::default()` must give the same state as
+/// `merge(fold(a), fold(b))`, each folded from `S::default()`. That holds
+/// when `merge` is associative, `S::default()` is its identity, and the
+/// reducer folds each event the same way whatever state it starts from —
+/// counters, sums, sets and per-key maps usually qualify; anything that
+/// looks at the previous state to decide what an event means does not.
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::{Event, Merge};
+/// use serde::{Deserialize, Serialize};
+/// use std::collections::BTreeMap;
+///
+/// #[derive(Default, Clone, Serialize, Deserialize)]
+/// struct CountByType(BTreeMap<String, u64>);
+///
+/// impl Merge for CountByType {
+///     fn merge(mut a: Self, b: Self) -> Self {
+///         for (event_type, n) in b.0 {
+///             *a.0.entry(event_type).or_default() += n;
+///         }
+///         a
+///     }
+/// }
+///
+/// fn count_by_type(mut state: CountByType, event: &Event) -> CountByType {
+///     *state.0.entry(event.event_type.clone()).or_default() += 1;
+///     state
+/// }
+/// ```
+pub trait Merge: Sized {
+    /// Combine the state folded from one stretch of events, `a`, with the
+    /// state folded from the stretch right after it, `b`.
+    fn merge(a: Self, b: Self) -> Self;
+}
+
+/// Where an event sits in the log, as passed to a [`ContextReduceFn`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventContext<'a> {
+    /// Byte offset in the active log to pass to
+    /// [`EventReader::read_from`] to read this event first, or `None` if
+    /// the event is archived (which a full replay folds first).
+    ///
+    /// Offsets start again at 0 when the log rotates; `sequence` is the
+    /// pointer that lasts.
+    pub offset: Option<u64>,
+
+    /// The event's log-wide sequence number, as reported by
+    /// [`AppendResult::sequence`](crate::AppendResult::sequence). Read the
+    /// event back with [`EventReader::read_from_sequence`].
+    pub sequence: u64,
+
+    /// Hash of the event's line, as yielded by
+    /// [`EventReader::read_from`].
+    pub line_hash: &'a str,
+}
+
+/// A reducer that can fail, for events it cannot make sense of.
+///
+/// Used with [`View::new_fallible`]. When the reducer returns `Err`,
+/// [`View::refresh`] stops at that event and returns the error instead of
+/// folding on with wrong state.
+///
+/// # Examples
+///
+/// ```
+/// use eventfold::{Event, ReduceError, TryReduceFn};
+///
+/// fn balance(state: u64, event: &Event) -> Result<u64, ReduceError> {
+///     let amount = event.data["amount"]
+///         .as_u64()
+///         .ok_or_else(|| ReduceError::new("amount is missing or not a number"))?;
+///     Ok(state + amount)
+/// }
+///
+/// let reducer: TryReduceFn<u64> = balance;
+/// ```
+pub type TryReduceFn<S> = fn(S, &Event) -> Result<S, ReduceError>;
+
+/// Why a fallible reducer ([`TryReduceFn`]) could not fold an event.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message}")]
+pub struct ReduceError {
+    message: String,
+}
+
+impl ReduceError {
+    /// Create an error with the given message.
+    pub fn new(message: impl Into<String>) -> Self {
+        ReduceError {
+            message: message.into(),
+        }
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// A fallible reducer failed during a refresh.
+///
+/// [`View::refresh`] returns this inside an [`io::Error`] of kind
+/// `InvalidData`; recover it with
+/// `err.get_ref().and_then(|e| e.downcast_ref::<ReduceFailure>())`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "view '{view}': reducer failed{}: {source}",
+    .offset.map(|o| format!(" on the event ending at byte {o}")).unwrap_or_default()
+)]
+pub struct ReduceFailure {
+    /// Name of the view whose reducer failed.
+    pub view: String,
+
+    /// The offending event's `next_byte_offset` in the active log, as
+    /// yielded by [`EventReader::read_from`]. `None` if the event was read
+    /// during a full replay, which does not track offsets.
+    pub offset: Option<u64>,
+
+    /// The reducer's error.
+    #[source]
+    pub source: ReduceError,
+}
+
+/// A reducer that may reject an event, handing back the state untouched
+/// together with the reason.
+type CheckedReduceFn<S> = Box<dyn Fn(S, &Event) -> Result<S, (S, String)> + Send + Sync>;
+
+/// How a view folds each event.
+enum Reducer<S> {
+    /// Every event is folded.
+    Plain(ReduceFn<S>),
+    /// Rejected events are skipped and sent to the dead-letter log.
+    Checked(CheckedReduceFn<S>),
+    /// A failure stops the refresh.
+    Fallible(TryReduceFn<S>),
+    /// Every event is folded, with its [`EventContext`].
+    Context(ContextReduceFn<S>),
+}
+
+impl<S> Reducer<S> {
+    /// Fold one event into `state` for the view named `view`, without side
+    /// effects. An event rejected by a checked reducer comes back as the
+    /// unchanged state and the reason.
+    fn reduce(
+        &self,
+        view: &str,
+        state: S,
+        event: &Event,
+        offset: Option<u64>,
+        context: EventContext<'_>,
+    ) -> io::Result<Result<S, (S, String)>> {
+        match self {
+            Reducer::Plain(reducer) => Ok(Ok(reducer(state, event))),
+            Reducer::Context(reducer) => Ok(Ok(reducer(state, event, context))),
+            Reducer::Fallible(reducer) => match reducer(state, event) {
+                Ok(state) => Ok(Ok(state)),
+                Err(source) => {
+                    let failure = ReduceFailure {
+                        view: view.to_string(),
+                        offset,
+                        source,
+                    };
+                    Err(io::Error::new(io::ErrorKind::InvalidData, failure))
+                }
+            },
+            Reducer::Checked(reducer) => Ok(reducer(state, event)),
+        }
+    }
+}
+
+/// An unparsed line of the history with where it sits, read ahead of a
+/// [`View::rebuild_parallel`].
+struct LocatedLine {
+    line: String,
+    /// Where the line starts in the active log, or `None` if archived.
+    offset: Option<u64>,
+    sequence: u64,
+    hash: String,
+}
+
+/// What folding one chunk of a parallel rebuild produced: its state, and
+/// the events a checked reducer rejected, with the reasons.
+type ChunkFold<S> = (S, Vec<(Event, String)>);
+
+/// Decrypt (with `cipher`, if set), parse, upcast and fold `lines` from
+/// `S::default()`, without side effects.
+fn fold_lines<S: Default>(
+    reducer: &Reducer<S>,
+    upcasters: &Upcasters,
+    cipher: Option<&Cipher>,
+    view: &str,
+    lines: &[LocatedLine],
+) -> io::Result<ChunkFold<S>> {
+    let mut state = S::default();
+    let mut rejected = Vec::new();
+    for located in lines {
+        let json = crypto::open_line(cipher, &located.line)?;
+        let event: Event = serde_json::from_str(&json)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let context = EventContext {
+            offset: located.offset,
+            sequence: located.sequence,
+            line_hash: &located.hash,
+        };
+        let upcast = upcasters.apply(&event);
+        state = match reducer.reduce(view, state, &upcast, None, context)? {
+            Ok(state) => state,
+            Err((state, reason)) => {
+                rejected.push((event, reason));
+                state
+            }
+        };
+    }
+    Ok((state, rejected))
+}
+
+/// The active-log offset and sequence number of the next event to fold,
+/// for building each [`EventContext`].
+struct Tracker {
+    offset: u64,
+    /// `None` unless the view's reducer takes an [`EventContext`].
+    sequence: Option<u64>,
+}
+
+impl Tracker {
+    fn context<'a>(&self, archived: bool, line_hash: &'a str) -> EventContext<'a> {
+        EventContext {
+            offset: (!archived).then_some(self.offset),
+            sequence: self.sequence.unwrap_or_default(),
+            line_hash,
+        }
+    }
+
+    /// Move past an event that ends at `end` in the active log, or is
+    /// archived if `end` is `None`.
+    fn advance(&mut self, end: Option<u64>) {
+        if let Some(end) = end {
+            self.offset = end;
+        }
+        if let Some(sequence) = &mut self.sequence {
+            *sequence += 1;
+        }
+    }
+}
+
+pub(crate) mod sealed {
     pub trait Sealed {}
 }
 
@@ -34,10 +339,33 @@ mod sealed {
 ///
 /// This trait is sealed and cannot be implemented outside of this crate.
 pub trait ViewOps: sealed::Sealed {
-    /// Refresh the view from the event reader, discarding the state reference.
-    fn refresh_boxed(&mut self, reader: &EventReader) -> io::Result<()>;
+    /// Refresh the view from the event reader, reporting what it did.
+    fn refresh_boxed(&mut self, reader: &EventReader) -> io::Result<RefreshStats>;
+    /// Discard the snapshot and replay the full history.
+    fn rebuild_boxed(&mut self, reader: &EventReader) -> io::Result<()>;
+    /// Load the snapshot and return the active-log offset the next refresh
+    /// resumes from, or `None` if the view will read events itself (a full
+    /// replay, or a view that does not share reads).
+    fn resume_offset(&mut self, reader: &EventReader) -> io::Result<Option<u64>>;
+    /// Refresh from `events` already read from the active log, as yielded
+    /// by [`EventReader::read_from`] starting at or before the view's
+    /// [`resume_offset`](Self::resume_offset). Falls back to reading for
+    /// itself if the view returned `None` there.
+    fn refresh_from_events(
+        &mut self,
+        reader: &EventReader,
+        events: &[(Event, u64, String)],
+    ) -> io::Result<RefreshStats>;
+    /// Recompute the view from scratch and compare it with its snapshot.
+    fn verify_boxed(&self, reader: &EventReader) -> io::Result<ViewStatus>;
     /// Reset the offset to 0 and save the snapshot.
     fn reset_offset(&mut self) -> io::Result<()>;
+    /// Set when refreshes save the snapshot.
+    fn set_snapshot_policy(&mut self, policy: SnapshotPolicy);
+    /// Save the snapshot if the in-memory state is ahead of it.
+    fn flush_snapshot(&mut self) -> io::Result<()>;
+    /// The in-memory state as JSON, without the snapshot's offset and hash.
+    fn state_json(&self) -> io::Result<serde_json::Value>;
     /// Returns the view name.
     fn view_name(&self) -> &str;
     /// Downcast to `&dyn Any` for type recovery.
@@ -46,6 +374,66 @@ pub trait ViewOps: sealed::Sealed {
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
 
+/// What a single refresh did, returned by [`View::refresh_counted`] and
+/// [`EventLog::refresh_all_counted`](crate::EventLog::refresh_all_counted).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefreshStats {
+    /// Number of events passed to the reducer. Zero means the view was
+    /// already up to date.
+    pub applied: u64,
+
+    /// The view's offset in the active log after the refresh.
+    pub new_offset: u64,
+
+    /// `true` if the refresh replayed the full history (archive and active
+    /// log) instead of resuming from the snapshot: the snapshot was
+    /// missing, failed its integrity check, or was discarded by
+    /// [`View::rebuild`].
+    pub rebuilt: bool,
+}
+
+/// When a refresh saves the view's snapshot, set with
+/// [`EventLogBuilder::snapshot_every`](crate::EventLogBuilder::snapshot_every)
+/// or [`View::with_snapshot_policy`].
+///
+/// Between saves the state lives only in memory. The snapshot is always
+/// saved after a full replay, on rotation, and, best effort, when the
+/// [`View`] or its [`EventLog`] is dropped; call [`View::flush`] or
+/// [`EventLog::flush_views`] to be sure it is persisted. **After a
+/// crash**, the view resumes from the last saved snapshot and replays the
+/// events since — up to one policy window's worth. The result is the
+/// same, only slower to reach.
+///
+/// [`EventLog`]: crate::EventLog
+/// [`EventLog::flush_views`]: crate::EventLog::flush_views
+///
+/// # Examples
+///
+/// ```
+/// # use tempfile::tempdir;
+/// use eventfold::{EventLog, SnapshotPolicy};
+/// # let dir = tempdir()?;
+/// let log = EventLog::builder(dir.path())
+///     .snapshot_every(SnapshotPolicy::EveryNEvents(1000))
+///     .view::<u64>("counter", |state, _event| state + 1)
+///     .open()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SnapshotPolicy {
+    /// Save after every refresh that applies an event. The default.
+    #[default]
+    EveryRefresh,
+
+    /// Save once at least this many events have been applied since the
+    /// last save.
+    EveryNEvents(u64),
+
+    /// Save on the first refresh that applies an event at least this long
+    /// after the last save.
+    EveryDuration(Duration),
+}
+
 /// A derived view over an event log.
 ///
 /// Owns a reducer function, manages its snapshot on disk, and supports
@@ -67,20 +455,53 @@ pub trait ViewOps: sealed::Sealed {
 /// ```
 pub struct View<S> {
     name: String,
-    reducer: ReduceFn<S>,
-    snapshot_path: PathBuf,
+    reducer: Reducer<S>,
+    snapshots: SnapshotStore,
     state: S,
     offset: u64,
     hash: String,
     loaded: bool,
     needs_full_replay: bool,
+    /// Whether the state came from a snapshot that passed its integrity
+    /// check.
+    from_snapshot: bool,
+    /// Whether the last refresh replayed the full history.
+    replayed_full: bool,
+    /// Sequence number of the event at `offset`, once a context reducer
+    /// has needed it.
+    next_sequence: Option<u64>,
+    snapshot_policy: SnapshotPolicy,
+    /// Version of the reducer, checked against the snapshot's.
+    reducer_version: Option<u32>,
+    /// Events applied since the snapshot was last saved.
+    unsaved_events: u64,
+    /// When the snapshot was last saved, kept only under
+    /// [`SnapshotPolicy::EveryDuration`] so other policies never read the
+    /// clock.
+    last_save: Option<Instant>,
+    /// [`View::flush`], captured where `S` is known to be serializable so
+    /// `Drop` can call it.
+    flush_on_drop: fn(&mut View<S>) -> io::Result<()>,
+}
+
+impl<S> Drop for View<S> {
+    /// Best-effort [`flush`](View::flush) of a snapshot held back by a
+    /// [`SnapshotPolicy`]; errors are only logged.
+    fn drop(&mut self) {
+        if let Err(e) = (self.flush_on_drop)(self) {
+            warn!(
+                "eventfold: view '{}': failed to flush snapshot on drop: {e}",
+                self.name
+            );
+        }
+    }
 }
 
 impl<S: std::fmt::Debug> std::fmt::Debug for View<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("View")
             .field("name", &self.name)
-            .field("snapshot_path", &self.snapshot_path)
+            .field("snapshots", &self.snapshots)
             .field("state", &self.state)
             .field("offset", &self.offset)
             .finish()
@@ -112,16 +533,217 @@ where
     /// # Ok::<(), std::io::Error>(())
     /// ```
     pub fn new(name: &str, reducer: ReduceFn<S>, views_dir: &Path) -> Self {
+        Self::with_reducer(name, Reducer::Plain(reducer), views_dir)
+    }
+
+    /// Create a view that keeps its snapshot in `snapshot_dir` instead of
+    /// the log's `views/` directory, created on the first save.
+    ///
+    /// A reader process uses this to fold the writer's log while keeping
+    /// its own snapshots, say under `views-reader/`, so the two never
+    /// overwrite each other's. The view still reads the log directory it
+    /// is refreshed from.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventReader, EventWriter, View};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("click", json!({})))?;
+    ///
+    /// let reader = EventReader::new(dir.path());
+    /// let mut view: View<u64> = View::new_with_snapshot_dir(
+    ///     "counter",
+    ///     |state, _event| state + 1,
+    ///     &dir.path().join("views-reader"),
+    /// );
+    /// view.refresh(&reader)?;
+    /// assert!(dir.path().join("views-reader/counter.snapshot.json").exists());
+    /// assert!(!dir.path().join("views/counter.snapshot.json").exists());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn new_with_snapshot_dir(name: &str, reducer: ReduceFn<S>, snapshot_dir: &Path) -> Self {
+        Self::with_reducer(name, Reducer::Plain(reducer), snapshot_dir)
+    }
+
+    /// Create a view whose snapshot is `views_dir/filename` rather than
+    /// `views_dir/{name}.snapshot.json`, so two views that must share a
+    /// name (say, the same projection over two logs in one directory)
+    /// don't overwrite each other's snapshots.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, View};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("click", json!({})))?;
+    ///
+    /// let mut view: View<u64> = View::new_with_filename(
+    ///     "counter",
+    ///     |state, _event| state + 1,
+    ///     writer.views_dir(),
+    ///     "counter-v2.snapshot.json",
+    /// );
+    /// view.refresh(&writer.reader())?;
+    /// assert!(writer.views_dir().join("counter-v2.snapshot.json").exists());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn new_with_filename(
+        name: &str,
+        reducer: ReduceFn<S>,
+        views_dir: &Path,
+        filename: &str,
+    ) -> Self {
+        Self::new(name, reducer, views_dir)
+            .with_snapshot_store(SnapshotStore::File(views_dir.join(filename)))
+    }
+
+    /// Create a view whose reducer receives each event's `data`
+    /// deserialized as `P`.
+    ///
+    /// Events whose `data` does not match `P` are skipped: the view's state
+    /// and offset move past them, and they are appended to
+    /// `dead-letter.jsonl` in the log directory with the deserialization
+    /// error as the reason. Read them back with
+    /// [`EventReader::read_dead_letters`]. A full replay (first refresh
+    /// without a snapshot, or [`rebuild`](Self::rebuild)) records them again.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, View};
+    /// use serde::Deserialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Deposit { amount: u64 }
+    ///
+    /// fn balance(state: u64, _event: &Event, deposit: Deposit) -> u64 {
+    ///     state + deposit.amount
+    /// }
+    ///
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("deposit", json!({"amount": 5})))?;
+    /// writer.append(&Event::new("deposit", json!({"amount": "lots"})))?;
+    /// let mut view: View<u64> = View::new_typed("balance", balance, writer.views_dir());
+    /// assert_eq!(*view.refresh(&writer.reader())?, 5);
+    /// assert_eq!(writer.reader().read_dead_letters()?.count(), 1);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn new_typed<P>(name: &str, reducer: TypedReduceFn<S, P>, views_dir: &Path) -> Self
+    where
+        S: 'static,
+        P: DeserializeOwned + 'static,
+    {
+        let checked = move |state: S, event: &Event| {
+            let payload = serde_json::from_value::<P>(event.data.clone());
+            match payload {
+                Ok(payload) => Ok(reducer(state, event, payload)),
+                Err(e) => Err((state, format!("data does not match payload type: {e}"))),
+            }
+        };
+        Self::with_reducer(name, Reducer::Checked(Box::new(checked)), views_dir)
+    }
+
+    /// Create a view with a reducer that can fail.
+    ///
+    /// When the reducer returns `Err`, [`refresh`](Self::refresh) stops at
+    /// that event and returns a [`ReduceFailure`] (inside an `InvalidData`
+    /// [`io::Error`]) naming it. No snapshot is saved for the failed
+    /// refresh, and the in-memory state reverts to the last snapshot, so
+    /// the view never holds state folded past a bad event. Every later
+    /// refresh fails at the same event until the log or reducer is fixed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, ReduceError, ReduceFailure, View};
+    /// use serde_json::json;
+    ///
+    /// fn balance(state: u64, event: &Event) -> Result<u64, ReduceError> {
+    ///     let amount = event.data["amount"]
+    ///         .as_u64()
+    ///         .ok_or_else(|| ReduceError::new("bad amount"))?;
+    ///     Ok(state + amount)
+    /// }
+    ///
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let mut view: View<u64> = View::new_fallible("balance", balance, writer.views_dir());
+    /// writer.append(&Event::new("deposit", json!({"amount": 5})))?;
+    /// assert_eq!(*view.refresh(&writer.reader())?, 5);
+    ///
+    /// let bad = writer.append(&Event::new("deposit", json!({"amount": "lots"})))?;
+    /// let err = view.refresh(&writer.reader()).unwrap_err();
+    /// let failure = err.get_ref().and_then(|e| e.downcast_ref::<ReduceFailure>()).unwrap();
+    /// assert_eq!(failure.offset, Some(bad.end_offset));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn new_fallible(name: &str, reducer: TryReduceFn<S>, views_dir: &Path) -> Self {
+        Self::with_reducer(name, Reducer::Fallible(reducer), views_dir)
+    }
+
+    /// Create a view whose reducer also receives each event's
+    /// [`EventContext`]: its offset, sequence number, and line hash.
+    ///
+    /// During an incremental refresh the offset comes from
+    /// [`EventReader::read_from`]. During a full replay, archived events
+    /// have no offset, and sequence numbers are counted from the oldest
+    /// archived event, which costs an extra pass over the archive. The
+    /// first incremental refresh after loading a snapshot counts the
+    /// events before the snapshot's offset to find its sequence number.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventContext, EventWriter, View};
+    /// use serde_json::json;
+    /// use std::collections::BTreeMap;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("a", json!({})))?;
+    /// let b = writer.append(&Event::new("b", json!({})))?;
+    ///
+    /// let mut view: View<BTreeMap<String, u64>> = View::new_with_context(
+    ///     "offsets",
+    ///     |mut state, event, at: EventContext<'_>| {
+    ///         state.insert(event.event_type.clone(), at.offset.unwrap());
+    ///         state
+    ///     },
+    ///     writer.views_dir(),
+    /// );
+    /// let offsets = view.refresh(&writer.reader())?;
+    /// assert_eq!(offsets["b"], b.start_offset);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn new_with_context(name: &str, reducer: ContextReduceFn<S>, views_dir: &Path) -> Self {
+        Self::with_reducer(name, Reducer::Context(reducer), views_dir)
+    }
+
+    fn with_reducer(name: &str, reducer: Reducer<S>, views_dir: &Path) -> Self {
         let snapshot_path = views_dir.join(format!("{name}.snapshot.json"));
         View {
             name: name.to_string(),
             reducer,
-            snapshot_path,
+            snapshots: SnapshotStore::File(snapshot_path),
             state: S::default(),
             offset: 0,
             hash: String::new(),
             loaded: false,
             needs_full_replay: false,
+            from_snapshot: false,
+            replayed_full: false,
+            next_sequence: None,
+            snapshot_policy: SnapshotPolicy::default(),
+            reducer_version: None,
+            unsaved_events: 0,
+            last_save: None,
+            flush_on_drop: View::flush,
         }
     }
 
@@ -152,86 +774,280 @@ where
     ///
     /// # Errors
     ///
-    /// Returns an error if reading events or saving the snapshot fails.
+    /// Returns an error if reading events or saving the snapshot fails, or
+    /// a [`ReduceFailure`] if a fallible reducer fails. After an error the
+    /// in-memory state reverts to the last snapshot.
     pub fn refresh(&mut self, reader: &EventReader) -> io::Result<&S> {
-        if !self.loaded {
-            if let Some(snap) = snapshot::load::<S>(&self.snapshot_path)? {
-                self.state = snap.state;
-                self.offset = snap.offset;
-                self.hash = snap.hash;
-            } else {
-                self.needs_full_replay = true;
-            }
-            self.loaded = true;
+        self.refresh_counted(reader)?;
+        Ok(&self.state)
+    }
 
-            // Verify snapshot integrity
-            if self.offset > 0 {
-                match self.verify_snapshot(reader)? {
-                    SnapshotValidity::Valid => {}
-                    SnapshotValidity::OffsetBeyondEof => {
-                        log::warn!(
-                            "eventfold: view '{}': snapshot offset {} is beyond log EOF, rebuilding",
-                            self.name, self.offset
-                        );
-                        self.state = S::default();
-                        self.offset = 0;
-                        self.hash = String::new();
-                        self.needs_full_replay = true;
-                    }
-                    SnapshotValidity::HashMismatch => {
-                        log::warn!(
-                            "eventfold: view '{}': snapshot hash mismatch, rebuilding",
-                            self.name
-                        );
-                        self.state = S::default();
-                        self.offset = 0;
-                        self.hash = String::new();
-                        self.needs_full_replay = true;
-                    }
-                }
-            }
+    /// Refresh the view like [`refresh`](Self::refresh), returning what
+    /// the refresh did instead of the state.
+    ///
+    /// Useful in a tailing loop to tell whether anything changed without
+    /// comparing states.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, View};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let mut view: View<u64> = View::new(
+    ///     "counter",
+    ///     |state, _event| state + 1,
+    ///     writer.views_dir(),
+    /// );
+    /// writer.append(&Event::new("a", json!({})))?;
+    /// let result = writer.append(&Event::new("b", json!({})))?;
+    ///
+    /// let stats = view.refresh_counted(&writer.reader())?;
+    /// assert_eq!(stats.applied, 2);
+    /// assert_eq!(stats.new_offset, result.end_offset);
+    ///
+    /// assert_eq!(view.refresh_counted(&writer.reader())?.applied, 0);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`refresh`](Self::refresh).
+    pub fn refresh_counted(&mut self, reader: &EventReader) -> io::Result<RefreshStats> {
+        self.refresh_shared(reader, None)
+    }
+
+    /// Refresh, folding `shared` events read by the caller instead of
+    /// reading the active log when they are given and no full replay is
+    /// due.
+    fn refresh_shared(
+        &mut self,
+        reader: &EventReader,
+        shared: Option<&[(Event, u64, String)]>,
+    ) -> io::Result<RefreshStats> {
+        let stats = self
+            .fold_new_events(reader, shared)
+            .inspect_err(|_| self.unload())?;
+        if let Some(observer) = reader.observer() {
+            observer.on_refresh(&self.name, stats.applied);
         }
+        Ok(stats)
+    }
+
+    /// The body of [`refresh`](Self::refresh).
+    fn fold_new_events(
+        &mut self,
+        reader: &EventReader,
+        shared: Option<&[(Event, u64, String)]>,
+    ) -> io::Result<RefreshStats> {
+        self.load(reader)?;
 
         let mut state = std::mem::take(&mut self.state);
         let mut new_offset = self.offset;
         let mut new_hash = self.hash.clone();
-        let mut processed = false;
+        let mut applied = 0;
+        let rebuilt = self.needs_full_replay;
+        self.replayed_full = rebuilt;
 
+        let mut at;
         if self.needs_full_replay {
             self.needs_full_replay = false;
-            for result in reader.read_full()? {
-                let (event, line_hash) = result?;
-                state = (self.reducer)(state, &event);
+            at = self.replay_tracker(reader)?;
+            for result in reader.read_full_located()? {
+                let (event, end, line_hash) = result?;
+                let context = at.context(end.is_none(), &line_hash);
+                state = self.apply(state, &event, None, context, reader)?;
+                at.advance(end);
                 new_hash = line_hash;
-                processed = true;
+                applied += 1;
             }
-            if processed {
+            if applied > 0 {
                 new_offset = reader.active_log_size()?;
             }
+        } else if let Some(events) = shared {
+            at = self.resume_tracker(reader)?;
+            let mut last = None;
+            for (event, next_offset, line_hash) in events {
+                if *next_offset > self.offset {
+                    let context = at.context(false, line_hash);
+                    state = self.apply(state, event, Some(*next_offset), context, reader)?;
+                    at.advance(Some(*next_offset));
+                    last = Some((*next_offset, line_hash));
+                    applied += 1;
+                }
+            }
+            if let Some((next_offset, line_hash)) = last {
+                new_offset = next_offset;
+                new_hash = line_hash.clone();
+            }
         } else {
+            at = self.resume_tracker(reader)?;
             for result in reader.read_from(self.offset)? {
                 let (event, next_offset, line_hash) = result?;
-                state = (self.reducer)(state, &event);
+                let context = at.context(false, &line_hash);
+                state = self.apply(state, &event, Some(next_offset), context, reader)?;
+                at.advance(Some(next_offset));
                 new_offset = next_offset;
                 new_hash = line_hash;
-                processed = true;
+                applied += 1;
             }
         }
 
         self.state = state;
+        self.next_sequence = at.sequence;
 
-        if processed {
+        if applied > 0 {
             self.offset = new_offset;
             self.hash = new_hash;
-            snapshot::save(
-                &self.snapshot_path,
-                &Snapshot::new(self.state.clone(), self.offset, self.hash.clone()),
-            )?;
+            self.unsaved_events += applied;
+            if rebuilt || self.snapshot_due() {
+                self.save_snapshot()?;
+            }
         }
 
+        Ok(RefreshStats {
+            applied,
+            new_offset: self.offset,
+            rebuilt,
+        })
+    }
+
+    /// Refresh the view in chunks of `chunk` events, calling `yield_fn`
+    /// between chunks.
+    ///
+    /// Behaves like [`refresh`](Self::refresh), but gives the caller a
+    /// chance to yield to a scheduler while folding a large backlog. An async
+    /// caller can use this to cooperate with its runtime without eventfold
+    /// depending on one. `yield_fn` is called only between chunks, never
+    /// after the last event. During incremental refresh the snapshot is
+    /// saved before each yield; a full replay is saved once at the end,
+    /// because offsets into the archive cannot be resumed from.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, View};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// for _ in 0..5 {
+    ///     writer.append(&Event::new("a", json!({})))?;
+    /// }
+    /// let mut view: View<u64> = View::new(
+    ///     "counter",
+    ///     |state, _event| state + 1,
+    ///     writer.views_dir(),
+    /// );
+    /// let mut yields = 0;
+    /// let state = view.refresh_chunked(&writer.reader(), 2, || yields += 1)?;
+    /// assert_eq!(*state, 5);
+    /// assert_eq!(yields, 2);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if `chunk` is zero. Returns an error if reading
+    /// events or saving the snapshot fails, or a [`ReduceFailure`] if a
+    /// fallible reducer fails. After an error the in-memory state reverts
+    /// to the last snapshot — which, during incremental refresh, may include
+    /// chunks folded before the failure.
+    pub fn refresh_chunked(
+        &mut self,
+        reader: &EventReader,
+        chunk: usize,
+        yield_fn: impl FnMut(),
+    ) -> io::Result<&S> {
+        if chunk == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "chunk size must be at least 1",
+            ));
+        }
+
+        match self.fold_chunked(reader, chunk, yield_fn) {
+            Ok(folded) => {
+                if let Some(observer) = reader.observer() {
+                    observer.on_refresh(&self.name, folded);
+                }
+            }
+            Err(e) => {
+                self.unload();
+                return Err(e);
+            }
+        }
         Ok(&self.state)
     }
 
+    /// The body of [`refresh_chunked`](Self::refresh_chunked). Returns the
+    /// number of events folded.
+    fn fold_chunked(
+        &mut self,
+        reader: &EventReader,
+        chunk: usize,
+        mut yield_fn: impl FnMut(),
+    ) -> io::Result<u64> {
+        self.load(reader)?;
+
+        let mut folded = 0;
+        self.replayed_full = self.needs_full_replay;
+
+        if self.needs_full_replay {
+            let mut state = S::default();
+            let mut new_hash = String::new();
+            let mut at = self.replay_tracker(reader)?;
+            let mut events = reader.read_full_located()?.peekable();
+            while let Some(result) = events.next() {
+                let (event, end, line_hash) = result?;
+                let context = at.context(end.is_none(), &line_hash);
+                state = self.apply(state, &event, None, context, reader)?;
+                at.advance(end);
+                new_hash = line_hash;
+                folded += 1;
+                if folded % chunk == 0 && events.peek().is_some() {
+                    yield_fn();
+                }
+            }
+            self.needs_full_replay = false;
+            self.state = state;
+            self.next_sequence = at.sequence;
+
+            if folded > 0 {
+                self.offset = reader.active_log_size()?;
+                self.hash = new_hash;
+                self.save_snapshot()?;
+            }
+        } else {
+            let mut unsaved = false;
+            let mut at = self.resume_tracker(reader)?;
+            let mut events = reader.read_from(self.offset)?.peekable();
+            while let Some(result) = events.next() {
+                let (event, next_offset, line_hash) = result?;
+                let state = std::mem::take(&mut self.state);
+                let context = at.context(false, &line_hash);
+                self.state = self.apply(state, &event, Some(next_offset), context, reader)?;
+                at.advance(Some(next_offset));
+                self.next_sequence = at.sequence;
+                self.offset = next_offset;
+                self.hash = line_hash;
+                unsaved = true;
+                folded += 1;
+                if folded % chunk == 0 && events.peek().is_some() {
+                    self.save_snapshot()?;
+                    unsaved = false;
+                    yield_fn();
+                }
+            }
+
+            if unsaved {
+                self.save_snapshot()?;
+            }
+        }
+
+        Ok(folded as u64)
+    }
+
     /// Return a reference to the current in-memory state.
     ///
     /// No I/O — returns whatever state is currently held. If `refresh`
@@ -269,36 +1085,667 @@ where
     /// Returns an error if deleting the snapshot, reading events, or saving
     /// the new snapshot fails.
     pub fn rebuild(&mut self, reader: &EventReader) -> io::Result<&S> {
-        snapshot::delete(&self.snapshot_path)?;
+        self.snapshots.delete()?;
         self.state = S::default();
         self.offset = 0;
         self.hash = String::new();
         self.loaded = true;
         self.needs_full_replay = true;
+        self.from_snapshot = false;
+        self.report_rebuild(reader, RebuildReason::Requested);
         self.refresh(reader)
     }
 
+    /// Fold the full history (archive + active log) through `f`, starting
+    /// from `init`.
+    ///
+    /// A read-side helper for one-off projections and exports: it does not
+    /// touch this view's state or snapshot, and the accumulator need not be
+    /// the view's `S`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, View};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("a", json!({})))?;
+    /// writer.append(&Event::new("b", json!({})))?;
+    /// let view: View<u64> = View::new(
+    ///     "counter",
+    ///     |state, _event| state + 1,
+    ///     writer.views_dir(),
+    /// );
+    /// let types = view.fold_full(&writer.reader(), String::new(), |mut acc, event| {
+    ///     acc.push_str(&event.event_type);
+    ///     acc
+    /// })?;
+    /// assert_eq!(types, "ab");
+    /// assert_eq!(*view.state(), 0);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading events fails.
+    pub fn fold_full<A, F>(&self, reader: &EventReader, init: A, mut f: F) -> io::Result<A>
+    where
+        F: FnMut(A, &Event) -> A,
+    {
+        let mut acc = init;
+        for result in reader.read_full()? {
+            let (event, _) = result?;
+            acc = f(acc, &event);
+        }
+        Ok(acc)
+    }
+
+    /// Replay the full history up to `offset` and return the state the
+    /// view had there.
+    ///
+    /// `offset` is a byte position in the history as
+    /// [`read_full`](EventReader::read_full) walks it: the decompressed
+    /// archive followed by the active log. Every event ending at or before
+    /// it is folded; `u64::MAX` folds everything. Before the first
+    /// rotation this is the same as an active-log offset.
+    ///
+    /// Like [`fold_full`](Self::fold_full), this leaves the view's state,
+    /// offset, and snapshot alone. Events a checked reducer rejects are
+    /// skipped without being written to the dead-letter log.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, View};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("a", json!({})))?;
+    /// let after_first = writer.active_log_size()?;
+    /// writer.append(&Event::new("b", json!({})))?;
+    ///
+    /// let mut view: View<u64> = View::new(
+    ///     "counter",
+    ///     |state, _event| state + 1,
+    ///     writer.views_dir(),
+    /// );
+    /// view.refresh(&writer.reader())?;
+    /// assert_eq!(view.state_at(&writer.reader(), after_first)?, 1);
+    /// assert_eq!(*view.state(), 2);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading events fails, or an `InvalidData` error
+    /// wrapping a [`ReduceFailure`] if a fallible reducer rejects an event.
+    pub fn state_at(&self, reader: &EventReader, offset: u64) -> io::Result<S> {
+        let mut state = S::default();
+        let mut at = self.replay_tracker(reader)?;
+        reader.for_each_full(|line| {
+            if line.end > offset {
+                return Ok(ControlFlow::Break(()));
+            }
+            let context = at.context(line.active_end.is_none(), line.hash);
+            state = match self.reduce(
+                reader,
+                std::mem::take(&mut state),
+                line.event,
+                line.active_end,
+                context,
+            )? {
+                Ok(next) | Err((next, _)) => next,
+            };
+            at.advance(line.active_end);
+            Ok(ControlFlow::Continue(()))
+        })?;
+        Ok(state)
+    }
+
+    /// Save the snapshot on the cadence `policy` sets instead of after
+    /// every refresh; see [`SnapshotPolicy`] for the trade-off.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{snapshot, Event, EventWriter, SnapshotPolicy, View};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// let mut view: View<u64> = View::new(
+    ///     "counter",
+    ///     |state, _event| state + 1,
+    ///     writer.views_dir(),
+    /// )
+    /// .with_snapshot_policy(SnapshotPolicy::EveryNEvents(100));
+    /// writer.append(&Event::new("a", json!({})))?;
+    /// view.refresh(&writer.reader())?; // a full replay always saves
+    /// writer.append(&Event::new("b", json!({})))?;
+    /// view.refresh(&writer.reader())?;
+    ///
+    /// let path = writer.views_dir().join("counter.snapshot.json");
+    /// assert_eq!(snapshot::load::<u64>(&path)?.unwrap().state, 1);
+    /// view.flush()?;
+    /// assert_eq!(snapshot::load::<u64>(&path)?.unwrap().state, 2);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn with_snapshot_policy(mut self, policy: SnapshotPolicy) -> Self {
+        self.snapshot_policy = policy;
+        self.restart_save_interval();
+        self
+    }
+
+    /// Tag the reducer with `version`, recorded in the snapshot. A snapshot
+    /// written under a different version, or with none, is discarded and
+    /// the view replays the full history, so bump the version whenever the
+    /// reducer's logic changes. Untagged views trust any snapshot.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, View};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("a", json!({})))?;
+    /// let mut view: View<u64> =
+    ///     View::new("counter", |state, _event| state + 1, writer.views_dir());
+    /// view.refresh(&writer.reader())?;
+    ///
+    /// // The reducer changed: version 2 ignores the old snapshot.
+    /// let mut view: View<u64> =
+    ///     View::new("counter", |state, _event| state + 10, writer.views_dir())
+    ///         .with_reducer_version(2);
+    /// assert_eq!(*view.refresh(&writer.reader())?, 10);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn with_reducer_version(mut self, version: u32) -> Self {
+        self.reducer_version = Some(version);
+        self
+    }
+
+    /// Persist the in-memory state as the snapshot now, even if the
+    /// view's [`SnapshotPolicy`] would wait longer — for example from a
+    /// graceful-shutdown handler, so the next start replays nothing.
+    ///
+    /// A no-op if no event has been applied since the snapshot was last
+    /// saved: the file is not rewritten and keeps its modification time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if saving the snapshot fails.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.unsaved_events > 0 {
+            self.save_snapshot()?;
+        }
+        Ok(())
+    }
+
+    /// Whether the snapshot policy calls for saving now.
+    fn snapshot_due(&self) -> bool {
+        match self.snapshot_policy {
+            SnapshotPolicy::EveryRefresh => true,
+            SnapshotPolicy::EveryNEvents(n) => self.unsaved_events >= n,
+            SnapshotPolicy::EveryDuration(d) => {
+                self.last_save.is_none_or(|last| last.elapsed() >= d)
+            }
+        }
+    }
+
+    /// Save the in-memory state as the snapshot.
+    fn save_snapshot(&mut self) -> io::Result<()> {
+        let mut snap = Snapshot::new(self.state.clone(), self.offset, self.hash.clone());
+        snap.reducer_version = self.reducer_version;
+        self.snapshots.save(&snap)?;
+        self.unsaved_events = 0;
+        self.restart_save_interval();
+        Ok(())
+    }
+
+    /// Start timing the snapshot interval from now, if the policy has one.
+    fn restart_save_interval(&mut self) {
+        self.last_save =
+            matches!(self.snapshot_policy, SnapshotPolicy::EveryDuration(_)).then(Instant::now);
+    }
+
+    /// Keep this view's snapshot in `snapshots` instead of its own file.
+    pub(crate) fn with_snapshot_store(mut self, snapshots: SnapshotStore) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+
     /// Returns the view name.
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// Whether the view's state was loaded from a snapshot that still
+    /// matched the log, rather than built by replaying the full history.
+    ///
+    /// `false` until the first refresh, and after [`rebuild`](Self::rebuild).
+    /// Together with
+    /// [`last_refresh_replayed_full`](Self::last_refresh_replayed_full) it
+    /// tells a fast start from a cold one at startup.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, View};
+    /// use serde_json::json;
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// writer.append(&Event::new("a", json!({})))?;
+    ///
+    /// let mut cold: View<u64> = View::new("counter", |s, _| s + 1, writer.views_dir());
+    /// cold.refresh(&writer.reader())?;
+    /// assert!(!cold.was_loaded_from_snapshot());
+    /// assert!(cold.last_refresh_replayed_full());
+    ///
+    /// let mut warm: View<u64> = View::new("counter", |s, _| s + 1, writer.views_dir());
+    /// warm.refresh(&writer.reader())?;
+    /// assert!(warm.was_loaded_from_snapshot());
+    /// assert!(!warm.last_refresh_replayed_full());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn was_loaded_from_snapshot(&self) -> bool {
+        self.from_snapshot
+    }
+
+    /// Whether the most recent refresh replayed the full history (archive
+    /// and active log): the snapshot was missing, failed its integrity
+    /// check, or was discarded by [`rebuild`](Self::rebuild). `false`
+    /// before the first refresh. See
+    /// [`was_loaded_from_snapshot`](Self::was_loaded_from_snapshot).
+    pub fn last_refresh_replayed_full(&self) -> bool {
+        self.replayed_full
+    }
+
+    /// Fold one event, which ends at `offset` in the active log if known,
+    /// into `state`. A rejected event leaves `state` unchanged and is
+    /// recorded in the dead-letter log.
+    fn apply(
+        &self,
+        state: S,
+        event: &Event,
+        offset: Option<u64>,
+        context: EventContext<'_>,
+        reader: &EventReader,
+    ) -> io::Result<S> {
+        match self.reduce(reader, state, event, offset, context)? {
+            Ok(state) => Ok(state),
+            Err((state, reason)) => {
+                reader.require_local("dead letters")?;
+                let reason = format!("view '{}': {reason}", self.name);
+                dead_letter::append_record(
+                    &reader.dead_letter_path(),
+                    event,
+                    &reason,
+                    reader.cipher(),
+                )?;
+                Ok(state)
+            }
+        }
+    }
+
+    /// Fold one event, upcast by `reader`'s upcasters, into `state`
+    /// without side effects. An event rejected by a checked reducer comes
+    /// back as the unchanged state and the reason.
+    fn reduce(
+        &self,
+        reader: &EventReader,
+        state: S,
+        event: &Event,
+        offset: Option<u64>,
+        context: EventContext<'_>,
+    ) -> io::Result<Result<S, (S, String)>> {
+        let event = reader.upcasters().apply(event);
+        self.reducer
+            .reduce(&self.name, state, &event, offset, context)
+    }
+
+    /// A [`Tracker`] for replaying the full history, counting sequence
+    /// numbers from the oldest archived event if the reducer needs them.
+    fn replay_tracker(&self, reader: &EventReader) -> io::Result<Tracker> {
+        let sequence = match self.reducer {
+            Reducer::Context(_) => {
+                let base = reader.sequence_base()?;
+                Some(base.saturating_sub(reader.archived_events()?))
+            }
+            _ => None,
+        };
+        Ok(Tracker {
+            offset: 0,
+            sequence,
+        })
+    }
+
+    /// A [`Tracker`] for folding the active log from `offset`, finding the
+    /// sequence number there the first time the reducer needs it.
+    fn resume_tracker(&mut self, reader: &EventReader) -> io::Result<Tracker> {
+        if matches!(self.reducer, Reducer::Context(_)) && self.next_sequence.is_none() {
+            let mut before = 0;
+            for result in reader.read_range(0, self.offset)? {
+                result?;
+                before += 1;
+            }
+            self.next_sequence = Some(reader.sequence_base()? + before);
+        }
+        Ok(Tracker {
+            offset: self.offset,
+            sequence: self.next_sequence,
+        })
+    }
+
+    /// Drop in-memory state so the next refresh starts again from the
+    /// snapshot.
+    fn unload(&mut self) {
+        self.state = S::default();
+        self.offset = 0;
+        self.hash = String::new();
+        self.loaded = false;
+        self.needs_full_replay = false;
+        self.from_snapshot = false;
+        self.next_sequence = None;
+        self.replayed_full = false;
+        self.unsaved_events = 0;
+    }
+
+    /// Load the snapshot on first use and discard it if it no longer
+    /// matches the log.
+    fn load(&mut self, reader: &EventReader) -> io::Result<()> {
+        if !self.loaded {
+            match self.snapshots.load::<S>()? {
+                Some(snap) if !self.reducer_matches(&snap) => {
+                    self.needs_full_replay = true;
+                    self.report_rebuild(reader, RebuildReason::ReducerChanged);
+                    warn!(
+                        "eventfold: view '{}': snapshot reducer version {:?} differs from {:?}, rebuilding",
+                        self.name, snap.reducer_version, self.reducer_version
+                    );
+                }
+                Some(snap) => {
+                    self.state = snap.state;
+                    self.offset = snap.offset;
+                    self.hash = snap.hash;
+                    self.from_snapshot = true;
+                }
+                None => {
+                    self.needs_full_replay = true;
+                    self.report_rebuild(reader, RebuildReason::MissingSnapshot);
+                }
+            }
+            self.loaded = true;
+
+            // Verify snapshot integrity
+            if self.offset > 0 {
+                match self.verify_snapshot(reader)? {
+                    SnapshotValidity::Valid => {}
+                    SnapshotValidity::OffsetBeyondEof => {
+                        self.report_rebuild(reader, RebuildReason::OffsetBeyondEof);
+                        warn!(
+                            "eventfold: view '{}': snapshot offset {} is beyond log EOF, rebuilding",
+                            self.name, self.offset
+                        );
+                        self.state = S::default();
+                        self.offset = 0;
+                        self.hash = String::new();
+                        self.needs_full_replay = true;
+                        self.from_snapshot = false;
+                    }
+                    SnapshotValidity::HashMismatch => {
+                        self.report_rebuild(reader, RebuildReason::HashMismatch);
+                        warn!(
+                            "eventfold: view '{}': snapshot hash mismatch, rebuilding",
+                            self.name
+                        );
+                        self.state = S::default();
+                        self.offset = 0;
+                        self.hash = String::new();
+                        self.needs_full_replay = true;
+                        self.from_snapshot = false;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn report_rebuild(&self, reader: &EventReader, reason: RebuildReason) {
+        if let Some(observer) = reader.observer() {
+            observer.on_view_rebuild(&self.name, reason);
+        }
+    }
+
+    /// Whether `snap` was written by this view's reducer version. Views
+    /// without a version accept any snapshot.
+    fn reducer_matches(&self, snap: &Snapshot<S>) -> bool {
+        self.reducer_version
+            .is_none_or(|version| snap.reducer_version == Some(version))
+    }
+
     fn verify_snapshot(&self, reader: &EventReader) -> io::Result<SnapshotValidity> {
-        let file_size = reader.active_log_size()?;
+        snapshot_validity(reader, self.offset, &self.hash)
+    }
+
+    /// Replay the history the persisted snapshot covers and compare the
+    /// result with the snapshot's state. Leaves the view untouched.
+    fn check_snapshot(&self, reader: &EventReader) -> io::Result<ViewStatus> {
+        let Some(snap) = self.snapshots.load::<S>()? else {
+            return Ok(ViewStatus::NoSnapshot);
+        };
+        if !self.reducer_matches(&snap) {
+            return Ok(ViewStatus::StaleSnapshot);
+        }
+        match snapshot_validity(reader, snap.offset, &snap.hash)? {
+            SnapshotValidity::Valid => {}
+            SnapshotValidity::OffsetBeyondEof | SnapshotValidity::HashMismatch => {
+                return Ok(ViewStatus::StaleSnapshot);
+            }
+        }
+
+        let mut state = S::default();
+        let mut at = self.replay_tracker(reader)?;
+        let replay = reader.for_each_full(|line| {
+            if line.active_end.is_some_and(|end| end > snap.offset) {
+                return Ok(ControlFlow::Break(()));
+            }
+            let context = at.context(line.active_end.is_none(), line.hash);
+            state = match self.reduce(
+                reader,
+                std::mem::take(&mut state),
+                line.event,
+                line.active_end,
+                context,
+            )? {
+                Ok(next) | Err((next, _)) => next,
+            };
+            at.advance(line.active_end);
+            Ok(ControlFlow::Continue(()))
+        });
+        if let Err(e) = replay {
+            return Ok(ViewStatus::Failed(e.to_string()));
+        }
+
+        if serde_json::to_value(&state)? == serde_json::to_value(&snap.state)? {
+            Ok(ViewStatus::Matches)
+        } else {
+            Ok(ViewStatus::Mismatch)
+        }
+    }
+}
+
+/// Check that a snapshot taken at `offset` after the line hashed `hash`
+/// still fits the active log.
+impl<S> View<S>
+where
+    S: Serialize + DeserializeOwned + Default + Clone + Merge + Send,
+{
+    /// Rebuild the view like [`rebuild`](Self::rebuild), parsing and
+    /// folding the history on `threads` threads.
+    ///
+    /// The history is split into `threads` consecutive chunks; each is
+    /// folded from `S::default()` on its own thread, and the results are
+    /// combined in order with [`Merge::merge`]. Only views whose state
+    /// implements [`Merge`] can do this, and the result is only correct
+    /// if the reducer keeps the promise described there.
+    ///
+    /// The whole history is read into memory first, unparsed, so this
+    /// trades memory for a faster cold start on a large log. Events a
+    /// checked reducer rejects reach the dead-letter log in order once all
+    /// chunks are folded. Context reducers see the same offsets and
+    /// sequence numbers as in a sequential replay.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tempfile::tempdir;
+    /// use eventfold::{Event, EventWriter, Merge, View};
+    /// use serde::{Deserialize, Serialize};
+    /// use serde_json::json;
+    ///
+    /// #[derive(Default, Clone, Serialize, Deserialize)]
+    /// struct Count(u64);
+    ///
+    /// impl Merge for Count {
+    ///     fn merge(a: Self, b: Self) -> Self {
+    ///         Count(a.0 + b.0)
+    ///     }
+    /// }
+    ///
+    /// # let dir = tempdir()?;
+    /// let mut writer = EventWriter::open(dir.path())?;
+    /// for _ in 0..100 {
+    ///     writer.append(&Event::new("click", json!({})))?;
+    /// }
+    /// let mut view: View<Count> = View::new(
+    ///     "count",
+    ///     |state, _event| Count(state.0 + 1),
+    ///     writer.views_dir(),
+    /// );
+    /// let state = view.rebuild_parallel(&writer.reader(), 4)?;
+    /// assert_eq!(state.0, 100);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if `threads` is zero. Returns an error if
+    /// deleting the snapshot, reading or parsing events, writing dead
+    /// letters, or saving the new snapshot fails, or a [`ReduceFailure`]
+    /// if a fallible reducer fails. After an error the in-memory state
+    /// reverts to the last snapshot.
+    pub fn rebuild_parallel(&mut self, reader: &EventReader, threads: usize) -> io::Result<&S> {
+        if threads == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "thread count must be at least 1",
+            ));
+        }
 
-        if self.offset > file_size {
-            return Ok(SnapshotValidity::OffsetBeyondEof);
+        self.snapshots.delete()?;
+        self.unload();
+        self.report_rebuild(reader, RebuildReason::Requested);
+        let applied = self
+            .fold_parallel(reader, threads)
+            .inspect_err(|_| self.unload())?;
+        if let Some(observer) = reader.observer() {
+            observer.on_refresh(&self.name, applied);
         }
+        Ok(&self.state)
+    }
 
-        if self.offset == 0 {
-            return Ok(SnapshotValidity::Valid);
+    /// The body of [`rebuild_parallel`](Self::rebuild_parallel). Returns
+    /// the number of events folded.
+    fn fold_parallel(&mut self, reader: &EventReader, threads: usize) -> io::Result<u64> {
+        let mut at = self.replay_tracker(reader)?;
+        let first_sequence = at.sequence.unwrap_or_default();
+        let mut lines = Vec::new();
+        for (sequence, result) in (first_sequence..).zip(reader.read_raw_full_located()?) {
+            let (line, end, hash) = result?;
+            lines.push(LocatedLine {
+                line,
+                offset: end.is_some().then_some(at.offset),
+                sequence,
+                hash,
+            });
+            at.advance(end);
         }
 
-        match reader.read_line_hash_before(self.offset)? {
-            Some(hash) if hash == self.hash => Ok(SnapshotValidity::Valid),
-            Some(_) => Ok(SnapshotValidity::HashMismatch),
-            None => Ok(SnapshotValidity::Valid),
+        let chunk_len = lines.len().div_ceil(threads).max(1);
+        let reducer = &self.reducer;
+        let upcasters = reader.upcasters();
+        let cipher = reader.cipher();
+        let name = self.name.as_str();
+        let folds: Vec<io::Result<ChunkFold<S>>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = lines
+                .chunks(chunk_len)
+                .map(|chunk| {
+                    scope.spawn(move || fold_lines(reducer, upcasters, cipher, name, chunk))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        });
+
+        let mut state = None;
+        for fold in folds {
+            let (folded, rejected) = fold?;
+            for (event, reason) in rejected {
+                reader.require_local("dead letters")?;
+                let reason = format!("view '{}': {reason}", self.name);
+                dead_letter::append_record(
+                    &reader.dead_letter_path(),
+                    &event,
+                    &reason,
+                    reader.cipher(),
+                )?;
+            }
+            state = Some(match state {
+                Some(state) => S::merge(state, folded),
+                None => folded,
+            });
         }
+
+        self.state = state.unwrap_or_default();
+        self.loaded = true;
+        self.replayed_full = true;
+        self.next_sequence = at.sequence;
+        if let Some(last) = lines.last() {
+            self.offset = reader.active_log_size()?;
+            self.hash = last.hash.clone();
+            self.unsaved_events = lines.len() as u64;
+            self.save_snapshot()?;
+        }
+        Ok(lines.len() as u64)
+    }
+}
+
+fn snapshot_validity(
+    reader: &EventReader,
+    offset: u64,
+    hash: &str,
+) -> io::Result<SnapshotValidity> {
+    let file_size = reader.active_log_size()?;
+
+    if offset > file_size {
+        return Ok(SnapshotValidity::OffsetBeyondEof);
+    }
+
+    if offset == 0 {
+        return Ok(SnapshotValidity::Valid);
+    }
+
+    match reader.read_line_hash_before(offset)? {
+        Some(line) if line == hash => Ok(SnapshotValidity::Valid),
+        Some(_) => Ok(SnapshotValidity::HashMismatch),
+        None => Ok(SnapshotValidity::Valid),
     }
 }
 
@@ -308,18 +1755,51 @@ impl<S> ViewOps for View<S>
 where
     S: Serialize + DeserializeOwned + Default + Clone + 'static,
 {
-    fn refresh_boxed(&mut self, reader: &EventReader) -> io::Result<()> {
-        self.refresh(reader)?;
+    fn refresh_boxed(&mut self, reader: &EventReader) -> io::Result<RefreshStats> {
+        self.refresh_counted(reader)
+    }
+
+    fn rebuild_boxed(&mut self, reader: &EventReader) -> io::Result<()> {
+        self.rebuild(reader)?;
         Ok(())
     }
 
+    fn resume_offset(&mut self, reader: &EventReader) -> io::Result<Option<u64>> {
+        self.load(reader).inspect_err(|_| self.unload())?;
+        Ok((!self.needs_full_replay).then_some(self.offset))
+    }
+
+    fn refresh_from_events(
+        &mut self,
+        reader: &EventReader,
+        events: &[(Event, u64, String)],
+    ) -> io::Result<RefreshStats> {
+        // Only a loaded view knows its offset was covered by the read.
+        let shared = Some(events).filter(|_| self.loaded);
+        self.refresh_shared(reader, shared)
+    }
+
+    fn verify_boxed(&self, reader: &EventReader) -> io::Result<ViewStatus> {
+        self.check_snapshot(reader)
+    }
+
     fn reset_offset(&mut self) -> io::Result<()> {
         self.offset = 0;
         self.hash = String::new();
-        snapshot::save(
-            &self.snapshot_path,
-            &Snapshot::new(self.state.clone(), self.offset, self.hash.clone()),
-        )
+        self.save_snapshot()
+    }
+
+    fn set_snapshot_policy(&mut self, policy: SnapshotPolicy) {
+        self.snapshot_policy = policy;
+        self.restart_save_interval();
+    }
+
+    fn flush_snapshot(&mut self) -> io::Result<()> {
+        View::flush(self)
+    }
+
+    fn state_json(&self) -> io::Result<serde_json::Value> {
+        Ok(serde_json::to_value(&self.state)?)
     }
 
     fn view_name(&self) -> &str {
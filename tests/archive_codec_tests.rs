@@ -0,0 +1,193 @@
+mod common;
+
+use common::{append_n, counter_reducer};
+use eventfold::{ArchiveCompression, Codec, EventLog, EventReader, Retention, list_frames};
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+fn plain_log(dir: &std::path::Path) -> EventLog {
+    EventLog::builder(dir)
+        .archive_codec(Codec::None)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap()
+}
+
+#[test]
+fn test_plain_archive_is_ndjson() {
+    let dir = tempdir().unwrap();
+    let mut log = plain_log(dir.path());
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+
+    assert!(log.archive_path().ends_with("archive.jsonl"));
+    assert!(!dir.path().join("archive.jsonl.zst").exists());
+    let archived = std::fs::read_to_string(log.archive_path()).unwrap();
+    assert_eq!(archived.lines().count(), 5);
+    assert_eq!(log.read_full().unwrap().count(), 5);
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 5);
+}
+
+#[test]
+fn test_plain_archive_reads_as_one_frame() {
+    let dir = tempdir().unwrap();
+    let mut log = plain_log(dir.path());
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+
+    let frames = list_frames(log.archive_path()).unwrap();
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].event_count, 5);
+    assert_eq!(log.reader().read_archive_frame(0).unwrap().count(), 5);
+    assert!(log.verify().unwrap().is_ok());
+}
+
+#[test]
+fn test_reopen_keeps_existing_codec() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = plain_log(dir.path());
+        append_n(&mut log, 3);
+        log.rotate().unwrap();
+    }
+
+    let mut log = EventLog::open(dir.path()).unwrap();
+    assert!(log.archive_path().ends_with("archive.jsonl"));
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+    assert_eq!(log.read_full().unwrap().count(), 5);
+    assert_eq!(EventReader::new(dir.path()).read_full().unwrap().count(), 5);
+
+    let info = EventLog::inspect(dir.path()).unwrap();
+    assert_eq!(info.compression, Some(ArchiveCompression::None));
+    assert_eq!(info.event_count_estimate, 5);
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_codec_mismatch_is_rejected() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        append_n(&mut log, 1);
+        log.rotate().unwrap();
+    }
+
+    let err = EventLog::builder(dir.path())
+        .archive_codec(Codec::None)
+        .open()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_plain_codec_rejects_frame_options() {
+    let dir = tempdir().unwrap();
+    let err = EventLog::builder(dir.path())
+        .archive_codec(Codec::None)
+        .archive_retention(Retention::MaxFrames(2))
+        .open()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+/// Rotate twice with `codec`, then read the archive back whole, by frame,
+/// after reopening, and after retention drops a frame.
+#[cfg(any(feature = "gzip", feature = "lz4"))]
+fn check_framed_codec(codec: Codec, file_name: &str, compression: ArchiveCompression) {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .archive_codec(codec)
+            .view::<u64>("counter", counter_reducer)
+            .open()
+            .unwrap();
+        append_n(&mut log, 3);
+        log.rotate().unwrap();
+        append_n(&mut log, 2);
+        log.rotate().unwrap();
+
+        assert!(log.archive_path().ends_with(file_name));
+        assert!(!dir.path().join("archive.jsonl.zst").exists());
+        assert_eq!(log.read_full().unwrap().count(), 5);
+        assert_eq!(*log.view::<u64>("counter").unwrap(), 5);
+
+        let frames = list_frames(log.archive_path()).unwrap();
+        assert_eq!(frames.iter().map(|f| f.event_count).collect::<Vec<_>>(), [3, 2]);
+        assert_eq!(log.reader().read_archive_frame(1).unwrap().count(), 2);
+        assert!(log.verify().unwrap().is_ok());
+    }
+
+    let info = EventLog::inspect(dir.path()).unwrap();
+    assert_eq!(info.compression, Some(compression));
+    assert_eq!(info.event_count_estimate, 5);
+    assert_eq!(EventReader::new(dir.path()).read_full().unwrap().count(), 5);
+
+    let mut log = EventLog::builder(dir.path())
+        .archive_retention(Retention::MaxFrames(2))
+        .open()
+        .unwrap();
+    assert!(log.archive_path().ends_with(file_name));
+    append_n(&mut log, 1);
+    log.rotate().unwrap();
+    assert_eq!(list_frames(log.archive_path()).unwrap().len(), 2);
+    assert_eq!(log.read_full().unwrap().count(), 3);
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_gzip_archive_round_trips() {
+    check_framed_codec(Codec::Gzip, "archive.jsonl.gz", ArchiveCompression::Gzip);
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_gzip_archive_is_standard_gzip() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .archive_codec(Codec::Gzip)
+        .open()
+        .unwrap();
+    append_n(&mut log, 1);
+    log.rotate().unwrap();
+    let bytes = std::fs::read(log.archive_path()).unwrap();
+    assert_eq!(bytes[..2], [0x1f, 0x8b]);
+}
+
+#[test]
+#[cfg(feature = "lz4")]
+fn test_lz4_archive_round_trips() {
+    check_framed_codec(Codec::Lz4, "archive.jsonl.lz4", ArchiveCompression::Lz4);
+}
+
+#[test]
+#[cfg(feature = "lz4")]
+fn test_lz4_archive_is_standard_lz4() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .archive_codec(Codec::Lz4)
+        .open()
+        .unwrap();
+    append_n(&mut log, 1);
+    log.rotate().unwrap();
+    let bytes = std::fs::read(log.archive_path()).unwrap();
+    assert_eq!(bytes[..4], [0x04, 0x22, 0x4d, 0x18]);
+}
+
+#[test]
+#[cfg(not(feature = "gzip"))]
+fn test_gzip_without_feature_is_unsupported() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .archive_codec(Codec::Gzip)
+        .open()
+        .unwrap();
+    append_n(&mut log, 1);
+    let err = log.rotate().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+    assert!(err.to_string().contains("`gzip` feature"));
+}
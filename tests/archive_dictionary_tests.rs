@@ -0,0 +1,123 @@
+mod common;
+
+use eventfold::{Event, EventLog};
+use serde_json::json;
+use std::fs;
+use tempfile::tempdir;
+
+fn sample_event(i: u64) -> Event {
+    let mut event = Event::new(
+        "sensor_reading",
+        json!({"sensor": format!("sensor-{}", i % 5), "value": i % 97, "unit": "celsius"}),
+    );
+    event.ts = 1_700_000_000 + i;
+    event
+}
+
+fn samples() -> Vec<String> {
+    (0..2000)
+        .map(|i| serde_json::to_string(&sample_event(i)).unwrap())
+        .collect()
+}
+
+fn history(log: &EventLog) -> Vec<Event> {
+    log.read_full().unwrap().map(|r| r.unwrap().0).collect()
+}
+
+fn fill_and_rotate(log: &mut EventLog, start: u64, n: u64) {
+    for i in start..start + n {
+        log.append(&sample_event(i)).unwrap();
+    }
+    log.rotate().unwrap();
+}
+
+#[test]
+fn test_dictionary_frames_read_back_identically() {
+    let dir = tempdir().unwrap();
+    let dict = EventLog::train_archive_dictionary(&samples()).unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .archive_dictionary(dict)
+        .open()
+        .unwrap();
+
+    fill_and_rotate(&mut log, 0, 50);
+
+    let expected: Vec<Event> = (0..50).map(sample_event).collect();
+    assert_eq!(history(&log), expected);
+}
+
+#[test]
+fn test_dictionary_frames_not_larger() {
+    let plain_dir = tempdir().unwrap();
+    let dict_dir = tempdir().unwrap();
+    let dict = EventLog::train_archive_dictionary(&samples()).unwrap();
+
+    let mut plain = EventLog::open(plain_dir.path()).unwrap();
+    let mut with_dict = EventLog::builder(dict_dir.path())
+        .archive_dictionary(dict)
+        .open()
+        .unwrap();
+    fill_and_rotate(&mut plain, 0, 20);
+    fill_and_rotate(&mut with_dict, 0, 20);
+
+    let plain_size = fs::metadata(plain.archive_path()).unwrap().len();
+    let dict_size = fs::metadata(with_dict.archive_path()).unwrap().len();
+    assert!(
+        dict_size <= plain_size,
+        "dictionary frame {dict_size} > plain frame {plain_size}"
+    );
+}
+
+#[test]
+fn test_dictionary_persisted_for_reopen_without_it() {
+    let dir = tempdir().unwrap();
+    let dict = EventLog::train_archive_dictionary(&samples()).unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .archive_dictionary(dict)
+            .open()
+            .unwrap();
+        fill_and_rotate(&mut log, 0, 10);
+    }
+
+    let log = EventLog::open(dir.path()).unwrap();
+    assert!(dir.path().join("archive.manifest.json").exists());
+    assert_eq!(history(&log).len(), 10);
+}
+
+#[test]
+fn test_mixed_plain_and_dictionary_frames() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        fill_and_rotate(&mut log, 0, 10);
+    }
+    let dict = EventLog::train_archive_dictionary(&samples()).unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .archive_dictionary(dict)
+        .open()
+        .unwrap();
+    fill_and_rotate(&mut log, 10, 10);
+    log.append(&sample_event(20)).unwrap();
+
+    let expected: Vec<Event> = (0..21).map(sample_event).collect();
+    assert_eq!(history(&log), expected);
+}
+
+#[test]
+fn test_raw_dictionary_rejected_on_open() {
+    let dir = tempdir().unwrap();
+
+    let err = EventLog::builder(dir.path())
+        .archive_dictionary(b"not a trained dictionary".to_vec())
+        .open()
+        .unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_training_with_too_few_samples_fails() {
+    let result = EventLog::train_archive_dictionary(&["{}"]);
+    assert!(result.is_err());
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::{append_n, dummy_event};
+use eventfold::{list_frames, read_frame, EventLog, SegmentId};
+use std::io;
+use tempfile::tempdir;
+
+/// A log whose archive holds frames of 2, 3, and 1 events.
+fn rotated_log(dir: &std::path::Path) -> EventLog {
+    let mut log = EventLog::open(dir).unwrap();
+    for (frame, size) in [2, 3, 1].into_iter().enumerate() {
+        for i in 0..size {
+            log.append(&dummy_event(&format!("f{frame}_e{i}"))).unwrap();
+        }
+        log.rotate().unwrap();
+    }
+    append_n(&mut log, 1);
+    log
+}
+
+#[test]
+fn test_list_frames_ranges_and_counts() {
+    let dir = tempdir().unwrap();
+    let log = rotated_log(dir.path());
+
+    let frames = list_frames(log.archive_path()).unwrap();
+    let counts: Vec<u64> = frames.iter().map(|f| f.event_count).collect();
+    assert_eq!(counts, [2, 3, 1]);
+
+    // Ranges are contiguous and cover the whole file.
+    assert_eq!(frames[0].compressed.start, 0);
+    for pair in frames.windows(2) {
+        assert_eq!(pair[0].compressed.end, pair[1].compressed.start);
+    }
+    let archive_len = std::fs::metadata(log.archive_path()).unwrap().len();
+    assert_eq!(frames[2].compressed.end, archive_len);
+
+    assert_eq!(log.reader().archive_frames().unwrap(), frames);
+}
+
+#[test]
+fn test_read_archive_frame_reads_only_that_frame() {
+    let dir = tempdir().unwrap();
+    let log = rotated_log(dir.path());
+    let reader = log.reader();
+
+    let types: Vec<String> = reader
+        .read_archive_frame(1)
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+    assert_eq!(types, ["f1_e0", "f1_e1", "f1_e2"]);
+
+    let raw = read_frame(log.archive_path(), 2).unwrap();
+    assert_eq!(String::from_utf8(raw).unwrap().lines().count(), 1);
+
+    // Hashes match the ones read_full reports for the same events.
+    let full: Vec<_> = reader.read_full().unwrap().map(|r| r.unwrap()).collect();
+    let frame: Vec<_> = reader
+        .read_archive_frame(1)
+        .unwrap()
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(frame, full[2..5]);
+}
+
+#[test]
+fn test_read_archive_frame_out_of_range() {
+    let dir = tempdir().unwrap();
+    let log = rotated_log(dir.path());
+
+    let err = log.reader().read_archive_frame(3).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    let empty = tempdir().unwrap();
+    let log = EventLog::open(empty.path()).unwrap();
+    assert!(log.reader().archive_frames().unwrap().is_empty());
+    let err = read_frame(log.archive_path(), 0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_read_full_with_segment_tags_each_event() {
+    let dir = tempdir().unwrap();
+    let log = rotated_log(dir.path());
+    let reader = log.reader();
+
+    let tagged: Vec<_> = reader
+        .read_full_with_segment()
+        .unwrap()
+        .map(|r| r.unwrap())
+        .collect();
+    let segments: Vec<SegmentId> = tagged.iter().map(|(_, _, segment)| *segment).collect();
+    assert_eq!(
+        segments,
+        [
+            SegmentId::Archive(0),
+            SegmentId::Archive(0),
+            SegmentId::Archive(1),
+            SegmentId::Archive(1),
+            SegmentId::Archive(1),
+            SegmentId::Archive(2),
+            SegmentId::Active,
+        ]
+    );
+
+    // Events and hashes are exactly those of read_full.
+    let full: Vec<_> = reader.read_full().unwrap().map(|r| r.unwrap()).collect();
+    let untagged: Vec<_> = tagged
+        .into_iter()
+        .map(|(event, hash, _)| (event, hash))
+        .collect();
+    assert_eq!(untagged, full);
+
+    let empty = tempdir().unwrap();
+    let log = EventLog::open(empty.path()).unwrap();
+    assert_eq!(log.reader().read_full_with_segment().unwrap().count(), 0);
+}
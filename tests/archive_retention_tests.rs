@@ -0,0 +1,90 @@
+mod common;
+
+use common::{counter_reducer, dummy_event, event_types};
+use eventfold::{list_frames, EventLog, Retention};
+use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::tempdir;
+
+#[test]
+fn test_max_frames_keeps_newest_rotations() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .archive_retention(Retention::MaxFrames(3))
+        .open()
+        .unwrap();
+
+    let mut sizes = Vec::new();
+    for i in 0..10 {
+        log.append(&dummy_event(&format!("event_{i}"))).unwrap();
+        log.rotate().unwrap();
+        sizes.push(fs::metadata(log.archive_path()).unwrap().len());
+    }
+
+    assert_eq!(list_frames(log.archive_path()).unwrap().len(), 3);
+    assert!(
+        sizes[9] < sizes[2] * 2,
+        "archive should stop growing: {sizes:?}"
+    );
+    assert_eq!(event_types(&log), ["event_7", "event_8", "event_9"]);
+
+    // The view already folded the purged events and keeps them.
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 10);
+}
+
+#[test]
+fn test_max_bytes_bounds_archive_size() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .archive_retention(Retention::MaxBytes(300))
+        .open()
+        .unwrap();
+
+    for i in 0..20 {
+        log.append(&dummy_event(&format!("event_{i}"))).unwrap();
+        log.rotate().unwrap();
+        assert!(fs::metadata(log.archive_path()).unwrap().len() <= 300);
+    }
+
+    let frames = list_frames(log.archive_path()).unwrap();
+    assert!(!frames.is_empty() && frames.len() < 20);
+    let types = event_types(&log);
+    assert_eq!(types.last().unwrap(), "event_19");
+    assert_eq!(types.len(), frames.len());
+}
+
+#[test]
+fn test_max_age_drops_old_frames() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .archive_retention(Retention::MaxAge(Duration::from_secs(3600)))
+        .open()
+        .unwrap();
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    for (name, age) in [("ancient", 86_400), ("old", 7_200), ("recent", 60)] {
+        let mut event = dummy_event(name);
+        event.ts = now - age;
+        log.append(&event).unwrap();
+        log.rotate().unwrap();
+    }
+    log.append(&dummy_event("active")).unwrap();
+
+    assert_eq!(event_types(&log), ["recent", "active"]);
+}
+
+#[test]
+fn test_no_retention_keeps_everything() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    for i in 0..5 {
+        log.append(&dummy_event(&format!("event_{i}"))).unwrap();
+        log.rotate().unwrap();
+    }
+    assert_eq!(list_frames(log.archive_path()).unwrap().len(), 5);
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::{AsyncEventReader, EventWriter};
+use futures_core::Stream;
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+use tempfile::tempdir;
+
+/// Wakes the test thread that is blocked on a future.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// A minimal executor: poll `future`, parking the thread between wakes.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+#[test]
+fn test_next_events_returns_existing_events() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("a")).unwrap();
+    let end = writer.append(&dummy_event("b")).unwrap().end_offset;
+
+    let reader = AsyncEventReader::new(writer.reader()).unwrap();
+    let events = block_on(reader.next_events(0)).unwrap();
+    let types: Vec<&str> = events.iter().map(|e| e.0.event_type.as_str()).collect();
+    assert_eq!(types, ["a", "b"]);
+    assert_eq!(events[1].1, end);
+}
+
+#[test]
+fn test_next_events_waits_for_append() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    let reader = AsyncEventReader::new(writer.reader()).unwrap();
+
+    let appender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        writer.append(&dummy_event("late")).unwrap();
+    });
+
+    let events = block_on(reader.next_events(0)).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0.event_type, "late");
+    appender.join().unwrap();
+}
+
+#[test]
+fn test_event_stream_skips_partial_line() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("a")).unwrap();
+    let log_path = writer.log_path().to_path_buf();
+    let reader = AsyncEventReader::new(writer.reader()).unwrap();
+    let mut stream = reader.events(0);
+
+    let (event, offset, _) = block_on(stream.next()).unwrap();
+    assert_eq!(event.event_type, "a");
+    assert_eq!(stream.offset(), offset);
+
+    let appender = thread::spawn(move || {
+        use std::io::Write;
+        // A half-written line must not be yielded until it is complete.
+        let line = serde_json::to_string(&dummy_event("b")).unwrap();
+        let (head, tail) = line.split_at(line.len() / 2);
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&log_path)
+            .unwrap();
+        file.write_all(head.as_bytes()).unwrap();
+        file.flush().unwrap();
+        thread::sleep(Duration::from_millis(100));
+        file.write_all(tail.as_bytes()).unwrap();
+        file.write_all(b"\n").unwrap();
+    });
+
+    let (event, _, _) = block_on(stream.next()).unwrap();
+    assert_eq!(event.event_type, "b");
+    appender.join().unwrap();
+}
+
+#[test]
+fn test_event_stream_implements_stream() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("a")).unwrap();
+    let reader = AsyncEventReader::new(writer.reader()).unwrap();
+    let mut stream = reader.events(0);
+    let mut next = || block_on(future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)));
+
+    let (event, _, _) = next().unwrap().unwrap();
+    assert_eq!(event.event_type, "a");
+
+    let appender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        writer.append(&dummy_event("b")).unwrap();
+    });
+    let (event, offset, _) = next().unwrap().unwrap();
+    assert_eq!(event.event_type, "b");
+    appender.join().unwrap();
+    assert_eq!(stream.offset(), offset);
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::{EventLog, IdScheme};
+use tempfile::tempdir;
+
+#[test]
+fn test_auto_id_written_and_survives_reopen() {
+    let dir = tempdir().unwrap();
+    let mut ids = Vec::new();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .auto_id(IdScheme::Uuidv7)
+            .open()
+            .unwrap();
+        for i in 0..3 {
+            let result = log.append(&dummy_event(&format!("e{i}"))).unwrap();
+            ids.push(result.assigned_id.unwrap());
+        }
+        let batch = log.append_batch(&[dummy_event("e3")]).unwrap();
+        ids.push(batch[0].assigned_id.clone().unwrap());
+    }
+
+    let log = EventLog::open(dir.path()).unwrap();
+    let stored: Vec<String> = log
+        .read_full()
+        .unwrap()
+        .map(|r| r.unwrap().0.id.unwrap())
+        .collect();
+    assert_eq!(stored, ids);
+    for id in &ids {
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "7");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+    }
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 4);
+}
+
+#[test]
+fn test_auto_id_keeps_existing_id() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .auto_id(IdScheme::Ulid)
+        .open()
+        .unwrap();
+
+    let result = log.append(&dummy_event("a").with_id("mine")).unwrap();
+    assert_eq!(result.assigned_id, None);
+    let (event, _, _) = log.read_from(0).unwrap().next().unwrap().unwrap();
+    assert_eq!(event.id.as_deref(), Some("mine"));
+}
+
+#[test]
+fn test_ulid_format() {
+    let id = IdScheme::Ulid.generate().unwrap();
+    assert_eq!(id.len(), 26);
+    assert!(id
+        .bytes()
+        .all(|b| b"0123456789ABCDEFGHJKMNPQRSTVWXYZ".contains(&b)));
+    // 48-bit timestamps never set the top two bits of the first character.
+    assert!(id.as_bytes()[0] <= b'7');
+    assert_ne!(id, IdScheme::Ulid.generate().unwrap());
+}
+
+#[test]
+fn test_no_auto_id_by_default() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let result = log.append(&dummy_event("a")).unwrap();
+    assert_eq!(result.assigned_id, None);
+    let (event, _, _) = log.read_from(0).unwrap().next().unwrap().unwrap();
+    assert_eq!(event.id, None);
+}
+
+#[test]
+fn test_append_returning_includes_assigned_id() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .auto_id(IdScheme::Uuidv7)
+        .open()
+        .unwrap();
+
+    let (stored, result) = log.append_returning(dummy_event("a")).unwrap();
+    assert_eq!(stored.id, result.assigned_id);
+    assert!(stored.id.is_some());
+    let (read, _, _) = log.read_from(0).unwrap().next().unwrap().unwrap();
+    assert_eq!(read, stored);
+
+    let (stored, result) = log
+        .append_returning(dummy_event("b").with_id("mine"))
+        .unwrap();
+    assert_eq!(stored.id.as_deref(), Some("mine"));
+    assert_eq!(result.assigned_id, None);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::{Event, EventLog, EventWriter, SyncPolicy};
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn events(n: usize) -> Vec<Event> {
+    (0..n).map(|i| dummy_event(&format!("event_{i}"))).collect()
+}
+
+#[test]
+fn test_append_batch_matches_individual_appends() {
+    let batch_dir = tempdir().unwrap();
+    let single_dir = tempdir().unwrap();
+    let mut batch_writer = EventWriter::open(batch_dir.path()).unwrap();
+    let mut single_writer = EventWriter::open(single_dir.path()).unwrap();
+
+    // Start both from a non-zero offset.
+    batch_writer.append(&dummy_event("first")).unwrap();
+    single_writer.append(&dummy_event("first")).unwrap();
+
+    let events = events(10);
+    let batch = batch_writer.append_batch(&events).unwrap();
+    let single: Vec<_> = events
+        .iter()
+        .map(|e| single_writer.append(e).unwrap())
+        .collect();
+
+    assert_eq!(batch, single);
+    assert_eq!(
+        std::fs::read(batch_writer.log_path()).unwrap(),
+        std::fs::read(single_writer.log_path()).unwrap()
+    );
+}
+
+#[test]
+fn test_append_batch_syncs_once() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .sync_policy(SyncPolicy::Interval(Duration::from_secs(3600)))
+        .open()
+        .unwrap();
+
+    log.append(&dummy_event("a")).unwrap();
+    assert_eq!(log.writer().unsynced_events(), 1);
+    log.append_batch(&events(5)).unwrap();
+    assert_eq!(log.writer().unsynced_events(), 0);
+
+    assert!(log.append_batch(&[]).unwrap().is_empty());
+    assert_eq!(log.read_from(0).unwrap().count(), 6);
+}
+
+#[test]
+fn test_append_batch_rotates_once_after_batch() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .max_log_size(100)
+        .open()
+        .unwrap();
+
+    // Far more than 100 bytes, but the whole batch lands before rotating.
+    let results = log.append_batch(&events(10)).unwrap();
+    assert_eq!(results.len(), 10);
+    assert_eq!(log.active_log_size().unwrap(), 0);
+
+    let types: Vec<String> = log
+        .read_full()
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+    let expected: Vec<String> = (0..10).map(|i| format!("event_{i}")).collect();
+    assert_eq!(types, expected);
+}
@@ -85,6 +85,102 @@ fn test_view_accessor_nonexistent() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_export_view_state_and_json() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .view::<TodoState>("todos", todo_reducer)
+        .log_view("feed", |event, out| writeln!(out, "{}", event.event_type))
+        .open()
+        .unwrap();
+    log.append(&Event::new("todo_added", json!({"text": "milk"})))
+        .unwrap();
+    log.refresh_all().unwrap();
+
+    let state: TodoState = log.export_view_state("todos").unwrap();
+    assert_eq!(&state, log.view::<TodoState>("todos").unwrap());
+
+    // Only the state is exported, with no snapshot envelope around it.
+    let json = log.export_view_json("todos").unwrap();
+    assert!(json.contains('\n'), "pretty-printed: {json}");
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value, serde_json::to_value(&state).unwrap());
+    assert!(value.get("offset").is_none());
+
+    let kind = |name| log.export_view_json(name).unwrap_err().kind();
+    assert_eq!(kind("missing"), std::io::ErrorKind::NotFound);
+    assert_eq!(kind("feed"), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_compact_snapshots_have_no_newlines() {
+    let dir = tempdir().unwrap();
+    let open = |pretty| {
+        EventLog::builder(dir.path())
+            .pretty_snapshots(pretty)
+            .view::<TodoState>("todos", todo_reducer)
+            .open()
+            .unwrap()
+    };
+    let snapshot_path = dir.path().join("views/todos.snapshot.json");
+    {
+        let mut log = open(false);
+        for i in 0..50 {
+            log.append(&Event::new("todo_added", json!({"text": format!("item {i}")})))
+                .unwrap();
+        }
+        log.refresh_all().unwrap();
+    }
+    let compact = std::fs::read_to_string(&snapshot_path).unwrap();
+    assert!(!compact.contains('\n'), "compact snapshot: {compact}");
+
+    // Either format loads, and the setting applies from the next save.
+    let mut log = open(true);
+    log.append(&Event::new("todo_added", json!({"text": "last"})))
+        .unwrap();
+    log.refresh_all().unwrap();
+    assert_eq!(log.view::<TodoState>("todos").unwrap().items.len(), 51);
+    let pretty = std::fs::read_to_string(&snapshot_path).unwrap();
+    assert!(pretty.contains('\n'));
+    assert!(pretty.len() > compact.len());
+}
+
+#[test]
+fn test_views_dir_name_and_path_move_the_views_tree() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .views_dir_name("projections")
+            .view::<u64>("counter", counter_reducer)
+            .log_view("feed", |event, out| writeln!(out, "{}", event.event_type))
+            .open()
+            .unwrap();
+        append_n(&mut log, 2);
+        log.refresh_all().unwrap();
+    }
+    let projections = dir.path().join("projections");
+    assert!(projections.join("counter.snapshot.json").is_file());
+    assert!(projections.join("feed.output.jsonl").is_file());
+    assert!(projections.join("feed.checkpoint.json").is_file());
+    assert!(!dir.path().join("views/counter.snapshot.json").exists());
+
+    // views_path overrides the name, and a read-only log saves there.
+    let external = tempdir().unwrap();
+    let views = external.path().join("views");
+    let mut log = EventLog::builder(dir.path())
+        .read_only(true)
+        .views_dir_name("ignored")
+        .views_path(&views)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 2);
+    assert_eq!(log.views_dir(), views);
+    assert!(views.join("counter.snapshot.json").is_file());
+    assert!(!dir.path().join("ignored").exists());
+}
+
 #[test]
 fn test_rotate_uses_registry() {
     let dir = tempdir().unwrap();
@@ -265,3 +361,73 @@ fn test_builder_chaining() {
         .open()
         .unwrap();
 }
+
+#[test]
+fn test_duplicate_view_names_rejected() {
+    let dir = tempdir().unwrap();
+    let err = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .view::<TodoState>("counter", todo_reducer)
+        .open()
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("counter"));
+}
+
+#[test]
+fn test_prune_orphan_snapshots() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .view::<u64>("counter", counter_reducer)
+            .view::<TodoState>("todos", todo_reducer)
+            .open()
+            .unwrap();
+        append_n(&mut log, 3);
+        log.refresh_all().unwrap();
+    }
+
+    let log = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    let views_dir = log.views_dir().to_path_buf();
+    std::fs::write(views_dir.join("stale.snapshot.json.tmp"), "{").unwrap();
+    std::fs::write(views_dir.join("notes.txt"), "keep me").unwrap();
+
+    let pruned = log.prune_orphan_snapshots().unwrap();
+    assert_eq!(
+        pruned,
+        [
+            views_dir.join("stale.snapshot.json.tmp"),
+            views_dir.join("todos.snapshot.json"),
+        ]
+    );
+    assert!(views_dir.join("counter.snapshot.json").exists());
+    assert!(views_dir.join("notes.txt").exists());
+    assert!(log.prune_orphan_snapshots().unwrap().is_empty());
+}
+
+#[test]
+fn test_rebuild_all_replays_with_new_reducer() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .view::<u64>("counter", counter_reducer)
+            .open()
+            .unwrap();
+        append_n(&mut log, 3);
+        log.refresh_all().unwrap();
+    }
+
+    // The reducer changed, but the snapshot still holds the old state.
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("counter", |state, _event| state + 2)
+        .open()
+        .unwrap();
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 3);
+
+    log.rebuild_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 6);
+}
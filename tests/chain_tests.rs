@@ -0,0 +1,168 @@
+mod common;
+
+use common::{append_n, dummy_event};
+use eventfold::EventLog;
+use serde_json::json;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn open_chained(dir: &Path) -> EventLog {
+    EventLog::builder(dir).hash_chain(true).open().unwrap()
+}
+
+fn rewrite_lines(log: &EventLog, f: impl FnOnce(&mut Vec<String>)) {
+    let contents = fs::read_to_string(log.log_path()).unwrap();
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    f(&mut lines);
+    fs::write(log.log_path(), lines.join("\n") + "\n").unwrap();
+}
+
+#[test]
+fn test_chain_survives_rotation_and_reopen() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = open_chained(dir.path());
+        append_n(&mut log, 3);
+        log.rotate().unwrap();
+    }
+    let mut log = open_chained(dir.path());
+    append_n(&mut log, 2);
+    log.append_batch(&[dummy_event("b1"), dummy_event("b2")])
+        .unwrap();
+
+    assert_eq!(log.verify_chain().unwrap(), Ok(()));
+    assert_eq!(log.read_full().unwrap().count(), 7);
+}
+
+#[test]
+fn test_deleted_event_breaks_chain() {
+    let dir = tempdir().unwrap();
+    let mut log = open_chained(dir.path());
+    append_n(&mut log, 4);
+    let ends: Vec<u64> = log.read_from(0).unwrap().map(|r| r.unwrap().1).collect();
+
+    rewrite_lines(&log, |lines| {
+        lines.remove(1);
+    });
+
+    let broken = log.verify_chain().unwrap().unwrap_err();
+    assert_eq!(broken.index, 1);
+    assert_eq!(broken.offset, ends[2] - (ends[1] - ends[0]));
+    assert!(broken.found.is_some());
+    assert_ne!(broken.found.as_deref(), Some(broken.expected.as_str()));
+}
+
+#[test]
+fn test_edited_event_breaks_next_link() {
+    let dir = tempdir().unwrap();
+    let mut log = open_chained(dir.path());
+    append_n(&mut log, 3);
+
+    rewrite_lines(&log, |lines| {
+        lines[0] = lines[0].replace("event_0", "forged_");
+    });
+
+    assert_eq!(log.verify_chain().unwrap().unwrap_err().index, 1);
+}
+
+#[test]
+fn test_unlinked_event_after_chain_start_breaks_chain() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = open_chained(dir.path());
+        append_n(&mut log, 2);
+    }
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        log.append(&dummy_event("unchained")).unwrap();
+    }
+
+    let log = open_chained(dir.path());
+    let broken = log.verify_chain().unwrap().unwrap_err();
+    assert_eq!(broken.index, 2);
+    assert_eq!(broken.found, None);
+}
+
+#[test]
+fn test_chain_can_start_on_existing_log() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        append_n(&mut log, 2);
+    }
+    let mut log = open_chained(dir.path());
+    append_n(&mut log, 2);
+
+    assert_eq!(log.verify_chain().unwrap(), Ok(()));
+}
+
+#[test]
+fn test_chain_rejects_non_object_meta() {
+    let dir = tempdir().unwrap();
+    let mut log = open_chained(dir.path());
+    let err = log
+        .append(&dummy_event("a").with_meta(json!("not an object")))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_append_returning_includes_chain_link() {
+    let dir = tempdir().unwrap();
+    let mut log = open_chained(dir.path());
+    let first = log.append(&dummy_event("a")).unwrap();
+
+    let (stored, result) = log.writer_mut().append_returning(dummy_event("b")).unwrap();
+    assert_eq!(
+        stored.meta.as_ref().unwrap()["prev"],
+        json!(first.line_hash)
+    );
+    let (read, _, _) = log
+        .read_from(result.start_offset)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(read, stored);
+}
+
+#[test]
+fn test_verify_chain_reports_no_break_after_compact() {
+    let dir = tempdir().unwrap();
+    let mut log = open_chained(dir.path());
+    append_n(&mut log, 4);
+
+    assert_eq!(log.compact(|e| e.event_type != "event_1").unwrap(), 1);
+    let reader = log.reader();
+    assert_eq!(reader.verify_chain().unwrap(), Ok(()));
+
+    // Tampering after compaction is still caught.
+    rewrite_lines(&log, |lines| lines.swap(1, 2));
+    assert!(log.reader().verify_chain().unwrap().is_err());
+}
+
+#[test]
+fn test_compact_relinks_kept_events() {
+    let dir = tempdir().unwrap();
+    let mut log = open_chained(dir.path());
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+    append_n(&mut log, 5);
+
+    let dropped = log
+        .compact(|e| !["event_0", "event_2", "event_3"].contains(&e.event_type.as_str()))
+        .unwrap();
+    assert_eq!(dropped, 3);
+    assert_eq!(log.verify_chain().unwrap(), Ok(()));
+
+    log.append(&dummy_event("after")).unwrap();
+    assert_eq!(log.verify_chain().unwrap(), Ok(()));
+    let types: Vec<_> = log
+        .read_full()
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+    assert_eq!(types, ["event_0", "event_1", "event_1", "event_4", "after"]);
+}
@@ -2,6 +2,7 @@
 
 use eventfold::Event;
 use eventfold::EventLog;
+use eventfold::EventWriter;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
@@ -18,6 +19,23 @@ pub fn append_n(log: &mut EventLog, n: usize) {
     }
 }
 
+pub fn event_types(log: &EventLog) -> Vec<String> {
+    log.read_full()
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect()
+}
+
+pub fn append_at(writer: &mut EventWriter, event_type: &str, ts: u64) {
+    let mut event = dummy_event(event_type);
+    event.ts = ts;
+    writer.append(&event).unwrap();
+}
+
+pub fn full_history(log: &EventLog) -> Vec<(Event, String)> {
+    log.read_full().unwrap().map(|r| r.unwrap()).collect()
+}
+
 pub fn counter_reducer(state: u64, _event: &Event) -> u64 {
     state + 1
 }
@@ -0,0 +1,83 @@
+mod common;
+
+use common::{append_n, counter_reducer, dummy_event, event_types};
+#[cfg(feature = "std-fs")]
+use eventfold::EventWriter;
+use eventfold::{EventLog, EventReader};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_compact_keeps_matching_events_in_order() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 6);
+
+    let dropped = log
+        .compact(|e| e.event_type != "event_1" && e.event_type != "event_4")
+        .unwrap();
+
+    assert_eq!(dropped, 2);
+    assert_eq!(
+        event_types(&log),
+        ["event_0", "event_2", "event_3", "event_5"]
+    );
+    assert!(!dir.path().join("app.jsonl.tmp").exists());
+}
+
+#[test]
+fn test_compact_without_drops_leaves_log_untouched() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+    let before = fs::read(log.log_path()).unwrap();
+
+    assert_eq!(log.compact(|_| true).unwrap(), 0);
+    assert_eq!(fs::read(log.log_path()).unwrap(), before);
+}
+
+#[test]
+fn test_compact_rebuilds_registered_views() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    append_n(&mut log, 5);
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 5);
+
+    log.compact(|e| e.event_type == "event_0").unwrap();
+
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 1);
+    log.append(&dummy_event("after")).unwrap();
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 2);
+}
+
+#[test]
+fn test_appends_after_compact_land_in_new_log() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 4);
+
+    log.compact(|e| e.event_type == "event_3").unwrap();
+    log.append(&dummy_event("after")).unwrap();
+
+    assert_eq!(event_types(&log), ["event_3", "after"]);
+    let reader = EventReader::new(dir.path());
+    assert_eq!(reader.read_from(0).unwrap().count(), 2);
+}
+
+#[test]
+#[cfg(feature = "std-fs")]
+fn test_compact_keeps_writer_lock() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 2);
+
+    log.compact(|e| e.event_type == "event_0").unwrap();
+
+    let err = EventWriter::open(dir.path()).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+}
@@ -1,7 +1,7 @@
 mod common;
 
 use common::{counter_reducer, dummy_event};
-use eventfold::{ConditionalAppendError, Event, EventLog, EventWriter};
+use eventfold::{ConditionalAppendError, Event, EventLog, EventWriter, LockMode};
 use serde_json::json;
 use tempfile::tempdir;
 
@@ -309,3 +309,47 @@ fn test_eventlog_append_if_auto_rotation() {
     assert_eq!(r1.start_offset, 0);
     assert!(r1.end_offset > 0);
 }
+
+#[test]
+fn test_append_with_retry_shared_writers() {
+    let dir = tempdir().unwrap();
+    let handles: Vec<_> = (0..4)
+        .map(|w| {
+            let path = dir.path().to_path_buf();
+            std::thread::spawn(move || {
+                let mut writer = EventWriter::open_with_lock(&path, LockMode::None).unwrap();
+                for i in 0..10 {
+                    let event = dummy_event(&format!("w{w}_{i}"));
+                    writer.append_with_retry(&event, 100).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let writer = EventWriter::open_with_lock(dir.path(), LockMode::None).unwrap();
+    let events: Vec<_> = writer
+        .reader()
+        .read_from(0)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(events.len(), 40);
+}
+
+#[test]
+fn test_append_with_retry_always_attempts_once() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    log.append(&dummy_event("first")).unwrap();
+
+    let result = log.append_with_retry(&dummy_event("second"), 0).unwrap();
+    assert_eq!(result.end_offset, log.active_log_size().unwrap());
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 2);
+}
@@ -45,6 +45,59 @@ fn test_crash_during_append() {
     log.append(&event).unwrap();
 }
 
+/// Reopening a writer drops the partial line, so the next append starts a
+/// line of its own instead of being glued onto the garbage.
+#[test]
+fn test_reopen_truncates_partial_tail() {
+    let dir = tempdir().unwrap();
+    let end = {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        append_n(&mut log, 2);
+        log.active_log_size().unwrap()
+    };
+    let log_path = dir.path().join("app.jsonl");
+    {
+        let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        write!(file, r#"{{"event_type":"partial","data":{{}}"#).unwrap();
+    }
+
+    // A read-only open leaves the file alone.
+    EventLog::open_readonly(dir.path()).unwrap();
+    assert!(fs::metadata(&log_path).unwrap().len() > end);
+
+    let mut log = EventLog::open(dir.path()).unwrap();
+    assert_eq!(log.active_log_size().unwrap(), end);
+    log.append(&dummy_event("after_crash")).unwrap();
+
+    let types: Vec<String> = log
+        .read_from(0)
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+    assert_eq!(types, ["event_0", "event_1", "after_crash"]);
+}
+
+/// A log holding nothing but a partial line: after reopening, an append
+/// yields exactly one readable event.
+#[test]
+fn test_append_after_reopen_on_partial_only_log() {
+    let dir = tempdir().unwrap();
+    drop(EventLog::open(dir.path()).unwrap());
+    fs::write(dir.path().join("app.jsonl"), r#"{"type":"lost","da"#).unwrap();
+
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let result = log.append(&dummy_event("valid")).unwrap();
+    assert_eq!(result.start_offset, 0);
+
+    let events: Vec<_> = log
+        .read_full()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0.event_type, "valid");
+}
+
 /// Crash during snapshot write leaves a .tmp file.
 /// The .tmp is ignored; state is rebuilt from events.
 #[test]
@@ -0,0 +1,72 @@
+mod common;
+
+use common::{append_n, dummy_event};
+use eventfold::EventLog;
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+#[test]
+fn test_cursor_defaults_to_zero_and_persists() {
+    let dir = tempdir().unwrap();
+    let log = EventLog::open(dir.path()).unwrap();
+    let cursor = log.cursor("mailer").unwrap();
+    assert_eq!(cursor.load().unwrap(), 0);
+    assert!(!cursor.path().exists());
+
+    cursor.save(7).unwrap();
+    assert_eq!(
+        cursor.path(),
+        dir.path().join("cursors").join("mailer.cursor")
+    );
+    drop(log);
+
+    let log = EventLog::open_readonly(dir.path()).unwrap();
+    let cursor = log.cursor("mailer").unwrap();
+    assert_eq!(cursor.name(), "mailer");
+    assert_eq!(cursor.load().unwrap(), 7);
+    cursor.save(8).unwrap();
+    assert_eq!(log.cursor("mailer").unwrap().load().unwrap(), 8);
+    assert_eq!(log.cursor("other").unwrap().load().unwrap(), 0);
+}
+
+#[test]
+fn test_cursor_resumes_across_rotation() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+    let cursor = log.cursor("consumer").unwrap();
+    for result in log.read_from_sequence(cursor.load().unwrap()).unwrap() {
+        let (_, sequence, _) = result.unwrap();
+        cursor.save(sequence + 1).unwrap();
+    }
+
+    log.rotate().unwrap();
+    log.append(&dummy_event("after")).unwrap();
+    let pending: Vec<String> = log
+        .read_from_sequence(cursor.load().unwrap())
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+    assert_eq!(pending, ["after"]);
+}
+
+#[test]
+fn test_cursor_rejects_bad_names_and_files() {
+    let dir = tempdir().unwrap();
+    let log = EventLog::open(dir.path()).unwrap();
+    for name in ["", "..", "a/b", "/abs"] {
+        let err = log.cursor(name).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput, "{name:?}");
+    }
+
+    let cursor = log.cursor("broken").unwrap();
+    std::fs::create_dir_all(dir.path().join("cursors")).unwrap();
+    std::fs::write(cursor.path(), "not a number").unwrap();
+    assert_eq!(cursor.load().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let log = EventLog::in_memory().open().unwrap();
+    assert_eq!(
+        log.cursor("mailer").unwrap_err().kind(),
+        ErrorKind::Unsupported
+    );
+}
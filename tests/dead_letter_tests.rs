@@ -0,0 +1,82 @@
+
+use eventfold::{Event, EventLog, View};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tempfile::tempdir;
+
+#[derive(Deserialize)]
+struct Deposit {
+    amount: u64,
+}
+
+fn deposit(data: Value) -> Event {
+    Event::new("deposit", data)
+}
+
+fn balance_reducer(state: u64, _event: &Event, deposit: Deposit) -> u64 {
+    state + deposit.amount
+}
+
+#[test]
+fn test_typed_view_dead_letters_mismatched_payload() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .typed_view::<u64, Deposit>("balance", balance_reducer)
+        .open()
+        .unwrap();
+    log.append(&deposit(json!({"amount": 10}))).unwrap();
+    log.append(&deposit(json!({"amount": "ten"}))).unwrap();
+    log.append(&deposit(json!({"amount": 5}))).unwrap();
+
+    log.refresh_all().unwrap();
+
+    assert_eq!(*log.view::<u64>("balance").unwrap(), 15);
+    let dead: Vec<_> = log.reader().read_dead_letters().unwrap().collect();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].0.data, json!({"amount": "ten"}));
+    assert!(dead[0].1.contains("balance"), "reason: {}", dead[0].1);
+    assert!(dead[0].1.contains("invalid type"), "reason: {}", dead[0].1);
+}
+
+#[test]
+fn test_dead_letters_not_rerecorded_on_incremental_refresh() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let mut view: View<u64> = View::new_typed("balance", balance_reducer, log.views_dir());
+    log.append(&deposit(json!({}))).unwrap();
+    view.refresh(&log.reader()).unwrap();
+
+    log.append(&deposit(json!({"amount": 3}))).unwrap();
+    let state = view.refresh(&log.reader()).unwrap();
+
+    assert_eq!(*state, 3);
+    assert_eq!(log.reader().read_dead_letters().unwrap().count(), 1);
+}
+
+#[test]
+fn test_read_dead_letters_empty_when_nothing_rejected() {
+    let dir = tempdir().unwrap();
+    let log = EventLog::open(dir.path()).unwrap();
+
+    assert_eq!(log.reader().read_dead_letters().unwrap().count(), 0);
+    assert!(!log.reader().dead_letter_path().exists());
+}
+
+#[test]
+fn test_read_dead_letters_skips_trailing_partial_line() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let mut view: View<u64> = View::new_typed("balance", balance_reducer, log.views_dir());
+    log.append(&deposit(json!(null))).unwrap();
+    view.refresh(&log.reader()).unwrap();
+    use std::io::Write;
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(log.reader().dead_letter_path())
+        .unwrap();
+    write!(file, r#"{{"reason":"partial"#).unwrap();
+
+    let dead: Vec<_> = log.reader().read_dead_letters().unwrap().collect();
+
+    assert_eq!(dead.len(), 1);
+}
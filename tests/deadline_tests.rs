@@ -0,0 +1,101 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::EventWriter;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+fn large_log(writer: &mut EventWriter, n: usize) {
+    let events: Vec<_> = (0..n)
+        .map(|i| {
+            let mut e = dummy_event("bulk");
+            e.data = json!({"i": i, "padding": "x".repeat(200)});
+            e
+        })
+        .collect();
+    writer.append_transaction(&events).unwrap();
+}
+
+#[test]
+fn test_deadline_reads_everything_when_time_allows() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    for _ in 0..5 {
+        writer.append(&dummy_event("a")).unwrap();
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let (events, offset) = writer.reader().read_until_deadline(0, deadline).unwrap();
+
+    assert_eq!(events.len(), 5);
+    assert_eq!(offset, writer.active_log_size().unwrap());
+}
+
+#[test]
+fn test_deadline_stops_early_with_resumable_offset() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    large_log(&mut writer, 20_000);
+    let reader = writer.reader();
+
+    let start = Instant::now();
+    let (first, offset) = reader
+        .read_until_deadline(0, start + Duration::from_millis(1))
+        .unwrap();
+
+    assert!(!first.is_empty());
+    assert!(first.len() < 20_000);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    // The offset falls on a line boundary: the line before it hashes cleanly
+    // and resuming parses without error.
+    assert!(reader.read_line_hash_before(offset).unwrap().is_some());
+    let rest: Vec<_> = reader
+        .read_from(offset)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(first.len() + rest.len(), 20_000);
+    assert_eq!(rest[0].0.data["i"], first.len());
+}
+
+#[test]
+fn test_deadline_loop_makes_progress_with_expired_deadline() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    for _ in 0..3 {
+        writer.append(&dummy_event("a")).unwrap();
+    }
+    let reader = writer.reader();
+
+    let mut offset = 0;
+    let mut batches = 0;
+    loop {
+        let (events, next) = reader.read_until_deadline(offset, Instant::now()).unwrap();
+        if events.is_empty() {
+            break;
+        }
+        assert_eq!(events.len(), 1);
+        offset = next;
+        batches += 1;
+    }
+
+    assert_eq!(batches, 3);
+    assert_eq!(offset, writer.active_log_size().unwrap());
+}
+
+#[test]
+fn test_deadline_at_end_returns_same_offset() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("a")).unwrap();
+    let end = writer.active_log_size().unwrap();
+
+    let (events, offset) = writer
+        .reader()
+        .read_until_deadline(end, Instant::now() + Duration::from_secs(1))
+        .unwrap();
+
+    assert!(events.is_empty());
+    assert_eq!(offset, end);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::EventWriter;
+use tempfile::tempdir;
+
+#[test]
+fn test_deferred_append_visible_before_wait() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    let (result, handle) = writer.append_deferred_sync(&dummy_event("a")).unwrap();
+
+    // Readable before anyone waits on the handle.
+    let events: Vec<_> = writer
+        .reader()
+        .read_from(0)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0.event_type, "a");
+    assert_eq!(events[0].1, result.end_offset);
+
+    handle.wait().unwrap();
+    assert!(handle.is_synced());
+}
+
+#[test]
+fn test_deferred_handles_all_complete() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    let handles: Vec<_> = (0..20)
+        .map(|i| {
+            writer
+                .append_deferred_sync(&dummy_event(&format!("event_{i}")))
+                .unwrap()
+                .1
+        })
+        .collect();
+
+    // Waiting on the last handle covers every earlier one.
+    handles.last().unwrap().wait().unwrap();
+    assert!(handles.iter().all(|h| h.is_synced()));
+    assert_eq!(writer.reader().read_from(0).unwrap().count(), 20);
+}
+
+#[test]
+fn test_explicit_sync_completes_deferred_handles() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    let (_, handle) = writer.append_deferred_sync(&dummy_event("a")).unwrap();
+
+    writer.sync().unwrap();
+
+    assert!(handle.is_synced());
+}
+
+#[test]
+fn test_deferred_handle_outlives_writer() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    let (_, handle) = writer.append_deferred_sync(&dummy_event("a")).unwrap();
+
+    // Dropping the writer drains pending syncs before the thread exits.
+    drop(writer);
+
+    handle.wait().unwrap();
+    assert!(handle.is_synced());
+}
+
+#[test]
+fn test_deferred_appends_interleave_with_regular_appends() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    writer.append(&dummy_event("a")).unwrap();
+    let (deferred, handle) = writer.append_deferred_sync(&dummy_event("b")).unwrap();
+    let regular = writer.append(&dummy_event("c")).unwrap();
+
+    assert_eq!(regular.start_offset, deferred.end_offset);
+    handle.wait().unwrap();
+    let types: Vec<_> = writer
+        .reader()
+        .read_from(0)
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+    assert_eq!(types, vec!["a", "b", "c"]);
+}
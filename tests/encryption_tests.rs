@@ -0,0 +1,389 @@
+mod common;
+
+use common::{counter_reducer, dummy_event, stats_reducer, StatsState};
+use eventfold::{
+    Codec, Event, EventLog, EventReader, HashAlgo, LogFormat, MemStorage, Merge, Storage, View,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+use tempfile::tempdir;
+
+const KEY: [u8; 32] = [7; 32];
+
+fn secret(n: u64) -> Event {
+    let mut event = Event::new("secret_type", json!({"ssn": "123-45-6789", "n": n}));
+    event.ts = 1000 + n;
+    event
+}
+
+fn encrypted_log(dir: &Path) -> EventLog {
+    EventLog::builder(dir)
+        .encryption(KEY)
+        .archive_codec(Codec::None)
+        .open()
+        .unwrap()
+}
+
+fn assert_no_plaintext(path: &Path) {
+    let contents = std::fs::read_to_string(path).unwrap();
+    assert!(!contents.is_empty(), "{} is empty", path.display());
+    assert!(!contents.contains("123-45-6789"), "{}: {contents}", path.display());
+    assert!(!contents.contains("secret_type"), "{}: {contents}", path.display());
+}
+
+#[test]
+fn test_encrypted_lines_round_trip_through_reads() {
+    let dir = tempdir().unwrap();
+    let mut log = encrypted_log(dir.path());
+    let results: Vec<_> = (0..3).map(|n| log.append(&secret(n)).unwrap()).collect();
+
+    assert_no_plaintext(log.log_path());
+    let events: Vec<_> = log.read_from(0).unwrap().map(Result::unwrap).collect();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[2].0.data["n"], 2);
+    assert_eq!(events[2].1, results[2].end_offset);
+
+    // Offsets still land on line boundaries.
+    let rest: Vec<_> = log
+        .read_from(results[1].start_offset)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(rest.len(), 2);
+    assert_eq!(rest[0].0.data["n"], 1);
+
+    let full: Vec<_> = log.read_full().unwrap().map(Result::unwrap).collect();
+    assert_eq!(full.len(), 3);
+    assert_eq!(full[0].0.event_type, "secret_type");
+}
+
+#[test]
+fn test_line_hash_is_over_stored_ciphertext() {
+    let dir = tempdir().unwrap();
+    let mut log = encrypted_log(dir.path());
+    let result = log.append(&secret(0)).unwrap();
+
+    let stored = std::fs::read_to_string(log.log_path()).unwrap();
+    assert_eq!(result.line_hash, HashAlgo::Xxh64.hash(stored.trim_end().as_bytes()));
+    let (_, _, hash) = log.read_from(0).unwrap().next().unwrap().unwrap();
+    assert_eq!(hash, result.line_hash);
+
+    log.append_if(&secret(1), result.end_offset, &result.line_hash)
+        .unwrap();
+    assert_eq!(log.read_full().unwrap().count(), 2);
+}
+
+#[test]
+fn test_same_event_encrypts_differently_each_time() {
+    let dir = tempdir().unwrap();
+    let mut log = encrypted_log(dir.path());
+    let first = log.append(&secret(0)).unwrap();
+    let second = log.append(&secret(0)).unwrap();
+    assert_ne!(first.line_hash, second.line_hash);
+}
+
+#[test]
+fn test_rotated_archive_stays_encrypted() {
+    let dir = tempdir().unwrap();
+    let mut log = encrypted_log(dir.path());
+    log.append(&secret(0)).unwrap();
+    log.rotate().unwrap();
+    log.append(&secret(1)).unwrap();
+
+    assert_no_plaintext(&dir.path().join("archive.jsonl"));
+    let full: Vec<_> = log.read_full().unwrap().map(Result::unwrap).collect();
+    assert_eq!(full.len(), 2);
+    assert_eq!(full[0].0.data["n"], 0);
+    assert!(log.verify().unwrap().is_ok());
+}
+
+#[test]
+fn test_view_snapshots_are_encrypted() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .encryption(KEY)
+            .view::<StatsState>("stats", stats_reducer)
+            .open()
+            .unwrap();
+        log.append(&secret(0)).unwrap();
+        log.refresh_all().unwrap();
+    }
+
+    let path = dir.path().join("views/stats.snapshot.json");
+    assert_no_plaintext(&path);
+    let envelope: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(envelope["encrypted"].is_string());
+
+    let mut log = EventLog::builder(dir.path())
+        .encryption(KEY)
+        .view::<StatsState>("stats", stats_reducer)
+        .open()
+        .unwrap();
+    log.refresh_all().unwrap();
+    let stats: &StatsState = log.view("stats").unwrap();
+    assert_eq!(stats.event_count, 1);
+    assert_eq!(stats.last_event_type, "secret_type");
+}
+
+#[test]
+fn test_undecryptable_snapshot_is_rebuilt() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .encryption(KEY)
+            .view::<u64>("counter", counter_reducer)
+            .open()
+            .unwrap();
+        log.append(&secret(0)).unwrap();
+        log.refresh_all().unwrap();
+    }
+    let path = dir.path().join("views/counter.snapshot.json");
+    std::fs::write(&path, r#"{"encrypted":"AAAA"}"#).unwrap();
+
+    let mut log = EventLog::builder(dir.path())
+        .encryption(KEY)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 1);
+}
+
+#[test]
+fn test_encrypted_log_does_not_open_without_its_key() {
+    let dir = tempdir().unwrap();
+    encrypted_log(dir.path()).append(&secret(0)).unwrap();
+    let manifest = std::fs::read_to_string(dir.path().join("manifest.json")).unwrap();
+    let manifest: Value = serde_json::from_str(&manifest).unwrap();
+    assert_eq!(manifest["encrypted"], true);
+
+    let err = EventLog::open(dir.path()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = EventLog::builder(dir.path()).open().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = EventLog::open_readonly(dir.path()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_wrong_key_fails_reads() {
+    let dir = tempdir().unwrap();
+    encrypted_log(dir.path()).append(&secret(0)).unwrap();
+
+    let log = EventLog::builder(dir.path())
+        .encryption([8; 32])
+        .open()
+        .unwrap();
+    let err = log.read_from(0).unwrap().next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(log.reader().read_from_lenient(0).unwrap().count(), 0);
+    assert!(!log.verify().unwrap().is_ok());
+}
+
+#[test]
+fn test_plaintext_log_with_history_refuses_a_key() {
+    let dir = tempdir().unwrap();
+    EventLog::open(dir.path())
+        .unwrap()
+        .append(&dummy_event("a"))
+        .unwrap();
+
+    let err = EventLog::builder(dir.path())
+        .encryption(KEY)
+        .open()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_reader_with_encryption_reads_standalone() {
+    let dir = tempdir().unwrap();
+    let mut log = encrypted_log(dir.path());
+    log.append(&secret(0)).unwrap();
+    log.rotate().unwrap();
+    log.append(&secret(1)).unwrap();
+
+    let reader = EventReader::new(dir.path()).with_encryption(KEY);
+    assert_eq!(reader.read_full().unwrap().count(), 2);
+    let keyless = EventReader::new(dir.path());
+    assert!(keyless.read_from(0).unwrap().next().unwrap().is_err());
+}
+
+#[test]
+fn test_in_memory_log_is_encrypted() {
+    let storage = MemStorage::new();
+    let mut log = EventLog::with_storage(storage.clone())
+        .encryption(KEY)
+        .open()
+        .unwrap();
+    log.append(&secret(0)).unwrap();
+
+    let mut stored = String::new();
+    storage.read_from(0).unwrap().read_to_string(&mut stored).unwrap();
+    assert!(!stored.contains("secret_type"));
+    assert_eq!(log.read_full().unwrap().count(), 1);
+}
+
+#[test]
+fn test_compact_and_redact_keep_lines_encrypted_and_chained() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .encryption(KEY)
+        .archive_codec(Codec::None)
+        .hash_chain(true)
+        .open()
+        .unwrap();
+    for n in 0..3 {
+        log.append(&secret(n)).unwrap();
+    }
+    log.rotate().unwrap();
+    for n in 3..6 {
+        log.append(&secret(n)).unwrap();
+    }
+
+    assert_eq!(log.compact(|e| e.data["n"] != 4).unwrap(), 1);
+    assert_eq!(log.redact(|e| e.data["n"] == 1).unwrap(), 1);
+
+    assert_no_plaintext(log.log_path());
+    assert_no_plaintext(&dir.path().join("archive.jsonl"));
+    let full: Vec<_> = log.read_full().unwrap().map(Result::unwrap).collect();
+    let ns: Vec<_> = full.iter().map(|(e, _)| e.data["n"].clone()).collect();
+    assert_eq!(ns, [json!(0), Value::Null, json!(2), json!(3), json!(5)]);
+    assert_eq!(log.verify_chain().unwrap(), Ok(()));
+    assert!(log.verify().unwrap().is_ok());
+}
+
+#[derive(Deserialize)]
+struct Deposit {
+    amount: u64,
+}
+
+fn balance_reducer(state: u64, _event: &Event, deposit: Deposit) -> u64 {
+    state + deposit.amount
+}
+
+#[test]
+fn test_dead_letters_are_encrypted() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .encryption(KEY)
+        .typed_view::<u64, Deposit>("balance", balance_reducer)
+        .open()
+        .unwrap();
+    log.append(&Event::new("deposit", json!({"amount": "123-45-6789"})))
+        .unwrap();
+    log.refresh_all().unwrap();
+
+    assert_no_plaintext(&log.reader().dead_letter_path());
+    let dead: Vec<_> = log.reader().read_dead_letters().unwrap().collect();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].0.data["amount"], "123-45-6789");
+}
+
+#[test]
+fn test_split_by_encrypts_shards() {
+    let dir = tempdir().unwrap();
+    let dest = tempdir().unwrap();
+    let mut log = encrypted_log(dir.path());
+    log.append(&secret(0)).unwrap();
+    log.append(&secret(1)).unwrap();
+
+    let report = log
+        .split_by(|e| format!("shard-{}", e.data["n"]), dest.path())
+        .unwrap();
+    assert_eq!(report.total(), 2);
+
+    let shard = dest.path().join("shard-1");
+    assert_no_plaintext(&shard.join("app.jsonl"));
+    assert!(EventLog::open(&shard).is_err());
+    let shard = EventLog::builder(&shard).encryption(KEY).open().unwrap();
+    assert_eq!(shard.read_full().unwrap().next().unwrap().unwrap().0.data["n"], 1);
+}
+
+#[test]
+fn test_log_views_are_refused() {
+    let dir = tempdir().unwrap();
+    let err = EventLog::builder(dir.path())
+        .encryption(KEY)
+        .log_view("feed", |event, out| writeln!(out, "{}", event.event_type))
+        .open()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_inspect_reports_encrypted_format() {
+    let dir = tempdir().unwrap();
+    encrypted_log(dir.path()).append(&secret(0)).unwrap();
+    let info = EventLog::inspect(dir.path()).unwrap();
+    assert_eq!(info.format, LogFormat::Encrypted);
+    assert_eq!(info.event_count_estimate, 1);
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Total(u64);
+
+impl Merge for Total {
+    fn merge(a: Self, b: Self) -> Self {
+        Total(a.0 + b.0)
+    }
+}
+
+fn total_reducer(state: Total, event: &Event) -> Total {
+    Total(state.0 + event.data["n"].as_u64().unwrap())
+}
+
+#[test]
+fn test_standalone_view_over_encrypted_reader() {
+    let dir = tempdir().unwrap();
+    let mut log = encrypted_log(dir.path());
+    log.append(&secret(0)).unwrap();
+    log.append(&secret(1)).unwrap();
+
+    log.append(&secret(2)).unwrap();
+
+    let mut view = View::new("total", total_reducer, log.views_dir());
+    assert_eq!(*view.refresh(&log.reader()).unwrap(), Total(3));
+    assert_eq!(*view.rebuild_parallel(&log.reader(), 2).unwrap(), Total(3));
+}
+
+fn files_under(dir: &Path) -> Vec<std::path::PathBuf> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(files_under(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+#[test]
+fn test_only_rotation_notes_stay_plaintext() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .encryption(KEY)
+        .archive_codec(Codec::None)
+        .index(true)
+        .view::<StatsState>("stats", stats_reducer)
+        .open()
+        .unwrap();
+    log.append(&secret(0)).unwrap();
+    log.rotate_annotated("monthly archive").unwrap();
+    log.append(&secret(1)).unwrap();
+    log.refresh_all().unwrap();
+    log.cursor("mailer").unwrap().save(1).unwrap();
+
+    let rotations = std::fs::read_to_string(log.rotations_path()).unwrap();
+    assert!(rotations.contains("monthly archive"));
+    for path in files_under(dir.path()) {
+        let contents = String::from_utf8_lossy(&std::fs::read(&path).unwrap()).into_owned();
+        assert!(!contents.contains("123-45-6789"), "{}", path.display());
+        assert!(!contents.contains("secret_type"), "{}", path.display());
+    }
+}
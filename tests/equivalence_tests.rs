@@ -0,0 +1,103 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::{EventCmp, EventLog, EventWriter};
+use serde_json::json;
+use tempfile::tempdir;
+
+fn populate(writer: &mut EventWriter, n: usize) {
+    for i in 0..n {
+        writer.append(&dummy_event(&format!("event_{i}"))).unwrap();
+    }
+}
+
+#[test]
+fn test_equivalent_to_identical_copy() {
+    let source_dir = tempdir().unwrap();
+    let target_dir = tempdir().unwrap();
+    let mut source = EventLog::open(source_dir.path()).unwrap();
+    populate(source.writer_mut(), 3);
+    source.rotate().unwrap();
+    populate(source.writer_mut(), 2);
+    let mut target = EventWriter::open(target_dir.path()).unwrap();
+    for result in source.read_full().unwrap() {
+        target.append(&result.unwrap().0).unwrap();
+    }
+
+    let report = source
+        .equivalent_to(&target.reader(), EventCmp::ALL)
+        .unwrap();
+
+    assert!(report.is_equivalent());
+    assert_eq!(report.matched, 5);
+}
+
+#[test]
+fn test_equivalent_to_reports_altered_event() {
+    let source_dir = tempdir().unwrap();
+    let target_dir = tempdir().unwrap();
+    let mut source = EventLog::open(source_dir.path()).unwrap();
+    populate(source.writer_mut(), 5);
+    let mut target = EventWriter::open(target_dir.path()).unwrap();
+    for (i, result) in source.read_full().unwrap().enumerate() {
+        let mut event = result.unwrap().0;
+        if i == 3 {
+            event.data = json!({"key": "altered"});
+        }
+        target.append(&event).unwrap();
+    }
+
+    let report = source
+        .equivalent_to(&target.reader(), EventCmp::ALL)
+        .unwrap();
+
+    assert!(!report.is_equivalent());
+    assert_eq!(report.matched, 3);
+    let divergence = report.divergence.unwrap();
+    assert_eq!(divergence.index, 3);
+    assert_eq!(divergence.left.unwrap().data, json!({"key": "value"}));
+    assert_eq!(divergence.right.unwrap().data, json!({"key": "altered"}));
+}
+
+#[test]
+fn test_equivalent_to_reports_shorter_log() {
+    let source_dir = tempdir().unwrap();
+    let target_dir = tempdir().unwrap();
+    let mut source = EventLog::open(source_dir.path()).unwrap();
+    populate(source.writer_mut(), 3);
+    let mut target = EventWriter::open(target_dir.path()).unwrap();
+    populate(&mut target, 2);
+
+    let report = source
+        .equivalent_to(&target.reader(), EventCmp::ALL)
+        .unwrap();
+
+    let divergence = report.divergence.unwrap();
+    assert_eq!(divergence.index, 2);
+    assert_eq!(divergence.left.unwrap().event_type, "event_2");
+    assert_eq!(divergence.right, None);
+}
+
+#[test]
+fn test_equivalent_to_ignores_unselected_fields() {
+    let source_dir = tempdir().unwrap();
+    let target_dir = tempdir().unwrap();
+    let mut source = EventLog::open(source_dir.path()).unwrap();
+    source
+        .append(&dummy_event("a").with_actor("alice"))
+        .unwrap();
+    let mut target = EventWriter::open(target_dir.path()).unwrap();
+    target
+        .append(&dummy_event("a").with_meta(json!({"migrated": true})))
+        .unwrap();
+
+    let core = source
+        .equivalent_to(&target.reader(), EventCmp::CORE)
+        .unwrap();
+    let all = source
+        .equivalent_to(&target.reader(), EventCmp::ALL)
+        .unwrap();
+
+    assert!(core.is_equivalent());
+    assert_eq!(all.divergence.unwrap().index, 0);
+}
@@ -1,7 +1,7 @@
 mod common;
 
 use common::dummy_event;
-use eventfold::Event;
+use eventfold::{Event, TypedEvent};
 use serde_json::json;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -323,6 +323,23 @@ fn test_with_meta() {
     assert_eq!(event.meta, Some(meta));
 }
 
+#[test]
+fn test_with_schema_version() {
+    let event = Event::new("test", json!({}));
+    assert_eq!(event.schema_version(), None);
+
+    let event = event.with_schema_version(3);
+    assert_eq!(event.meta, Some(json!({"schema_version": 3})));
+    assert_eq!(event.schema_version(), Some(3));
+
+    // Other metadata survives, and a second call replaces the version.
+    let event = Event::new("test", json!({}))
+        .with_meta(json!({"session": "s1", "schema_version": 2}))
+        .with_schema_version(4);
+    assert_eq!(event.meta, Some(json!({"session": "s1", "schema_version": 4})));
+    assert_eq!(event.with_meta(json!("opaque")).schema_version(), None);
+}
+
 #[test]
 fn test_metadata_builder_chaining() {
     let event = Event::new("test", json!({"x": 1}))
@@ -429,7 +446,7 @@ fn test_round_trip_with_metadata() {
 #[test]
 fn test_mixed_log_events() {
     // Simulate a log with old-style and new-style events
-    let lines = vec![
+    let lines = [
         r#"{"type":"old","data":{"v":1},"ts":1000}"#,
         r#"{"type":"new","data":{"v":2},"ts":2000,"id":"e1","actor":"u1"}"#,
         r#"{"type":"partial_meta","data":{"v":3},"ts":3000,"actor":"u2"}"#,
@@ -449,3 +466,64 @@ fn test_mixed_log_events() {
     assert_eq!(events[2].id, None);
     assert_eq!(events[2].actor, Some("u2".to_string()));
 }
+
+#[test]
+fn test_default_event() {
+    let event = Event::default();
+    assert_eq!(event.event_type, "");
+    assert_eq!(event.data, serde_json::Value::Null);
+    assert_eq!(event.ts, 0);
+    assert_eq!(event.id, None);
+    assert_eq!(event.actor, None);
+    assert_eq!(event.meta, None);
+}
+
+#[test]
+fn test_struct_literal_with_default_rest() {
+    let event = Event {
+        event_type: "imported".to_string(),
+        data: json!({"row": 7}),
+        ts: 1234,
+        ..Default::default()
+    };
+    assert_eq!(event.event_type, "imported");
+    assert_eq!(event.data["row"], 7);
+    assert_eq!(event.ts, 1234);
+    assert_eq!(event.id, None);
+    assert_eq!(event.actor, None);
+    assert_eq!(event.meta, None);
+}
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Deposit {
+    account: String,
+    amount: u64,
+}
+
+impl TypedEvent for Deposit {
+    fn event_type() -> &'static str {
+        "deposit"
+    }
+}
+
+#[test]
+fn test_typed_event_round_trip() {
+    let deposit = Deposit {
+        account: "acc-1".to_string(),
+        amount: 25,
+    };
+    let event = Event::from_typed(&deposit).unwrap().with_actor("teller");
+
+    assert_eq!(event.event_type, "deposit");
+    assert_eq!(event.data, json!({"account": "acc-1", "amount": 25}));
+    assert_eq!(event.as_typed::<Deposit>(), Some(deposit));
+}
+
+#[test]
+fn test_as_typed_rejects_other_type_or_shape() {
+    let wrong_type = Event::new("withdrawal", json!({"account": "a", "amount": 1}));
+    assert_eq!(wrong_type.as_typed::<Deposit>(), None);
+
+    let wrong_shape = Event::new("deposit", json!({"account": "a", "amount": "1"}));
+    assert_eq!(wrong_shape.as_typed::<Deposit>(), None);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::append_n;
+use eventfold::{Event, EventLog};
+use serde_json::json;
+use tempfile::tempdir;
+
+fn export(log: &EventLog, columns: &[&str]) -> String {
+    let mut out = Vec::new();
+    log.export_csv_with_columns(&mut out, columns).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn event(event_type: &str, data: serde_json::Value) -> Event {
+    let mut e = Event::new(event_type, data);
+    e.ts = 1000;
+    e
+}
+
+#[test]
+fn test_export_covers_archive_and_active_log() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+    append_n(&mut log, 1);
+
+    let mut out = Vec::new();
+    log.export_csv(&mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+
+    let rows: Vec<&str> = csv.split_terminator("\r\n").collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0], "ts,type,id,actor,data");
+    assert_eq!(rows[1], r#"1000,event_0,,,"{""key"":""value""}""#);
+    assert!(rows[3].starts_with("1000,event_0,"));
+}
+
+#[test]
+fn test_export_quotes_special_characters() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(
+        &event("note", json!({"text": "a, \"b\"\nc"}))
+            .with_id("id,1")
+            .with_actor("ops"),
+    )
+    .unwrap();
+
+    let csv = export(&log, &["text"]);
+    let row = csv.strip_prefix("ts,type,id,actor,data,text\r\n").unwrap();
+    assert!(row.starts_with("1000,note,\"id,1\",ops,"));
+    assert!(row.ends_with(",\"a, \"\"b\"\"\nc\"\r\n"), "{row}");
+}
+
+#[test]
+fn test_export_flattens_only_scalar_columns() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&event(
+        "order",
+        json!({"sku": "A-1", "qty": 2, "paid": true, "tags": ["x"], "note": null}),
+    ))
+    .unwrap();
+
+    let csv = export(&log, &["sku", "qty", "paid", "tags", "note", "missing"]);
+    let row = csv.split_terminator("\r\n").nth(1).unwrap();
+    assert!(row.ends_with(",A-1,2,true,,,"), "{row}");
+}
+
+#[test]
+fn test_export_empty_log_writes_header() {
+    let dir = tempdir().unwrap();
+    let log = EventLog::open(dir.path()).unwrap();
+    assert_eq!(export(&log, &["sku"]), "ts,type,id,actor,data,sku\r\n");
+}
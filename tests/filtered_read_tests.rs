@@ -0,0 +1,159 @@
+mod common;
+
+use eventfold::{Event, EventLog};
+use serde_json::json;
+use tempfile::tempdir;
+
+fn actor_event(event_type: &str, actor: &str) -> Event {
+    Event::new(event_type, json!({"k": 1})).with_actor(actor)
+}
+
+#[test]
+fn test_read_full_filtered_excludes_actor_across_archive() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&actor_event("a1", "alice")).unwrap();
+    log.append(&actor_event("x1", "user-x")).unwrap();
+    log.rotate().unwrap();
+    log.append(&actor_event("x2", "user-x")).unwrap();
+    log.append(&actor_event("b1", "bob")).unwrap();
+
+    let exported: Vec<String> = log
+        .reader()
+        .read_full_filtered(|e| e.actor.as_deref() != Some("user-x"))
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+
+    assert_eq!(exported, vec!["a1", "b1"]);
+}
+
+#[test]
+fn test_read_full_filtered_leaves_log_untouched() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&actor_event("a1", "alice")).unwrap();
+    log.append(&actor_event("x1", "user-x")).unwrap();
+
+    let kept = log
+        .reader()
+        .read_full_filtered(|e| e.actor.as_deref() != Some("user-x"))
+        .unwrap()
+        .count();
+
+    assert_eq!(kept, 1);
+    assert_eq!(log.read_full().unwrap().count(), 2);
+}
+
+#[test]
+fn test_read_full_filtered_preserves_hashes() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&actor_event("a1", "alice")).unwrap();
+    let r = log.append(&actor_event("b1", "bob")).unwrap();
+
+    let kept: Vec<_> = log
+        .reader()
+        .read_full_filtered(|e| e.event_type == "b1")
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].1, r.line_hash);
+}
+
+#[test]
+fn test_read_full_filtered_passes_errors_through() {
+    let dir = tempdir().unwrap();
+    let log = EventLog::open(dir.path()).unwrap();
+    std::fs::write(log.log_path(), "not json\n").unwrap();
+
+    let results: Vec<_> = log
+        .reader()
+        .read_full_filtered(|_| false)
+        .unwrap()
+        .collect();
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+}
+
+#[test]
+fn test_read_from_filtered_by_type_resumes() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    for event_type in ["todo_added", "todo_completed", "todo_added", "todo_deleted"] {
+        log.append(&actor_event(event_type, "alice")).unwrap();
+    }
+    let reader = log.reader();
+
+    let added: Vec<_> = reader
+        .read_from_filtered(0, &["todo_added"])
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(added.len(), 2);
+    assert!(added.iter().all(|(e, _, _)| e.event_type == "todo_added"));
+
+    // Offsets are the real line ends, past the skipped "todo_completed".
+    let all: Vec<_> = reader
+        .read_from(0)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(added[0].1, all[0].1);
+    assert_eq!(added[1].1, all[2].1);
+    assert_eq!(added[1].2, all[2].2);
+
+    // Resuming from the last yielded offset sees only later events.
+    log.append(&actor_event("todo_added", "bob")).unwrap();
+    let resumed: Vec<_> = reader
+        .read_from_filtered(added[1].1, &["todo_added", "todo_deleted"])
+        .unwrap()
+        .map(|r| r.unwrap().0)
+        .collect();
+    let types: Vec<_> = resumed.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(types, ["todo_deleted", "todo_added"]);
+}
+
+#[test]
+fn test_read_full_redacted_honors_tombstones_across_archive() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&actor_event("a", "alice").with_id("e1"))
+        .unwrap();
+    log.append(&actor_event("b", "bob").with_id("e2")).unwrap();
+    log.rotate().unwrap();
+    log.append(&Event::tombstone("e1")).unwrap();
+    log.append(&actor_event("c", "carol").with_id("e3"))
+        .unwrap();
+
+    let kept: Vec<String> = log
+        .reader()
+        .read_full_redacted()
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+    assert_eq!(kept, ["b", "c"]);
+    assert_eq!(log.read_full().unwrap().count(), 4);
+}
+
+#[test]
+fn test_read_full_redacted_ignores_earlier_tombstones() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&Event::tombstone("e1")).unwrap();
+    log.append(&actor_event("a", "alice").with_id("e1"))
+        .unwrap();
+
+    let kept: Vec<Event> = log
+        .reader()
+        .read_full_redacted()
+        .unwrap()
+        .map(|r| r.unwrap().0)
+        .collect();
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].id.as_deref(), Some("e1"));
+    assert_eq!(kept[0].tombstone_target(), None);
+}
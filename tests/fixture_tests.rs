@@ -0,0 +1,237 @@
+mod common;
+
+use common::{StatsState, append_n, counter_reducer, full_history, stats_reducer};
+use eventfold::testing::{FIXTURE_FORMAT_VERSION, Fixture, capture_fixture, load_fixture};
+use eventfold::{Codec, EventLog};
+use tempfile::tempdir;
+
+fn populated_log(dir: &std::path::Path) -> EventLog {
+    let mut log = EventLog::builder(dir)
+        .view::<u64>("counter", counter_reducer)
+        .view::<StatsState>("stats", stats_reducer)
+        .open()
+        .unwrap();
+    append_n(&mut log, 4);
+    log.rotate().unwrap();
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    log.refresh_all().unwrap();
+    log
+}
+
+#[test]
+fn test_fixture_round_trip_reproduces_log_and_views() {
+    let source = tempdir().unwrap();
+    let target = tempdir().unwrap();
+    let log = populated_log(source.path());
+
+    let fixture = capture_fixture(&log).unwrap();
+    // Round-trip through JSON, as a committed fixture file would.
+    let json = serde_json::to_string_pretty(&fixture).unwrap();
+    let fixture: Fixture = serde_json::from_str(&json).unwrap();
+    load_fixture(target.path(), &fixture).unwrap();
+
+    let mut restored = EventLog::builder(target.path())
+        .view::<u64>("counter", counter_reducer)
+        .view::<StatsState>("stats", stats_reducer)
+        .open()
+        .unwrap();
+    restored.refresh_all().unwrap();
+
+    assert_eq!(full_history(&restored), full_history(&log));
+    assert_eq!(
+        restored.view::<u64>("counter").unwrap(),
+        log.view::<u64>("counter").unwrap()
+    );
+    assert_eq!(
+        restored.view::<StatsState>("stats").unwrap(),
+        log.view::<StatsState>("stats").unwrap()
+    );
+}
+
+#[test]
+fn test_fixture_preserves_archive_frames() {
+    let source = tempdir().unwrap();
+    let log = populated_log(source.path());
+
+    let fixture = capture_fixture(&log).unwrap();
+
+    assert_eq!(fixture.format_version, FIXTURE_FORMAT_VERSION);
+    assert_eq!(fixture.archive_frames.len(), 2);
+    assert_eq!(fixture.archive_frames[0].lines().count(), 4);
+    assert_eq!(fixture.archive_frames[1].lines().count(), 3);
+    assert_eq!(fixture.active_log.lines().count(), 2);
+    assert_eq!(fixture.snapshots.len(), 2);
+    assert_eq!(fixture.snapshots["counter"]["state"], 9);
+}
+
+#[test]
+fn test_fixture_restored_snapshot_is_trusted() {
+    let source = tempdir().unwrap();
+    let target = tempdir().unwrap();
+    let log = populated_log(source.path());
+
+    load_fixture(target.path(), &capture_fixture(&log).unwrap()).unwrap();
+
+    // Restored snapshot passes integrity checks, so its offset is kept.
+    let restored = EventLog::open(target.path()).unwrap();
+    let snap: eventfold::Snapshot<u64> =
+        eventfold::snapshot::load(&restored.views_dir().join("counter.snapshot.json"))
+            .unwrap()
+            .unwrap();
+    assert_eq!(snap.offset, restored.active_log_size().unwrap());
+}
+
+#[test]
+fn test_load_fixture_rejects_unknown_version() {
+    let source = tempdir().unwrap();
+    let target = tempdir().unwrap();
+    let log = populated_log(source.path());
+
+    let mut fixture = capture_fixture(&log).unwrap();
+    fixture.format_version = FIXTURE_FORMAT_VERSION + 1;
+
+    let err = load_fixture(target.path(), &fixture).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_fixture_captures_in_memory_log() {
+    let target = tempdir().unwrap();
+    let mut log = EventLog::in_memory()
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    log.refresh_all().unwrap();
+
+    let fixture = capture_fixture(&log).unwrap();
+    assert_eq!(fixture.archive_frames.len(), 1);
+    assert_eq!(fixture.active_log.lines().count(), 2);
+    assert_eq!(fixture.snapshots["counter"]["state"], 5);
+    assert!(fixture.sidecars.is_empty());
+
+    load_fixture(target.path(), &fixture).unwrap();
+    let mut restored = EventLog::builder(target.path())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    restored.refresh_all().unwrap();
+    assert_eq!(full_history(&restored), full_history(&log));
+    assert_eq!(*restored.view::<u64>("counter").unwrap(), 5);
+}
+
+#[test]
+fn test_fixture_restores_views_location() {
+    let source = tempdir().unwrap();
+    let target = tempdir().unwrap();
+    let mut log = EventLog::builder(source.path())
+        .views_dir_name("projections")
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    append_n(&mut log, 3);
+    log.refresh_all().unwrap();
+
+    let fixture = capture_fixture(&log).unwrap();
+    assert_eq!(fixture.views_dir, "projections");
+
+    load_fixture(target.path(), &fixture).unwrap();
+    assert!(
+        target
+            .path()
+            .join("projections/counter.snapshot.json")
+            .exists()
+    );
+    assert!(!target.path().join("views").exists());
+}
+
+#[test]
+fn test_fixture_restores_archive_codec() {
+    let source = tempdir().unwrap();
+    let target = tempdir().unwrap();
+    let mut log = EventLog::builder(source.path())
+        .archive_codec(Codec::None)
+        .open()
+        .unwrap();
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    append_n(&mut log, 1);
+
+    let fixture = capture_fixture(&log).unwrap();
+    assert_eq!(fixture.archive_codec, Codec::None);
+
+    load_fixture(target.path(), &fixture).unwrap();
+    assert!(target.path().join("archive.jsonl").exists());
+    assert!(!target.path().join("archive.jsonl.zst").exists());
+
+    let restored = EventLog::open(target.path()).unwrap();
+    assert_eq!(restored.manifest().archive_codec, Codec::None);
+    assert_eq!(full_history(&restored), full_history(&log));
+}
+
+#[test]
+fn test_fixture_restores_manifest_and_sidecars() {
+    let source = tempdir().unwrap();
+    let target = tempdir().unwrap();
+    let log = populated_log(source.path());
+    log.cursor("mailer").unwrap().save(5).unwrap();
+
+    let fixture = capture_fixture(&log).unwrap();
+    assert!(fixture.manifest.is_some());
+    assert!(fixture.sidecars.contains_key("seq"));
+    assert!(fixture.sidecars.contains_key("cursors/mailer.cursor"));
+
+    load_fixture(target.path(), &fixture).unwrap();
+    let restored = EventLog::open(target.path()).unwrap();
+    assert_eq!(restored.manifest(), log.manifest());
+    assert_eq!(
+        restored.reader().sequence_base().unwrap(),
+        log.reader().sequence_base().unwrap()
+    );
+    assert_eq!(restored.cursor("mailer").unwrap().load().unwrap(), 5);
+}
+
+#[test]
+fn test_load_fixture_accepts_version_1() {
+    let source = tempdir().unwrap();
+    let target = tempdir().unwrap();
+    let log = populated_log(source.path());
+
+    let fixture = capture_fixture(&log).unwrap();
+    let json = serde_json::json!({
+        "format_version": 1,
+        "active_log": fixture.active_log,
+        "archive_frames": fixture.archive_frames,
+        "snapshots": fixture.snapshots,
+    });
+    let v1: Fixture = serde_json::from_value(json).unwrap();
+    assert_eq!(v1.archive_codec, Codec::Zstd);
+    assert_eq!(v1.views_dir, "views");
+
+    load_fixture(target.path(), &v1).unwrap();
+    let restored = EventLog::open(target.path()).unwrap();
+    assert_eq!(full_history(&restored), full_history(&log));
+}
+
+#[test]
+fn test_load_fixture_rejects_escaping_paths() {
+    let source = tempdir().unwrap();
+    let target = tempdir().unwrap();
+    let log = populated_log(source.path());
+
+    let mut fixture = capture_fixture(&log).unwrap();
+    fixture.views_dir = "../views".to_string();
+    let err = load_fixture(target.path(), &fixture).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut fixture = capture_fixture(&log).unwrap();
+    fixture
+        .sidecars
+        .insert("/tmp/seq".to_string(), "0".to_string());
+    let err = load_fixture(target.path(), &fixture).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
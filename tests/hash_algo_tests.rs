@@ -0,0 +1,147 @@
+mod common;
+
+use common::{append_n, counter_reducer, dummy_event};
+use eventfold::{EventLog, EventReader, HashAlgo, View};
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+fn sha256_log(dir: &std::path::Path) -> EventLog {
+    EventLog::builder(dir)
+        .hash_algo(HashAlgo::Sha256)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap()
+}
+
+#[test]
+fn test_sha256_known_vectors() {
+    assert_eq!(
+        HashAlgo::Sha256.hash(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        HashAlgo::Sha256.hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    assert_eq!(
+        HashAlgo::Sha256.hash(&vec![b'a'; 1_000_000]),
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+    );
+}
+
+#[test]
+fn test_sha256_used_for_appends_and_reads() {
+    let dir = tempdir().unwrap();
+    let mut log = sha256_log(dir.path());
+    let result = log.append(&dummy_event("a")).unwrap();
+    log.rotate().unwrap();
+    append_n(&mut log, 1);
+
+    let line = std::fs::read_to_string(dir.path().join("app.jsonl")).unwrap();
+    let (_, _, hash) = log.read_from(0).unwrap().next().unwrap().unwrap();
+    assert_eq!(hash, HashAlgo::Sha256.hash(line.trim_end().as_bytes()));
+
+    let hashes: Vec<String> = log.read_full().unwrap().map(|r| r.unwrap().1).collect();
+    assert_eq!(hashes[0], result.line_hash);
+    assert!(hashes.iter().all(|h| h.len() == 64));
+}
+
+#[test]
+fn test_reopen_uses_recorded_algorithm() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = sha256_log(dir.path());
+        append_n(&mut log, 3);
+        log.refresh_all().unwrap();
+    }
+
+    let mut log = EventLog::open(dir.path()).unwrap();
+    assert_eq!(log.append(&dummy_event("b")).unwrap().line_hash.len(), 64);
+    let (_, hash) = EventReader::new(dir.path())
+        .read_full()
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(hash.len(), 64);
+
+    // The snapshot saved under sha256 is still valid.
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    assert_eq!(*view.refresh(&log.reader()).unwrap(), 4);
+    assert!(view.was_loaded_from_snapshot());
+}
+
+#[test]
+fn test_mismatched_algorithm_is_rejected() {
+    let dir = tempdir().unwrap();
+    drop(sha256_log(dir.path()));
+    let err = EventLog::builder(dir.path())
+        .hash_algo(HashAlgo::Xxh64)
+        .open()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let dir = tempdir().unwrap();
+    append_n(&mut EventLog::open(dir.path()).unwrap(), 1);
+    let err = EventLog::builder(dir.path())
+        .hash_algo(HashAlgo::Sha256)
+        .open()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("xxh64"));
+}
+
+#[test]
+fn test_sha256_hash_chain_verifies() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .hash_algo(HashAlgo::Sha256)
+        .hash_chain(true)
+        .open()
+        .unwrap();
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    assert_eq!(log.verify_chain().unwrap(), Ok(()));
+    assert!(log.verify().unwrap().is_ok());
+}
+
+#[test]
+#[cfg(feature = "blake3")]
+fn test_blake3_known_vectors() {
+    assert_eq!(
+        HashAlgo::Blake3.hash(b""),
+        "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+    );
+    assert_eq!(
+        HashAlgo::Blake3.hash(b"abc"),
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+    );
+}
+
+#[test]
+#[cfg(feature = "blake3")]
+fn test_blake3_used_for_appends_and_recorded() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .hash_algo(HashAlgo::Blake3)
+        .hash_chain(true)
+        .open()
+        .unwrap();
+    let result = log.append(&dummy_event("a")).unwrap();
+    append_n(&mut log, 2);
+
+    let line = std::fs::read_to_string(dir.path().join("app.jsonl")).unwrap();
+    let first = line.lines().next().unwrap();
+    assert_eq!(result.line_hash, HashAlgo::Blake3.hash(first.as_bytes()));
+    assert_eq!(log.verify_chain().unwrap(), Ok(()));
+    drop(log);
+
+    assert_eq!(EventLog::open(dir.path()).unwrap().manifest().hash_algo, HashAlgo::Blake3);
+    let err = EventLog::builder(dir.path())
+        .hash_algo(HashAlgo::Sha256)
+        .open()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("blake3"));
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::{EventLog, EventReader, IdScheme};
+use tempfile::tempdir;
+
+fn found(reader: &EventReader, id: &str) -> Option<(String, u64)> {
+    reader
+        .find_by_id(id)
+        .unwrap()
+        .map(|(event, sequence)| (event.event_type, sequence))
+}
+
+fn fill(log: &mut EventLog) {
+    log.append(&dummy_event("a").with_id("x")).unwrap();
+    log.append(&dummy_event("b").with_id("y")).unwrap();
+    log.rotate().unwrap();
+    log.append_batch(&[dummy_event("c").with_id("x"), dummy_event("d").with_id("z")])
+        .unwrap();
+    log.append(&dummy_event("e")).unwrap();
+}
+
+#[test]
+fn test_find_by_id_scans_archive_and_active_log() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    fill(&mut log);
+
+    let reader = log.reader();
+    assert_eq!(found(&reader, "x"), Some(("a".into(), 0)));
+    assert_eq!(found(&reader, "y"), Some(("b".into(), 1)));
+    assert_eq!(found(&reader, "z"), Some(("d".into(), 3)));
+    assert_eq!(found(&reader, "missing"), None);
+}
+
+#[test]
+fn test_id_index_matches_scan() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        log.append(&dummy_event("old").with_id("w")).unwrap();
+    }
+    let mut log = EventLog::builder(dir.path())
+        .id_index(true)
+        .index(true)
+        .open()
+        .unwrap();
+    fill(&mut log);
+
+    let indexed = log.reader();
+    let scanned = EventReader::new(dir.path());
+    for id in ["w", "x", "y", "z", "missing"] {
+        assert_eq!(found(&indexed, id), found(&scanned, id), "id {id}");
+    }
+    assert_eq!(found(&indexed, "z"), Some(("d".into(), 4)));
+}
+
+#[test]
+fn test_id_index_tracks_auto_ids_and_compaction() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .auto_id(IdScheme::Ulid)
+        .id_index(true)
+        .open()
+        .unwrap();
+    let first = log.append(&dummy_event("drop")).unwrap();
+    let second = log.append(&dummy_event("keep")).unwrap();
+    let first_id = first.assigned_id.unwrap();
+    let second_id = second.assigned_id.unwrap();
+    assert_eq!(found(&log.reader(), &second_id), Some(("keep".into(), 1)));
+
+    log.compact(|e| e.event_type == "keep").unwrap();
+    let reader = log.reader();
+    assert_eq!(found(&reader, &first_id), None);
+    assert_eq!(
+        found(&reader, &second_id),
+        found(&EventReader::new(dir.path()), &second_id)
+    );
+}
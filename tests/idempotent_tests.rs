@@ -0,0 +1,88 @@
+mod common;
+
+use common::{counter_reducer, dummy_event};
+use eventfold::{EventLog, EventWriter};
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+#[test]
+fn test_retries_are_written_once() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    let first = log.append_idempotent(&dummy_event("a"), "k1").unwrap();
+    log.append(&dummy_event("b")).unwrap();
+    let retry = log.append_idempotent(&dummy_event("a"), "k1").unwrap();
+    assert_eq!(retry, first);
+    log.append_idempotent(&dummy_event("a"), "k2").unwrap();
+
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 3);
+    let (stored, _, _) = log.read_from(0).unwrap().next().unwrap().unwrap();
+    assert_eq!(stored.id.as_deref(), Some("k1"));
+}
+
+#[test]
+fn test_window_is_seeded_from_the_active_log() {
+    let dir = tempdir().unwrap();
+    let first = {
+        let mut writer = EventWriter::open(dir.path()).unwrap();
+        let first = writer.append_idempotent(&dummy_event("a"), "k1").unwrap();
+        writer.append(&dummy_event("b").with_id("plain")).unwrap();
+        first
+    };
+
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    assert_eq!(
+        writer.append_idempotent(&dummy_event("a"), "k1").unwrap(),
+        first
+    );
+    assert_eq!(
+        writer
+            .append_idempotent(&dummy_event("b"), "plain")
+            .unwrap()
+            .sequence,
+        1
+    );
+    assert_eq!(writer.reader().read_from(0).unwrap().count(), 2);
+}
+
+#[test]
+fn test_id_index_extends_the_window_to_full_history() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .dedup_window(1)
+        .id_index(true)
+        .open()
+        .unwrap();
+    let first = log.append_idempotent(&dummy_event("a"), "k1").unwrap();
+    log.append_idempotent(&dummy_event("b"), "k2").unwrap();
+    assert_eq!(
+        log.append_idempotent(&dummy_event("a"), "k1").unwrap(),
+        first
+    );
+    assert_eq!(log.read_from(0).unwrap().count(), 2);
+
+    log.rotate().unwrap();
+    log.append_idempotent(&dummy_event("c"), "k3").unwrap();
+    let err = log.append_idempotent(&dummy_event("a"), "k1").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    assert_eq!(log.read_full().unwrap().count(), 3);
+}
+
+#[test]
+fn test_mismatched_id_or_empty_key_is_rejected() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let event = dummy_event("a").with_id("other");
+    let err = log.append_idempotent(&event, "k1").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = log.append_idempotent(&dummy_event("a"), "").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    log.append_idempotent(&dummy_event("a").with_id("k1"), "k1")
+        .unwrap();
+    assert_eq!(log.read_from(0).unwrap().count(), 1);
+}
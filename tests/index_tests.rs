@@ -0,0 +1,128 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::{Event, EventLog, EventReader};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn open_indexed(dir: &Path) -> EventLog {
+    EventLog::builder(dir).index(true).open().unwrap()
+}
+
+fn append_events(log: &mut EventLog, n: usize) {
+    let events: Vec<Event> = (0..n).map(|i| dummy_event(&format!("e{i}"))).collect();
+    log.append_batch(&events).unwrap();
+}
+
+fn event_type_at(log: &EventLog, offset: u64) -> String {
+    log.read_from(offset)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .0
+        .event_type
+}
+
+fn index_lines(dir: &Path) -> usize {
+    fs::read_to_string(dir.join("app.index"))
+        .unwrap()
+        .lines()
+        .count()
+}
+
+#[test]
+fn test_offset_of_event_with_index() {
+    let dir = tempdir().unwrap();
+    let mut log = open_indexed(dir.path());
+    append_events(&mut log, 300);
+    for i in 0..300 {
+        log.append(&dummy_event(&format!("e{}", 300 + i))).unwrap();
+    }
+    assert_eq!(index_lines(dir.path()), 2);
+
+    let reader = log.reader();
+    for n in [0, 1, 255, 256, 257, 511, 512, 599] {
+        let offset = reader.offset_of_event(n).unwrap().unwrap();
+        assert_eq!(event_type_at(&log, offset), format!("e{n}"));
+    }
+    assert_eq!(reader.offset_of_event(600).unwrap(), None);
+}
+
+#[test]
+fn test_offset_of_event_without_index_scans() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_events(&mut log, 300);
+    assert!(!dir.path().join("app.index").exists());
+
+    let offset = log.reader().offset_of_event(280).unwrap().unwrap();
+    assert_eq!(event_type_at(&log, offset), "e280");
+    assert_eq!(log.reader().offset_of_event(300).unwrap(), None);
+}
+
+#[test]
+fn test_rotation_resets_index() {
+    let dir = tempdir().unwrap();
+    let mut log = open_indexed(dir.path());
+    append_events(&mut log, 600);
+    log.rotate().unwrap();
+    assert_eq!(index_lines(dir.path()), 0);
+    assert_eq!(log.reader().offset_of_event(0).unwrap(), None);
+
+    append_events(&mut log, 300);
+    assert_eq!(index_lines(dir.path()), 1);
+    let offset = log.reader().offset_of_event(260).unwrap().unwrap();
+    assert_eq!(event_type_at(&log, offset), "e260");
+}
+
+#[test]
+fn test_stale_index_entries_ignored_and_rebuilt() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = open_indexed(dir.path());
+        append_events(&mut log, 300);
+    }
+    let size = fs::metadata(dir.path().join("app.jsonl")).unwrap().len();
+    let index_path = dir.path().join("app.index");
+    fs::write(&index_path, format!("{}\n", size + 100)).unwrap();
+
+    let offset = EventReader::new(dir.path())
+        .offset_of_event(299)
+        .unwrap()
+        .unwrap();
+    assert!(offset < size);
+
+    let _log = open_indexed(dir.path());
+    let rebuilt: u64 = fs::read_to_string(&index_path)
+        .unwrap()
+        .trim()
+        .parse()
+        .unwrap();
+    assert!(rebuilt < size);
+}
+
+#[test]
+fn test_unindexed_writer_deletes_index() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = open_indexed(dir.path());
+        append_events(&mut log, 300);
+    }
+    let _log = EventLog::open(dir.path()).unwrap();
+    assert!(!dir.path().join("app.index").exists());
+}
+
+#[test]
+fn test_compact_rebuilds_index() {
+    let dir = tempdir().unwrap();
+    let mut log = open_indexed(dir.path());
+    append_events(&mut log, 600);
+    log.compact(|e| e.event_type.len() == 4).unwrap();
+
+    // e100..e599 remain
+    assert_eq!(index_lines(dir.path()), 1);
+    let offset = log.reader().offset_of_event(300).unwrap().unwrap();
+    assert_eq!(event_type_at(&log, offset), "e400");
+}
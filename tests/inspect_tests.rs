@@ -0,0 +1,87 @@
+mod common;
+
+use common::append_n;
+#[cfg(feature = "zstd")]
+use common::{StatsState, counter_reducer, stats_reducer};
+#[cfg(feature = "zstd")]
+use eventfold::ArchiveCompression;
+use eventfold::{EventLog, LogFormat};
+use tempfile::tempdir;
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_inspect_populated_directory() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .view::<StatsState>("stats", stats_reducer)
+        .open()
+        .unwrap();
+    append_n(&mut log, 4);
+    log.rotate().unwrap();
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    log.refresh_all().unwrap();
+
+    // The writer is still open and holding its lock.
+    let info = EventLog::inspect(dir.path()).unwrap();
+
+    assert_eq!(info.format, LogFormat::Jsonl);
+    assert_eq!(info.compression, Some(ArchiveCompression::Zstd));
+    assert!(info.has_archive);
+    assert_eq!(info.view_names, vec!["counter", "stats"]);
+    assert_eq!(info.generation, 2);
+    assert_eq!(info.event_count_estimate, 9);
+}
+
+#[test]
+fn test_inspect_fresh_directory() {
+    let dir = tempdir().unwrap();
+    drop(EventLog::open(dir.path()).unwrap());
+
+    let info = EventLog::inspect(dir.path()).unwrap();
+
+    assert_eq!(info.format, LogFormat::Jsonl);
+    assert_eq!(info.compression, None);
+    assert!(!info.has_archive);
+    assert!(info.view_names.is_empty());
+    assert_eq!(info.generation, 0);
+    assert_eq!(info.event_count_estimate, 0);
+}
+
+#[test]
+fn test_inspect_unknown_format() {
+    let dir = tempdir().unwrap();
+    std::fs::write(dir.path().join("app.jsonl"), b"\x93\x01\x02binary\n").unwrap();
+
+    let info = EventLog::inspect(dir.path()).unwrap();
+
+    assert_eq!(info.format, LogFormat::Unknown);
+}
+
+#[test]
+fn test_inspect_ignores_trailing_partial_line() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 2);
+    use std::io::Write;
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(log.log_path())
+        .unwrap();
+    write!(file, r#"{{"type":"partial""#).unwrap();
+
+    let info = EventLog::inspect(dir.path()).unwrap();
+
+    assert_eq!(info.event_count_estimate, 2);
+}
+
+#[test]
+fn test_inspect_missing_directory() {
+    let dir = tempdir().unwrap();
+
+    let err = EventLog::inspect(dir.path().join("nope")).unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
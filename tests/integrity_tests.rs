@@ -93,6 +93,31 @@ fn test_hash_mismatch() {
     assert_eq!(*state, 5);
 }
 
+#[test]
+fn test_refresh_counted_reports_rebuild() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 5);
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    view.refresh(&log.reader()).unwrap();
+
+    // Rewrite the log with different events of the same count.
+    let log_path = dir.path().join("app.jsonl");
+    let tampered: String = (0..5)
+        .map(|i| {
+            let event = Event::new("tampered", json!({"i": i}));
+            serde_json::to_string(&event).unwrap() + "\n"
+        })
+        .collect();
+    fs::write(&log_path, tampered).unwrap();
+
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    let stats = view.refresh_counted(&log.reader()).unwrap();
+    assert!(stats.rebuilt);
+    assert_eq!(stats.applied, 5);
+    assert_eq!(stats.new_offset, log.active_log_size().unwrap());
+}
+
 #[test]
 fn test_empty_log_nonzero_offset() {
     let dir = tempdir().unwrap();
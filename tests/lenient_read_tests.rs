@@ -0,0 +1,60 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::EventLog;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn append_raw(dir: &Path, bytes: &[u8]) {
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(dir.join("app.jsonl"))
+        .unwrap();
+    file.write_all(bytes).unwrap();
+}
+
+#[test]
+fn test_read_from_lenient_skips_corrupt_lines() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&dummy_event("a")).unwrap();
+    append_raw(dir.path(), b"{\"truncated\": \n[1, 2, 3]\n");
+    let b = log.append(&dummy_event("b")).unwrap();
+    let c = log.append(&dummy_event("c")).unwrap();
+
+    // The strict read stops at the first corrupt line.
+    assert!(log.read_from(0).unwrap().any(|r| r.is_err()));
+
+    let mut events = log.reader().read_from_lenient(0).unwrap();
+    let read: Vec<_> = events.by_ref().map(|r| r.unwrap()).collect();
+    assert_eq!(events.skipped(), 2);
+    let types: Vec<&str> = read.iter().map(|(e, _, _)| e.event_type.as_str()).collect();
+    assert_eq!(types, ["a", "b", "c"]);
+    assert_eq!(read[1].1, b.end_offset);
+    assert_eq!(read[1].2, b.line_hash);
+    assert_eq!(read[2].1, c.end_offset);
+
+    // Resuming from a yielded offset picks up where the read left off.
+    let mut rest = log.reader().read_from_lenient(b.end_offset).unwrap();
+    assert_eq!(rest.next().unwrap().unwrap().0.event_type, "c");
+    assert!(rest.next().is_none());
+    assert_eq!(rest.skipped(), 0);
+}
+
+#[test]
+fn test_read_full_lenient_spans_archive() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&dummy_event("a")).unwrap();
+    append_raw(dir.path(), b"garbage\n");
+    log.rotate().unwrap();
+    append_raw(dir.path(), b"more garbage\n");
+    log.append(&dummy_event("b")).unwrap();
+
+    let mut events = log.reader().read_full_lenient().unwrap();
+    let types: Vec<String> = events.by_ref().map(|r| r.unwrap().0.event_type).collect();
+    assert_eq!(types, ["a", "b"]);
+    assert_eq!(events.skipped(), 2);
+}
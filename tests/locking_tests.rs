@@ -2,6 +2,7 @@ mod common;
 
 use common::dummy_event;
 use eventfold::{EventLog, EventReader, EventWriter, LockMode};
+use std::time::{Duration, Instant};
 use tempfile::tempdir;
 
 #[test]
@@ -157,3 +158,65 @@ fn test_builder_default_locks() {
         std::io::ErrorKind::AlreadyExists
     );
 }
+
+#[test]
+fn test_shared_reader_locks_coexist_with_writer() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    assert!(!log.readers_present().unwrap());
+
+    // Readers lock before and alongside the writer without conflict.
+    let reader = EventReader::new(dir.path());
+    let lock_a = reader.try_lock_shared().unwrap();
+    let lock_b = log.reader().try_lock_shared().unwrap();
+    assert!(log.readers_present().unwrap());
+    log.append(&dummy_event("event_0")).unwrap();
+
+    drop(lock_a);
+    assert!(log.readers_present().unwrap());
+    drop(lock_b);
+    assert!(!log.readers_present().unwrap());
+
+    // The writer's own exclusive lock is unaffected.
+    assert!(EventWriter::open(dir.path()).is_err());
+}
+
+#[test]
+fn test_blocking_lock_waits_for_release() {
+    let dir = tempdir().unwrap();
+    let mut first = EventWriter::open(dir.path()).unwrap();
+    first.append(&dummy_event("event_0")).unwrap();
+
+    let releaser = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(100));
+        drop(first);
+    });
+
+    let mode = LockMode::FlockBlocking {
+        timeout: Duration::from_secs(10),
+    };
+    let mut second = EventWriter::open_with_lock(dir.path(), mode).unwrap();
+    second.append(&dummy_event("event_1")).unwrap();
+    releaser.join().unwrap();
+    assert_eq!(second.reader().read_from(0).unwrap().count(), 2);
+
+    // Lock is still released on drop.
+    drop(second);
+    let _third = EventWriter::open(dir.path()).unwrap();
+}
+
+#[test]
+fn test_blocking_lock_times_out() {
+    let dir = tempdir().unwrap();
+    let _writer = EventLog::open(dir.path()).unwrap();
+
+    let start = Instant::now();
+    let err = EventLog::builder(dir.path())
+        .lock_mode(LockMode::FlockBlocking {
+            timeout: Duration::from_millis(50),
+        })
+        .open()
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
@@ -467,6 +467,7 @@ fn test_append_result_hash_deterministic() {
 }
 
 #[test]
+#[cfg(feature = "zstd")]
 fn test_paths_correct() {
     let dir = tempdir().unwrap();
     let log = EventLog::open(dir.path()).unwrap();
@@ -476,3 +477,113 @@ fn test_paths_correct() {
     assert_eq!(log.archive_path(), dir.path().join("archive.jsonl.zst"));
     assert_eq!(log.views_dir(), dir.path().join("views"));
 }
+
+fn write_with_bom(dir: &std::path::Path, lines: &[&str]) {
+    let mut contents = String::from("\u{feff}");
+    for line in lines {
+        contents.push_str(line);
+        contents.push('\n');
+    }
+    std::fs::write(dir.join("app.jsonl"), contents).unwrap();
+}
+
+#[test]
+fn test_leading_bom_is_stripped() {
+    let dir = tempdir().unwrap();
+    write_with_bom(
+        dir.path(),
+        &[
+            r#"{"type":"first","data":{},"ts":1}"#,
+            r#"{"type":"second","data":{},"ts":2}"#,
+        ],
+    );
+    let log = EventLog::open(dir.path()).unwrap();
+
+    let events: Vec<_> = log.read_from(0).unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].0.event_type, "first");
+    // Offsets still count the BOM's bytes.
+    assert_eq!(events[1].1, log.active_log_size().unwrap());
+
+    let full: Vec<_> = log.read_full().unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(full.len(), 2);
+    assert_eq!(full[0].0.event_type, "first");
+    // The hash covers the line without the BOM, wherever it is computed.
+    assert_eq!(full[0].1, events[0].2);
+    assert_eq!(
+        log.read_line_hash_before(events[0].1).unwrap(),
+        Some(events[0].2.clone())
+    );
+}
+
+#[test]
+fn test_bom_mid_file_is_not_stripped() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 1);
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(log.log_path())
+        .unwrap();
+    file.write_all(b"\xef\xbb\xbf{\"type\":\"x\",\"data\":{},\"ts\":1}\n")
+        .unwrap();
+
+    let results: Vec<_> = log.read_from(0).unwrap().collect();
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
+
+#[test]
+fn test_whitespace_only_lines_are_skipped() {
+    let dir = tempdir().unwrap();
+    write_with_bom(
+        dir.path(),
+        &["   ", r#"{"type":"a","data":{},"ts":1}"#, "\t "],
+    );
+    let log = EventLog::open(dir.path()).unwrap();
+
+    let from: Vec<_> = log.read_from(0).unwrap().collect::<Result<_, _>>().unwrap();
+    let full: Vec<_> = log.read_full().unwrap().collect::<Result<_, _>>().unwrap();
+
+    assert_eq!(from.len(), 1);
+    assert_eq!(full.len(), 1);
+    assert_eq!(from[0].0.event_type, "a");
+}
+
+#[test]
+fn test_count_from_skips_partial_and_blank_lines() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+    let second = log.read_from(0).unwrap().next().unwrap().unwrap().1;
+    {
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(log.log_path())
+            .unwrap();
+        write!(file, "\n  \n{{\"type\":\"partial\"").unwrap();
+    }
+
+    let reader = log.reader();
+    assert_eq!(reader.count_from(0).unwrap(), 3);
+    assert_eq!(reader.count_from(second).unwrap(), 2);
+}
+
+#[test]
+fn test_count_full_includes_archive() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 4);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+
+    let reader = log.reader();
+    assert_eq!(reader.count_from(0).unwrap(), 2);
+    assert_eq!(reader.count_full().unwrap(), 6);
+    assert_eq!(
+        reader.count_full().unwrap(),
+        log.read_full().unwrap().count() as u64
+    );
+}
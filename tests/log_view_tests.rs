@@ -0,0 +1,166 @@
+mod common;
+
+use common::{append_n, dummy_event};
+use eventfold::{Event, EventLog, EventWriter, LogView};
+use std::fs;
+use std::io::Write;
+use tempfile::tempdir;
+
+fn one_line_per_event(event: &Event, out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "{}", event.event_type)
+}
+
+fn output_lines(view: &LogView) -> Vec<String> {
+    fs::read_to_string(view.output_path())
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_log_view_writes_one_line_per_event() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    for t in ["a", "b", "c"] {
+        writer.append(&dummy_event(t)).unwrap();
+    }
+
+    let mut view = LogView::new("feed", one_line_per_event, writer.views_dir());
+    let processed = view.refresh(&writer.reader()).unwrap();
+
+    assert_eq!(processed, 3);
+    assert_eq!(output_lines(&view), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn test_log_view_incremental_refresh_appends() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("a")).unwrap();
+
+    let mut view = LogView::new("feed", one_line_per_event, writer.views_dir());
+    view.refresh(&writer.reader()).unwrap();
+
+    writer.append(&dummy_event("b")).unwrap();
+    writer.append(&dummy_event("c")).unwrap();
+    let processed = view.refresh(&writer.reader()).unwrap();
+
+    assert_eq!(processed, 2);
+    assert_eq!(output_lines(&view), vec!["a", "b", "c"]);
+    assert_eq!(view.refresh(&writer.reader()).unwrap(), 0);
+}
+
+#[test]
+fn test_log_view_resumes_from_checkpoint() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("a")).unwrap();
+
+    let mut view = LogView::new("feed", one_line_per_event, writer.views_dir());
+    view.refresh(&writer.reader()).unwrap();
+    drop(view);
+
+    writer.append(&dummy_event("b")).unwrap();
+    let mut view = LogView::new("feed", one_line_per_event, writer.views_dir());
+    let processed = view.refresh(&writer.reader()).unwrap();
+
+    assert_eq!(processed, 1);
+    assert_eq!(output_lines(&view), vec!["a", "b"]);
+}
+
+#[test]
+fn test_log_view_truncates_output_past_checkpoint() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("a")).unwrap();
+
+    let mut view = LogView::new("feed", one_line_per_event, writer.views_dir());
+    view.refresh(&writer.reader()).unwrap();
+
+    // Simulate a crash after output was written but before the checkpoint.
+    writer.append(&dummy_event("b")).unwrap();
+    let mut out = fs::OpenOptions::new()
+        .append(true)
+        .open(view.output_path())
+        .unwrap();
+    writeln!(out, "b").unwrap();
+
+    let mut view = LogView::new("feed", one_line_per_event, writer.views_dir());
+    view.refresh(&writer.reader()).unwrap();
+
+    assert_eq!(output_lines(&view), vec!["a", "b"]);
+}
+
+#[test]
+fn test_log_view_rebuild_truncates_and_replays() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("a")).unwrap();
+    writer.append(&dummy_event("b")).unwrap();
+
+    let mut view = LogView::new("feed", one_line_per_event, writer.views_dir());
+    view.refresh(&writer.reader()).unwrap();
+    let processed = view.rebuild(&writer.reader()).unwrap();
+
+    assert_eq!(processed, 2);
+    assert_eq!(output_lines(&view), vec!["a", "b"]);
+}
+
+#[test]
+fn test_log_view_rebuilds_on_hash_mismatch() {
+    let dir = tempdir().unwrap();
+    let log_path = dir.path().join("app.jsonl");
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("a")).unwrap();
+
+    let mut view = LogView::new("feed", one_line_per_event, writer.views_dir());
+    view.refresh(&writer.reader()).unwrap();
+
+    // Replace the log with a different event of the same length.
+    let replaced = fs::read_to_string(&log_path)
+        .unwrap()
+        .replace("\"a\"", "\"z\"");
+    fs::write(&log_path, replaced).unwrap();
+
+    let mut view = LogView::new("feed", one_line_per_event, writer.views_dir());
+    view.refresh(&writer.reader()).unwrap();
+
+    assert_eq!(output_lines(&view), vec!["z"]);
+}
+
+#[test]
+fn test_registered_log_view_survives_rotation() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .log_view("feed", one_line_per_event)
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    log.refresh_all().unwrap();
+
+    let view = log.log_view("feed").unwrap();
+    assert_eq!(output_lines(view).len(), 5);
+    assert_eq!(view.offset(), log.active_log_size().unwrap());
+}
+
+#[test]
+fn test_log_view_lookup_errors() {
+    let dir = tempdir().unwrap();
+    let log = EventLog::builder(dir.path())
+        .view::<u64>("counter", common::counter_reducer)
+        .open()
+        .unwrap();
+
+    assert_eq!(
+        log.log_view("missing").unwrap_err().kind(),
+        std::io::ErrorKind::NotFound
+    );
+    assert_eq!(
+        log.log_view("counter").unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::append_n;
+#[cfg(all(feature = "zstd", feature = "sha256"))]
+use eventfold::HashAlgo;
+use eventfold::{Codec, EventLog, EventReader, Manifest};
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+fn read_manifest(dir: &std::path::Path) -> serde_json::Value {
+    serde_json::from_slice(&std::fs::read(dir.join("manifest.json")).unwrap()).unwrap()
+}
+
+#[test]
+#[cfg(all(feature = "zstd", feature = "sha256"))]
+fn test_manifest_written_on_first_open() {
+    let dir = tempdir().unwrap();
+    let log = EventLog::builder(dir.path())
+        .hash_algo(HashAlgo::Sha256)
+        .open()
+        .unwrap();
+    assert_eq!(log.manifest().hash_algo, HashAlgo::Sha256);
+
+    let json = read_manifest(dir.path());
+    assert_eq!(json["format_version"], Manifest::FORMAT_VERSION);
+    assert_eq!(json["hash_algo"], "sha256");
+    assert_eq!(json["archive_codec"], "zstd");
+    assert_eq!(json["snapshot_format"], Manifest::SNAPSHOT_FORMAT);
+}
+
+#[test]
+fn test_newer_format_is_rejected() {
+    let dir = tempdir().unwrap();
+    drop(EventLog::open(dir.path()).unwrap());
+    let mut json = read_manifest(dir.path());
+    json["format_version"] = (Manifest::FORMAT_VERSION + 1).into();
+    std::fs::write(dir.path().join("manifest.json"), json.to_string()).unwrap();
+
+    let err = EventLog::open(dir.path()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("newer"));
+    let err = EventLog::open_readonly(dir.path()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_directory_without_manifest_is_adopted() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .archive_codec(Codec::None)
+            .open()
+            .unwrap();
+        append_n(&mut log, 2);
+        log.rotate().unwrap();
+        append_n(&mut log, 1);
+    }
+    std::fs::remove_file(dir.path().join("manifest.json")).unwrap();
+
+    let log = EventLog::open_readonly(dir.path()).unwrap();
+    assert_eq!(log.manifest().archive_codec, Codec::None);
+    assert!(!dir.path().join("manifest.json").exists());
+    drop(log);
+
+    let log = EventLog::open(dir.path()).unwrap();
+    assert_eq!(log.manifest().archive_codec, Codec::None);
+    assert_eq!(log.read_full().unwrap().count(), 3);
+    assert_eq!(read_manifest(dir.path())["archive_codec"], "none");
+}
+
+#[test]
+fn test_codec_recorded_before_first_rotation() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .archive_codec(Codec::None)
+        .open()
+        .unwrap();
+    append_n(&mut log, 2);
+    assert_eq!(read_manifest(dir.path())["archive_codec"], "none");
+
+    log.rotate().unwrap();
+    drop(log);
+    assert_eq!(EventReader::new(dir.path()).read_full().unwrap().count(), 2);
+
+    let err = EventLog::builder(dir.path())
+        .archive_codec(Codec::Zstd)
+        .open()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
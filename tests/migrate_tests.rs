@@ -0,0 +1,68 @@
+mod common;
+
+use common::append_n;
+use eventfold::{Codec, EventLog, Manifest, MigrationReport};
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+#[test]
+fn test_migrate_legacy_directory_is_idempotent() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .archive_codec(Codec::None)
+            .open()
+            .unwrap();
+        append_n(&mut log, 2);
+        log.rotate().unwrap();
+    }
+    std::fs::remove_file(dir.path().join("manifest.json")).unwrap();
+    let active = std::fs::read(dir.path().join("app.jsonl")).unwrap();
+    let archive = std::fs::read(dir.path().join("archive.jsonl")).unwrap();
+
+    let report = EventLog::migrate(dir.path(), 1).unwrap();
+    assert_eq!(
+        report,
+        MigrationReport {
+            from_version: 0,
+            to_version: 1,
+        }
+    );
+    assert!(!report.is_noop());
+    assert_eq!(std::fs::read(dir.path().join("app.jsonl")).unwrap(), active);
+    assert_eq!(
+        std::fs::read(dir.path().join("archive.jsonl")).unwrap(),
+        archive
+    );
+
+    let log = EventLog::open(dir.path()).unwrap();
+    assert_eq!(log.manifest().format_version, 1);
+    assert_eq!(log.manifest().archive_codec, Codec::None);
+    assert_eq!(log.read_full().unwrap().count(), 2);
+    drop(log);
+
+    assert!(EventLog::migrate(dir.path(), 1).unwrap().is_noop());
+}
+
+#[test]
+fn test_migrate_refuses_downgrade_and_unknown_versions() {
+    let dir = tempdir().unwrap();
+    drop(EventLog::open(dir.path()).unwrap());
+
+    let err = EventLog::migrate(dir.path(), 0).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(err.to_string().contains("downgrade"));
+
+    let err = EventLog::migrate(dir.path(), Manifest::FORMAT_VERSION + 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_migrate_requires_an_idle_log() {
+    let dir = tempdir().unwrap();
+    let err = EventLog::migrate(dir.path(), 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    let _log = EventLog::open(dir.path()).unwrap();
+    assert!(EventLog::migrate(dir.path(), 1).is_err());
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::{append_n, dummy_event};
+use eventfold::{EventLog, EventReader, EventWriter};
+use std::fs::OpenOptions;
+use std::io::Write;
+use tempfile::tempdir;
+
+fn mapped(reader: EventReader) -> EventReader {
+    // These tests never truncate a log while reading it.
+    reader.with_mmap()
+}
+
+fn types(reader: &EventReader, offset: u64) -> Vec<String> {
+    reader
+        .read_from(offset)
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect()
+}
+
+#[test]
+fn test_mmap_reads_match_buffered_reads() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+    let plain = log.reader();
+    let reader = mapped(log.reader());
+
+    let expected: Vec<_> = plain.read_from(0).unwrap().map(Result::unwrap).collect();
+    let actual: Vec<_> = reader.read_from(0).unwrap().map(Result::unwrap).collect();
+    assert_eq!(actual, expected);
+
+    let first_end = expected[0].1;
+    let ranged: Vec<_> = reader
+        .read_range(first_end, expected[1].1)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(ranged, expected[1..2]);
+}
+
+#[test]
+fn test_mmap_remaps_after_growth() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    let reader = mapped(writer.reader());
+    assert!(types(&reader, 0).is_empty());
+
+    writer.append(&dummy_event("a")).unwrap();
+    assert_eq!(types(&reader, 0), ["a"]);
+
+    let offset = writer.append(&dummy_event("b")).unwrap().end_offset;
+    writer.append(&dummy_event("c")).unwrap();
+    assert_eq!(types(&reader, 0), ["a", "b", "c"]);
+    assert_eq!(types(&reader.clone(), offset), ["c"]);
+}
+
+#[test]
+fn test_mmap_skips_partial_last_line() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("a")).unwrap();
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(dir.path().join("app.jsonl"))
+        .unwrap();
+    file.write_all(b"{\"type\":\"partial\"").unwrap();
+
+    let reader = mapped(writer.reader());
+    assert_eq!(types(&reader, 0), ["a"]);
+}
+
+#[test]
+fn test_mmap_remaps_after_rotation() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+    let reader = mapped(log.reader());
+    assert_eq!(types(&reader, 0).len(), 3);
+
+    log.rotate().unwrap();
+    log.append(&dummy_event("after")).unwrap();
+    assert_eq!(types(&reader, 0), ["after"]);
+}
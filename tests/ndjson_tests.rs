@@ -0,0 +1,76 @@
+mod common;
+
+use common::{append_n, dummy_event, full_history};
+use eventfold::EventLog;
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+#[test]
+fn test_export_import_round_trip() {
+    let src = tempdir().unwrap();
+    let dst = tempdir().unwrap();
+    let mut log = EventLog::open(src.path()).unwrap();
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    log.append(&dummy_event("active").with_id("e-1").with_actor("ops"))
+        .unwrap();
+
+    let mut backup = Vec::new();
+    log.export_ndjson(&mut backup).unwrap();
+    assert_eq!(backup.iter().filter(|&&b| b == b'\n').count(), 4);
+
+    let mut restored = EventLog::open(dst.path()).unwrap();
+    assert_eq!(restored.import_ndjson(backup.as_slice()).unwrap(), 4);
+
+    // Same events, byte for byte: the line hashes match too.
+    assert_eq!(full_history(&restored), full_history(&log));
+}
+
+#[test]
+fn test_import_skips_blank_lines_and_accepts_missing_final_newline() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let input = "\u{feff}{\"type\":\"a\",\"data\":{},\"ts\":1}\r\n\n   \n{\"type\":\"b\",\"data\":{},\"ts\":2}";
+
+    assert_eq!(log.import_ndjson(input.as_bytes()).unwrap(), 2);
+    let types: Vec<String> = full_history(&log)
+        .into_iter()
+        .map(|(e, _)| e.event_type)
+        .collect();
+    assert_eq!(types, ["a", "b"]);
+}
+
+#[test]
+fn test_import_stops_at_first_invalid_line() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let input = concat!(
+        "{\"type\":\"a\",\"data\":{},\"ts\":1}\n",
+        "{\"type\":\"b\",\"data\":{},\"ts\":2}\n",
+        "{\"type\":\"c\"}\n",
+        "{\"type\":\"d\",\"data\":{},\"ts\":4}\n",
+    );
+
+    let err = log.import_ndjson(input.as_bytes()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.to_string().contains("line 3"), "{err}");
+
+    let types: Vec<String> = full_history(&log)
+        .into_iter()
+        .map(|(e, _)| e.event_type)
+        .collect();
+    assert_eq!(types, ["a", "b"]);
+    assert!(std::fs::read(log.log_path()).unwrap().ends_with(b"\n"));
+}
+
+#[test]
+fn test_import_large_input_spans_batches() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let input: String = (0..2500)
+        .map(|i| format!("{{\"type\":\"e{i}\",\"data\":{{}},\"ts\":{i}}}\n"))
+        .collect();
+
+    assert_eq!(log.import_ndjson(input.as_bytes()).unwrap(), 2500);
+    assert_eq!(log.read_from(0).unwrap().count(), 2500);
+}
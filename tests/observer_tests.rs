@@ -0,0 +1,120 @@
+mod common;
+
+use common::{append_n, counter_reducer, dummy_event};
+use eventfold::{AppendResult, EventLog, LogObserver, RebuildReason};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tempfile::tempdir;
+
+#[derive(Default)]
+struct Recorder(Mutex<Vec<String>>);
+
+impl Recorder {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+impl LogObserver for Recorder {
+    fn on_append(&self, result: &AppendResult) {
+        let note = format!("append {}", result.sequence);
+        self.0.lock().unwrap().push(note);
+    }
+
+    fn on_rotate(&self, archived_bytes: u64) {
+        let note = format!("rotate {archived_bytes}");
+        self.0.lock().unwrap().push(note);
+    }
+
+    fn on_view_rebuild(&self, name: &str, reason: RebuildReason) {
+        let note = format!("rebuild {name} {reason:?}");
+        self.0.lock().unwrap().push(note);
+    }
+
+    fn on_refresh(&self, name: &str, applied: u64) {
+        let note = format!("refresh {name} {applied}");
+        self.0.lock().unwrap().push(note);
+    }
+}
+
+fn open_observed(dir: &Path, recorder: &Arc<Recorder>) -> EventLog {
+    EventLog::builder(dir)
+        .view::<u64>("counter", counter_reducer)
+        .observer(recorder.clone())
+        .open()
+        .unwrap()
+}
+
+#[test]
+fn test_observer_sees_appends_and_batches() {
+    let dir = tempdir().unwrap();
+    let recorder = Arc::new(Recorder::default());
+    let mut log = open_observed(dir.path(), &recorder);
+
+    log.append(&dummy_event("a")).unwrap();
+    log.append_batch(&[dummy_event("b"), dummy_event("c")])
+        .unwrap();
+    assert_eq!(recorder.take(), ["append 0", "append 1", "append 2"]);
+}
+
+#[test]
+fn test_observer_sees_refresh_rotate_and_rebuilds() {
+    let dir = tempdir().unwrap();
+    let recorder = Arc::new(Recorder::default());
+    let mut log = open_observed(dir.path(), &recorder);
+    append_n(&mut log, 2);
+    let size = log.active_log_size().unwrap();
+    recorder.take();
+
+    log.refresh_all().unwrap();
+    log.refresh_all().unwrap();
+    assert_eq!(
+        recorder.take(),
+        [
+            "rebuild counter MissingSnapshot",
+            "refresh counter 2",
+            "refresh counter 0",
+        ]
+    );
+
+    log.rotate().unwrap();
+    assert_eq!(
+        recorder.take(),
+        ["refresh counter 0", format!("rotate {size}").as_str()]
+    );
+
+    log.compact(|_| true).unwrap();
+    log.append(&dummy_event("c")).unwrap();
+    log.compact(|e| e.event_type != "c").unwrap();
+    assert_eq!(
+        recorder.take(),
+        ["append 2", "rebuild counter Requested", "refresh counter 2"]
+    );
+}
+
+#[test]
+fn test_observer_sees_integrity_rebuild() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .view::<u64>("counter", counter_reducer)
+            .open()
+            .unwrap();
+        append_n(&mut log, 2);
+        log.refresh_all().unwrap();
+    }
+    let snapshot_path = dir.path().join("views/counter.snapshot.json");
+    let mut snapshot: Value = serde_json::from_slice(&fs::read(&snapshot_path).unwrap()).unwrap();
+    snapshot["hash"] = json!("0000000000000000");
+    fs::write(&snapshot_path, snapshot.to_string()).unwrap();
+
+    let recorder = Arc::new(Recorder::default());
+    let mut log = open_observed(dir.path(), &recorder);
+    log.refresh_all().unwrap();
+    assert_eq!(
+        recorder.take(),
+        ["rebuild counter HashMismatch", "refresh counter 2"]
+    );
+}
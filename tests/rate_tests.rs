@@ -0,0 +1,81 @@
+mod common;
+
+use common::append_at;
+use eventfold::EventWriter;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[test]
+fn test_rate_counts_events_within_window() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    // Old burst well outside the window.
+    for _ in 0..50 {
+        append_at(&mut writer, "tick", 100);
+    }
+    // 3 events per second for ts 200..=209.
+    for ts in 200..210 {
+        for _ in 0..3 {
+            append_at(&mut writer, "tick", ts);
+        }
+    }
+
+    let rate = writer.reader().rate(Duration::from_secs(5)).unwrap();
+
+    // ts 205..=209 are within 5s of the latest (209): 15 events / 5s.
+    assert_eq!(rate, 3.0);
+}
+
+#[test]
+fn test_rate_across_many_chunks() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    // 10 events per second over 200 seconds — several backward-scan chunks.
+    for i in 0..2000u64 {
+        append_at(&mut writer, "tick", 1000 + i / 10);
+    }
+
+    let rate = writer.reader().rate(Duration::from_secs(60)).unwrap();
+
+    assert_eq!(rate, 10.0);
+}
+
+#[test]
+fn test_rate_ignores_trailing_partial_line() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    append_at(&mut writer, "tick", 10);
+    append_at(&mut writer, "tick", 11);
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(writer.log_path())
+        .unwrap();
+    write!(file, r#"{{"type":"partial","data":{{}},"ts":99"#).unwrap();
+
+    let rate = writer.reader().rate(Duration::from_secs(2)).unwrap();
+
+    assert_eq!(rate, 1.0);
+}
+
+#[test]
+fn test_rate_empty_log_is_zero() {
+    let dir = tempdir().unwrap();
+    let writer = EventWriter::open(dir.path()).unwrap();
+
+    assert_eq!(writer.reader().rate(Duration::from_secs(10)).unwrap(), 0.0);
+}
+
+#[test]
+fn test_rate_rejects_subsecond_window() {
+    let dir = tempdir().unwrap();
+    let writer = EventWriter::open(dir.path()).unwrap();
+
+    let err = writer
+        .reader()
+        .rate(Duration::from_millis(500))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
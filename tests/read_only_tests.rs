@@ -0,0 +1,130 @@
+mod common;
+
+use common::{append_n, counter_reducer, dummy_event};
+use eventfold::{EventLog, Snapshot, snapshot};
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+fn open_read_only(dir: &std::path::Path) -> EventLog {
+    EventLog::builder(dir)
+        .read_only(true)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap()
+}
+
+#[test]
+fn test_read_only_coexists_with_writer() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventLog::open(dir.path()).unwrap();
+    append_n(&mut writer, 3);
+
+    let mut first = open_read_only(dir.path());
+    let mut second = open_read_only(dir.path());
+    first.refresh_all().unwrap();
+    second.refresh_all().unwrap();
+    assert_eq!(*first.view::<u64>("counter").unwrap(), 3);
+
+    append_n(&mut writer, 2);
+    first.refresh_all().unwrap();
+    assert_eq!(*first.view::<u64>("counter").unwrap(), 5);
+}
+
+#[test]
+fn test_read_only_rejects_writes() {
+    let dir = tempdir().unwrap();
+    append_n(&mut EventLog::open(dir.path()).unwrap(), 2);
+    let size = std::fs::metadata(dir.path().join("app.jsonl"))
+        .unwrap()
+        .len();
+
+    let mut log = EventLog::open_readonly(dir.path()).unwrap();
+    let denied = |result: std::io::Result<()>| result.unwrap_err().kind();
+    assert_eq!(
+        denied(log.append(&dummy_event("x")).map(drop)),
+        ErrorKind::PermissionDenied
+    );
+    assert_eq!(denied(log.rotate()), ErrorKind::PermissionDenied);
+    assert_eq!(
+        denied(log.compact(|e| e.event_type != "event_0").map(drop)),
+        ErrorKind::PermissionDenied
+    );
+    assert_eq!(
+        std::fs::metadata(dir.path().join("app.jsonl"))
+            .unwrap()
+            .len(),
+        size
+    );
+    assert!(!dir.path().join("archive.jsonl.zst").exists());
+}
+
+#[test]
+fn test_read_only_does_not_write_snapshots() {
+    let dir = tempdir().unwrap();
+    append_n(&mut EventLog::open(dir.path()).unwrap(), 2);
+    let path = dir.path().join("views/counter.snapshot.json");
+
+    let mut log = open_read_only(dir.path());
+    log.refresh_all().unwrap();
+    drop(log);
+    assert!(!path.exists());
+
+    let mut log = EventLog::builder(dir.path())
+        .read_only(true)
+        .write_snapshots(true)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    log.refresh_all().unwrap();
+    let saved: Snapshot<u64> = snapshot::load(&path).unwrap().unwrap();
+    assert_eq!(saved.state, 2);
+}
+
+#[test]
+fn test_read_only_missing_log() {
+    let dir = tempdir().unwrap();
+    let err = EventLog::open_readonly(dir.path().join("missing")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(!dir.path().join("missing").exists());
+}
+
+#[test]
+fn test_read_only_keeps_snapshots_in_its_own_dir() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    append_n(&mut writer, 3);
+    writer.refresh_all().unwrap();
+    let writer_snapshot = dir.path().join("views/counter.snapshot.json");
+    let before = std::fs::read(&writer_snapshot).unwrap();
+
+    let reader_dir = dir.path().join("views-reader");
+    append_n(&mut writer, 2);
+    {
+        let mut reader = EventLog::builder(dir.path())
+            .read_only(true)
+            .view_snapshot_dir(&reader_dir)
+            .view::<u64>("counter", counter_reducer)
+            .open()
+            .unwrap();
+        reader.refresh_all().unwrap();
+        assert_eq!(*reader.view::<u64>("counter").unwrap(), 5);
+    }
+
+    assert_eq!(std::fs::read(&writer_snapshot).unwrap(), before);
+    let saved: Snapshot<u64> = snapshot::load(&reader_dir.join("counter.snapshot.json"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(saved.state, 5);
+
+    let mut reader = EventLog::builder(dir.path())
+        .read_only(true)
+        .view_snapshot_dir(&reader_dir)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    reader.refresh_all().unwrap();
+    assert_eq!(*reader.view::<u64>("counter").unwrap(), 5);
+}
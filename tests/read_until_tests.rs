@@ -0,0 +1,74 @@
+mod common;
+
+use common::{append_n, dummy_event};
+use eventfold::{EventLog, StopAt};
+use tempfile::tempdir;
+
+fn types(events: &[eventfold::Event]) -> Vec<&str> {
+    events.iter().map(|e| e.event_type.as_str()).collect()
+}
+
+#[test]
+fn test_read_until_stops_before_or_after_match() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&dummy_event("a")).unwrap();
+    let checkpoint = log.append(&dummy_event("checkpoint")).unwrap();
+    log.append(&dummy_event("b")).unwrap();
+    let reader = log.reader();
+    let is_checkpoint = |e: &eventfold::Event| e.event_type == "checkpoint";
+
+    let before = reader.read_until(0, StopAt::Before, is_checkpoint).unwrap();
+    assert_eq!(types(&before.events), ["a"]);
+    assert_eq!(before.resume, checkpoint.start_offset);
+    assert!(before.stopped);
+
+    let after = reader.read_until(0, StopAt::After, is_checkpoint).unwrap();
+    assert_eq!(types(&after.events), ["a", "checkpoint"]);
+    assert_eq!(after.resume, checkpoint.end_offset);
+
+    let rest = reader
+        .read_until(after.resume, StopAt::Before, is_checkpoint)
+        .unwrap();
+    assert_eq!(types(&rest.events), ["b"]);
+    assert_eq!(rest.resume, log.active_log_size().unwrap());
+    assert!(!rest.stopped);
+
+    let none = reader
+        .read_until(rest.resume, StopAt::After, |_| true)
+        .unwrap();
+    assert!(none.events.is_empty());
+    assert_eq!(none.resume, rest.resume);
+}
+
+#[test]
+fn test_read_full_until_resumes_by_sequence_across_rotation() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 2);
+    log.append(&dummy_event("checkpoint")).unwrap();
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    log.append(&dummy_event("checkpoint")).unwrap();
+    let reader = log.reader();
+    let is_checkpoint = |e: &eventfold::Event| e.event_type == "checkpoint";
+
+    let first = reader
+        .read_full_until(0, StopAt::Before, is_checkpoint)
+        .unwrap();
+    assert_eq!(first.events.len(), 2);
+    assert_eq!(first.resume, 2);
+
+    let second = reader
+        .read_full_until(first.resume + 1, StopAt::After, is_checkpoint)
+        .unwrap();
+    assert_eq!(types(&second.events), ["event_0", "event_1", "checkpoint"]);
+    assert_eq!(second.resume, 6);
+    assert!(second.stopped);
+
+    let end = reader
+        .read_full_until(second.resume, StopAt::After, is_checkpoint)
+        .unwrap();
+    assert!(end.events.is_empty() && !end.stopped);
+    assert_eq!(end.resume, 6);
+}
@@ -1,7 +1,7 @@
 mod common;
 
 use common::{append_n, counter_reducer, dummy_event};
-use eventfold::{EventLog, EventReader, EventWriter, View};
+use eventfold::{Event, EventLog, EventReader, EventWriter, View};
 use std::collections::HashMap;
 use tempfile::tempdir;
 
@@ -216,3 +216,109 @@ fn test_eventlog_reader_method() {
     assert_eq!(events[0].0.event_type, "event_0");
     assert_eq!(events[2].0.event_type, "event_2");
 }
+
+#[test]
+fn test_read_range_end_on_line_boundary() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    let results: Vec<_> = (0..5)
+        .map(|i| writer.append(&dummy_event(&format!("e{i}"))).unwrap())
+        .collect();
+
+    let events: Vec<_> = writer
+        .reader()
+        .read_range(results[1].start_offset, results[3].end_offset)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let types: Vec<_> = events
+        .iter()
+        .map(|(e, _, _)| e.event_type.as_str())
+        .collect();
+    assert_eq!(types, ["e1", "e2", "e3"]);
+    assert_eq!(events[2].1, results[3].end_offset);
+    assert_eq!(events[2].2, results[3].line_hash);
+}
+
+#[test]
+fn test_read_range_end_mid_line() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    let results: Vec<_> = (0..5)
+        .map(|i| writer.append(&dummy_event(&format!("e{i}"))).unwrap())
+        .collect();
+    let reader = writer.reader();
+
+    // Cutting through e3 — even one byte short of its newline — stops before it.
+    for end in [results[3].start_offset + 1, results[3].end_offset - 1] {
+        let types: Vec<_> = reader
+            .read_range(0, end)
+            .unwrap()
+            .map(|r| r.unwrap().0.event_type)
+            .collect();
+        assert_eq!(types, ["e0", "e1", "e2"]);
+    }
+
+    // An end past EOF reads to EOF, like read_from.
+    assert_eq!(reader.read_range(0, u64::MAX).unwrap().count(), 5);
+}
+
+#[test]
+fn test_read_raw_from_matches_read_from() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    for i in 0..3 {
+        writer.append(&dummy_event(&format!("e{i}"))).unwrap();
+    }
+    // A blank line, a malformed line and a partial trailing line.
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(dir.path().join("app.jsonl"))
+        .unwrap();
+    std::io::Write::write_all(&mut file, b"\nnot json\n{\"partial").unwrap();
+
+    let reader = writer.reader();
+    let raw: Vec<_> = reader
+        .read_raw_from(0)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(raw.len(), 4);
+    assert_eq!(raw[3].0, "not json");
+
+    for (parsed, (line, next, hash)) in reader.read_from(0).unwrap().zip(&raw).take(3) {
+        let (event, parsed_next, parsed_hash) = parsed.unwrap();
+        assert_eq!(serde_json::from_str::<Event>(line).unwrap(), event);
+        assert_eq!((*next, hash), (parsed_next, &parsed_hash));
+    }
+
+    let tail: Vec<_> = reader
+        .read_raw_from(raw[1].1)
+        .unwrap()
+        .map(|r| r.unwrap().0)
+        .collect();
+    assert_eq!(tail, [raw[2].0.clone(), raw[3].0.clone()]);
+}
+
+#[test]
+fn test_read_raw_full_includes_archive() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+    append_n(&mut log, 1);
+
+    let raw: Vec<(String, String)> = log
+        .reader()
+        .read_raw_full()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let parsed: Vec<_> = log.read_full().unwrap().map(|r| r.unwrap()).collect();
+    assert_eq!(raw.len(), 3);
+    for ((line, hash), (event, parsed_hash)) in raw.iter().zip(&parsed) {
+        assert_eq!(&serde_json::from_str::<Event>(line).unwrap(), event);
+        assert_eq!(hash, parsed_hash);
+    }
+}
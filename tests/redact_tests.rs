@@ -0,0 +1,128 @@
+mod common;
+
+use common::append_n;
+#[cfg(feature = "zstd")]
+use eventfold::list_frames;
+use eventfold::{Event, EventLog, read_frame};
+use serde_json::json;
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+fn by(actor: &str) -> Event {
+    let mut event = Event::new("note", json!({"text": format!("from {actor}")})).with_actor(actor);
+    event.ts = 1000;
+    event
+}
+
+fn alice_reducer(state: u64, event: &Event) -> u64 {
+    state + u64::from(event.actor.as_deref() == Some("alice"))
+}
+
+fn is_alice(event: &Event) -> bool {
+    event.actor.as_deref() == Some("alice")
+}
+
+#[test]
+fn test_redact_rewrites_archive_and_active_log() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("alice", alice_reducer)
+        .open()
+        .unwrap();
+    log.append(&by("alice")).unwrap();
+    log.append(&by("bob")).unwrap();
+    log.rotate().unwrap();
+    log.append(&by("alice")).unwrap();
+    log.append(&by("bob")).unwrap();
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("alice").unwrap(), 2);
+
+    assert_eq!(log.redact(is_alice).unwrap(), 2);
+
+    let events: Vec<Event> = log.read_full().unwrap().map(|r| r.unwrap().0).collect();
+    assert_eq!(events.len(), 4);
+    for i in [0, 2] {
+        assert!(events[i].data.is_null());
+        assert_eq!(events[i].actor, None);
+        assert_eq!(events[i].ts, 1000);
+        assert_eq!(events[i].meta, Some(json!({"redacted": true})));
+    }
+    assert_eq!(events[1].data, json!({"text": "from bob"}));
+    assert_eq!(*log.view::<u64>("alice").unwrap(), 0);
+
+    let archived = read_frame(log.archive_path(), 0).unwrap();
+    assert!(!String::from_utf8(archived).unwrap().contains("alice"));
+    assert!(
+        !std::fs::read_to_string(log.log_path())
+            .unwrap()
+            .contains("alice")
+    );
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_redact_copies_untouched_frames() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&by("bob")).unwrap();
+    log.rotate().unwrap();
+    log.append(&by("alice")).unwrap();
+    log.rotate().unwrap();
+    let before = std::fs::read(log.archive_path()).unwrap();
+    let first = list_frames(log.archive_path()).unwrap()[0].clone();
+
+    assert_eq!(log.redact(is_alice).unwrap(), 1);
+
+    let after = std::fs::read(log.archive_path()).unwrap();
+    let range = first.compressed.start as usize..first.compressed.end as usize;
+    assert_eq!(after[range.clone()], before[range]);
+    assert_ne!(after, before);
+    assert_eq!(list_frames(log.archive_path()).unwrap().len(), 2);
+}
+
+#[test]
+fn test_redact_keeps_hash_chain_valid() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .hash_chain(true)
+        .open()
+        .unwrap();
+    log.append(&by("bob")).unwrap();
+    log.append(&by("alice")).unwrap();
+    log.rotate().unwrap();
+    log.append(&by("bob")).unwrap();
+    log.append(&by("alice")).unwrap();
+
+    assert_eq!(log.redact(is_alice).unwrap(), 2);
+    assert_eq!(log.verify_chain().unwrap(), Ok(()));
+
+    log.append(&by("bob")).unwrap();
+    assert_eq!(log.verify_chain().unwrap(), Ok(()));
+}
+
+#[test]
+fn test_redact_without_match_rewrites_nothing() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    let archive = std::fs::read(log.archive_path()).unwrap();
+    let active = std::fs::read(log.log_path()).unwrap();
+
+    assert_eq!(log.redact(is_alice).unwrap(), 0);
+    assert_eq!(std::fs::read(log.archive_path()).unwrap(), archive);
+    assert_eq!(std::fs::read(log.log_path()).unwrap(), active);
+}
+
+#[test]
+fn test_redact_read_only_is_rejected() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        log.append(&by("alice")).unwrap();
+    }
+    let mut log = EventLog::open_readonly(dir.path()).unwrap();
+    let err = log.redact(is_alice).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+}
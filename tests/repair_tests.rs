@@ -0,0 +1,97 @@
+mod common;
+
+use common::{append_n, counter_reducer, dummy_event};
+use eventfold::{EventLog, RepairMode, RepairReport};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn write_raw(path: &Path, bytes: &[u8]) {
+    let mut file = OpenOptions::new().append(true).open(path).unwrap();
+    file.write_all(bytes).unwrap();
+}
+
+fn open_counted(dir: &Path) -> EventLog {
+    EventLog::builder(dir)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap()
+}
+
+#[test]
+fn test_truncate_keeps_prefix_before_first_error() {
+    let dir = tempdir().unwrap();
+    let mut log = open_counted(dir.path());
+    append_n(&mut log, 2);
+    let good_size = log.active_log_size().unwrap();
+    write_raw(log.log_path(), b"{\"broken\n");
+    append_n(&mut log, 3);
+    write_raw(log.log_path(), b"also bad\n");
+    let total_size = log.active_log_size().unwrap();
+
+    let report = log.repair(RepairMode::TruncateAtFirstError).unwrap();
+    assert_eq!(
+        report,
+        RepairReport {
+            bytes_dropped: total_size - good_size,
+            invalid_lines: 2,
+            events_dropped: 3,
+        }
+    );
+    assert_eq!(log.active_log_size().unwrap(), good_size);
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 2);
+    assert!(log.verify().unwrap().is_ok());
+}
+
+#[test]
+fn test_drop_invalid_keeps_surrounding_events() {
+    let dir = tempdir().unwrap();
+    let mut log = open_counted(dir.path());
+    append_n(&mut log, 2);
+    write_raw(log.log_path(), b"garbage\n");
+    append_n(&mut log, 3);
+    write_raw(log.log_path(), b"{\"type\":\"partial\"");
+
+    let report = log.repair(RepairMode::DropInvalid).unwrap();
+    assert_eq!(report.invalid_lines, 1);
+    assert_eq!(report.events_dropped, 0);
+    assert_eq!(report.bytes_dropped, 8 + 17);
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 5);
+
+    let types: Vec<String> = log
+        .read_from(0)
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+    assert_eq!(
+        types,
+        ["event_0", "event_1", "event_0", "event_1", "event_2"]
+    );
+}
+
+#[test]
+fn test_repair_healthy_log_is_noop() {
+    let dir = tempdir().unwrap();
+    let mut log = open_counted(dir.path());
+    append_n(&mut log, 3);
+    let before = fs::read(log.log_path()).unwrap();
+
+    let report = log.repair(RepairMode::TruncateAtFirstError).unwrap();
+    assert!(report.is_empty());
+    assert_eq!(fs::read(log.log_path()).unwrap(), before);
+}
+
+#[test]
+fn test_repaired_log_accepts_appends_with_fresh_sequences() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 2);
+    write_raw(log.log_path(), b"garbage\n");
+    let before = log.append(&dummy_event("before")).unwrap().sequence;
+
+    log.repair(RepairMode::DropInvalid).unwrap();
+    let after = log.append(&dummy_event("after")).unwrap().sequence;
+    assert!(after > before);
+    assert_eq!(log.read_from(0).unwrap().count(), 4);
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::{append_n, counter_reducer, dummy_event, event_types};
+use eventfold::EventLog;
+use std::fs;
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+#[test]
+fn test_replace_dir_swaps_log_and_views() {
+    let root = tempdir().unwrap();
+    let current = root.path().join("data");
+    let staged = root.path().join("staged");
+    EventLog::open(&current)
+        .unwrap()
+        .append(&dummy_event("old"))
+        .unwrap();
+    {
+        let mut log = EventLog::builder(&staged)
+            .view::<u64>("counter", counter_reducer)
+            .open()
+            .unwrap();
+        append_n(&mut log, 3);
+        log.refresh_all().unwrap();
+    }
+
+    EventLog::replace_dir(&current, &staged).unwrap();
+    assert!(!staged.exists());
+    assert!(!root.path().join("data.old").exists());
+    assert_eq!(event_types(&EventLog::open(&current).unwrap()), ["event_0", "event_1", "event_2"]);
+    assert!(current.join("views/counter.snapshot.json").is_file());
+}
+
+#[test]
+fn test_replace_dir_refuses_while_writer_holds_lock() {
+    let root = tempdir().unwrap();
+    let current = root.path().join("data");
+    let staged = root.path().join("staged");
+    let mut writer = EventLog::open(&current).unwrap();
+    writer.append(&dummy_event("old")).unwrap();
+    drop(EventLog::open(&staged).unwrap());
+
+    let err = EventLog::replace_dir(&current, &staged).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    assert!(staged.join("app.jsonl").is_file());
+    drop(writer);
+    assert_eq!(event_types(&EventLog::open(&current).unwrap()), ["old"]);
+
+    // A writer on the staged directory blocks the swap too.
+    let _staged_writer = EventLog::open(&staged).unwrap();
+    let err = EventLog::replace_dir(&current, &staged).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+}
+
+#[test]
+fn test_replace_dir_into_missing_current() {
+    let root = tempdir().unwrap();
+    let current = root.path().join("data");
+    let staged = root.path().join("staged");
+    EventLog::open(&staged)
+        .unwrap()
+        .append(&dummy_event("new"))
+        .unwrap();
+
+    EventLog::replace_dir(&current, &staged).unwrap();
+    assert_eq!(event_types(&EventLog::open(&current).unwrap()), ["new"]);
+}
+
+#[test]
+fn test_replace_dir_rejects_non_log_directories() {
+    let root = tempdir().unwrap();
+    let current = root.path().join("data");
+    let staged = root.path().join("staged");
+    fs::create_dir(&staged).unwrap();
+    let err = EventLog::replace_dir(&current, &staged).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    drop(EventLog::open(&staged).unwrap());
+    fs::create_dir(&current).unwrap();
+    fs::write(current.join("notes.txt"), "keep me").unwrap();
+    let err = EventLog::replace_dir(&current, &staged).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(current.join("notes.txt").is_file());
+
+    let err = EventLog::replace_dir(&staged, &staged).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
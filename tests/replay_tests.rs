@@ -0,0 +1,77 @@
+mod common;
+
+use common::append_at;
+use eventfold::{EventLog, EventWriter};
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+#[test]
+fn test_replay_timed_delivers_all_events_in_order() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    append_at(&mut writer, "a", 100);
+    append_at(&mut writer, "b", 101);
+    append_at(&mut writer, "c", 102);
+
+    let start = Instant::now();
+    let mut seen = Vec::new();
+    writer
+        .reader()
+        .replay_timed(1000.0, |e| seen.push(e.event_type.clone()))
+        .unwrap();
+    let elapsed = start.elapsed();
+
+    assert_eq!(seen, vec!["a", "b", "c"]);
+    // 2s of recorded time at 1000x is ~2ms.
+    assert!(elapsed >= Duration::from_millis(2));
+    assert!(elapsed < Duration::from_secs(1));
+}
+
+#[test]
+fn test_replay_timed_clamps_backward_and_zero_gaps() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    append_at(&mut writer, "a", 500);
+    append_at(&mut writer, "b", 500);
+    append_at(&mut writer, "c", 10);
+    append_at(&mut writer, "d", 501);
+
+    let start = Instant::now();
+    let mut seen = Vec::new();
+    writer
+        .reader()
+        .replay_timed(1000.0, |e| seen.push(e.event_type.clone()))
+        .unwrap();
+
+    assert_eq!(seen, vec!["a", "b", "c", "d"]);
+    // Only the 1s gap from 500 to 501 is slept; the jump back to 10 is not.
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_replay_timed_includes_archive() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_at(log.writer_mut(), "archived", 100);
+    log.rotate().unwrap();
+    append_at(log.writer_mut(), "active", 100);
+
+    let mut seen = Vec::new();
+    log.reader()
+        .replay_timed(1.0, |e| seen.push(e.event_type.clone()))
+        .unwrap();
+
+    assert_eq!(seen, vec!["archived", "active"]);
+}
+
+#[test]
+fn test_replay_timed_rejects_invalid_speed() {
+    let dir = tempdir().unwrap();
+    let writer = EventWriter::open(dir.path()).unwrap();
+    let reader = writer.reader();
+
+    for speed in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let err = reader.replay_timed(speed, |_| {}).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::{append_n, dummy_event};
+use eventfold::{CompareResult, EventLog, EventWriter};
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+#[test]
+fn test_replicate_copies_lines_verbatim() {
+    let (src, dst) = (tempdir().unwrap(), tempdir().unwrap());
+    let mut log = EventLog::builder(src.path())
+        .hash_chain(true)
+        .open()
+        .unwrap();
+    let mut follower = EventWriter::open(dst.path()).unwrap();
+
+    append_n(&mut log, 3);
+    let offset = log.replicate_to(0, &mut follower).unwrap();
+    assert_eq!(offset, log.active_log_size().unwrap());
+    assert_eq!(log.replicate_to(offset, &mut follower).unwrap(), offset);
+
+    log.append(&dummy_event("late")).unwrap();
+    let offset = log.replicate_to(offset, &mut follower).unwrap();
+    assert_eq!(offset, log.active_log_size().unwrap());
+
+    assert_eq!(
+        std::fs::read(dst.path().join("app.jsonl")).unwrap(),
+        std::fs::read(src.path().join("app.jsonl")).unwrap()
+    );
+    let hashes = |log: &EventLog| -> Vec<String> {
+        log.read_full().unwrap().map(|r| r.unwrap().1).collect()
+    };
+    drop(follower);
+    let copy = EventLog::open(dst.path()).unwrap();
+    assert_eq!(hashes(&copy), hashes(&log));
+}
+
+#[test]
+fn test_replicate_offset_past_end_is_rejected() {
+    let (src, dst) = (tempdir().unwrap(), tempdir().unwrap());
+    let mut log = EventLog::open(src.path()).unwrap();
+    let mut follower = EventWriter::open(dst.path()).unwrap();
+    append_n(&mut log, 2);
+    let offset = log.replicate_to(0, &mut follower).unwrap();
+    log.rotate().unwrap();
+
+    let err = log.replicate_to(offset, &mut follower).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(follower.reader().read_from(0).unwrap().count(), 2);
+}
+
+#[test]
+fn test_compare_tail_tracks_replica() {
+    let (src, dst) = (tempdir().unwrap(), tempdir().unwrap());
+    let mut log = EventLog::open(src.path()).unwrap();
+    let mut replica = EventWriter::open(dst.path()).unwrap();
+    append_n(&mut log, 2);
+    let offset = log.replicate_to(0, &mut replica).unwrap();
+    assert_eq!(
+        log.compare_tail(&replica.reader()).unwrap(),
+        CompareResult::Identical { events: 2 }
+    );
+
+    // Rotating only the source changes nothing.
+    log.rotate().unwrap();
+    assert!(log.compare_tail(&replica.reader()).unwrap().is_identical());
+
+    replica.append(&dummy_event("extra")).unwrap();
+    assert_eq!(
+        log.compare_tail(&replica.reader()).unwrap(),
+        CompareResult::Behind {
+            events: 2,
+            other_offset: Some(offset),
+        }
+    );
+}
+
+#[test]
+fn test_compare_tail_reports_divergence() {
+    let (src, dst) = (tempdir().unwrap(), tempdir().unwrap());
+    let mut log = EventLog::open(src.path()).unwrap();
+    let mut replica = EventWriter::open(dst.path()).unwrap();
+    let first = log.append(&dummy_event("a")).unwrap();
+    log.replicate_to(0, &mut replica).unwrap();
+    log.append(&dummy_event("b")).unwrap();
+    replica.append(&dummy_event("c")).unwrap();
+
+    assert_eq!(
+        log.compare_tail(&replica.reader()).unwrap(),
+        CompareResult::Diverged {
+            index: 1,
+            offset: Some(first.end_offset),
+            other_offset: Some(first.end_offset),
+        }
+    );
+}
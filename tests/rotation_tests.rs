@@ -1,7 +1,13 @@
 mod common;
 
-use common::{append_n, counter_reducer};
-use eventfold::{EventLog, Snapshot, View};
+use common::{append_n, counter_reducer, dummy_event};
+#[cfg(feature = "zstd")]
+use eventfold::Event;
+use eventfold::{EventLog, RotationStatus, Snapshot, View};
+#[cfg(feature = "zstd")]
+use eventfold::Retention;
+#[cfg(feature = "zstd")]
+use serde_json::json;
 use tempfile::tempdir;
 
 #[test]
@@ -247,3 +253,303 @@ fn test_read_full_empty_everything() {
         .unwrap();
     assert_eq!(events.len(), 0);
 }
+
+#[test]
+fn test_rotate_annotated_records_history_in_order() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 3);
+    log.rotate_annotated("monthly archive").unwrap();
+    append_n(&mut log, 2);
+    log.rotate_annotated("pre-migration").unwrap();
+
+    let history = log.rotation_history().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].note, "monthly archive");
+    assert_eq!((history[0].first_sequence, history[0].last_sequence), (0, 2));
+    assert_eq!(history[1].note, "pre-migration");
+    assert_eq!((history[1].first_sequence, history[1].last_sequence), (3, 4));
+    assert!(history[0].ts > 0);
+    assert!(history[1].ts >= history[0].ts);
+}
+
+#[test]
+fn test_rotate_annotated_counts_unannotated_rotations() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    let record = log.rotate_annotated("second").unwrap().unwrap();
+
+    assert_eq!((record.first_sequence, record.last_sequence), (2, 3));
+    assert_eq!(log.rotation_history().unwrap(), vec![record]);
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_rotation_history_survives_archive_retention() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .archive_retention(Retention::MaxFrames(1))
+        .open()
+        .unwrap();
+    for (n, note) in [(2, "first"), (3, "second"), (1, "third")] {
+        append_n(&mut log, n);
+        log.rotate_annotated(note).unwrap();
+    }
+
+    let history = log.rotation_history().unwrap();
+    let ranges: Vec<_> = history
+        .iter()
+        .map(|r| (r.first_sequence, r.last_sequence))
+        .collect();
+    assert_eq!(ranges, [(0, 1), (2, 4), (5, 5)]);
+
+    // Only the last rotation's frame is left, and its record still names
+    // the events in it.
+    let kept: Vec<u64> = log
+        .reader()
+        .read_from_sequence(0)
+        .unwrap()
+        .map(|r| r.unwrap().1)
+        .collect();
+    assert_eq!(kept, [history[2].first_sequence]);
+}
+
+#[test]
+fn test_rotate_annotated_empty_log_records_nothing() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+
+    let record = log.rotate_annotated("nothing to do").unwrap();
+
+    assert!(record.is_none());
+    assert!(log.rotation_history().unwrap().is_empty());
+    assert!(!log.rotations_path().exists());
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_max_log_events_rotates_every_n() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .max_log_events(3)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 7);
+    assert_eq!(log.reader().count_from(0).unwrap(), 1);
+    assert_eq!(log.reader().count_full().unwrap(), 7);
+
+    // Two full frames of three events each.
+    assert_eq!(EventLog::inspect(dir.path()).unwrap().generation, 2);
+
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 7);
+}
+
+#[test]
+fn test_max_log_events_count_survives_reopen() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .max_log_events(3)
+            .open()
+            .unwrap();
+        append_n(&mut log, 2);
+    }
+
+    let mut log = EventLog::builder(dir.path())
+        .max_log_events(3)
+        .open()
+        .unwrap();
+    assert!(log.active_log_size().unwrap() > 0);
+    append_n(&mut log, 1);
+    assert_eq!(log.active_log_size().unwrap(), 0);
+}
+
+#[test]
+fn test_max_log_events_checked_once_per_batch() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .max_log_events(3)
+        .open()
+        .unwrap();
+
+    let events: Vec<_> = (0..5).map(|i| dummy_event(&format!("e{i}"))).collect();
+    log.append_batch(&events).unwrap();
+    assert_eq!(log.active_log_size().unwrap(), 0);
+    assert_eq!(EventLog::inspect(dir.path()).unwrap().generation, 1);
+
+    append_n(&mut log, 2);
+    assert_eq!(log.reader().count_from(0).unwrap(), 2);
+}
+
+#[test]
+fn test_read_full_indexed_survives_rotation() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+
+    let before: Vec<(u64, String)> = log
+        .read_full_indexed()
+        .unwrap()
+        .map(|r| r.map(|(_, index, hash)| (index, hash)).unwrap())
+        .collect();
+    let indexes: Vec<u64> = before.iter().map(|(index, _)| *index).collect();
+    assert_eq!(indexes, [0, 1, 2, 3, 4]);
+
+    // Hashes match read_full, and indexes don't shift when the active log
+    // moves into the archive.
+    let hashes: Vec<String> = log.read_full().unwrap().map(|r| r.unwrap().1).collect();
+    assert!(before.iter().map(|(_, h)| h).eq(hashes.iter()));
+    log.rotate().unwrap();
+    log.append(&dummy_event("after")).unwrap();
+
+    let after: Vec<_> = log
+        .read_full_indexed()
+        .unwrap()
+        .map(|r| r.unwrap())
+        .collect();
+    assert_eq!(after.len(), 6);
+    for ((_, index, hash), (old_index, old_hash)) in after.iter().zip(&before) {
+        assert_eq!((index, hash), (old_index, old_hash));
+    }
+    assert_eq!(after[5].0.event_type, "after");
+    assert_eq!(after[5].1, 5);
+}
+
+#[test]
+fn test_detect_rotation() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let reader = log.reader();
+    let status = |seen| reader.detect_rotation(seen).unwrap();
+    append_n(&mut log, 3);
+    let seen = log.active_log_size().unwrap();
+    assert_eq!(status(seen), RotationStatus::Unchanged);
+
+    log.append(&dummy_event("more")).unwrap();
+    assert_eq!(status(seen), RotationStatus::Appended);
+
+    let seen = log.active_log_size().unwrap();
+    log.rotate().unwrap();
+    log.append(&dummy_event("after")).unwrap();
+    assert_eq!(status(seen), RotationStatus::Rotated);
+}
+
+#[test]
+fn test_detect_rotation_reports_shrink_without_archive() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+    let seen = log.active_log_size().unwrap();
+
+    log.compact(|e| e.event_type == "event_0").unwrap();
+    assert_eq!(
+        log.reader().detect_rotation(seen).unwrap(),
+        RotationStatus::Shrunk
+    );
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_rotation_preview_matches_rotation() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 20);
+    log.rotate().unwrap();
+    append_n(&mut log, 50);
+
+    let archive_before = std::fs::read(log.archive_path()).unwrap();
+    let preview = log.rotation_preview().unwrap();
+    assert_eq!(preview.active_log_size, log.active_log_size().unwrap());
+    assert_eq!(preview.archive_size, archive_before.len() as u64);
+    assert!(preview.estimated_frame_size < preview.active_log_size);
+    assert_eq!(std::fs::read(log.archive_path()).unwrap(), archive_before);
+
+    log.rotate().unwrap();
+    let archive_after = std::fs::metadata(log.archive_path()).unwrap().len();
+    assert_eq!(
+        archive_after,
+        preview.archive_size + preview.estimated_frame_size
+    );
+}
+
+#[test]
+fn test_rotation_preview_of_empty_log() {
+    let dir = tempdir().unwrap();
+    let log = EventLog::open(dir.path()).unwrap();
+    let preview = log.rotation_preview().unwrap();
+    assert_eq!(preview.active_log_size, 0);
+    assert_eq!(preview.estimated_frame_size, 0);
+    assert_eq!(preview.archive_size, 0);
+    assert_eq!(preview.compression_ratio(), 1.0);
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_event_larger_than_max_log_size_rotates_once() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .max_log_size(1024)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+
+    let big = "x".repeat(1 << 20);
+    for _ in 0..3 {
+        log.append(&Event::new("upload", json!({ "blob": big })))
+            .unwrap();
+        assert_eq!(log.active_log_size().unwrap(), 0);
+    }
+    log.append(&dummy_event("small")).unwrap();
+
+    // One frame per oversized event; the small one stays active.
+    let frames = log.reader().archive_frames().unwrap();
+    assert_eq!(frames.len(), 3);
+    assert!(frames.iter().all(|frame| frame.event_count == 1));
+    assert!(log.active_log_size().unwrap() > 0);
+
+    let events: Vec<Event> = log.read_full().unwrap().map(|r| r.unwrap().0).collect();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0].data["blob"].as_str().unwrap().len(), 1 << 20);
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 4);
+}
+
+#[test]
+fn test_paused_auto_rotation_resumes_on_next_append() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .max_log_events(3)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    assert!(log.auto_rotation());
+
+    log.set_auto_rotation(false);
+    append_n(&mut log, 5);
+    let events: Vec<_> = (0..2).map(|i| dummy_event(&format!("b{i}"))).collect();
+    log.append_batch(&events).unwrap();
+    assert_eq!(log.reader().count_from(0).unwrap(), 7);
+    assert_eq!(EventLog::inspect(dir.path()).unwrap().generation, 0);
+
+    log.set_auto_rotation(true);
+    append_n(&mut log, 1);
+    assert_eq!(log.active_log_size().unwrap(), 0);
+    assert_eq!(EventLog::inspect(dir.path()).unwrap().generation, 1);
+    assert_eq!(log.reader().count_full().unwrap(), 8);
+
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 8);
+}
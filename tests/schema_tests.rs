@@ -0,0 +1,84 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::{Event, EventLog, SchemaViolation};
+use serde_json::{json, Value};
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+fn require_name(data: &Value) -> Result<(), String> {
+    match data["name"].as_str() {
+        Some(name) if !name.is_empty() => Ok(()),
+        _ => Err("name must be a non-empty string".to_string()),
+    }
+}
+
+fn violation(err: &std::io::Error) -> &SchemaViolation {
+    err.get_ref()
+        .and_then(|e| e.downcast_ref::<SchemaViolation>())
+        .expect("a schema violation")
+}
+
+#[test]
+fn test_schema_rejects_invalid_data_without_writing() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .schema("user_created", require_name)
+        .open()
+        .unwrap();
+
+    log.append(&Event::new("user_created", json!({"name": "ada"})))
+        .unwrap();
+    let err = log
+        .append(&Event::new("user_created", json!({"name": 7})))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let violation = violation(&err);
+    assert_eq!(violation.event_type, "user_created");
+    assert_eq!(violation.message, "name must be a non-empty string");
+    assert_eq!(log.read_from(0).unwrap().count(), 1);
+}
+
+#[test]
+fn test_unregistered_types_pass_through() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .schema("user_created", require_name)
+        .open()
+        .unwrap();
+
+    log.append(&dummy_event("other")).unwrap();
+    assert_eq!(log.read_from(0).unwrap().count(), 1);
+}
+
+#[test]
+fn test_schema_rejects_whole_batch() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .schema("user_created", require_name)
+        .open()
+        .unwrap();
+
+    let events = [
+        Event::new("user_created", json!({"name": "ada"})),
+        Event::new("user_created", json!({})),
+    ];
+    let err = log.append_batch(&events).unwrap_err();
+
+    assert_eq!(violation(&err).event_type, "user_created");
+    assert_eq!(log.read_from(0).unwrap().count(), 0);
+}
+
+#[test]
+fn test_later_schema_replaces_earlier() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .schema("user_created", |_| Err("always".to_string()))
+        .schema("user_created", require_name)
+        .open()
+        .unwrap();
+
+    log.append(&Event::new("user_created", json!({"name": "ada"})))
+        .unwrap();
+}
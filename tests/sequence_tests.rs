@@ -0,0 +1,155 @@
+mod common;
+
+use common::{append_n, dummy_event};
+use eventfold::{EventLog, EventReader, HashAlgo};
+use serde_json::json;
+use std::fs;
+use tempfile::tempdir;
+
+fn sequences_from(reader: &EventReader, sequence: u64) -> Vec<(String, u64)> {
+    reader
+        .read_from_sequence(sequence)
+        .unwrap()
+        .map(|r| r.map(|(event, seq, _)| (event.event_type, seq)).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_sequence_continues_across_rotation_and_reopen() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        assert_eq!(log.append(&dummy_event("a")).unwrap().sequence, 0);
+        assert_eq!(log.append(&dummy_event("b")).unwrap().sequence, 1);
+        log.rotate().unwrap();
+
+        let result = log.append(&dummy_event("c")).unwrap();
+        assert_eq!(result.start_offset, 0);
+        assert_eq!(result.sequence, 2);
+    }
+
+    let mut log = EventLog::open(dir.path()).unwrap();
+    assert_eq!(log.reader().sequence_base().unwrap(), 2);
+    assert_eq!(log.append(&dummy_event("d")).unwrap().sequence, 3);
+    assert_eq!(fs::read_to_string(dir.path().join("seq")).unwrap(), "2\n");
+}
+
+#[test]
+fn test_batch_gets_consecutive_sequences() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 2);
+
+    let events: Vec<_> = (0..3).map(|i| dummy_event(&format!("e{i}"))).collect();
+    let sequences: Vec<u64> = log
+        .append_batch(&events)
+        .unwrap()
+        .iter()
+        .map(|r| r.sequence)
+        .collect();
+    assert_eq!(sequences, [2, 3, 4]);
+}
+
+#[test]
+fn test_read_from_sequence_spans_archive_and_active_log() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&dummy_event("a")).unwrap();
+    log.append(&dummy_event("b")).unwrap();
+    log.rotate().unwrap();
+    log.append(&dummy_event("c")).unwrap();
+    log.rotate().unwrap();
+    log.append(&dummy_event("d")).unwrap();
+
+    let reader = log.reader();
+    assert_eq!(
+        sequences_from(&reader, 1),
+        [
+            ("b".to_string(), 1),
+            ("c".to_string(), 2),
+            ("d".to_string(), 3)
+        ]
+    );
+    assert_eq!(sequences_from(&reader, 3), [("d".to_string(), 3)]);
+    assert!(sequences_from(&reader, 4).is_empty());
+}
+
+#[test]
+fn test_legacy_log_counts_archive_for_sequence_base() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        append_n(&mut log, 3);
+        log.rotate().unwrap();
+        append_n(&mut log, 1);
+    }
+    fs::remove_file(dir.path().join("seq")).unwrap();
+
+    let mut log = EventLog::open(dir.path()).unwrap();
+    assert_eq!(log.append(&dummy_event("next")).unwrap().sequence, 4);
+    log.rotate().unwrap();
+    assert_eq!(fs::read_to_string(dir.path().join("seq")).unwrap(), "5\n");
+}
+
+#[test]
+fn test_compact_never_reuses_sequences() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 4);
+
+    log.compact(|e| e.event_type == "event_1").unwrap();
+
+    assert_eq!(
+        sequences_from(&log.reader(), 0),
+        [("event_1".to_string(), 3)]
+    );
+    assert_eq!(log.append(&dummy_event("after")).unwrap().sequence, 4);
+}
+
+/// Crash after compaction swapped the log but before it saved the new
+/// base: the pending base matches the log on disk and is applied on open.
+#[test]
+fn test_compact_crash_after_log_swap_keeps_new_base() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        append_n(&mut log, 4);
+        log.compact(|e| e.event_type == "event_3").unwrap();
+    }
+    let log_bytes = fs::read(dir.path().join("app.jsonl")).unwrap();
+    let pending = json!({
+        "base": 3,
+        "log_len": log_bytes.len(),
+        "log_hash": HashAlgo::Xxh64.hash(&log_bytes),
+    });
+    fs::write(dir.path().join("seq"), "0\n").unwrap();
+    fs::write(dir.path().join("seq.pending"), pending.to_string()).unwrap();
+
+    let mut log = EventLog::open(dir.path()).unwrap();
+    assert_eq!(log.reader().sequence_base().unwrap(), 3);
+    assert_eq!(fs::read_to_string(dir.path().join("seq")).unwrap(), "3\n");
+    assert!(!dir.path().join("seq.pending").exists());
+    assert_eq!(log.append(&dummy_event("after")).unwrap().sequence, 4);
+}
+
+/// Crash after compaction staged the new base but before it swapped the
+/// log: the old log keeps its old base.
+#[test]
+fn test_compact_crash_before_log_swap_keeps_old_base() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        append_n(&mut log, 4);
+    }
+    let pending = json!({
+        "base": 3,
+        "log_len": 10,
+        "log_hash": HashAlgo::Xxh64.hash(b"compacted"),
+    });
+    fs::write(dir.path().join("seq.pending"), pending.to_string()).unwrap();
+
+    let mut log = EventLog::open(dir.path()).unwrap();
+    assert_eq!(log.reader().sequence_base().unwrap(), 0);
+    assert!(!dir.path().join("seq.pending").exists());
+    assert_eq!(log.append(&dummy_event("after")).unwrap().sequence, 4);
+}
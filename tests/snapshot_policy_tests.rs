@@ -0,0 +1,151 @@
+mod common;
+
+use common::{append_n, counter_reducer, dummy_event};
+use eventfold::{EventLog, EventWriter, Snapshot, SnapshotPolicy, View, snapshot};
+use std::path::Path;
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn open(dir: &Path, policy: SnapshotPolicy) -> EventLog {
+    EventLog::builder(dir)
+        .snapshot_every(policy)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap()
+}
+
+fn saved_count(dir: &Path) -> Option<u64> {
+    let path = dir.join("views/counter.snapshot.json");
+    let snap: Option<Snapshot<u64>> = snapshot::load(&path).unwrap();
+    snap.map(|s| s.state)
+}
+
+#[test]
+fn test_every_n_events_holds_back_snapshot() {
+    let dir = tempdir().unwrap();
+    let mut log = open(dir.path(), SnapshotPolicy::EveryNEvents(5));
+    append_n(&mut log, 1);
+    log.refresh_all().unwrap();
+    assert_eq!(saved_count(dir.path()), Some(1), "full replay always saves");
+
+    append_n(&mut log, 3);
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 4);
+    assert_eq!(saved_count(dir.path()), Some(1));
+
+    append_n(&mut log, 2);
+    log.refresh_all().unwrap();
+    assert_eq!(saved_count(dir.path()), Some(6));
+}
+
+#[test]
+fn test_every_duration_holds_back_snapshot() {
+    let dir = tempdir().unwrap();
+    let mut log = open(
+        dir.path(),
+        SnapshotPolicy::EveryDuration(Duration::from_secs(3600)),
+    );
+    append_n(&mut log, 1);
+    log.refresh_all().unwrap();
+    append_n(&mut log, 2);
+    log.refresh_all().unwrap();
+    assert_eq!(saved_count(dir.path()), Some(1));
+
+    log.flush_views().unwrap();
+    assert_eq!(saved_count(dir.path()), Some(3));
+}
+
+#[test]
+fn test_drop_flushes_snapshot() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = open(dir.path(), SnapshotPolicy::EveryNEvents(100));
+        append_n(&mut log, 1);
+        log.refresh_all().unwrap();
+        append_n(&mut log, 2);
+        log.refresh_all().unwrap();
+        assert_eq!(saved_count(dir.path()), Some(1));
+    }
+    assert_eq!(saved_count(dir.path()), Some(3));
+}
+
+#[test]
+fn test_crash_replays_policy_window() {
+    let dir = tempdir().unwrap();
+    let mut log = open(dir.path(), SnapshotPolicy::EveryNEvents(100));
+    append_n(&mut log, 1);
+    log.refresh_all().unwrap();
+    append_n(&mut log, 4);
+    log.refresh_all().unwrap();
+    // Skip the flush on drop, as a crash would.
+    std::mem::forget(log);
+    assert_eq!(saved_count(dir.path()), Some(1));
+
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .lock_mode(eventfold::LockMode::None)
+        .open()
+        .unwrap();
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 5);
+}
+
+#[test]
+fn test_flush_views_skips_unchanged_snapshot() {
+    let dir = tempdir().unwrap();
+    let mut log = open(dir.path(), SnapshotPolicy::EveryNEvents(100));
+    append_n(&mut log, 2);
+    log.refresh_all().unwrap();
+    let path = dir.path().join("views/counter.snapshot.json");
+    let mtime = || std::fs::metadata(&path).unwrap().modified().unwrap();
+    let before = mtime();
+
+    std::thread::sleep(Duration::from_millis(20));
+    log.flush_views().unwrap();
+    assert_eq!(mtime(), before);
+
+    append_n(&mut log, 1);
+    log.refresh_all().unwrap();
+    log.flush_views().unwrap();
+    assert_eq!(saved_count(dir.path()), Some(3));
+    assert_ne!(mtime(), before);
+}
+
+#[test]
+fn test_dropping_view_flushes_snapshot() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    let views_dir = writer.views_dir().to_path_buf();
+    let new_view = || {
+        View::new("counter", counter_reducer, &views_dir)
+            .with_snapshot_policy(SnapshotPolicy::EveryNEvents(100))
+    };
+    let mut view = new_view();
+    writer.append(&dummy_event("a")).unwrap();
+    view.refresh(&writer.reader()).unwrap();
+    writer.append(&dummy_event("b")).unwrap();
+    view.refresh(&writer.reader()).unwrap();
+    assert_eq!(saved_count(dir.path()), Some(1));
+
+    drop(view);
+    assert_eq!(saved_count(dir.path()), Some(2));
+}
+
+#[test]
+fn test_drop_after_flush_views_does_not_rewrite() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("views/counter.snapshot.json");
+    let mtime = || std::fs::metadata(&path).unwrap().modified().unwrap();
+    let mut log = open(dir.path(), SnapshotPolicy::EveryNEvents(100));
+    append_n(&mut log, 1);
+    log.refresh_all().unwrap();
+    append_n(&mut log, 1);
+    log.refresh_all().unwrap();
+    log.flush_views().unwrap();
+    let flushed = mtime();
+
+    std::thread::sleep(Duration::from_millis(20));
+    drop(log);
+    assert_eq!(mtime(), flushed);
+    assert_eq!(saved_count(dir.path()), Some(2));
+}
@@ -222,6 +222,36 @@ fn test_truncated_file_returns_none() {
     assert!(loaded.is_none(), "truncated JSON should return None");
 }
 
+#[test]
+fn test_checksum_mismatch_returns_none() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("checksum.snapshot.json");
+    let snap = Snapshot::new(
+        TestState {
+            count: 42,
+            items: vec!["hello".into()],
+        },
+        100,
+        "hash".into(),
+    );
+    snapshot::save(&path, &snap).unwrap();
+    let loaded: Snapshot<TestState> = snapshot::load(&path).unwrap().unwrap();
+    assert!(loaded.checksum.is_some());
+
+    // Still valid JSON of the right shape, but not the state that was saved.
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, contents.replace("\"count\": 42", "\"count\": 43")).unwrap();
+    let loaded: Option<Snapshot<TestState>> = snapshot::load(&path).unwrap();
+    assert!(loaded.is_none(), "checksum mismatch should return None");
+
+    // Snapshots from before checksums are trusted.
+    let legacy = r#"{"state":{"count":7,"items":[]},"offset":0,"hash":""}"#;
+    std::fs::write(&path, legacy).unwrap();
+    let loaded: Snapshot<TestState> = snapshot::load(&path).unwrap().unwrap();
+    assert_eq!(loaded.state.count, 7);
+    assert_eq!(loaded.checksum, None);
+}
+
 #[test]
 fn test_tmp_cleanup_on_delete() {
     let dir = tempdir().unwrap();
@@ -0,0 +1,65 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::{Event, EventLog};
+use serde_json::json;
+use std::io;
+use tempfile::tempdir;
+
+fn full_history(log: &EventLog) -> Vec<Event> {
+    log.read_full().unwrap().map(|r| r.unwrap().0).collect()
+}
+
+#[test]
+fn test_split_by_event_type() {
+    let dir = tempdir().unwrap();
+    let dest = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+
+    let mut source = Vec::new();
+    for i in 0..6 {
+        let event_type = if i % 3 == 0 { "refund" } else { "sale" };
+        let event = Event::new(event_type, json!({"n": i}))
+            .with_id(format!("id-{i}"))
+            .with_actor("till-1")
+            .with_meta(json!({"source": "pos"}));
+        log.append(&event).unwrap();
+        source.push(event);
+        if i == 2 {
+            log.rotate().unwrap(); // part of the history comes from the archive
+        }
+    }
+
+    let report = log.split_by(|e| e.event_type.clone(), dest.path()).unwrap();
+    assert_eq!(report.shards.len(), 2);
+    assert_eq!(report.shards["sale"], 4);
+    assert_eq!(report.shards["refund"], 2);
+    assert_eq!(report.total(), 6);
+
+    for shard in ["sale", "refund"] {
+        let expected: Vec<Event> = source
+            .iter()
+            .filter(|e| e.event_type == shard)
+            .cloned()
+            .collect();
+        let shard_log = EventLog::open(dest.path().join(shard)).unwrap();
+        assert_eq!(full_history(&shard_log), expected);
+    }
+
+    // The source is untouched.
+    assert_eq!(full_history(&log), source);
+}
+
+#[test]
+fn test_split_by_rejects_path_keys() {
+    let dir = tempdir().unwrap();
+    let dest = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&dummy_event("a")).unwrap();
+
+    for key in ["", "..", "a/b"] {
+        let err = log.split_by(|_| key.to_string(), dest.path()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "key {key:?}");
+    }
+    assert!(std::fs::read_dir(dest.path()).unwrap().next().is_none());
+}
@@ -0,0 +1,233 @@
+mod common;
+
+use common::{counter_reducer, dummy_event};
+use eventfold::{EventLog, Storage};
+use std::collections::HashMap;
+use std::io::{self, Cursor, Read};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+struct MemState {
+    log: Vec<u8>,
+    archive: Vec<u8>,
+    snapshots: HashMap<String, Vec<u8>>,
+}
+
+/// A `Storage` kept entirely in memory. Clones share the same bytes, so a
+/// test can reopen a log or look inside after handing one to a log.
+#[derive(Debug, Default, Clone)]
+struct MemStorage(Arc<Mutex<MemState>>);
+
+impl MemStorage {
+    fn state(&self) -> std::sync::MutexGuard<'_, MemState> {
+        self.0.lock().unwrap()
+    }
+}
+
+impl Storage for MemStorage {
+    fn append(&self, bytes: &[u8]) -> io::Result<u64> {
+        let mut state = self.state();
+        let offset = state.log.len() as u64;
+        state.log.extend_from_slice(bytes);
+        Ok(offset)
+    }
+
+    fn sync(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.state().log.len() as u64)
+    }
+
+    fn read_from(&self, offset: u64) -> io::Result<Box<dyn Read + Send>> {
+        let log = &self.state().log;
+        let start = (offset as usize).min(log.len());
+        Ok(Box::new(Cursor::new(log[start..].to_vec())))
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        let log = &self.state().log;
+        let bytes = log
+            .get(offset as usize..offset as usize + buf.len())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        buf.copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn truncate(&self) -> io::Result<()> {
+        self.state().log.clear();
+        Ok(())
+    }
+
+    fn read_archive(&self) -> io::Result<Option<Box<dyn Read + Send>>> {
+        let archive = &self.state().archive;
+        if archive.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(Cursor::new(archive.clone()))))
+    }
+
+    fn append_archive(&self, frame: &[u8]) -> io::Result<()> {
+        self.state().archive.extend_from_slice(frame);
+        Ok(())
+    }
+
+    fn load_snapshot(&self, view: &str) -> io::Result<Option<Vec<u8>>> {
+        Ok(self.state().snapshots.get(view).cloned())
+    }
+
+    fn save_snapshot(&self, view: &str, bytes: &[u8]) -> io::Result<()> {
+        self.state()
+            .snapshots
+            .insert(view.to_string(), bytes.to_vec());
+        Ok(())
+    }
+
+    fn delete_snapshot(&self, view: &str) -> io::Result<()> {
+        self.state().snapshots.remove(view);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_memory_storage_append_read_view() {
+    let storage = MemStorage::default();
+    let mut log = EventLog::with_storage(storage.clone())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+
+    let mut last = None;
+    for i in 0..3 {
+        last = Some(log.append(&dummy_event(&format!("e{i}"))).unwrap());
+    }
+    let last = last.unwrap();
+
+    let events: Vec<_> = log.read_from(0).unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[2].0.event_type, "e2");
+    assert_eq!(log.active_log_size().unwrap(), last.end_offset);
+    assert_eq!(
+        log.read_line_hash_before(last.end_offset).unwrap(),
+        Some(last.line_hash)
+    );
+
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 3);
+    assert!(storage.state().snapshots.contains_key("counter"));
+}
+
+#[test]
+fn test_memory_storage_rotation_and_reopen() {
+    let storage = MemStorage::default();
+    {
+        let mut log = EventLog::with_storage(storage.clone())
+            .view::<u64>("counter", counter_reducer)
+            .open()
+            .unwrap();
+        for i in 0..5 {
+            log.append(&dummy_event(&format!("e{i}"))).unwrap();
+        }
+        log.rotate().unwrap();
+        log.append(&dummy_event("e5")).unwrap();
+        log.refresh_all().unwrap();
+        assert_eq!(*log.view::<u64>("counter").unwrap(), 6);
+    }
+    assert!(!storage.state().archive.is_empty());
+
+    // Reopening resumes from the stored snapshot and sees the full history.
+    let mut log = EventLog::with_storage(storage.clone())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    log.append(&dummy_event("e6")).unwrap();
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 7);
+
+    let types: Vec<String> = log
+        .read_full()
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+    assert_eq!(types, ["e0", "e1", "e2", "e3", "e4", "e5", "e6"]);
+}
+
+#[test]
+fn test_memory_storage_filesystem_features_unsupported() {
+    let mut log = EventLog::with_storage(MemStorage::default())
+        .open()
+        .unwrap();
+    log.append(&dummy_event("a")).unwrap();
+
+    let err = log
+        .wait_for_events(u64::MAX, Duration::from_millis(1))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    let err = log.rotation_history().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+}
+
+#[test]
+fn test_builtin_memory_storage_compacts_and_redacts() {
+    let storage = eventfold::MemStorage::new();
+    let mut log = EventLog::with_storage(storage.clone())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    for i in 0..4 {
+        log.append(&dummy_event(&format!("e{i}"))).unwrap();
+    }
+    log.rotate().unwrap();
+    log.append(&dummy_event("keep")).unwrap();
+    log.append(&dummy_event("drop")).unwrap();
+
+    assert_eq!(log.compact(|event| event.event_type != "drop").unwrap(), 1);
+    assert_eq!(log.redact(|event| event.event_type == "e0").unwrap(), 1);
+    assert!(!String::from_utf8_lossy(&storage.log_bytes()).contains("drop"));
+    assert!(!storage.archive_bytes().is_empty());
+    drop(log);
+
+    let mut log = EventLog::with_storage(storage)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 5);
+}
+
+#[test]
+fn test_in_memory_logs_are_independent() {
+    let mut first = EventLog::in_memory()
+        .view::<u64>("counter", counter_reducer)
+        .max_log_events(2)
+        .open()
+        .unwrap();
+    let mut second = EventLog::in_memory().open().unwrap();
+    for i in 0..5 {
+        first.append(&dummy_event(&format!("e{i}"))).unwrap();
+    }
+    second.append(&dummy_event("other")).unwrap();
+
+    first.refresh_all().unwrap();
+    assert_eq!(*first.view::<u64>("counter").unwrap(), 5);
+    assert_eq!(first.read_full().unwrap().count(), 5);
+    assert!(first.read_from(0).unwrap().count() < 5);
+    assert_eq!(second.read_full().unwrap().count(), 1);
+}
+
+#[test]
+fn test_in_memory_log_creates_no_views_directory() {
+    let name = "in-memory-views-not-on-disk";
+    let mut log = EventLog::in_memory()
+        .views_dir_name(name)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    log.append(&dummy_event("a")).unwrap();
+    log.refresh_all().unwrap();
+
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 1);
+    assert!(!std::path::Path::new(name).exists());
+}
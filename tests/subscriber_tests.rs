@@ -0,0 +1,78 @@
+mod common;
+
+use common::{append_n, counter_reducer, dummy_event};
+use eventfold::{AppendResult, Event, EventLog};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tempfile::tempdir;
+
+type Seen = Rc<RefCell<Vec<(String, u64)>>>;
+
+fn record(log: &mut EventLog) -> Seen {
+    let seen = Seen::default();
+    let sink = seen.clone();
+    log.subscribe(Box::new(move |event: &Event, result: &AppendResult| {
+        sink.borrow_mut()
+            .push((event.event_type.clone(), result.sequence));
+    }));
+    seen
+}
+
+#[test]
+fn test_subscriber_runs_once_per_appended_event() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .max_log_events(2)
+        .open()
+        .unwrap();
+    let seen = record(&mut log);
+
+    log.append(&dummy_event("a")).unwrap();
+    log.append_batch(&[dummy_event("b"), dummy_event("c")])
+        .unwrap();
+    log.append_transaction(&[dummy_event("d")]).unwrap();
+    let (_, result) = log.append_returning(dummy_event("e")).unwrap();
+    log.append_with_retry(&dummy_event("f"), 3).unwrap();
+
+    let expected: Vec<(String, u64)> = ["a", "b", "c", "d", "e", "f"]
+        .iter()
+        .zip(0..)
+        .map(|(t, i)| (t.to_string(), i))
+        .collect();
+    assert_eq!(*seen.borrow(), expected);
+    assert_eq!(result.sequence, 4);
+}
+
+#[test]
+fn test_subscriber_is_not_replayed() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = EventLog::open(dir.path()).unwrap();
+        append_n(&mut log, 3);
+    }
+
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap();
+    let seen = record(&mut log);
+    log.refresh_all().unwrap();
+    assert!(seen.borrow().is_empty());
+
+    log.append(&dummy_event("new")).unwrap();
+    assert_eq!(*seen.borrow(), [("new".to_string(), 3)]);
+}
+
+#[test]
+fn test_failed_append_runs_no_subscriber() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let seen = record(&mut log);
+
+    let err = log.append_if(&dummy_event("a"), 100, "").unwrap_err();
+    assert!(matches!(
+        err,
+        eventfold::ConditionalAppendError::Conflict(_)
+    ));
+    assert!(seen.borrow().is_empty());
+}
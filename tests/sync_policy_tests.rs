@@ -0,0 +1,125 @@
+mod common;
+
+use common::{append_n, dummy_event};
+use eventfold::{EventLog, SyncPolicy};
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[test]
+fn test_every_append_leaves_nothing_unsynced() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+
+    append_n(&mut log, 3);
+
+    assert_eq!(log.writer().unsynced_events(), 0);
+}
+
+#[test]
+fn test_interval_policy_defers_sync() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .sync_policy(SyncPolicy::Interval(Duration::from_secs(3600)))
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 3);
+
+    assert_eq!(log.writer().unsynced_events(), 3);
+    // Unsynced events are still visible to readers.
+    assert_eq!(log.read_from(0).unwrap().count(), 3);
+}
+
+#[test]
+fn test_max_unsynced_events_forces_sync_on_nth_append() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .sync_policy(SyncPolicy::Interval(Duration::from_secs(3600)))
+        .max_unsynced_events(5)
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 4);
+    assert_eq!(log.writer().unsynced_events(), 4);
+
+    log.append(&dummy_event("fifth")).unwrap();
+    assert_eq!(log.writer().unsynced_events(), 0);
+
+    append_n(&mut log, 2);
+    assert_eq!(log.writer().unsynced_events(), 2);
+}
+
+#[test]
+fn test_interval_elapsed_triggers_sync() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .sync_policy(SyncPolicy::Interval(Duration::from_millis(20)))
+        .open()
+        .unwrap();
+
+    log.append(&dummy_event("a")).unwrap();
+    std::thread::sleep(Duration::from_millis(40));
+    log.append(&dummy_event("b")).unwrap();
+
+    assert_eq!(log.writer().unsynced_events(), 0);
+}
+
+#[test]
+fn test_explicit_sync_resets_count() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .sync_policy(SyncPolicy::Interval(Duration::from_secs(3600)))
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 2);
+    log.writer_mut().sync().unwrap();
+
+    assert_eq!(log.writer().unsynced_events(), 0);
+}
+
+#[test]
+fn test_rotation_syncs_pending_events() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .sync_policy(SyncPolicy::Interval(Duration::from_secs(3600)))
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+
+    assert_eq!(log.writer().unsynced_events(), 0);
+}
+
+#[test]
+fn test_every_n_syncs_on_nth_append() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .sync_policy(SyncPolicy::EveryN(3))
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 2);
+    assert_eq!(log.writer().unsynced_events(), 2);
+    append_n(&mut log, 1);
+    assert_eq!(log.writer().unsynced_events(), 0);
+    append_n(&mut log, 1);
+    assert_eq!(log.writer().unsynced_events(), 1);
+}
+
+#[test]
+fn test_never_policy_syncs_only_on_request() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .sync_policy(SyncPolicy::Never)
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 50);
+    assert_eq!(log.writer().unsynced_events(), 50);
+    assert_eq!(log.read_from(0).unwrap().count(), 50);
+
+    log.sync().unwrap();
+    assert_eq!(log.writer().unsynced_events(), 0);
+}
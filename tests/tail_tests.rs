@@ -2,6 +2,10 @@ mod common;
 
 use common::dummy_event;
 use eventfold::{EventLog, EventWriter};
+#[cfg(feature = "std-fs")]
+use std::thread;
+#[cfg(feature = "std-fs")]
+use std::time::Duration;
 use tempfile::tempdir;
 
 #[test]
@@ -201,3 +205,46 @@ fn test_eventlog_has_new_events() {
         reader.has_new_events(r1.end_offset).unwrap()
     );
 }
+
+#[test]
+#[cfg(feature = "std-fs")]
+fn test_events_stream_yields_appends_and_ends_when_idle() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    writer.append(&dummy_event("event_0")).unwrap();
+    let reader = writer.reader();
+
+    let appender = thread::spawn(move || {
+        for i in 1..3 {
+            thread::sleep(Duration::from_millis(50));
+            writer.append(&dummy_event(&format!("event_{i}"))).unwrap();
+        }
+        writer
+    });
+
+    let mut tail = reader
+        .events_stream(0, Duration::from_millis(500))
+        .max_idle(1);
+    let types: Vec<String> = tail.by_ref().map(|r| r.unwrap().0.event_type).collect();
+    assert_eq!(types, ["event_0", "event_1", "event_2"]);
+
+    let writer = appender.join().unwrap();
+    assert_eq!(tail.offset(), writer.reader().active_log_size().unwrap());
+}
+
+#[test]
+#[cfg(feature = "std-fs")]
+fn test_events_stream_resumes_from_offset() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let first = log.append(&dummy_event("event_0")).unwrap();
+    log.append(&dummy_event("event_1")).unwrap();
+
+    let events: Vec<_> = log
+        .events_stream(first.end_offset, Duration::from_millis(10))
+        .max_idle(2)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0.event_type, "event_1");
+}
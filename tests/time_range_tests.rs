@@ -0,0 +1,75 @@
+use eventfold::{Event, EventReader, EventWriter};
+use serde_json::json;
+use tempfile::tempdir;
+
+fn write_ts(writer: &mut EventWriter, timestamps: impl IntoIterator<Item = u64>) {
+    for ts in timestamps {
+        let mut event = Event::new("tick", json!({ "ts": ts }));
+        event.ts = ts;
+        writer.append(&event).unwrap();
+    }
+}
+
+fn window(reader: &EventReader, from_ts: u64, to_ts: u64) -> Vec<u64> {
+    reader
+        .read_time_range(from_ts, to_ts)
+        .unwrap()
+        .map(|r| r.unwrap().0.ts)
+        .collect()
+}
+
+#[test]
+fn test_time_range_matches_linear_filter() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    // Ten events per timestamp, so the search has to land on the first.
+    write_ts(&mut writer, (0..1000).map(|i| 1000 + i / 10));
+    let reader = writer.reader();
+
+    let windows = [
+        (0, 999),
+        (1000, 1000),
+        (1003, 1042),
+        (1099, 5000),
+        (1050, 1049),
+    ];
+    for (from, to) in windows {
+        let expected: Vec<u64> = (0..1000)
+            .map(|i| 1000 + i / 10)
+            .filter(|ts| (from..=to).contains(ts))
+            .collect();
+        assert_eq!(window(&reader, from, to), expected, "{from}..={to}");
+    }
+}
+
+#[test]
+fn test_time_range_on_empty_and_partial_logs() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    assert!(window(&writer.reader(), 0, u64::MAX).is_empty());
+
+    write_ts(&mut writer, [10, 20, 30]);
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(writer.log_path())
+        .unwrap();
+    std::io::Write::write_all(&mut file, b"{\"type\":\"partial\",\"ts\":25").unwrap();
+    assert_eq!(window(&writer.reader(), 15, 100), [20, 30]);
+}
+
+#[test]
+fn test_time_range_offsets_resume_reads() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+    write_ts(&mut writer, [10, 20, 30, 40]);
+    let reader = writer.reader();
+
+    let (_, offset, _) = reader
+        .read_time_range(20, 20)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let (next, _, _) = reader.read_from(offset).unwrap().next().unwrap().unwrap();
+    assert_eq!(next.ts, 30);
+}
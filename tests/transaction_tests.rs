@@ -0,0 +1,197 @@
+mod common;
+
+use common::dummy_event;
+use eventfold::{Event, EventLog, EventWriter};
+use serde_json::json;
+use std::fs::OpenOptions;
+use std::io::Write;
+use tempfile::tempdir;
+
+fn transaction_sizes(writer: &EventWriter) -> Vec<usize> {
+    writer
+        .reader()
+        .read_transactions_from(0)
+        .unwrap()
+        .map(|txn| txn.unwrap().len())
+        .collect()
+}
+
+#[test]
+fn test_read_transactions_groups_committed_batches() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    writer
+        .append_transaction(&[dummy_event("a"), dummy_event("b"), dummy_event("c")])
+        .unwrap();
+    writer.append(&dummy_event("d")).unwrap();
+    writer.append(&dummy_event("e")).unwrap();
+
+    assert_eq!(transaction_sizes(&writer), vec![3, 1, 1]);
+}
+
+#[test]
+fn test_transaction_events_keep_order() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    writer
+        .append_transaction(&[dummy_event("a"), dummy_event("b"), dummy_event("c")])
+        .unwrap();
+
+    let txns: Vec<Vec<Event>> = writer
+        .reader()
+        .read_transactions_from(0)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let types: Vec<&str> = txns[0].iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(types, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn test_transaction_results_are_contiguous() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    let results = writer
+        .append_transaction(&[dummy_event("a"), dummy_event("b")])
+        .unwrap();
+
+    assert_eq!(results[0].start_offset, 0);
+    assert_eq!(results[1].start_offset, results[0].end_offset);
+    assert_eq!(results[1].end_offset, writer.active_log_size().unwrap());
+    assert_eq!(
+        writer
+            .reader()
+            .read_line_hash_before(results[1].end_offset)
+            .unwrap(),
+        Some(results[1].line_hash.clone())
+    );
+}
+
+#[test]
+fn test_torn_transaction_is_skipped() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    writer.append(&dummy_event("before")).unwrap();
+    let results = writer
+        .append_transaction(&[dummy_event("a"), dummy_event("b"), dummy_event("c")])
+        .unwrap();
+
+    // Simulate a crash that only persisted the first two lines plus a fragment.
+    let file = OpenOptions::new()
+        .write(true)
+        .open(writer.log_path())
+        .unwrap();
+    file.set_len(results[1].end_offset + 5).unwrap();
+
+    assert_eq!(transaction_sizes(&writer), vec![1]);
+}
+
+#[test]
+fn test_torn_transaction_followed_by_append() {
+    let dir = tempdir().unwrap();
+    let log_path = dir.path().join("app.jsonl");
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    let results = writer
+        .append_transaction(&[dummy_event("a"), dummy_event("b"), dummy_event("c")])
+        .unwrap();
+    let file = OpenOptions::new().write(true).open(&log_path).unwrap();
+    file.set_len(results[1].end_offset).unwrap();
+    drop(file);
+
+    let mut file = OpenOptions::new().append(true).open(&log_path).unwrap();
+    let line = serde_json::to_string(&dummy_event("after")).unwrap();
+    writeln!(file, "{line}").unwrap();
+
+    assert_eq!(transaction_sizes(&writer), vec![1]);
+}
+
+#[test]
+fn test_transaction_preserves_existing_meta() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    let event = Event::new("a", json!({})).with_meta(json!({"session": "s1"}));
+    writer
+        .append_transaction(&[event, dummy_event("b")])
+        .unwrap();
+
+    let (stored, _, _) = writer
+        .reader()
+        .read_from(0)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let meta = stored.meta.unwrap();
+    assert_eq!(meta["session"], "s1");
+    assert_eq!(meta["txn"], json!({"index": 0, "size": 2}));
+}
+
+#[test]
+fn test_transaction_rejects_non_object_meta() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    let event = Event::new("a", json!({})).with_meta(json!("not an object"));
+    let err = writer
+        .append_transaction(&[event, dummy_event("b")])
+        .unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(writer.active_log_size().unwrap(), 0);
+}
+
+#[test]
+fn test_single_event_transaction_is_unmarked() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    writer.append_transaction(&[dummy_event("solo")]).unwrap();
+
+    let (stored, _, _) = writer
+        .reader()
+        .read_from(0)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.meta, None);
+    assert_eq!(transaction_sizes(&writer), vec![1]);
+}
+
+#[test]
+fn test_empty_transaction_writes_nothing() {
+    let dir = tempdir().unwrap();
+    let mut writer = EventWriter::open(dir.path()).unwrap();
+
+    let results = writer.append_transaction(&[]).unwrap();
+
+    assert!(results.is_empty());
+    assert_eq!(writer.active_log_size().unwrap(), 0);
+}
+
+#[test]
+fn test_log_transaction_is_not_split_by_rotation() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .max_log_events(2)
+        .open()
+        .unwrap();
+
+    log.append(&dummy_event("before")).unwrap();
+    log.append_transaction(&[dummy_event("a"), dummy_event("b"), dummy_event("c")])
+        .unwrap();
+    assert_eq!(log.active_log_size().unwrap(), 0);
+
+    let types: Vec<String> = log
+        .read_full()
+        .unwrap()
+        .map(|r| r.unwrap().0.event_type)
+        .collect();
+    assert_eq!(types, ["before", "a", "b", "c"]);
+}
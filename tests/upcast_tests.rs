@@ -0,0 +1,104 @@
+mod common;
+
+use common::counter_reducer;
+use eventfold::{Event, EventLog, View};
+use serde_json::{json, Value};
+use std::fs;
+use tempfile::tempdir;
+
+/// Version 1 stored a full name, version 2 split it, version 3 renamed the
+/// fields.
+fn split_name(data: &mut Value) {
+    let name = data["name"].as_str().unwrap_or_default().to_string();
+    let (first, last) = name.split_once(' ').unwrap_or((&name, ""));
+    *data = json!({"first": first, "last": last});
+}
+
+fn rename_fields(data: &mut Value) {
+    *data = json!({"given": data["first"], "family": data["last"]});
+}
+
+fn family_names(mut state: Vec<String>, event: &Event) -> Vec<String> {
+    if event.event_type != "user_added" {
+        return state;
+    }
+    assert_eq!(event.schema_version(), Some(3), "reducer sees {event:?}");
+    state.push(event.data["family"].as_str().unwrap().to_string());
+    state
+}
+
+fn open(dir: &std::path::Path) -> EventLog {
+    EventLog::builder(dir)
+        .upcaster("user_added", 1, split_name)
+        .upcaster("user_added", 2, rename_fields)
+        .view::<Vec<String>>("families", family_names)
+        .view::<u64>("counter", counter_reducer)
+        .open()
+        .unwrap()
+}
+
+#[test]
+fn test_upcasters_chain_before_reducers() {
+    let dir = tempdir().unwrap();
+    let mut log = open(dir.path());
+    log.append(&Event::new("user_added", json!({"name": "Ada Lovelace"})))
+        .unwrap();
+    log.append(
+        &Event::new("user_added", json!({"first": "Alan", "last": "Turing"}))
+            .with_schema_version(2),
+    )
+    .unwrap();
+    log.append(
+        &Event::new("user_added", json!({"given": "Grace", "family": "Hopper"}))
+            .with_schema_version(3),
+    )
+    .unwrap();
+    log.append(&Event::new("other", json!({}))).unwrap();
+    log.refresh_all().unwrap();
+
+    let expected = ["Lovelace", "Turing", "Hopper"];
+    assert_eq!(*log.view::<Vec<String>>("families").unwrap(), expected);
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 4);
+
+    // A full replay and a standalone view on the log's reader agree.
+    log.rebuild_all().unwrap();
+    assert_eq!(*log.view::<Vec<String>>("families").unwrap(), expected);
+    let mut view: View<Vec<String>> = View::new("standalone", family_names, log.views_dir());
+    view.refresh(&log.reader()).unwrap();
+    assert_eq!(*view.state(), expected);
+}
+
+#[test]
+fn test_upcasting_leaves_log_and_reads_untouched() {
+    let dir = tempdir().unwrap();
+    let mut log = open(dir.path());
+    let original = Event::new("user_added", json!({"name": "Ada Lovelace"}));
+    log.append(&original).unwrap();
+    let on_disk = fs::read(dir.path().join("app.jsonl")).unwrap();
+
+    log.refresh_all().unwrap();
+    assert_eq!(fs::read(dir.path().join("app.jsonl")).unwrap(), on_disk);
+
+    let (read, _, _) = log.read_from(0).unwrap().next().unwrap().unwrap();
+    assert_eq!(read, original);
+    assert_eq!(read.schema_version(), None);
+}
+
+#[test]
+fn test_upcasters_survive_rotation_into_archive() {
+    let dir = tempdir().unwrap();
+    let mut log = open(dir.path());
+    log.append(&Event::new("user_added", json!({"name": "Ada Lovelace"})))
+        .unwrap();
+    log.rotate().unwrap();
+    log.append(&Event::new("user_added", json!({"name": "Alan Turing"})))
+        .unwrap();
+    drop(log);
+
+    let mut log = open(dir.path());
+    log.rebuild_all().unwrap();
+    assert_eq!(
+        *log.view::<Vec<String>>("families").unwrap(),
+        ["Lovelace", "Turing"]
+    );
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use common::{append_n, counter_reducer};
+use eventfold::{Event, EventLog, ViewStatus};
+use serde_json::{json, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn echo(event: &Event, out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "{}", event.event_type)
+}
+
+fn open_with_views(dir: &Path) -> EventLog {
+    EventLog::builder(dir)
+        .view::<u64>("counter", counter_reducer)
+        .log_view("feed", echo)
+        .open()
+        .unwrap()
+}
+
+#[test]
+fn test_verify_clean_log_across_rotation() {
+    let dir = tempdir().unwrap();
+    let mut log = open_with_views(dir.path());
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    log.refresh_all().unwrap();
+
+    let report = log.verify().unwrap();
+    assert!(report.is_ok(), "{report:?}");
+    assert_eq!(report.active_events, 2);
+    assert_eq!(report.archive_frames, 1);
+    assert_eq!(report.archived_events, 3);
+    assert_eq!(report.partial_line, None);
+    assert_eq!(report.views["counter"], ViewStatus::Matches);
+    assert_eq!(report.views["feed"], ViewStatus::Skipped);
+}
+
+#[test]
+fn test_verify_reports_bad_active_lines() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 1);
+    let bad_offset = log.active_log_size().unwrap();
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(log.log_path())
+        .unwrap();
+    file.write_all(b"not json\n").unwrap();
+    drop(file);
+    append_n(&mut log, 1);
+
+    let report = log.verify().unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.active_events, 2);
+    assert_eq!(report.bad_lines.len(), 1);
+    assert_eq!(report.bad_lines[0].offset, bad_offset);
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_verify_reports_corrupt_archive_frame() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+    append_n(&mut log, 2);
+    log.rotate().unwrap();
+
+    let frames = log.reader().archive_frames().unwrap();
+    let mut bytes = fs::read(log.archive_path()).unwrap();
+    let last = frames[1].compressed.end as usize - 5;
+    bytes[last] ^= 0xff;
+    fs::write(log.archive_path(), bytes).unwrap();
+
+    let report = log.verify().unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.archive_frames, 1);
+    assert_eq!(report.archived_events, 2);
+    assert_eq!(report.archive_problems.len(), 1);
+    assert_eq!(report.archive_problems[0].frame, Some(1));
+    assert_eq!(report.archive_problems[0].offset, None);
+}
+
+#[test]
+fn test_verify_compares_views_with_snapshots() {
+    let dir = tempdir().unwrap();
+    {
+        let mut log = open_with_views(dir.path());
+        append_n(&mut log, 3);
+        log.refresh_all().unwrap();
+    }
+    let snapshot_path = dir.path().join("views/counter.snapshot.json");
+    let mut snapshot: Value = serde_json::from_slice(&fs::read(&snapshot_path).unwrap()).unwrap();
+    // A hand-edited state only loads without its checksum.
+    snapshot["state"] = json!(7);
+    snapshot.as_object_mut().unwrap().remove("checksum");
+    fs::write(&snapshot_path, snapshot.to_string()).unwrap();
+
+    let log = open_with_views(dir.path());
+    assert_eq!(log.verify().unwrap().views["counter"], ViewStatus::Mismatch);
+
+    snapshot["hash"] = json!("0000000000000000");
+    fs::write(&snapshot_path, snapshot.to_string()).unwrap();
+    assert_eq!(
+        log.verify().unwrap().views["counter"],
+        ViewStatus::StaleSnapshot
+    );
+
+    fs::remove_file(&snapshot_path).unwrap();
+    let report = log.verify().unwrap();
+    assert_eq!(report.views["counter"], ViewStatus::NoSnapshot);
+    assert!(report.is_ok());
+}
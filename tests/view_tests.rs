@@ -1,7 +1,8 @@
 mod common;
 
-use common::{append_n, counter_reducer, todo_reducer, TodoState};
-use eventfold::{Event, EventLog, View};
+use common::{append_n, counter_reducer, dummy_event, todo_reducer, TodoState};
+use eventfold::{Event, EventContext, EventLog, Merge, View};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::fs;
 use tempfile::tempdir;
@@ -281,3 +282,502 @@ fn test_late_view_creation() {
     let state = view.refresh(&log.reader()).unwrap();
     assert_eq!(*state, 10);
 }
+
+#[test]
+fn test_refresh_chunked_yields_between_chunks() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    view.refresh(&log.reader()).unwrap();
+    append_n(&mut log, 10);
+
+    let mut yields = 0;
+    let state = view
+        .refresh_chunked(&log.reader(), 3, || yields += 1)
+        .unwrap();
+
+    assert_eq!(*state, 10);
+    // Chunks of 3, 3, 3, 1 — a yield between each, none after the last.
+    assert_eq!(yields, 3);
+
+    let mut fresh: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    assert_eq!(*fresh.refresh(&log.reader()).unwrap(), 10);
+}
+
+#[test]
+fn test_refresh_chunked_full_replay_across_archive() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 4);
+    log.rotate().unwrap();
+    log.append(&dummy_event("first")).unwrap();
+    log.append(&dummy_event("second")).unwrap();
+
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    let mut yields = 0;
+    let state = view
+        .refresh_chunked(&log.reader(), 2, || yields += 1)
+        .unwrap();
+
+    assert_eq!(*state, 6);
+    assert_eq!(yields, 2);
+
+    append_n(&mut log, 1);
+    assert_eq!(*view.refresh(&log.reader()).unwrap(), 7);
+}
+
+#[test]
+fn test_refresh_chunked_zero_chunk_rejected() {
+    let dir = tempdir().unwrap();
+    let log = EventLog::open(dir.path()).unwrap();
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+
+    let err = view.refresh_chunked(&log.reader(), 0, || {}).unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+fn strict_counter(state: u64, event: &Event) -> Result<u64, eventfold::ReduceError> {
+    if event.event_type == "bad" {
+        return Err(eventfold::ReduceError::new("bad event"));
+    }
+    Ok(state + 1)
+}
+
+#[test]
+fn test_fallible_reducer_stops_refresh() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .try_view::<u64>("strict", strict_counter)
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 2);
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("strict").unwrap(), 2);
+    let snapshot_path = log.views_dir().join("strict.snapshot.json");
+    let snapshot = fs::read(&snapshot_path).unwrap();
+
+    log.append(&Event::new("good", json!({}))).unwrap();
+    let bad = log.append(&Event::new("bad", json!({}))).unwrap();
+    log.append(&Event::new("good", json!({}))).unwrap();
+
+    let err = log.refresh_all().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let failure = err
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<eventfold::ReduceFailure>())
+        .unwrap();
+    assert_eq!(failure.view, "strict");
+    assert_eq!(failure.offset, Some(bad.end_offset));
+    assert_eq!(failure.source.message(), "bad event");
+
+    // No partial snapshot, and the failure repeats.
+    assert_eq!(fs::read(&snapshot_path).unwrap(), snapshot);
+    assert!(log.refresh_all().is_err());
+}
+
+#[test]
+fn test_fallible_reducer_full_replay_has_no_offset() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&Event::new("bad", json!({}))).unwrap();
+    log.rotate().unwrap();
+
+    let mut view: View<u64> = View::new_fallible("strict", strict_counter, log.views_dir());
+    let err = view.refresh(&log.reader()).unwrap_err();
+    let failure = err
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<eventfold::ReduceFailure>())
+        .unwrap();
+    assert_eq!(failure.offset, None);
+    assert!(!log.views_dir().join("strict.snapshot.json").exists());
+}
+
+#[test]
+fn test_refresh_counted() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+
+    append_n(&mut log, 3);
+    let stats = view.refresh_counted(&log.reader()).unwrap();
+    assert_eq!(stats.applied, 3);
+    assert_eq!(stats.new_offset, log.active_log_size().unwrap());
+    assert!(stats.rebuilt); // no snapshot yet
+
+    let stats = view.refresh_counted(&log.reader()).unwrap();
+    assert_eq!(stats.applied, 0);
+    assert!(!stats.rebuilt);
+
+    append_n(&mut log, 2);
+    let stats = view.refresh_counted(&log.reader()).unwrap();
+    assert_eq!(stats.applied, 2);
+    assert!(!stats.rebuilt);
+    assert_eq!(*view.state(), 5);
+}
+
+#[test]
+fn test_refresh_all_counted() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::builder(dir.path())
+        .view::<u64>("counter", counter_reducer)
+        .view::<TodoState>("todos", todo_reducer)
+        .open()
+        .unwrap();
+
+    append_n(&mut log, 4);
+    let stats = log.refresh_all_counted().unwrap();
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats["counter"].applied, 4);
+    assert_eq!(stats["todos"].applied, 4);
+
+    let stats = log.refresh_all_counted().unwrap();
+    assert!(stats.values().all(|s| s.applied == 0));
+}
+
+#[test]
+fn test_refresh_all_caught_up_views_share_read() {
+    let dir = tempdir().unwrap();
+    let open = || {
+        EventLog::builder(dir.path())
+            .view::<u64>("counter", counter_reducer)
+            .view::<TodoState>("todos", todo_reducer)
+            .open()
+            .unwrap()
+    };
+    let mut log = open();
+    append_n(&mut log, 2);
+    log.refresh_all().unwrap();
+
+    log.append(&Event::new("todo_added", json!({"text": "a"})))
+        .unwrap();
+    append_n(&mut log, 1);
+    let stats = log.refresh_all_counted().unwrap();
+    assert_eq!(stats["counter"].applied, 2);
+    assert_eq!(stats["todos"].applied, 2);
+    assert!(stats.values().all(|s| !s.rebuilt));
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 4);
+    assert_eq!(log.view::<TodoState>("todos").unwrap().items.len(), 1);
+
+    drop(log);
+    let mut log = open();
+    append_n(&mut log, 1);
+    let stats = log.refresh_all_counted().unwrap();
+    assert!(stats.values().all(|s| s.applied == 1));
+    assert_eq!(*log.view::<u64>("counter").unwrap(), 5);
+}
+
+#[test]
+fn test_refresh_all_shared_views_at_different_offsets() {
+    let dir = tempdir().unwrap();
+    let open = |names: &[&str]| {
+        let mut builder = EventLog::builder(dir.path());
+        for name in names {
+            builder = builder.view::<u64>(name, counter_reducer);
+        }
+        builder.open().unwrap()
+    };
+    {
+        let mut log = open(&["a", "b"]);
+        append_n(&mut log, 2);
+        log.refresh_all().unwrap();
+    }
+    {
+        let mut log = open(&["a"]);
+        append_n(&mut log, 2);
+        log.refresh_all().unwrap();
+    }
+
+    let mut log = open(&["a", "b", "c"]);
+    append_n(&mut log, 1);
+    let stats = log.refresh_all_shared().unwrap();
+    assert_eq!(stats["a"].applied, 1);
+    assert_eq!(stats["b"].applied, 3);
+    assert_eq!(stats["c"].applied, 5);
+    assert!(stats["c"].rebuilt);
+    for name in ["a", "b", "c"] {
+        assert_eq!(*log.view::<u64>(name).unwrap(), 5);
+        assert_eq!(stats[name].new_offset, log.active_log_size().unwrap());
+    }
+
+    let stats = log.refresh_all_shared().unwrap();
+    assert!(stats.values().all(|s| s.applied == 0));
+
+    // Snapshots written by the shared refresh resume correctly.
+    drop(log);
+    let mut log = open(&["a", "b", "c"]);
+    append_n(&mut log, 1);
+    log.refresh_all().unwrap();
+    assert_eq!(*log.view::<u64>("b").unwrap(), 6);
+}
+
+#[test]
+fn test_fold_full_leaves_view_untouched() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+    log.rotate().unwrap();
+    log.append(&dummy_event("first")).unwrap();
+    log.append(&dummy_event("second")).unwrap();
+
+    let view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    let types = view
+        .fold_full(&log.reader(), Vec::new(), |mut acc, event| {
+            acc.push(event.event_type.clone());
+            acc
+        })
+        .unwrap();
+    assert_eq!(types.len(), 5);
+    assert_eq!(*view.state(), 0);
+    assert!(!log.views_dir().join("counter.snapshot.json").exists());
+}
+
+#[test]
+fn test_state_at_offsets_in_archive_and_active_log() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    log.append(&dummy_event("first")).unwrap();
+    let first_end = fs::metadata(log.log_path()).unwrap().len();
+    append_n(&mut log, 2);
+    let archived = fs::metadata(log.log_path()).unwrap().len();
+    log.rotate().unwrap();
+    log.append(&dummy_event("first")).unwrap();
+    log.append(&dummy_event("second")).unwrap();
+
+    let view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    let reader = log.reader();
+    assert_eq!(view.state_at(&reader, 0).unwrap(), 0);
+    assert_eq!(view.state_at(&reader, first_end).unwrap(), 1);
+    assert_eq!(view.state_at(&reader, first_end - 1).unwrap(), 0);
+    assert_eq!(view.state_at(&reader, archived).unwrap(), 3);
+    let active_first_end = archived + first_end;
+    assert_eq!(view.state_at(&reader, active_first_end).unwrap(), 4);
+    assert_eq!(view.state_at(&reader, u64::MAX).unwrap(), 5);
+}
+
+#[test]
+fn test_state_at_leaves_view_untouched() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    view.refresh(&log.reader()).unwrap();
+    let snapshot_path = log.views_dir().join("counter.snapshot.json");
+    let snapshot = fs::read(&snapshot_path).unwrap();
+
+    assert_eq!(view.state_at(&log.reader(), 0).unwrap(), 0);
+    assert_eq!(*view.state(), 3);
+    assert_eq!(fs::read(&snapshot_path).unwrap(), snapshot);
+}
+
+#[test]
+fn test_snapshot_load_diagnostics() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    assert!(!view.was_loaded_from_snapshot());
+    assert!(!view.last_refresh_replayed_full());
+    view.refresh(&log.reader()).unwrap();
+    assert!(!view.was_loaded_from_snapshot());
+    assert!(view.last_refresh_replayed_full());
+
+    append_n(&mut log, 1);
+    view.refresh(&log.reader()).unwrap();
+    assert!(!view.last_refresh_replayed_full());
+
+    let mut warm: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    warm.refresh(&log.reader()).unwrap();
+    assert!(warm.was_loaded_from_snapshot());
+    assert!(!warm.last_refresh_replayed_full());
+
+    warm.rebuild(&log.reader()).unwrap();
+    assert!(!warm.was_loaded_from_snapshot());
+    assert!(warm.last_refresh_replayed_full());
+}
+
+#[test]
+fn test_invalidated_snapshot_is_not_reported_as_loaded() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    view.refresh(&log.reader()).unwrap();
+    drop(view);
+
+    // Truncating the log leaves the snapshot's offset beyond EOF.
+    fs::write(log.log_path(), "").unwrap();
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    view.refresh(&log.reader()).unwrap();
+    assert!(!view.was_loaded_from_snapshot());
+    assert!(view.last_refresh_replayed_full());
+}
+
+#[test]
+fn test_views_with_custom_filenames_do_not_collide() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 2);
+
+    let mut first: View<u64> = View::new_with_filename(
+        "counter",
+        counter_reducer,
+        log.views_dir(),
+        "a.snapshot.json",
+    );
+    let mut second: View<u64> = View::new_with_filename(
+        "counter",
+        |state, _event| state + 10,
+        log.views_dir(),
+        "b.snapshot.json",
+    );
+    first.refresh(&log.reader()).unwrap();
+    second.refresh(&log.reader()).unwrap();
+    assert!(!log.views_dir().join("counter.snapshot.json").exists());
+
+    let mut first: View<u64> = View::new_with_filename(
+        "counter",
+        counter_reducer,
+        log.views_dir(),
+        "a.snapshot.json",
+    );
+    let mut second: View<u64> = View::new_with_filename(
+        "counter",
+        |state, _event| state + 10,
+        log.views_dir(),
+        "b.snapshot.json",
+    );
+    assert_eq!(*first.refresh(&log.reader()).unwrap(), 2);
+    assert_eq!(*second.refresh(&log.reader()).unwrap(), 20);
+    assert!(first.was_loaded_from_snapshot());
+    assert!(second.was_loaded_from_snapshot());
+}
+
+/// Each event's `(offset, sequence, line_hash)`, in fold order.
+type Positions = Vec<(Option<u64>, u64, String)>;
+
+fn position_reducer(mut state: Positions, _event: &Event, at: EventContext<'_>) -> Positions {
+    state.push((at.offset, at.sequence, at.line_hash.to_string()));
+    state
+}
+
+#[test]
+fn test_context_view_sees_offsets_and_sequences() {
+    let dir = tempdir().unwrap();
+    let mut results = Vec::new();
+    {
+        let mut log = EventLog::builder(dir.path())
+            .view_with_context::<Positions>("positions", position_reducer)
+            .open()
+            .unwrap();
+        for i in 0..2 {
+            results.push(log.append(&dummy_event(&format!("a{i}"))).unwrap());
+        }
+        log.rotate().unwrap();
+        results.push(log.append(&dummy_event("b")).unwrap());
+        log.refresh_all().unwrap();
+    }
+
+    // Reopening resumes from the snapshot and finds the sequence there.
+    let mut log = EventLog::builder(dir.path())
+        .view_with_context::<Positions>("positions", position_reducer)
+        .open()
+        .unwrap();
+    results.push(log.append(&dummy_event("c")).unwrap());
+    log.refresh_all().unwrap();
+
+    let expected: Positions = results
+        .iter()
+        .map(|r| (Some(r.start_offset), r.sequence, r.line_hash.clone()))
+        .collect();
+    let positions = log.view::<Positions>("positions").unwrap().clone();
+    assert_eq!(positions, expected);
+
+    // A full replay folds the archived events without an offset.
+    let mut view = View::new_with_context("replayed", position_reducer, log.views_dir());
+    let positions = view.refresh(&log.reader()).unwrap();
+    assert_eq!(positions[0].0, None);
+    assert_eq!(positions[1].0, None);
+    assert_eq!(positions[2..], expected[2..]);
+    assert_eq!(
+        positions.iter().map(|p| p.1).collect::<Vec<_>>(),
+        [0, 1, 2, 3]
+    );
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Trail(Positions);
+
+impl Merge for Trail {
+    fn merge(mut a: Self, b: Self) -> Self {
+        a.0.extend(b.0);
+        a
+    }
+}
+
+fn trail_reducer(state: Trail, event: &Event, at: EventContext<'_>) -> Trail {
+    Trail(position_reducer(state.0, event, at))
+}
+
+#[test]
+fn test_rebuild_parallel_matches_sequential_replay() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 7);
+    log.rotate().unwrap();
+    append_n(&mut log, 6);
+    let reader = log.reader();
+
+    let mut sequential = View::new_with_context("sequential", trail_reducer, log.views_dir());
+    let expected = sequential.refresh(&reader).unwrap().clone();
+    assert_eq!(expected.0.len(), 13);
+
+    for threads in [1, 3, 64] {
+        let mut view = View::new_with_context("parallel", trail_reducer, log.views_dir());
+        let state = view.rebuild_parallel(&reader, threads).unwrap();
+        assert_eq!(*state, expected, "{threads} threads");
+    }
+
+    // The snapshot it saved resumes like one from a sequential replay.
+    log.append(&dummy_event("next")).unwrap();
+    let mut view = View::new_with_context("parallel", trail_reducer, log.views_dir());
+    let last = view.refresh(&log.reader()).unwrap().0.last().cloned();
+    assert!(view.was_loaded_from_snapshot());
+    assert_eq!(last.unwrap().1, 13);
+
+    let err = view.rebuild_parallel(&log.reader(), 0).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_reducer_version_change_forces_rebuild() {
+    let dir = tempdir().unwrap();
+    let mut log = EventLog::open(dir.path()).unwrap();
+    append_n(&mut log, 3);
+
+    let mut view: View<u64> =
+        View::new("counter", counter_reducer, log.views_dir()).with_reducer_version(1);
+    view.refresh(&log.reader()).unwrap();
+    let snapshot = fs::read_to_string(log.views_dir().join("counter.snapshot.json")).unwrap();
+    assert!(snapshot.contains("\"reducer_version\": 1"));
+    drop(view);
+
+    // Same version: the snapshot is trusted even though the reducer differs.
+    let mut view: View<u64> =
+        View::new("counter", |state, _event| state + 2, log.views_dir()).with_reducer_version(1);
+    assert_eq!(*view.refresh(&log.reader()).unwrap(), 3);
+    drop(view);
+
+    // Bumped version: the snapshot is discarded and the history replayed.
+    let mut view: View<u64> =
+        View::new("counter", |state, _event| state + 2, log.views_dir()).with_reducer_version(2);
+    assert_eq!(*view.refresh(&log.reader()).unwrap(), 6);
+    drop(view);
+
+    // Unversioned views trust any snapshot.
+    let mut view: View<u64> = View::new("counter", counter_reducer, log.views_dir());
+    assert_eq!(*view.refresh(&log.reader()).unwrap(), 6);
+}